@@ -0,0 +1,47 @@
+//! Cypher query text helpers shared by [`redisgraph`](https://docs.rs/redisgraph) and
+//! [`redisgraph-derive`](https://docs.rs/redisgraph-derive), so queries are checked the same way
+//! at runtime and at compile time.
+
+/// Returns the names of all `$name` parameter references outside of string literals and quoted identifiers.
+///
+/// Names are returned in the order of their references, including repeated ones.
+///
+/// ```
+/// use redisgraph_cypher::referenced_params;
+///
+/// assert_eq!(
+///     referenced_params("MATCH (p:`$label` {name: $name}) WHERE p.note <> '$note' RETURN $name"),
+///     vec!["name", "name"]
+/// );
+/// ```
+pub fn referenced_params(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == '\\' && q != '`' => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' || c == '`' => quote = Some(c),
+            None if c == '$' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_ascii_alphanumeric() || next == '_' {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if !name.is_empty() {
+                    names.push(name);
+                }
+            }
+            None => {}
+        }
+    }
+    names
+}
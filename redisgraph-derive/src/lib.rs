@@ -0,0 +1,672 @@
+//! Derive and attribute macros for [`redisgraph`](https://docs.rs/redisgraph).
+//!
+//! Use these through the `derive` feature of `redisgraph` instead of depending on this crate directly.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use redisgraph_cypher::referenced_params;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    spanned::Spanned,
+    AttributeArgs, Data, DeriveInput, Error, Fields, FnArg, ForeignItemFn, ItemFn, Lit, LitStr,
+    Meta, NestedMeta, Pat, ReturnType, Token, Type,
+};
+
+/// Derives `redisgraph::ToProperties` for a struct with named fields.
+///
+/// Every field is turned into a property named after the field and converted
+/// with `Into<CypherValue>`, so every field type must implement `Clone` and that conversion.
+/// Fields of type `Option<T>` are set with `Props::set_opt`, so `None` values are left out.
+///
+/// Fields can be configured with the `redisgraph` attribute:
+///
+/// - `#[redisgraph(rename = "name")]` uses the given property key instead of the field name.
+/// - `#[redisgraph(skip)]` leaves the field out of the property map.
+/// - `#[redisgraph(encrypted)]` stores the field encrypted with `EncryptedCodec`, using the key
+///   of the type named with `#[redisgraph(key_provider = "Type")]` on the struct, which must
+///   implement `KeyProvider`. The field type must implement `Serialize` instead of the
+///   conversion, and `to_properties` panics if the field can't be encoded. Equal values are
+///   encrypted differently, so examples for `find_like` only match encrypted fields that are `None`.
+///   Requires the `encryption` feature of `redisgraph`.
+#[proc_macro_derive(ToProperties, attributes(redisgraph))]
+pub fn derive_to_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match to_properties_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn to_properties_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(input, "ToProperties")?;
+    let key_provider = EntityOptions::from_attributes(&input.attrs)?.key_provider;
+
+    let mut codec = None;
+    let mut setters = Vec::new();
+    for field in fields {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        if options.skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().unwrap();
+        let key = options.rename.unwrap_or_else(|| ident.to_string());
+        let setter = if options.encrypted {
+            codec = Some(encrypted_codec(key_provider.as_ref(), field)?);
+            let message = format!("failed to encrypt the field `{}`", ident);
+            let encrypt = |value: TokenStream2| {
+                quote! {
+                    ::std::result::Result::expect(
+                        ::redisgraph::codec::PropertyCodec::encode(&codec, #value),
+                        #message,
+                    )
+                }
+            };
+            if is_option(&field.ty) {
+                let encrypted = encrypt(quote! { value });
+                quote! { props = props.set_opt(#key, self.#ident.as_ref().map(|value| #encrypted)); }
+            } else {
+                let encrypted = encrypt(quote! { &self.#ident });
+                quote! { props = props.set(#key, #encrypted); }
+            }
+        } else if is_option(&field.ty) {
+            quote! { props = props.set_opt(#key, ::std::clone::Clone::clone(&self.#ident)); }
+        } else {
+            quote! { props = props.set(#key, ::std::clone::Clone::clone(&self.#ident)); }
+        };
+        setters.push(setter);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::redisgraph::props::ToProperties for #name #ty_generics #where_clause {
+            fn to_properties(&self) -> ::redisgraph::props::Props {
+                #codec
+                let mut props = ::redisgraph::props::Props::new();
+                #(#setters)*
+                props
+            }
+        }
+    })
+}
+
+/// Derives `redisgraph::Entity` for a struct with named fields.
+///
+/// The label is the struct name, unless it is set with `#[redisgraph(label = "Name")]`
+/// on the struct. Every field is read with `Node::get`, so every field type must
+/// implement `FromCell`. Fields accept the same `rename` and `skip` attributes as
+/// `ToProperties`, and skipped fields are set with `Default::default()`. Fields marked with
+/// `#[redisgraph(encrypted)]` are decrypted with the key of the `key_provider` of the struct,
+/// and their types must implement `DeserializeOwned` instead of `FromCell`.
+///
+/// `#[redisgraph(key)]` marks the field identifying the entity. Relations with a deletion
+/// rule are declared on the struct, e.g.
+/// `#[redisgraph(relation(rel_type = "WROTE", direction = "outgoing", on_delete = "cascade"))]`,
+/// where `direction` is one of `outgoing` (the default), `incoming` and `both`, and
+/// `on_delete` is one of `cascade`, `detach` (the default) and `restrict`.
+///
+/// `#[redisgraph(before_save = "method", after_load = "method")]` on the struct names
+/// methods taking `&mut self` and returning `RedisGraphResult<()>` that are called as
+/// the lifecycle hooks of the entity.
+#[proc_macro_derive(Entity, attributes(redisgraph))]
+pub fn derive_entity(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match entity_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn entity_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(input, "Entity")?;
+    let options = EntityOptions::from_attributes(&input.attrs)?;
+    let key_provider = options.key_provider.clone();
+
+    let mut codec = None;
+    let mut initializers = Vec::new();
+    let mut key_fn = None;
+    for field in fields {
+        let options = FieldOptions::from_attributes(&field.attrs)?;
+        let ident = field.ident.as_ref().unwrap();
+        if options.skip {
+            initializers.push(quote! { #ident: ::std::default::Default::default(), });
+            continue;
+        }
+
+        let key = options.rename.unwrap_or_else(|| ident.to_string());
+        if !options.encrypted {
+            initializers.push(quote! { #ident: node.get(#key)?, });
+        } else if is_option(&field.ty) {
+            codec = Some(encrypted_codec(key_provider.as_ref(), field)?);
+            initializers.push(quote! {
+                #ident: match node.get::<::std::option::Option<::std::string::String>>(#key)? {
+                    ::std::option::Option::Some(encrypted) => ::std::option::Option::Some(
+                        ::redisgraph::codec::PropertyCodec::decode(&codec, &encrypted)?,
+                    ),
+                    ::std::option::Option::None => ::std::option::Option::None,
+                },
+            });
+        } else {
+            codec = Some(encrypted_codec(key_provider.as_ref(), field)?);
+            initializers.push(quote! { #ident: node.decode_property(#key, &codec)?, });
+        }
+        if options.key {
+            if options.encrypted {
+                return Err(Error::new(
+                    field.span(),
+                    "encrypted fields can't be the key",
+                ));
+            }
+            if key_fn.is_some() {
+                return Err(Error::new(field.span(), "only one field can be the key"));
+            }
+            key_fn = Some(quote! {
+                fn key(
+                    &self,
+                ) -> ::std::option::Option<(&'static str, ::redisgraph::params::CypherValue)> {
+                    ::std::option::Option::Some((
+                        #key,
+                        ::std::convert::Into::into(::std::clone::Clone::clone(&self.#ident)),
+                    ))
+                }
+            });
+        }
+    }
+    let relations = options.relations.iter().map(|relation| {
+        let rel_type = &relation.rel_type;
+        let direction = syn::Ident::new(&relation.direction, proc_macro2::Span::call_site());
+        let on_delete = syn::Ident::new(&relation.on_delete, proc_macro2::Span::call_site());
+        quote! {
+            ::redisgraph::entity::Relation {
+                rel_type: #rel_type,
+                direction: ::redisgraph::pattern::Direction::#direction,
+                on_delete: ::redisgraph::entity::OnDelete::#on_delete,
+            },
+        }
+    });
+
+    let before_save = options.before_save.map(|method| {
+        quote! {
+            fn before_save(&mut self) -> ::redisgraph::RedisGraphResult<()> {
+                self.#method()
+            }
+        }
+    });
+    let after_load = options.after_load.map(|method| {
+        quote! {
+            fn after_load(&mut self) -> ::redisgraph::RedisGraphResult<()> {
+                self.#method()
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let label = options.label.unwrap_or_else(|| name.to_string());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::redisgraph::entity::Entity for #name #ty_generics #where_clause {
+            const LABEL: &'static str = #label;
+            const RELATIONS: &'static [::redisgraph::entity::Relation] = &[#(#relations)*];
+
+            fn from_node(
+                node: &::redisgraph::result_set::Node,
+            ) -> ::redisgraph::RedisGraphResult<Self> {
+                #codec
+                ::std::result::Result::Ok(Self {
+                    #(#initializers)*
+                })
+            }
+
+            #key_fn
+            #before_save
+            #after_load
+        }
+    })
+}
+
+// Creates the codec for the encrypted fields, with the key of the key provider of the struct.
+fn encrypted_codec(
+    key_provider: Option<&syn::Path>,
+    field: &syn::Field,
+) -> syn::Result<TokenStream2> {
+    let key_provider = key_provider.ok_or_else(|| {
+        Error::new(
+            field.span(),
+            "encrypted fields need `#[redisgraph(key_provider = \"...\")]` on the struct",
+        )
+    })?;
+    Ok(quote! {
+        let codec = ::redisgraph::codec::EncryptedCodec::new(
+            &<#key_provider as ::redisgraph::codec::KeyProvider>::encryption_key(),
+        );
+    })
+}
+
+fn named_fields<'a>(
+    input: &'a DeriveInput,
+    trait_name: &str,
+) -> syn::Result<&'a syn::punctuated::Punctuated<syn::Field, Token![,]>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(Error::new(
+                input.span(),
+                format!(
+                    "{} can only be derived for structs with named fields",
+                    trait_name
+                ),
+            )),
+        },
+        _ => Err(Error::new(
+            input.span(),
+            format!("{} can only be derived for structs", trait_name),
+        )),
+    }
+}
+
+/// Turns a function taking a `&mut Graph` into a test that runs against a temporary graph.
+///
+/// The graph gets a unique name, so tests don't need to run serially, and is deleted
+/// after the test, even if it panics. The database is reached at the URI in the
+/// `TEST_REDIS_URI` environment variable, `redis://127.0.0.1` by default.
+///
+/// The graph can be seeded with a script of semicolon-separated statements using
+/// `#[graph_test(fixture = "path/to/fixture.cypher")]`, where the path is relative to the crate root.
+///
+/// ```ignore
+/// #[graph_test(fixture = "tests/fixtures/people.cypher")]
+/// fn test_people(graph: &mut Graph) {
+///     let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+///     assert_eq!(count, 3);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn graph_test(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let input = parse_macro_input!(input as ItemFn);
+    match graph_test_impl(args, input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn graph_test_impl(args: AttributeArgs, input: ItemFn) -> syn::Result<TokenStream2> {
+    let mut fixture = None;
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if name_value.path.is_ident("fixture") =>
+            {
+                match name_value.lit {
+                    Lit::Str(path) => fixture = Some(path.value()),
+                    lit => return Err(Error::new(lit.span(), "expected string literal")),
+                }
+            }
+            arg => return Err(Error::new(arg.span(), "unknown graph_test argument")),
+        }
+    }
+
+    if input.sig.inputs.len() != 1 {
+        return Err(Error::new(
+            input.sig.span(),
+            "graph_test functions must take exactly one argument of type `&mut Graph`",
+        ));
+    }
+
+    let fixture = match fixture {
+        Some(path) => quote! {
+            ::std::option::Option::Some(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #path)))
+        },
+        None => quote! { ::std::option::Option::None },
+    };
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let name = &sig.ident;
+    let output = &sig.output;
+    Ok(quote! {
+        #[test]
+        #(#attrs)*
+        #vis fn #name() #output {
+            #sig #block
+
+            ::redisgraph::testing::run_graph_test(
+                concat!(module_path!(), "::", stringify!(#name)),
+                #fixture,
+                #name,
+            )
+        }
+    })
+}
+
+/// Generates typed functions executing the queries in a directory of `.cypher` files.
+///
+/// Every declared function executes the file named after it, relative to the crate root,
+/// with its arguments passed as the parameters of the same names. Functions with a return type
+/// are executed with `Graph::query_with_params` and return `RedisGraphResult<T>`, functions
+/// without one with `Graph::mutate_with_params`. The generated functions take the graph as
+/// their first argument, over any connection implementing `redis::ConnectionLike`, and every
+/// argument type must implement `Into<CypherValue>`.
+///
+/// The files are embedded with `include_str!`. Compilation fails if a file doesn't exist,
+/// references a `$parameter` that is not an argument, or doesn't use one of the arguments.
+///
+/// ```ignore
+/// redisgraph::queries! {
+///     dir = "queries";
+///
+///     /// Executes `queries/find_people.cypher`.
+///     pub fn find_people(min_age: i64) -> Vec<String>;
+///     pub fn create_person(name: &str, age: i64);
+/// }
+///
+/// let names = find_people(&mut graph, 18)?;
+/// ```
+#[proc_macro]
+pub fn queries(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as QueriesInput);
+    match queries_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+struct QueriesInput {
+    dir: LitStr,
+    functions: Vec<ForeignItemFn>,
+}
+
+impl Parse for QueriesInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        if key != "dir" {
+            return Err(Error::new(key.span(), "expected `dir = \"...\";`"));
+        }
+        input.parse::<Token![=]>()?;
+        let dir = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        let mut functions = Vec::new();
+        while !input.is_empty() {
+            functions.push(input.parse()?);
+        }
+        Ok(Self { dir, functions })
+    }
+}
+
+fn queries_impl(input: QueriesInput) -> syn::Result<TokenStream2> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| Error::new(input.dir.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let dir = input.dir.value();
+
+    let mut functions = Vec::new();
+    for function in input.functions {
+        let ForeignItemFn {
+            attrs, vis, sig, ..
+        } = function;
+        let name = &sig.ident;
+        let relative_path = format!("{}/{}.cypher", dir.trim_end_matches('/'), name);
+        let path = std::path::Path::new(&manifest_dir).join(&relative_path);
+        let text = std::fs::read_to_string(&path).map_err(|error| {
+            Error::new(
+                name.span(),
+                format!("failed to read `{}`: {}", relative_path, error),
+            )
+        })?;
+
+        let mut args = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Typed(arg) => match &*arg.pat {
+                    Pat::Ident(ident) => args.push((ident.ident.clone(), arg.ty.clone())),
+                    pat => return Err(Error::new(pat.span(), "expected a plain argument name")),
+                },
+                FnArg::Receiver(receiver) => {
+                    return Err(Error::new(receiver.span(), "queries can't take `self`"))
+                }
+            }
+        }
+
+        let referenced = referenced_params(&text);
+        for param in &referenced {
+            if !args.iter().any(|(arg, _)| arg == param) {
+                return Err(Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` references `${}`, which is not an argument",
+                        relative_path, param
+                    ),
+                ));
+            }
+        }
+        for (arg, _) in &args {
+            if !referenced.iter().any(|param| arg == param) {
+                return Err(Error::new(
+                    arg.span(),
+                    format!("`{}` doesn't use the parameter `${}`", relative_path, arg),
+                ));
+            }
+        }
+
+        let arg_names: Vec<_> = args.iter().map(|(arg, _)| arg).collect();
+        let arg_types: Vec<_> = args.iter().map(|(_, ty)| ty).collect();
+        let (output, execute) = match &sig.output {
+            ReturnType::Default => (quote! { () }, quote! { mutate_with_params }),
+            ReturnType::Type(_, ty) => (quote! { #ty }, quote! { query_with_params }),
+        };
+        functions.push(quote! {
+            #(#attrs)*
+            #vis fn #name<C: ::redis::ConnectionLike>(
+                graph: &mut ::redisgraph::Graph<C>,
+                #(#arg_names: #arg_types),*
+            ) -> ::redisgraph::RedisGraphResult<#output> {
+                #[allow(unused_mut)]
+                let mut params = ::std::collections::HashMap::new();
+                #(
+                    params.insert(
+                        stringify!(#arg_names).to_string(),
+                        ::redisgraph::params::CypherValue::from(#arg_names),
+                    );
+                )*
+                graph.#execute(
+                    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #relative_path)),
+                    &params,
+                )
+            }
+        });
+    }
+
+    Ok(quote! { #(#functions)* })
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<String>,
+    skip: bool,
+    key: bool,
+    encrypted: bool,
+}
+
+impl FieldOptions {
+    fn from_attributes(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("redisgraph")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new(meta.span(), "expected #[redisgraph(...)]")),
+            };
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                        options.skip = true;
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("key") => {
+                        options.key = true;
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("encrypted") => {
+                        options.encrypted = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("rename") =>
+                    {
+                        match name_value.lit {
+                            Lit::Str(rename) => options.rename = Some(rename.value()),
+                            lit => return Err(Error::new(lit.span(), "expected string literal")),
+                        }
+                    }
+                    nested => {
+                        return Err(Error::new(nested.span(), "unknown redisgraph attribute"))
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Default)]
+struct EntityOptions {
+    label: Option<String>,
+    relations: Vec<RelationOptions>,
+    before_save: Option<syn::Ident>,
+    after_load: Option<syn::Ident>,
+    key_provider: Option<syn::Path>,
+}
+
+// The variant names of `Direction` and `OnDelete` for a declared relation.
+struct RelationOptions {
+    rel_type: String,
+    direction: String,
+    on_delete: String,
+}
+
+impl EntityOptions {
+    fn from_attributes(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("redisgraph")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new(meta.span(), "expected #[redisgraph(...)]")),
+            };
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("label") =>
+                    {
+                        match name_value.lit {
+                            Lit::Str(label) => options.label = Some(label.value()),
+                            lit => return Err(Error::new(lit.span(), "expected string literal")),
+                        }
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("before_save")
+                            || name_value.path.is_ident("after_load") =>
+                    {
+                        let method = match &name_value.lit {
+                            Lit::Str(method) => method.parse::<syn::Ident>()?,
+                            lit => return Err(Error::new(lit.span(), "expected string literal")),
+                        };
+                        if name_value.path.is_ident("before_save") {
+                            options.before_save = Some(method);
+                        } else {
+                            options.after_load = Some(method);
+                        }
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("key_provider") =>
+                    {
+                        match &name_value.lit {
+                            Lit::Str(path) => options.key_provider = Some(path.parse()?),
+                            lit => return Err(Error::new(lit.span(), "expected string literal")),
+                        }
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("relation") => {
+                        options.relations.push(RelationOptions::from_list(list)?);
+                    }
+                    nested => {
+                        return Err(Error::new(nested.span(), "unknown redisgraph attribute"))
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+impl RelationOptions {
+    fn from_list(list: syn::MetaList) -> syn::Result<Self> {
+        let span = list.span();
+        let mut rel_type = None;
+        let mut direction = "Outgoing".to_string();
+        let mut on_delete = "Detach".to_string();
+        for nested in list.nested {
+            let name_value = match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => name_value,
+                nested => return Err(Error::new(nested.span(), "expected `name = \"value\"`")),
+            };
+            let value = match &name_value.lit {
+                Lit::Str(value) => value.value(),
+                lit => return Err(Error::new(lit.span(), "expected string literal")),
+            };
+            let variant = |variants: &[(&str, &str)]| {
+                variants
+                    .iter()
+                    .find(|(name, _)| *name == value)
+                    .map(|(_, variant)| variant.to_string())
+                    .ok_or_else(|| {
+                        let names: Vec<&str> = variants.iter().map(|(name, _)| *name).collect();
+                        Error::new(
+                            name_value.lit.span(),
+                            format!("expected one of {}", names.join(", ")),
+                        )
+                    })
+            };
+            if name_value.path.is_ident("rel_type") {
+                rel_type = Some(value.clone());
+            } else if name_value.path.is_ident("direction") {
+                direction = variant(&[
+                    ("outgoing", "Outgoing"),
+                    ("incoming", "Incoming"),
+                    ("both", "Both"),
+                ])?;
+            } else if name_value.path.is_ident("on_delete") {
+                on_delete = variant(&[
+                    ("cascade", "Cascade"),
+                    ("detach", "Detach"),
+                    ("restrict", "Restrict"),
+                ])?;
+            } else {
+                return Err(Error::new(
+                    name_value.path.span(),
+                    "unknown relation attribute",
+                ));
+            }
+        }
+        Ok(Self {
+            rel_type: rel_type.ok_or_else(|| Error::new(span, "missing `rel_type`"))?,
+            direction,
+            on_delete,
+        })
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Option")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
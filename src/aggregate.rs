@@ -0,0 +1,88 @@
+use redis::{Connection, ConnectionLike};
+
+use crate::{
+    assignments::FromCell, labels::Labels, params::escape_identifier, Graph, RedisGraphResult,
+};
+
+/// Typed helpers for simple aggregations over the nodes with a set of labels,
+/// obtained through [`Graph::aggregate`](../graph/struct.Graph.html#method.aggregate).
+///
+/// An empty label set aggregates over all nodes in the graph.
+///
+/// ```no_run
+/// # use redis::Client;
+/// # use redisgraph::Graph;
+/// # let client = Client::open("redis://127.0.0.1").unwrap();
+/// # let mut connection = client.get_connection().unwrap();
+/// # let mut graph = Graph::open(connection, "MotoGP".to_string()).unwrap();
+/// let riders = graph.aggregate().count("Rider").unwrap();
+/// let average_age = graph.aggregate().avg("Rider", "age").unwrap();
+/// let oldest: Option<i64> = graph.aggregate().max("Rider", "age").unwrap();
+/// ```
+pub struct Aggregate<'a, C = Connection> {
+    graph: &'a mut Graph<C>,
+}
+
+impl<'a, C: ConnectionLike> Aggregate<'a, C> {
+    pub(crate) fn new(graph: &'a mut Graph<C>) -> Self {
+        Self { graph }
+    }
+
+    /// Returns the number of nodes with all of the given labels.
+    pub fn count<L: Into<Labels>>(&mut self, labels: L) -> RedisGraphResult<u64> {
+        self.aggregate(labels, "count(n)")
+    }
+
+    /// Returns the sum of the given property over the nodes with all of the given labels.
+    ///
+    /// The sum is an integer if all values are integers and a double otherwise.
+    pub fn sum<T: FromCell, L: Into<Labels>>(
+        &mut self,
+        labels: L,
+        property: &str,
+    ) -> RedisGraphResult<T> {
+        self.aggregate(labels, &format!("sum(n.{})", escape_identifier(property)))
+    }
+
+    /// Returns the average of the given property over the nodes with all of the given labels.
+    ///
+    /// Returns `None` if there are no such nodes.
+    pub fn avg<L: Into<Labels>>(
+        &mut self,
+        labels: L,
+        property: &str,
+    ) -> RedisGraphResult<Option<f64>> {
+        self.aggregate(labels, &format!("avg(n.{})", escape_identifier(property)))
+    }
+
+    /// Returns the smallest value of the given property among the nodes with all of the given labels.
+    ///
+    /// The value is null if there are no such nodes, so `T` should usually be an `Option`.
+    pub fn min<T: FromCell, L: Into<Labels>>(
+        &mut self,
+        labels: L,
+        property: &str,
+    ) -> RedisGraphResult<T> {
+        self.aggregate(labels, &format!("min(n.{})", escape_identifier(property)))
+    }
+
+    /// Returns the largest value of the given property among the nodes with all of the given labels.
+    ///
+    /// The value is null if there are no such nodes, so `T` should usually be an `Option`.
+    pub fn max<T: FromCell, L: Into<Labels>>(
+        &mut self,
+        labels: L,
+        property: &str,
+    ) -> RedisGraphResult<T> {
+        self.aggregate(labels, &format!("max(n.{})", escape_identifier(property)))
+    }
+
+    fn aggregate<T: FromCell, L: Into<Labels>>(
+        &mut self,
+        labels: L,
+        function: &str,
+    ) -> RedisGraphResult<T> {
+        self.graph
+            .query(&format!("MATCH (n{}) RETURN {}", labels.into(), function))
+    }
+}
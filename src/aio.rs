@@ -0,0 +1,518 @@
+//! An asynchronous graph for services running on an async runtime.
+//!
+//! [`AsyncGraph`](struct.AsyncGraph.html) sends its queries over a
+//! [`MultiplexedConnection`](https://docs.rs/redis/0.15.1/redis/aio/struct.MultiplexedConnection.html),
+//! so it can be used without blocking the runtime's threads. It supports the core query methods
+//! of [`Graph`](../graph/struct.Graph.html), including
+//! [parameter validation](struct.AsyncGraph.html#method.set_param_validation). The following
+//! features of `Graph` are not available on it yet:
+//!
+//! * [query statistics](../graph/struct.Graph.html#method.set_stats_enabled), i.e. latencies
+//!   and error counts,
+//! * the OpenTelemetry spans of the `otel` feature,
+//! * [mutation listeners](../graph/struct.Graph.html#method.on_mutation) and
+//!   [connection listeners](../graph/struct.Graph.html#method.on_connection_event),
+//!   though a [`ReconnectingGraph`](../reconnect/struct.ReconnectingGraph.html) reports
+//!   connection events,
+//! * retries.
+//!
+//! The module requires the `aio` feature and works with any runtime that drives the connection.
+//! The `tokio-comp` feature adds [`AsyncGraph::connect`](struct.AsyncGraph.html#method.connect),
+//! which lets tokio drive it, and
+//! [`set_blocking_decode_threshold`](struct.AsyncGraph.html#method.set_blocking_decode_threshold),
+//! which decodes large responses on a blocking thread. The connections of the `redis` crate
+//! need a tokio reactor even on other runtimes, so the `async-std-comp` feature adds
+//! [`detached_connection`](fn.detached_connection.html), which drives the connection on a thread
+//! of its own, for use with `async-std` and other runtimes.
+//!
+//! ```no_run
+//! # use redisgraph::{aio::AsyncGraph, RedisGraphResult};
+//! # fn spawn(_driver: impl std::future::Future<Output = ()>) {}
+//! # async fn example(client: redis::Client) -> RedisGraphResult<()> {
+//! let (conn, driver) = client.get_multiplexed_async_connection().await?;
+//! // Drive the connection on the runtime, e.g. with `tokio::spawn`.
+//! spawn(driver);
+//! let mut graph = AsyncGraph::open(conn, "MotoGP".to_string()).await?;
+//!
+//! graph.mutate("CREATE (:Rider {name: 'Valentino Rossi'})").await?;
+//! let names: Vec<String> = graph.query("MATCH (r:Rider) RETURN r.name").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use redis::{aio::MultiplexedConnection, Value};
+
+use crate::{
+    assignments::FromTable,
+    commands::CommandNames,
+    graph::parse_mapping,
+    params::{prepare_query, CypherValue},
+    result_set::{DecodeContext, FromRedisValueWithGraph, Statistics},
+    RedisGraphError, RedisGraphResult, RedisString, ResultSet,
+};
+
+/// Opens a multiplexed connection that is driven by a tokio reactor on a background thread,
+/// so it can be used from any runtime, e.g. `async-std`. Requires the `async-std-comp` feature.
+///
+/// The thread exits once the connection and all of its clones are dropped.
+///
+/// ```no_run
+/// # use redisgraph::{aio::{detached_connection, AsyncGraph}, RedisGraphResult};
+/// # async fn example(client: redis::Client) -> RedisGraphResult<()> {
+/// let conn = detached_connection(&client).await?;
+/// let mut graph = AsyncGraph::open(conn, "MotoGP".to_string()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async-std-comp")]
+pub async fn detached_connection(
+    client: &redis::Client,
+) -> RedisGraphResult<MultiplexedConnection> {
+    let client = client.clone();
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let mut runtime = tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .build()
+        .map_err(redis::RedisError::from)?;
+    std::thread::Builder::new()
+        .name("redisgraph-connection".to_string())
+        .spawn(move || {
+            runtime.block_on(async move {
+                match client.get_multiplexed_async_connection().await {
+                    Ok((conn, driver)) => {
+                        if sender.send(Ok(conn)).is_ok() {
+                            driver.await;
+                        }
+                    }
+                    Err(error) => {
+                        let _ = sender.send(Err(error));
+                    }
+                }
+            })
+        })
+        .map_err(redis::RedisError::from)?;
+    match receiver.await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(redis::RedisError::from((
+            redis::ErrorKind::IoError,
+            "connection thread exited",
+        ))
+        .into()),
+    }
+}
+
+/// Represents a single graph in the database, queried asynchronously.
+pub struct AsyncGraph {
+    conn: MultiplexedConnection,
+    name: String,
+    commands: CommandNames,
+
+    labels: Vec<RedisString>,
+    relationship_types: Vec<RedisString>,
+    property_keys: Vec<RedisString>,
+    reply_diagnostics: bool,
+    lenient_booleans: bool,
+    validate_params: bool,
+    #[cfg(feature = "tokio-comp")]
+    blocking_decode_threshold: Option<usize>,
+}
+
+impl AsyncGraph {
+    /// Opens the graph with the given name from the database.
+    ///
+    /// If the graph does not already exist, creates a new graph with the given name.
+    pub async fn open(conn: MultiplexedConnection, name: String) -> RedisGraphResult<Self> {
+        Self::open_with_commands(conn, name, CommandNames::default()).await
+    }
+
+    /// Opens the graph over a new multiplexed connection from the given client, driven by the
+    /// tokio runtime. Requires the `tokio-comp` feature.
+    #[cfg(feature = "tokio-comp")]
+    pub async fn connect(client: &redis::Client, name: String) -> RedisGraphResult<Self> {
+        let conn = client.get_multiplexed_tokio_connection().await?;
+        Self::open(conn, name).await
+    }
+
+    /// Same as [`open`](#method.open), but uses the given command names instead of `GRAPH.QUERY` etc.
+    pub async fn open_with_commands(
+        conn: MultiplexedConnection,
+        name: String,
+        commands: CommandNames,
+    ) -> RedisGraphResult<Self> {
+        let mut graph = Self {
+            conn,
+            name,
+            commands,
+            labels: Vec::new(),
+            relationship_types: Vec::new(),
+            property_keys: Vec::new(),
+            reply_diagnostics: false,
+            lenient_booleans: true,
+            validate_params: false,
+            #[cfg(feature = "tokio-comp")]
+            blocking_decode_threshold: None,
+        };
+
+        // See `Graph::open_with_commands`.
+        graph.mutate("CREATE (dummy:__DUMMY_LABEL__)").await?;
+        graph
+            .mutate("MATCH (dummy:__DUMMY_LABEL__) DELETE dummy")
+            .await?;
+
+        Ok(graph)
+    }
+
+    /// Executes the given query and returns its return values.
+    ///
+    /// Only use this for queries with a `RETURN` statement.
+    pub async fn query<T: FromTable>(&mut self, query: &str) -> RedisGraphResult<T> {
+        self.query_with_statistics(query)
+            .await
+            .map(|(value, _)| value)
+    }
+
+    /// Same as [`query`](#method.query), but also returns statistics about the query along with its return values.
+    pub async fn query_with_statistics<T: FromTable>(
+        &mut self,
+        query: &str,
+    ) -> RedisGraphResult<(T, Statistics)> {
+        let result_set = self.query_result_set(query).await?;
+        let value = T::from_table(&result_set)?;
+        Ok((value, result_set.statistics))
+    }
+
+    /// Same as [`query`](#method.query), but passes the given parameters to the query.
+    ///
+    /// Parameters are referenced in the query as `$name`.
+    pub async fn query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.query(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Same as [`query`](#method.query), but sends the query as a read-only query,
+    /// see [`Graph::ro_query`](../graph/struct.Graph.html#method.ro_query).
+    pub async fn ro_query<T: FromTable>(&mut self, query: &str) -> RedisGraphResult<T> {
+        let result_set = self.ro_query_result_set(query).await?;
+        T::from_table(&result_set)
+    }
+
+    /// Same as [`ro_query`](#method.ro_query), but passes the given parameters to the query.
+    pub async fn ro_query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.ro_query(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Executes the given query and returns its result set without converting it to a Rust type.
+    pub async fn query_result_set(&mut self, query: &str) -> RedisGraphResult<ResultSet> {
+        let response = self.request(query).await?;
+        self.get_result_set(response).await
+    }
+
+    /// Same as [`query_result_set`](#method.query_result_set), but sends the query as a
+    /// [read-only query](#method.ro_query).
+    pub async fn ro_query_result_set(&mut self, query: &str) -> RedisGraphResult<ResultSet> {
+        let command = self.commands.ro_query.clone();
+        let response = self.request_with(&command, query).await?;
+        self.get_result_set(response).await
+    }
+
+    /// Executes the given query while not returning any values.
+    ///
+    /// If you want to mutate the graph and retrieve values from it
+    /// using one query, use [`query`](#method.query) instead.
+    pub async fn mutate(&mut self, query: &str) -> RedisGraphResult<()> {
+        self.mutate_with_statistics(query).await.map(|_| ())
+    }
+
+    /// Same as [`mutate`](#method.mutate), but returns statistics about the query.
+    pub async fn mutate_with_statistics(&mut self, query: &str) -> RedisGraphResult<Statistics> {
+        self.query_result_set(query)
+            .await
+            .map(|result_set| result_set.statistics)
+    }
+
+    /// Same as [`mutate`](#method.mutate), but passes the given parameters to the query.
+    pub async fn mutate_with_params(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        self.mutate(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Deletes the entire graph from the database.
+    ///
+    /// *This action is not easily reversible.*
+    pub async fn delete(mut self) -> RedisGraphResult<()> {
+        redis::cmd(&self.commands.delete)
+            .arg(&self.name)
+            .query_async(&mut self.conn)
+            .await
+            .map_err(RedisGraphError::from)
+    }
+
+    /// Updates the internal label names by retrieving them from the database.
+    ///
+    /// There is no real need to call this function manually. This implementation
+    /// updates the label names automatically when they become outdated.
+    pub async fn update_labels(&mut self) -> RedisGraphResult<()> {
+        let refresh_response = self.request("CALL db.labels()").await?;
+        self.labels = parse_mapping(refresh_response, self)?;
+        Ok(())
+    }
+
+    /// Updates the internal relationship type names by retrieving them from the database.
+    ///
+    /// There is no real need to call this function manually. This implementation
+    /// updates the relationship type names automatically when they become outdated.
+    pub async fn update_relationship_types(&mut self) -> RedisGraphResult<()> {
+        let refresh_response = self.request("CALL db.relationshipTypes()").await?;
+        self.relationship_types = parse_mapping(refresh_response, self)?;
+        Ok(())
+    }
+
+    /// Updates the internal property key names by retrieving them from the database.
+    ///
+    /// There is no real need to call this function manually. This implementation
+    /// updates the property key names automatically when they become outdated.
+    pub async fn update_property_keys(&mut self) -> RedisGraphResult<()> {
+        let refresh_response = self.request("CALL db.propertyKeys()").await?;
+        self.property_keys = parse_mapping(refresh_response, self)?;
+        Ok(())
+    }
+
+    /// Enables or disables reply diagnostics,
+    /// see [`Graph::set_reply_diagnostics`](../graph/struct.Graph.html#method.set_reply_diagnostics).
+    pub fn set_reply_diagnostics(&mut self, enabled: bool) {
+        self.reply_diagnostics = enabled;
+    }
+
+    /// Enables or disables decoding of booleans returned as the integers `0` and `1`,
+    /// see [`Graph::set_lenient_booleans`](../graph/struct.Graph.html#method.set_lenient_booleans).
+    pub fn set_lenient_booleans(&mut self, enabled: bool) {
+        self.lenient_booleans = enabled;
+    }
+
+    /// Enables or disables checking parameters against the query before sending it,
+    /// see [`Graph::set_param_validation`](../graph/struct.Graph.html#method.set_param_validation).
+    pub fn set_param_validation(&mut self, enabled: bool) {
+        self.validate_params = enabled;
+    }
+
+    /// Returns `true` if parameters are checked against the query before sending it.
+    pub fn param_validation(&self) -> bool {
+        self.validate_params
+    }
+
+    /// Decodes responses larger than the given number of bytes with `tokio::task::spawn_blocking`,
+    /// or every response on the calling task if `None`, which is the default.
+    ///
+    /// Decoding a response of hundreds of megabytes takes long enough to stall the other tasks
+    /// on the same thread of the runtime. The size is estimated from the strings in the response
+    /// before decoding it. Requires the `tokio-comp` feature.
+    #[cfg(feature = "tokio-comp")]
+    pub fn set_blocking_decode_threshold(&mut self, threshold: Option<usize>) {
+        self.blocking_decode_threshold = threshold;
+    }
+
+    /// Returns the size above which responses are decoded on a blocking thread, if any.
+    #[cfg(feature = "tokio-comp")]
+    pub fn blocking_decode_threshold(&self) -> Option<usize> {
+        self.blocking_decode_threshold
+    }
+
+    /// Returns the name of this graph.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the command names used by this graph.
+    pub fn command_names(&self) -> &CommandNames {
+        &self.commands
+    }
+
+    /// Replaces the underlying connection, keeping the cached names.
+    #[cfg(feature = "tokio-comp")]
+    pub(crate) fn set_connection(&mut self, conn: MultiplexedConnection) {
+        self.conn = conn;
+    }
+
+    /// Returns the underlying connection, for helpers that issue plain Redis commands.
+    pub(crate) fn connection(&mut self) -> &mut MultiplexedConnection {
+        &mut self.conn
+    }
+
+    async fn request(&mut self, query: &str) -> RedisGraphResult<Value> {
+        let command = self.commands.query.clone();
+        self.request_with(&command, query).await
+    }
+
+    async fn request_with(&mut self, command: &str, query: &str) -> RedisGraphResult<Value> {
+        redis::cmd(command)
+            .arg(&self.name)
+            .arg(query)
+            .arg("--compact")
+            .query_async(&mut self.conn)
+            .await
+            .map_err(RedisGraphError::from)
+    }
+
+    async fn get_result_set(&mut self, mut response: Value) -> RedisGraphResult<ResultSet> {
+        loop {
+            let (returned, result) = self.decode(response).await?;
+            response = returned;
+            match result {
+                Err(RedisGraphError::LabelNotFound) => self.update_labels().await?,
+                Err(RedisGraphError::RelationshipTypeNotFound) => {
+                    self.update_relationship_types().await?
+                }
+                Err(RedisGraphError::PropertyKeyNotFound) => self.update_property_keys().await?,
+                result => return result,
+            }
+        }
+    }
+
+    // Decodes a response, handing it back along with the result so it can be decoded again
+    // after updating the names.
+    async fn decode(
+        &self,
+        response: Value,
+    ) -> RedisGraphResult<(Value, RedisGraphResult<ResultSet>)> {
+        #[cfg(feature = "tokio-comp")]
+        {
+            if let Some(threshold) = self.blocking_decode_threshold {
+                if exceeds_size(&response, threshold) {
+                    return decode_blocking(response, OwnedContext::new(self)).await;
+                }
+            }
+        }
+        let result = ResultSet::from_redis_value_with_graph(response.clone(), self);
+        Ok((response, result))
+    }
+}
+
+impl DecodeContext for AsyncGraph {
+    fn labels(&self) -> &[RedisString] {
+        &self.labels
+    }
+
+    fn relationship_types(&self) -> &[RedisString] {
+        &self.relationship_types
+    }
+
+    fn property_keys(&self) -> &[RedisString] {
+        &self.property_keys
+    }
+
+    fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans
+    }
+
+    fn reply_diagnostics(&self) -> bool {
+        self.reply_diagnostics
+    }
+}
+
+// A copy of the names and options of a graph, so a response can be decoded on another thread.
+#[cfg(feature = "tokio-comp")]
+pub(crate) struct OwnedContext {
+    labels: Vec<RedisString>,
+    relationship_types: Vec<RedisString>,
+    property_keys: Vec<RedisString>,
+    lenient_booleans: bool,
+    reply_diagnostics: bool,
+}
+
+#[cfg(feature = "tokio-comp")]
+impl OwnedContext {
+    pub(crate) fn new(context: &dyn DecodeContext) -> Self {
+        Self {
+            labels: context.labels().to_vec(),
+            relationship_types: context.relationship_types().to_vec(),
+            property_keys: context.property_keys().to_vec(),
+            lenient_booleans: context.lenient_booleans(),
+            reply_diagnostics: context.reply_diagnostics(),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-comp")]
+impl DecodeContext for OwnedContext {
+    fn labels(&self) -> &[RedisString] {
+        &self.labels
+    }
+
+    fn relationship_types(&self) -> &[RedisString] {
+        &self.relationship_types
+    }
+
+    fn property_keys(&self) -> &[RedisString] {
+        &self.property_keys
+    }
+
+    fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans
+    }
+
+    fn reply_diagnostics(&self) -> bool {
+        self.reply_diagnostics
+    }
+}
+
+// Returns `true` if the response is larger than the given number of bytes, counting the length
+// of its strings and 8 bytes for every value. Stops counting as soon as it is.
+#[cfg(feature = "tokio-comp")]
+pub(crate) fn exceeds_size(response: &Value, limit: usize) -> bool {
+    fn exceeds(value: &Value, remaining: &mut usize) -> bool {
+        let size = 8 + match value {
+            Value::Data(data) => data.len(),
+            Value::Status(status) => status.len(),
+            _ => 0,
+        };
+        if size > *remaining {
+            return true;
+        }
+        *remaining -= size;
+        match value {
+            Value::Bulk(values) => values.iter().any(|value| exceeds(value, remaining)),
+            _ => false,
+        }
+    }
+
+    exceeds(response, &mut { limit })
+}
+
+// Decodes a response with `spawn_blocking`. The response is handed back along with the result,
+// so it can be decoded again after updating the names.
+#[cfg(feature = "tokio-comp")]
+pub(crate) async fn decode_blocking(
+    response: Value,
+    context: OwnedContext,
+) -> RedisGraphResult<(Value, RedisGraphResult<ResultSet>)> {
+    let decoding = tokio::task::spawn_blocking(move || {
+        let result = ResultSet::from_redis_value_with_graph(response.clone(), &context);
+        (response, result)
+    });
+    match decoding.await {
+        Ok(decoded) => Ok(decoded),
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        // The runtime is shutting down.
+        Err(_) => Err(redis::RedisError::from((
+            redis::ErrorKind::IoError,
+            "decoding the response was cancelled",
+        ))
+        .into()),
+    }
+}
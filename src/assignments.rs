@@ -1,3 +1,7 @@
+use std::array;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
 use crate::{client_type_error, RedisGraphResult, ResultSet};
 
 /// Implemented by types that can be constructed from a [`ResultSet`](../result_set/struct.ResultSet.html).
@@ -38,6 +42,57 @@ impl<T: FromRow> FromTable for Vec<T> {
     }
 }
 
+/// Builds a lookup table from a result set with two columns,
+/// using the first column as keys and the second one as values.
+///
+/// If a key occurs multiple times, the value from the last row wins.
+impl<K: FromCell + Eq + Hash, V: FromCell> FromTable for HashMap<K, V> {
+    fn from_table(result_set: &ResultSet) -> RedisGraphResult<Self> {
+        check_key_value_columns(result_set)?;
+        let num_rows = result_set.num_rows();
+        let mut ret = Self::with_capacity(num_rows);
+
+        for i in 0..num_rows {
+            ret.insert(
+                K::from_cell(result_set, i, 0)?,
+                V::from_cell(result_set, i, 1)?,
+            );
+        }
+
+        Ok(ret)
+    }
+}
+
+/// Builds a lookup table from a result set with two columns,
+/// using the first column as keys and the second one as values.
+///
+/// If a key occurs multiple times, the value from the last row wins.
+impl<K: FromCell + Ord, V: FromCell> FromTable for BTreeMap<K, V> {
+    fn from_table(result_set: &ResultSet) -> RedisGraphResult<Self> {
+        check_key_value_columns(result_set)?;
+        let mut ret = Self::new();
+
+        for i in 0..result_set.num_rows() {
+            ret.insert(
+                K::from_cell(result_set, i, 0)?,
+                V::from_cell(result_set, i, 1)?,
+            );
+        }
+
+        Ok(ret)
+    }
+}
+
+fn check_key_value_columns(result_set: &ResultSet) -> RedisGraphResult<()> {
+    if result_set.num_columns() != 2 {
+        return client_type_error!(
+            "failed to construct map: expected 2 columns (key and value) but result table has {:?} columns",
+            result_set.num_columns()
+        );
+    }
+    Ok(())
+}
+
 // Altered version of https://github.com/mitsuhiko/redis-rs/blob/master/src/types.rs#L1080
 macro_rules! impl_row_for_tuple {
     () => ();
@@ -78,6 +133,34 @@ macro_rules! impl_row_for_tuple_peel {
 
 impl_row_for_tuple! { T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, }
 
+/// Reads a row with exactly `N` columns of the same type without allocating,
+/// e.g. an embedding returned as columns. Tables of such rows are read into a `Vec<[T; N]>`.
+impl<T: FromCell, const N: usize> FromRow for [T; N] {
+    fn from_row(result_set: &ResultSet, row_idx: usize) -> RedisGraphResult<Self> {
+        if result_set.num_columns() != N {
+            return client_type_error!(
+                "failed to construct array: array has {:?} entries but result table has {:?} columns",
+                N,
+                result_set.num_columns()
+            );
+        }
+
+        let mut error = None;
+        let row: [Option<T>; N] = array::from_fn(|column_idx| {
+            if error.is_some() {
+                return None;
+            }
+            T::from_cell(result_set, row_idx, column_idx)
+                .map_err(|e| error = Some(e))
+                .ok()
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(row.map(|cell| cell.unwrap())),
+        }
+    }
+}
+
 // Row and column indices default to zero for lower-level values
 impl<T: FromCell> FromRow for T {
     fn from_row(result_set: &ResultSet, row_idx: usize) -> RedisGraphResult<Self> {
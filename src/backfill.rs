@@ -0,0 +1,95 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use redis::ConnectionLike;
+
+use crate::{Graph, RedisGraphResult};
+
+/// Progress of a running [`Graph::backfill`](../graph/struct.Graph.html#method.backfill),
+/// reported after every batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackfillProgress {
+    /// The number of batches executed so far, including the last one.
+    pub batches: usize,
+    /// The number of properties set by the last batch.
+    pub batch_properties_set: usize,
+    /// The number of properties set so far.
+    pub properties_set: usize,
+    /// The time since the backfill started.
+    pub elapsed: Duration,
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Applies an update to all nodes matched by `match_clause` in batches of `batch_size`,
+    /// sleeping for `sleep` between batches to limit the load on the database.
+    ///
+    /// Every batch executes `{match_clause} WITH n LIMIT {batch_size} SET {set_clause}`,
+    /// so the match clause must bind the nodes to update to `n`. Batches are executed until
+    /// one sets no properties. **The match clause has to exclude nodes that were already updated**,
+    /// e.g. with `WHERE n.new_property IS NULL`, otherwise the backfill never finishes.
+    ///
+    /// Returns the progress after the last batch.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use redisgraph::{Graph, RedisGraphResult};
+    /// # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+    /// graph.backfill(
+    ///     "MATCH (n:Person) WHERE n.name_lower IS NULL",
+    ///     "n.name_lower = toLower(n.name)",
+    ///     1000,
+    ///     Duration::from_millis(50),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn backfill(
+        &mut self,
+        match_clause: &str,
+        set_clause: &str,
+        batch_size: usize,
+        sleep: Duration,
+    ) -> RedisGraphResult<BackfillProgress> {
+        self.backfill_with_progress(match_clause, set_clause, batch_size, sleep, |_| {})
+    }
+
+    /// Same as [`backfill`](#method.backfill), but calls `on_progress` after every batch.
+    pub fn backfill_with_progress<F>(
+        &mut self,
+        match_clause: &str,
+        set_clause: &str,
+        batch_size: usize,
+        sleep: Duration,
+        mut on_progress: F,
+    ) -> RedisGraphResult<BackfillProgress>
+    where
+        F: FnMut(&BackfillProgress),
+    {
+        let query = format!(
+            "{} WITH n LIMIT {} SET {}",
+            match_clause, batch_size, set_clause
+        );
+        let start = Instant::now();
+        let mut progress = BackfillProgress {
+            batches: 0,
+            batch_properties_set: 0,
+            properties_set: 0,
+            elapsed: Duration::from_secs(0),
+        };
+
+        loop {
+            let statistics = self.mutate_with_statistics(&query)?;
+            let properties_set = statistics.count("Properties set");
+            progress.batches += 1;
+            progress.batch_properties_set = properties_set;
+            progress.properties_set += properties_set;
+            progress.elapsed = start.elapsed();
+            on_progress(&progress);
+
+            if properties_set == 0 {
+                return Ok(progress);
+            }
+            thread::sleep(sleep);
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! A load generator for capacity planning against a RedisGraph deployment.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use redis::Client;
+//! use redisgraph::{bench::LoadGenerator, Graph};
+//!
+//! let client = Client::open("redis://127.0.0.1").unwrap();
+//! let report = LoadGenerator::new(move || {
+//!     Graph::open(client.get_connection()?, "load_test".to_string())
+//! })
+//! .duration(Duration::from_secs(10))
+//! .writers(4, "CREATE (:Item { worker: {worker}, n: {iteration} })")
+//! .readers(8, "MATCH (i:Item { worker: {worker} }) RETURN count(i)")
+//! .run()
+//! .unwrap();
+//!
+//! for operation in &report.operations {
+//!     println!("{}: {:?}", operation.template, operation.latency);
+//! }
+//! ```
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Graph, RedisGraphResult, ResultSet};
+
+/// Whether the queries of an operation are executed as mutations or read queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationKind {
+    /// Executed with [`Graph::mutate`](../graph/struct.Graph.html#method.mutate).
+    Write,
+    /// Executed with [`Graph::query`](../graph/struct.Graph.html#method.query).
+    Read,
+}
+
+/// Runs queries from several threads for a fixed duration and measures their latencies.
+///
+/// Query templates may contain the placeholders `{worker}`, which is replaced with the index
+/// of the thread executing the query among the threads of its operation, and `{iteration}`,
+/// which is replaced with the number of queries that thread has executed before.
+pub struct LoadGenerator<C> {
+    connect: C,
+    duration: Duration,
+    operations: Vec<(OperationKind, usize, String)>,
+}
+
+impl<C> LoadGenerator<C>
+where
+    C: Fn() -> RedisGraphResult<Graph> + Sync,
+{
+    /// Creates a load generator that opens a graph for every thread with the given function.
+    ///
+    /// Runs for 10 seconds by default.
+    pub fn new(connect: C) -> Self {
+        Self {
+            connect,
+            duration: Duration::from_secs(10),
+            operations: Vec::new(),
+        }
+    }
+
+    /// Sets how long the queries are executed.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Adds an operation executing the given mutation from `threads` threads.
+    pub fn writers(mut self, threads: usize, template: &str) -> Self {
+        self.operations
+            .push((OperationKind::Write, threads, template.to_string()));
+        self
+    }
+
+    /// Adds an operation executing the given read query from `threads` threads.
+    pub fn readers(mut self, threads: usize, template: &str) -> Self {
+        self.operations
+            .push((OperationKind::Read, threads, template.to_string()));
+        self
+    }
+
+    /// Runs all operations at the same time and waits until they are done.
+    ///
+    /// Fails if a graph couldn't be opened for one of the threads.
+    /// Failed queries are counted in the report instead.
+    pub fn run(self) -> RedisGraphResult<LoadReport> {
+        // Open all graphs up front, so connecting doesn't count towards the duration.
+        let mut workers = Vec::new();
+        for (operation_idx, (kind, threads, template)) in self.operations.iter().enumerate() {
+            for worker in 0..*threads {
+                let graph = (self.connect)()?;
+                workers.push((operation_idx, *kind, worker, template.as_str(), graph));
+            }
+        }
+
+        let deadline = Instant::now() + self.duration;
+        let results: Vec<(usize, WorkerResult)> = thread::scope(|scope| {
+            let handles: Vec<_> = workers
+                .into_iter()
+                .map(|(operation_idx, kind, worker, template, mut graph)| {
+                    scope.spawn(move || {
+                        let result = run_worker(&mut graph, kind, worker, template, deadline);
+                        (operation_idx, result)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("load generator thread panicked"))
+                .collect()
+        });
+
+        let operations = self
+            .operations
+            .iter()
+            .enumerate()
+            .map(|(operation_idx, (kind, threads, template))| {
+                let mut latencies = Vec::new();
+                let mut errors = 0;
+                for (_, result) in results.iter().filter(|(idx, _)| *idx == operation_idx) {
+                    latencies.extend_from_slice(&result.latencies);
+                    errors += result.errors;
+                }
+                OperationReport {
+                    kind: *kind,
+                    template: template.clone(),
+                    threads: *threads,
+                    errors,
+                    throughput: latencies.len() as f64 / self.duration.as_secs_f64(),
+                    latency: LatencyStats::from_durations(&latencies),
+                }
+            })
+            .collect();
+
+        Ok(LoadReport {
+            duration: self.duration,
+            operations,
+        })
+    }
+}
+
+struct WorkerResult {
+    latencies: Vec<Duration>,
+    errors: usize,
+}
+
+fn run_worker(
+    graph: &mut Graph,
+    kind: OperationKind,
+    worker: usize,
+    template: &str,
+    deadline: Instant,
+) -> WorkerResult {
+    let template = template.replace("{worker}", &worker.to_string());
+    let mut result = WorkerResult {
+        latencies: Vec::new(),
+        errors: 0,
+    };
+
+    let mut iteration = 0;
+    while Instant::now() < deadline {
+        let query = template.replace("{iteration}", &iteration.to_string());
+        let start = Instant::now();
+        let outcome = match kind {
+            OperationKind::Write => graph.mutate(&query),
+            OperationKind::Read => graph.query::<ResultSet>(&query).map(|_| ()),
+        };
+        match outcome {
+            Ok(()) => result.latencies.push(start.elapsed()),
+            Err(_) => result.errors += 1,
+        }
+        iteration += 1;
+    }
+
+    result
+}
+
+/// The results of a [`LoadGenerator`](struct.LoadGenerator.html) run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadReport {
+    /// How long the queries were executed.
+    pub duration: Duration,
+    /// The results of every operation, in the order they were added.
+    pub operations: Vec<OperationReport>,
+}
+
+/// The results of a single operation of a load generator run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationReport {
+    /// Whether the queries were executed as mutations or read queries.
+    pub kind: OperationKind,
+    /// The query template of the operation.
+    pub template: String,
+    /// The number of threads executing the operation.
+    pub threads: usize,
+    /// The number of failed queries.
+    pub errors: usize,
+    /// The number of successful queries per second.
+    pub throughput: f64,
+    /// The latencies of the successful queries, or `None` if there were none.
+    pub latency: Option<LatencyStats>,
+}
+
+/// Latency percentiles of a set of queries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyStats {
+    /// The number of queries.
+    pub count: usize,
+    /// The fastest query.
+    pub min: Duration,
+    /// The arithmetic mean of all queries.
+    pub mean: Duration,
+    /// The 50th percentile.
+    pub p50: Duration,
+    /// The 90th percentile.
+    pub p90: Duration,
+    /// The 99th percentile.
+    pub p99: Duration,
+    /// The 99.9th percentile.
+    pub p999: Duration,
+    /// The slowest query.
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Computes percentiles from the given latencies using the nearest-rank method.
+    ///
+    /// Returns `None` if `latencies` is empty.
+    pub fn from_durations(latencies: &[Duration]) -> Option<Self> {
+        if latencies.is_empty() {
+            return None;
+        }
+
+        let mut sorted = latencies.to_vec();
+        sorted.sort();
+
+        let len = sorted.len();
+        let percentile = |p: f64| sorted[((p * len as f64).ceil() as usize).clamp(1, len) - 1];
+        let total: Duration = sorted.iter().sum();
+
+        Some(Self {
+            count: len,
+            min: sorted[0],
+            mean: total / len as u32,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            p999: percentile(0.999),
+            max: sorted[len - 1],
+        })
+    }
+}
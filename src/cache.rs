@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use redis::{Connection, ConnectionLike, Value};
+
+use crate::{assignments::FromTable, params::CypherValue, Graph, RedisGraphResult};
+
+/// The key prefix used by [`RedisCache::new`](struct.RedisCache.html#method.new).
+pub const DEFAULT_CACHE_PREFIX: &str = "redisgraph-cache:";
+
+/// Storage for query responses cached by [`Graph::query_cached`](../graph/struct.Graph.html#method.query_cached).
+pub trait QueryCacheBackend {
+    /// Returns the value stored under the given key, or `None` if there is none or it expired.
+    fn get(&mut self, key: &str) -> RedisGraphResult<Option<Vec<u8>>>;
+
+    /// Stores a value under the given key for the given time.
+    fn put(&mut self, key: &str, value: &[u8], ttl: Duration) -> RedisGraphResult<()>;
+}
+
+/// A [`QueryCacheBackend`](trait.QueryCacheBackend.html) storing values as Redis strings that expire on their own.
+///
+/// It needs its own connection, which can go to the same Redis server as the graph.
+pub struct RedisCache {
+    conn: Connection,
+    prefix: String,
+}
+
+impl RedisCache {
+    /// Creates a cache with keys prefixed with [`DEFAULT_CACHE_PREFIX`](constant.DEFAULT_CACHE_PREFIX.html).
+    pub fn new(conn: Connection) -> Self {
+        Self::with_prefix(conn, DEFAULT_CACHE_PREFIX)
+    }
+
+    /// Creates a cache with keys prefixed with the given prefix.
+    pub fn with_prefix(conn: Connection, prefix: &str) -> Self {
+        Self {
+            conn,
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
+impl QueryCacheBackend for RedisCache {
+    fn get(&mut self, key: &str) -> RedisGraphResult<Option<Vec<u8>>> {
+        let value = redis::cmd("GET")
+            .arg(format!("{}{}", self.prefix, key))
+            .query(&mut self.conn)?;
+        Ok(value)
+    }
+
+    fn put(&mut self, key: &str, value: &[u8], ttl: Duration) -> RedisGraphResult<()> {
+        redis::cmd("SET")
+            .arg(format!("{}{}", self.prefix, key))
+            .arg(value)
+            .arg("PX")
+            .arg((ttl.as_millis() as u64).max(1))
+            .query::<()>(&mut self.conn)?;
+        Ok(())
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Same as [`query`](#method.query), but reads the response from the cache if it is there
+    /// and stores it in the cache for `ttl` otherwise.
+    ///
+    /// Responses are cached under the graph name and query text, and are not invalidated
+    /// by mutations, so only cache queries that may return stale results.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use redisgraph::{cache::RedisCache, Graph, RedisGraphResult};
+    /// # fn example(graph: &mut Graph, connection: redis::Connection) -> RedisGraphResult<()> {
+    /// let mut cache = RedisCache::new(connection);
+    /// let average_age: f64 = graph.query_cached(
+    ///     &mut cache,
+    ///     "MATCH (p:Person) RETURN avg(p.age)",
+    ///     Duration::from_secs(60),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_cached<T: FromTable, B: QueryCacheBackend>(
+        &mut self,
+        cache: &mut B,
+        query: &str,
+        ttl: Duration,
+    ) -> RedisGraphResult<T> {
+        let key = format!("{}:{}", self.name(), query);
+        let response = match cache.get(&key)? {
+            Some(bytes) => redis::parse_redis_value(&bytes)?,
+            None => {
+                let response = self.request(query)?;
+                let mut bytes = Vec::new();
+                encode(&response, &mut bytes);
+                cache.put(&key, &bytes, ttl)?;
+                response
+            }
+        };
+        let result_set = self.get_result_set(response)?;
+        T::from_table(&result_set)
+    }
+
+    /// Same as [`query_cached`](#method.query_cached), but with parameters.
+    pub fn query_cached_with_params<T: FromTable, B: QueryCacheBackend>(
+        &mut self,
+        cache: &mut B,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+        ttl: Duration,
+    ) -> RedisGraphResult<T> {
+        self.query_cached(cache, &self.prepare_query(query, params)?, ttl)
+    }
+}
+
+// Encodes a response in the Redis protocol, so it can be decoded with `redis::parse_redis_value`.
+fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Nil => out.extend_from_slice(b"$-1\r\n"),
+        Value::Int(int) => out.extend_from_slice(format!(":{}\r\n", int).as_bytes()),
+        Value::Data(data) => {
+            out.extend_from_slice(format!("${}\r\n", data.len()).as_bytes());
+            out.extend_from_slice(data);
+            out.extend_from_slice(b"\r\n");
+        }
+        Value::Bulk(values) => {
+            out.extend_from_slice(format!("*{}\r\n", values.len()).as_bytes());
+            for value in values {
+                encode(value, out);
+            }
+        }
+        Value::Status(status) => out.extend_from_slice(format!("+{}\r\n", status).as_bytes()),
+        Value::Okay => out.extend_from_slice(b"+OK\r\n"),
+    }
+}
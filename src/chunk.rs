@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+
+use redis::ConnectionLike;
+
+use crate::{
+    params::CypherValue,
+    result_set::Statistics,
+    retry::{is_ambiguous, is_transient},
+    Graph, RedisGraphError, RedisGraphResult,
+};
+
+/// Limits for the chunks created by [`Graph::mutate_chunked`](../graph/struct.Graph.html#method.mutate_chunked)
+/// and [`Graph::mutate_rows_chunked`](../graph/struct.Graph.html#method.mutate_rows_chunked).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkOptions {
+    /// The maximum size of a chunk's query text in bytes.
+    ///
+    /// An entity that is larger on its own is still sent, as a chunk by itself.
+    pub max_bytes: usize,
+    /// The maximum number of entities (patterns or rows) in a chunk.
+    ///
+    /// Patterns that share a variable are never split, so a group of them that is larger
+    /// on its own is still sent, as a chunk by itself.
+    pub max_entities: usize,
+    /// How often a chunk is resent if it was rejected with a
+    /// [transient](../retry/fn.is_transient.html) error before giving up on it.
+    ///
+    /// Other errors are not retried: the chunk either can never succeed, or, if the
+    /// connection broke, may already have been applied, see [`ChunkOutcome::is_ambiguous`](struct.ChunkOutcome.html#method.is_ambiguous).
+    pub retries: usize,
+}
+
+impl Default for ChunkOptions {
+    /// 256 KiB and 1000 entities per chunk, with 2 retries.
+    fn default() -> Self {
+        Self {
+            max_bytes: 256 * 1024,
+            max_entities: 1000,
+            retries: 2,
+        }
+    }
+}
+
+/// The result of executing a single chunk.
+#[derive(Debug)]
+pub struct ChunkOutcome {
+    /// The number of entities (patterns or rows) in the chunk.
+    pub entities: usize,
+    /// How often the chunk was sent.
+    pub attempts: usize,
+    /// The statistics of the chunk, or the error of its last attempt.
+    pub result: RedisGraphResult<Statistics>,
+}
+
+impl ChunkOutcome {
+    /// Returns `true` if the chunk failed [ambiguously](../retry/fn.is_ambiguous.html),
+    /// so it may or may not have been applied. Check the graph before sending it again.
+    pub fn is_ambiguous(&self) -> bool {
+        match &self.result {
+            Err(error) => is_ambiguous(error),
+            Ok(_) => false,
+        }
+    }
+}
+
+/// The results of a chunked mutation, one per chunk in the order they were executed.
+#[derive(Debug)]
+pub struct ChunkReport {
+    /// The outcome of every chunk.
+    pub chunks: Vec<ChunkOutcome>,
+}
+
+impl ChunkReport {
+    /// Returns `true` if every chunk succeeded.
+    pub fn is_success(&self) -> bool {
+        self.chunks.iter().all(|chunk| chunk.result.is_ok())
+    }
+
+    /// Returns the failed chunks.
+    pub fn failures(&self) -> impl Iterator<Item = &ChunkOutcome> {
+        self.chunks.iter().filter(|chunk| chunk.result.is_err())
+    }
+
+    /// Returns the chunks whose outcome is unknown, because they failed
+    /// [ambiguously](struct.ChunkOutcome.html#method.is_ambiguous).
+    pub fn ambiguous(&self) -> impl Iterator<Item = &ChunkOutcome> {
+        self.chunks.iter().filter(|chunk| chunk.is_ambiguous())
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Splits a large `CREATE` statement with [`split_create`](../chunk/fn.split_create.html)
+    /// and executes the chunks one after another.
+    ///
+    /// Every chunk is retried independently as configured in [`ChunkOptions::retries`](../chunk/struct.ChunkOptions.html#structfield.retries);
+    /// a chunk that fails doesn't stop the others.
+    /// Fails without executing anything if the statement can't be split.
+    pub fn mutate_chunked(
+        &mut self,
+        statement: &str,
+        options: &ChunkOptions,
+    ) -> RedisGraphResult<ChunkReport> {
+        let chunks = split_create(statement, options)?;
+        let chunks = chunks
+            .into_iter()
+            .map(|(query, entities)| {
+                let (attempts, result) =
+                    retry(options.retries, || self.mutate_with_statistics(&query));
+                ChunkOutcome {
+                    entities,
+                    attempts,
+                    result,
+                }
+            })
+            .collect();
+        Ok(ChunkReport { chunks })
+    }
+
+    /// Executes a query like `UNWIND $rows AS row CREATE ...` for the given rows,
+    /// splitting them into chunks that are passed as the parameter `param` one after another.
+    ///
+    /// Every chunk is retried independently as configured in [`ChunkOptions::retries`](../chunk/struct.ChunkOptions.html#structfield.retries);
+    /// a chunk that fails doesn't stop the others.
+    pub fn mutate_rows_chunked(
+        &mut self,
+        query: &str,
+        param: &str,
+        rows: Vec<CypherValue>,
+        options: &ChunkOptions,
+    ) -> RedisGraphResult<ChunkReport> {
+        let mut chunks = Vec::new();
+        for rows in split_rows(query.len(), rows, options) {
+            let entities = rows.len();
+            let mut params = HashMap::new();
+            params.insert(param.to_string(), CypherValue::Array(rows));
+            let (attempts, result) = retry(options.retries, || {
+                let query = self.prepare_query(query, &params)?;
+                self.mutate_with_statistics(&query)
+            });
+            chunks.push(ChunkOutcome {
+                entities,
+                attempts,
+                result,
+            });
+        }
+        Ok(ChunkReport { chunks })
+    }
+}
+
+fn retry<T>(
+    retries: usize,
+    mut f: impl FnMut() -> RedisGraphResult<T>,
+) -> (usize, RedisGraphResult<T>) {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match f() {
+            Err(error) if attempts <= retries && is_transient(&error) => continue,
+            result => return (attempts, result),
+        }
+    }
+}
+
+fn split_rows(
+    query_len: usize,
+    rows: Vec<CypherValue>,
+    options: &ChunkOptions,
+) -> Vec<Vec<CypherValue>> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes = query_len;
+    for row in rows {
+        // The row's literal plus a separator in the inlined parameter array.
+        let row_bytes = row.to_string().len() + 2;
+        if !current.is_empty()
+            && (current.len() >= options.max_entities
+                || current_bytes + row_bytes > options.max_bytes)
+        {
+            chunks.push(std::mem::take(&mut current));
+            current_bytes = query_len;
+        }
+        current.push(row);
+        current_bytes += row_bytes;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Splits a `CREATE` statement with many comma-separated patterns into smaller `CREATE` statements
+/// within the given limits. Returns every statement along with its number of patterns.
+///
+/// Patterns that share a variable stay in the same statement, so relationships between nodes
+/// created in the same statement keep working. Such a group is never split, even if it has more
+/// patterns than [`max_entities`](struct.ChunkOptions.html#structfield.max_entities), as in the
+/// example below.
+///
+/// Fails with [`UnsplittableStatement`](../error/enum.RedisGraphError.html#variant.UnsplittableStatement)
+/// if the statement has any clauses other than a single `CREATE`.
+///
+/// ```
+/// use redisgraph::chunk::{split_create, ChunkOptions};
+///
+/// let options = ChunkOptions { max_entities: 2, ..ChunkOptions::default() };
+/// let chunks = split_create("CREATE (a:A), (b:B), (c:C), (a)-[:R]->(c)", &options).unwrap();
+/// assert_eq!(chunks, vec![
+///     ("CREATE (a:A), (c:C), (a)-[:R]->(c)".to_string(), 3),
+///     ("CREATE (b:B)".to_string(), 1),
+/// ]);
+/// ```
+pub fn split_create(
+    statement: &str,
+    options: &ChunkOptions,
+) -> RedisGraphResult<Vec<(String, usize)>> {
+    let unsplittable = || RedisGraphError::UnsplittableStatement(statement.to_string());
+
+    let trimmed = statement.trim();
+    let body = match trimmed.get(..6) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("CREATE") => &trimmed[6..],
+        _ => return Err(unsplittable()),
+    };
+    if !body.starts_with(char::is_whitespace) {
+        return Err(unsplittable());
+    }
+
+    let patterns = split_patterns(body).ok_or_else(unsplittable)?;
+    let groups = group_by_variables(&patterns);
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut current_bytes = "CREATE".len();
+    for group in groups {
+        let group_bytes: usize = group.iter().map(|&i| patterns[i].len() + 2).sum();
+        if !current.is_empty()
+            && (current.len() + group.len() > options.max_entities
+                || current_bytes + group_bytes > options.max_bytes)
+        {
+            chunks.push((format!("CREATE {}", current.join(", ")), current.len()));
+            current.clear();
+            current_bytes = "CREATE".len();
+        }
+        current.extend(group.iter().map(|&i| patterns[i]));
+        current_bytes += group_bytes;
+    }
+    if !current.is_empty() {
+        chunks.push((format!("CREATE {}", current.join(", ")), current.len()));
+    }
+    Ok(chunks)
+}
+
+// Splits the body of a `CREATE` clause at top-level commas.
+// Returns `None` if a part is not a pattern enclosed in parentheses.
+fn split_patterns(body: &str) -> Option<Vec<&str>> {
+    let mut patterns = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    let mut chars = body.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(q) if c == '\\' && q != '`' => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    patterns.push(body[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            },
+        }
+    }
+    patterns.push(body[start..].trim());
+
+    let valid = quote.is_none()
+        && depth == 0
+        && patterns
+            .iter()
+            .all(|pattern| pattern.starts_with('(') && pattern.ends_with(')'));
+    if valid {
+        Some(patterns)
+    } else {
+        None
+    }
+}
+
+// Groups the indices of patterns that are connected through shared variables,
+// ordered by their first pattern.
+fn group_by_variables(patterns: &[&str]) -> Vec<Vec<usize>> {
+    let mut parents: Vec<usize> = (0..patterns.len()).collect();
+    let mut owners: HashMap<String, usize> = HashMap::new();
+    for (i, pattern) in patterns.iter().enumerate() {
+        for variable in pattern_variables(pattern) {
+            match owners.get(&variable) {
+                Some(&owner) => {
+                    let (a, b) = (find(&mut parents, owner), find(&mut parents, i));
+                    parents[a.max(b)] = a.min(b);
+                }
+                None => {
+                    owners.insert(variable, i);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for i in 0..patterns.len() {
+        let root = find(&mut parents, i);
+        match group_of_root.get(&root) {
+            Some(&group) => groups[group].push(i),
+            None => {
+                group_of_root.insert(root, groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    groups
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+// Returns the variables bound by the node and relationship patterns in `pattern`.
+fn pattern_variables(pattern: &str) -> Vec<String> {
+    let mut variables = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == '\\' && q != '`' => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '(' || c == '[' => {
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+                let mut variable = String::new();
+                if chars.peek() == Some(&'`') {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if c == '`' {
+                            break;
+                        }
+                        variable.push(c);
+                    }
+                } else {
+                    while let Some(&c) = chars.peek() {
+                        if c.is_alphanumeric() || c == '_' {
+                            variable.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                if !variable.is_empty() {
+                    variables.push(variable);
+                }
+            }
+            None => {}
+        }
+    }
+    variables
+}
@@ -0,0 +1,522 @@
+//! A graph handle that can be shared by the tasks of an async service.
+//!
+//! [`AsyncGraph`](../aio/struct.AsyncGraph.html) needs exclusive access for every query, so
+//! sharing one between request handlers means wrapping it in a mutex that every request waits
+//! for. A [`GraphClient`](struct.GraphClient.html) is `Send + Sync` and cheap to clone instead:
+//! its clones send their queries over the same multiplexed connection concurrently and share
+//! the cached label, relationship type and property key names.
+//!
+//! [`graph`](struct.GraphClient.html#method.graph) returns a client for another graph over the
+//! same connection, e.g. for one graph per tenant. The names are cached per graph, for all
+//! clients of that graph.
+//!
+//! [`query_coalesced`](struct.GraphClient.html#method.query_coalesced) sends identical reads
+//! that are issued concurrently by several clones only once, like
+//! [`SingleFlight`](../single_flight/struct.SingleFlight.html) does for blocking graphs.
+//!
+//! Like [`AsyncGraph`](../aio/struct.AsyncGraph.html), a client doesn't record query statistics,
+//! emit the OpenTelemetry spans of the `otel` feature or notify mutation listeners. It does
+//! [validate parameters](struct.GraphClient.html#method.set_param_validation) and reports broken
+//! connections to its [connection listeners](struct.GraphClient.html#method.on_connection_event).
+//!
+//! Requires the `aio` feature.
+//!
+//! ```no_run
+//! # use redisgraph::{client::GraphClient, RedisGraphResult};
+//! # fn spawn(_task: impl std::future::Future<Output = ()>) {}
+//! # async fn example(conn: redis::aio::MultiplexedConnection) -> RedisGraphResult<()> {
+//! let client = GraphClient::open(conn, "MotoGP".to_string()).await?;
+//! for _ in 0..8 {
+//!     let client = client.clone();
+//!     spawn(async move {
+//!         let riders: RedisGraphResult<i64> = client.query("MATCH (r:Rider) RETURN count(r)").await;
+//!     });
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::SystemTime;
+
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use redis::{aio::MultiplexedConnection, Value};
+
+#[cfg(feature = "tokio-comp")]
+use crate::aio::{decode_blocking, exceeds_size, OwnedContext};
+use crate::{
+    assignments::FromTable,
+    commands::CommandNames,
+    events::{ConnectionEvent, ConnectionListener},
+    graph::parse_mapping,
+    params::{prepare_query, CypherValue},
+    result_set::{DecodeContext, FromRedisValueWithGraph, Statistics},
+    RedisGraphError, RedisGraphResult, RedisString, ResultSet,
+};
+
+/// A cloneable graph handle for concurrent async queries, see the [module documentation](index.html).
+#[derive(Clone)]
+pub struct GraphClient {
+    conn: MultiplexedConnection,
+    name: Arc<str>,
+    commands: Arc<CommandNames>,
+    mappings: Arc<RwLock<Mappings>>,
+    // The mappings of every graph opened over this connection, by name.
+    graphs: Arc<Mutex<HashMap<String, Arc<RwLock<Mappings>>>>>,
+    // The coalesced queries that are being executed over this connection, by graph name and query.
+    in_flight: Arc<Mutex<HashMap<FlightKey, Flight>>>,
+    // The listeners of every client over this connection, and whether a failure was reported.
+    connection_listeners: Arc<RwLock<Vec<ConnectionListener>>>,
+    disconnected: Arc<AtomicBool>,
+    reply_diagnostics: bool,
+    lenient_booleans: bool,
+    validate_params: bool,
+    #[cfg(feature = "tokio-comp")]
+    blocking_decode_threshold: Option<usize>,
+}
+
+// The names the result sets of a graph refer to by their index.
+#[derive(Default)]
+struct Mappings {
+    labels: Vec<RedisString>,
+    relationship_types: Vec<RedisString>,
+    property_keys: Vec<RedisString>,
+}
+
+// A coalesced query that is being executed, which resolves to `None` if it failed.
+type Flight = Shared<BoxFuture<'static, Option<Arc<ResultSet>>>>;
+
+// The graph name and query of a coalesced query.
+type FlightKey = (Arc<str>, String);
+
+// Decodes with a snapshot of the shared mappings and the settings of one clone.
+struct Context<'a> {
+    mappings: RwLockReadGuard<'a, Mappings>,
+    reply_diagnostics: bool,
+    lenient_booleans: bool,
+}
+
+impl GraphClient {
+    /// Opens the graph with the given name from the database.
+    ///
+    /// If the graph does not already exist, creates a new graph with the given name.
+    pub async fn open(conn: MultiplexedConnection, name: String) -> RedisGraphResult<Self> {
+        Self::open_with_commands(conn, name, CommandNames::default()).await
+    }
+
+    /// Opens the graph over a new multiplexed connection from the given client, driven by the
+    /// tokio runtime. Requires the `tokio-comp` feature.
+    #[cfg(feature = "tokio-comp")]
+    pub async fn connect(client: &redis::Client, name: String) -> RedisGraphResult<Self> {
+        let conn = client.get_multiplexed_tokio_connection().await?;
+        Self::open(conn, name).await
+    }
+
+    /// Same as [`open`](#method.open), but uses the given command names instead of `GRAPH.QUERY` etc.
+    pub async fn open_with_commands(
+        conn: MultiplexedConnection,
+        name: String,
+        commands: CommandNames,
+    ) -> RedisGraphResult<Self> {
+        let mappings = Arc::<RwLock<Mappings>>::default();
+        let mut graphs = HashMap::new();
+        graphs.insert(name.clone(), Arc::clone(&mappings));
+        let client = Self {
+            conn,
+            name: name.into(),
+            commands: Arc::new(commands),
+            mappings,
+            graphs: Arc::new(Mutex::new(graphs)),
+            in_flight: Arc::default(),
+            connection_listeners: Arc::default(),
+            disconnected: Arc::default(),
+            reply_diagnostics: false,
+            lenient_booleans: true,
+            validate_params: false,
+            #[cfg(feature = "tokio-comp")]
+            blocking_decode_threshold: None,
+        };
+
+        // See `Graph::open_with_commands`.
+        client.mutate("CREATE (dummy:__DUMMY_LABEL__)").await?;
+        client
+            .mutate("MATCH (dummy:__DUMMY_LABEL__) DELETE dummy")
+            .await?;
+
+        Ok(client)
+    }
+
+    /// Returns a client for the graph with the given name over the same connection, with the
+    /// same command names and settings.
+    ///
+    /// Unlike [`open`](#method.open), this doesn't send any request. The graph is created by
+    /// the first mutation if it doesn't exist yet, and its names are fetched once for all
+    /// clients of the graph.
+    pub fn graph(&self, name: &str) -> Self {
+        let mappings = Arc::clone(
+            self.graphs
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(name.to_string())
+                .or_default(),
+        );
+        Self {
+            name: name.into(),
+            mappings,
+            ..self.clone()
+        }
+    }
+
+    /// Executes the given query and returns its return values.
+    ///
+    /// Only use this for queries with a `RETURN` statement.
+    pub async fn query<T: FromTable>(&self, query: &str) -> RedisGraphResult<T> {
+        self.query_with_statistics(query)
+            .await
+            .map(|(value, _)| value)
+    }
+
+    /// Same as [`query`](#method.query), but also returns statistics about the query along with its return values.
+    pub async fn query_with_statistics<T: FromTable>(
+        &self,
+        query: &str,
+    ) -> RedisGraphResult<(T, Statistics)> {
+        let result_set = self.query_result_set(query).await?;
+        let value = T::from_table(&result_set)?;
+        Ok((value, result_set.statistics))
+    }
+
+    /// Same as [`query`](#method.query), but passes the given parameters to the query.
+    ///
+    /// Parameters are referenced in the query as `$name`.
+    pub async fn query_with_params<T: FromTable>(
+        &self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.query(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Same as [`query`](#method.query), but sends the query as a read-only query,
+    /// see [`Graph::ro_query`](../graph/struct.Graph.html#method.ro_query).
+    pub async fn ro_query<T: FromTable>(&self, query: &str) -> RedisGraphResult<T> {
+        let response = self.request_with(&self.commands.ro_query, query).await?;
+        let result_set = self.get_result_set(response).await?;
+        T::from_table(&result_set)
+    }
+
+    /// Same as [`ro_query`](#method.ro_query), but passes the given parameters to the query.
+    pub async fn ro_query_with_params<T: FromTable>(
+        &self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.ro_query(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Executes the given query and returns its result set without converting it to a Rust type.
+    pub async fn query_result_set(&self, query: &str) -> RedisGraphResult<ResultSet> {
+        let response = self.request(query).await?;
+        self.get_result_set(response).await
+    }
+
+    /// Same as [`query_result_set`](#method.query_result_set), but sends the query as a
+    /// [read-only query](#method.ro_query).
+    pub async fn ro_query_result_set(&self, query: &str) -> RedisGraphResult<ResultSet> {
+        let response = self.request_with(&self.commands.ro_query, query).await?;
+        self.get_result_set(response).await
+    }
+
+    /// Executes the given read-only query, or waits for the result of an identical query that
+    /// a clone of this client is already executing on the same graph.
+    ///
+    /// The query is sent as a [read-only query](#method.ro_query) and decoded with the settings
+    /// of the clone that sent it. If it fails, the callers that waited for it send it
+    /// themselves, so every caller receives its own error. Queries that aren't coalesced don't
+    /// wait for each other.
+    pub async fn query_coalesced(&self, query: &str) -> RedisGraphResult<Arc<ResultSet>> {
+        let key = (Arc::clone(&self.name), query.to_string());
+        let flight = {
+            let mut in_flight = self
+                .in_flight
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let client = self.clone();
+            in_flight
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    async move {
+                        let result = client.ro_query_result_set(&key.1).await;
+                        client
+                            .in_flight
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .remove(&key);
+                        result.ok().map(Arc::new)
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+        match flight.await {
+            Some(result_set) => Ok(result_set),
+            None => self.ro_query_result_set(query).await.map(Arc::new),
+        }
+    }
+
+    /// Same as [`query_coalesced`](#method.query_coalesced), but with parameters. Queries are
+    /// only coalesced if their parameters are equal as well.
+    pub async fn query_coalesced_with_params(
+        &self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<Arc<ResultSet>> {
+        self.query_coalesced(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Executes the given query while not returning any values.
+    ///
+    /// If you want to mutate the graph and retrieve values from it
+    /// using one query, use [`query`](#method.query) instead.
+    pub async fn mutate(&self, query: &str) -> RedisGraphResult<()> {
+        self.mutate_with_statistics(query).await.map(|_| ())
+    }
+
+    /// Same as [`mutate`](#method.mutate), but returns statistics about the query.
+    pub async fn mutate_with_statistics(&self, query: &str) -> RedisGraphResult<Statistics> {
+        self.query_result_set(query)
+            .await
+            .map(|result_set| result_set.statistics)
+    }
+
+    /// Same as [`mutate`](#method.mutate), but passes the given parameters to the query.
+    pub async fn mutate_with_params(
+        &self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        self.mutate(&prepare_query(query, params, self.validate_params)?)
+            .await
+    }
+
+    /// Deletes the entire graph from the database.
+    ///
+    /// *This action is not easily reversible.* The other clones of this client
+    /// still refer to the graph and recreate it with their next mutation.
+    pub async fn delete(self) -> RedisGraphResult<()> {
+        redis::cmd(&self.commands.delete)
+            .arg(&*self.name)
+            .query_async::<_, ()>(&mut self.conn.clone())
+            .await
+            .map_err(RedisGraphError::from)?;
+        // A recreated graph numbers its names anew.
+        *self.write_mappings() = Mappings::default();
+        Ok(())
+    }
+
+    /// Updates the internal label names by retrieving them from the database.
+    ///
+    /// There is no real need to call this function manually. This implementation
+    /// updates the label names automatically when they become outdated.
+    pub async fn update_labels(&self) -> RedisGraphResult<()> {
+        let labels = self.fetch_mapping("CALL db.labels()").await?;
+        self.write_mappings().labels = labels;
+        Ok(())
+    }
+
+    /// Updates the internal relationship type names by retrieving them from the database.
+    ///
+    /// There is no real need to call this function manually. This implementation
+    /// updates the relationship type names automatically when they become outdated.
+    pub async fn update_relationship_types(&self) -> RedisGraphResult<()> {
+        let relationship_types = self.fetch_mapping("CALL db.relationshipTypes()").await?;
+        self.write_mappings().relationship_types = relationship_types;
+        Ok(())
+    }
+
+    /// Updates the internal property key names by retrieving them from the database.
+    ///
+    /// There is no real need to call this function manually. This implementation
+    /// updates the property key names automatically when they become outdated.
+    pub async fn update_property_keys(&self) -> RedisGraphResult<()> {
+        let property_keys = self.fetch_mapping("CALL db.propertyKeys()").await?;
+        self.write_mappings().property_keys = property_keys;
+        Ok(())
+    }
+
+    /// Enables or disables reply diagnostics for this clone,
+    /// see [`Graph::set_reply_diagnostics`](../graph/struct.Graph.html#method.set_reply_diagnostics).
+    pub fn set_reply_diagnostics(&mut self, enabled: bool) {
+        self.reply_diagnostics = enabled;
+    }
+
+    /// Enables or disables decoding of booleans returned as the integers `0` and `1` for this clone,
+    /// see [`Graph::set_lenient_booleans`](../graph/struct.Graph.html#method.set_lenient_booleans).
+    pub fn set_lenient_booleans(&mut self, enabled: bool) {
+        self.lenient_booleans = enabled;
+    }
+
+    /// Enables or disables checking parameters against the query before sending it for this clone,
+    /// see [`Graph::set_param_validation`](../graph/struct.Graph.html#method.set_param_validation).
+    pub fn set_param_validation(&mut self, enabled: bool) {
+        self.validate_params = enabled;
+    }
+
+    /// Returns `true` if this clone checks parameters against the query before sending it.
+    pub fn param_validation(&self) -> bool {
+        self.validate_params
+    }
+
+    /// Decodes responses larger than the given number of bytes on a blocking thread for this clone,
+    /// see [`AsyncGraph::set_blocking_decode_threshold`](../aio/struct.AsyncGraph.html#method.set_blocking_decode_threshold).
+    /// Requires the `tokio-comp` feature.
+    #[cfg(feature = "tokio-comp")]
+    pub fn set_blocking_decode_threshold(&mut self, threshold: Option<usize>) {
+        self.blocking_decode_threshold = threshold;
+    }
+
+    /// Returns the size above which responses are decoded on a blocking thread, if any.
+    #[cfg(feature = "tokio-comp")]
+    pub fn blocking_decode_threshold(&self) -> Option<usize> {
+        self.blocking_decode_threshold
+    }
+
+    /// Registers a callback that is invoked when a request fails because of a broken connection.
+    ///
+    /// The listener is shared by all clients over the same connection, including the ones for
+    /// [other graphs](#method.graph). A multiplexed connection isn't replaced once it broke, so
+    /// only the first failure is reported.
+    pub fn on_connection_event<F>(&self, listener: F)
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.connection_listeners
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new(listener));
+    }
+
+    /// Returns the name of this graph.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the command names used by this graph.
+    pub fn command_names(&self) -> &CommandNames {
+        &self.commands
+    }
+
+    async fn request(&self, query: &str) -> RedisGraphResult<Value> {
+        self.request_with(&self.commands.query, query).await
+    }
+
+    async fn request_with(&self, command: &str, query: &str) -> RedisGraphResult<Value> {
+        let response = redis::cmd(command)
+            .arg(&*self.name)
+            .arg(query)
+            .arg("--compact")
+            .query_async(&mut self.conn.clone())
+            .await
+            .map_err(RedisGraphError::from);
+
+        if let Err(RedisGraphError::RedisError(error)) = &response {
+            if error.is_io_error() && !self.disconnected.swap(true, Ordering::SeqCst) {
+                let event = ConnectionEvent::Disconnected {
+                    graph_name: self.name.to_string(),
+                    error: error.to_string(),
+                    timestamp: SystemTime::now(),
+                };
+                let listeners = self
+                    .connection_listeners
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner);
+                for listener in listeners.iter() {
+                    listener(&event);
+                }
+            }
+        }
+        response
+    }
+
+    async fn fetch_mapping(&self, query: &str) -> RedisGraphResult<Vec<RedisString>> {
+        let response = self.request(query).await?;
+        parse_mapping(response, &self.context())
+    }
+
+    async fn get_result_set(&self, mut response: Value) -> RedisGraphResult<ResultSet> {
+        loop {
+            let (returned, result) = self.decode(response).await?;
+            response = returned;
+            match result {
+                Err(RedisGraphError::LabelNotFound) => self.update_labels().await?,
+                Err(RedisGraphError::RelationshipTypeNotFound) => {
+                    self.update_relationship_types().await?
+                }
+                Err(RedisGraphError::PropertyKeyNotFound) => self.update_property_keys().await?,
+                result => return result,
+            }
+        }
+    }
+
+    // Decodes a response, handing it back along with the result so it can be decoded again
+    // after updating the names.
+    async fn decode(
+        &self,
+        response: Value,
+    ) -> RedisGraphResult<(Value, RedisGraphResult<ResultSet>)> {
+        // The guards are dropped before the next await, they must not be held across one.
+        #[cfg(feature = "tokio-comp")]
+        {
+            if let Some(threshold) = self.blocking_decode_threshold {
+                if exceeds_size(&response, threshold) {
+                    let context = OwnedContext::new(&self.context());
+                    return decode_blocking(response, context).await;
+                }
+            }
+        }
+        let result = ResultSet::from_redis_value_with_graph(response.clone(), &self.context());
+        Ok((response, result))
+    }
+
+    fn context(&self) -> Context<'_> {
+        Context {
+            mappings: self.mappings.read().unwrap_or_else(PoisonError::into_inner),
+            reply_diagnostics: self.reply_diagnostics,
+            lenient_booleans: self.lenient_booleans,
+        }
+    }
+
+    fn write_mappings(&self) -> RwLockWriteGuard<'_, Mappings> {
+        self.mappings
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl DecodeContext for Context<'_> {
+    fn labels(&self) -> &[RedisString] {
+        &self.mappings.labels
+    }
+
+    fn relationship_types(&self) -> &[RedisString] {
+        &self.mappings.relationship_types
+    }
+
+    fn property_keys(&self) -> &[RedisString] {
+        &self.mappings.property_keys
+    }
+
+    fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans
+    }
+
+    fn reply_diagnostics(&self) -> bool {
+        self.reply_diagnostics
+    }
+}
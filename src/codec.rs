@@ -0,0 +1,256 @@
+//! Storing structured Rust values in string properties, requires the `json` feature.
+//!
+//! RedisGraph properties can't hold nested objects, so values like structs or vectors of structs
+//! are encoded into a string with a [`PropertyCodec`](trait.PropertyCodec.html), JSON by default.
+//! With the `encryption` feature, [`EncryptedCodec`](struct.EncryptedCodec.html) encrypts the encoded
+//! values as well, so sensitive properties are unreadable in RDB dumps.
+//!
+//! ```no_run
+//! # use redisgraph::{codec::JsonCodec, props::Props, Graph, RedisGraphResult};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Address {
+//!     street: String,
+//!     city: String,
+//! }
+//!
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! let address = Address {
+//!     street: "Main Street 1".to_string(),
+//!     city: "Springfield".to_string(),
+//! };
+//! let props = Props::new()
+//!     .set("name", "Alice")
+//!     .set_encoded("address", &address, &JsonCodec)?;
+//! let node = graph.create_node("Person", &props)?;
+//!
+//! let address: Address = node.decode_property("address", &JsonCodec)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    props::Props,
+    result_set::{Edge, Node, Scalar},
+    RedisGraphError, RedisGraphResult, RedisString,
+};
+
+/// Encodes values into strings that can be stored as properties, and decodes them again.
+pub trait PropertyCodec {
+    /// Encodes the value into a string.
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> RedisGraphResult<String>;
+
+    /// Decodes a string returned by [`encode`](#tymethod.encode).
+    fn decode<T: DeserializeOwned>(&self, encoded: &str) -> RedisGraphResult<T>;
+}
+
+/// Encodes values as JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct JsonCodec;
+
+impl PropertyCodec for JsonCodec {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> RedisGraphResult<String> {
+        serde_json::to_string(value).map_err(|error| RedisGraphError::CodecError(error.into()))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, encoded: &str) -> RedisGraphResult<T> {
+        serde_json::from_str(encoded).map_err(|error| RedisGraphError::CodecError(error.into()))
+    }
+}
+
+/// Encrypts values encoded by another codec with AES-256-GCM, requires the `encryption` feature.
+///
+/// Every value is encrypted with a random nonce and stored as the Base64 encoding of the nonce
+/// followed by the ciphertext. Equal values therefore produce different properties, so
+/// encrypted properties can't be used to match or index nodes.
+///
+/// ```
+/// # use redisgraph::codec::{EncryptedCodec, PropertyCodec};
+/// // Load the key from a secret store instead.
+/// let key = [7; 32];
+/// let codec = EncryptedCodec::new(&key);
+///
+/// let encrypted = codec.encode("alice@example.com").unwrap();
+/// assert!(!encrypted.contains("alice"));
+/// assert_eq!(codec.decode::<String>(&encrypted).unwrap(), "alice@example.com");
+/// ```
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+pub struct EncryptedCodec<C = JsonCodec> {
+    cipher: aes_gcm::Aes256Gcm,
+    inner: C,
+}
+
+#[cfg(feature = "encryption")]
+impl EncryptedCodec {
+    /// Creates a codec that encodes values as JSON and encrypts them with the given 256-bit key.
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self::with_codec(key, JsonCodec)
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl<C: PropertyCodec> EncryptedCodec<C> {
+    /// Creates a codec that encodes values with the given codec and encrypts them with the given 256-bit key.
+    pub fn with_codec(key: &[u8; 32], inner: C) -> Self {
+        use aes_gcm::KeyInit;
+
+        Self {
+            cipher: aes_gcm::Aes256Gcm::new(key.into()),
+            inner,
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl<C> std::fmt::Debug for EncryptedCodec<C> {
+    /// Leaves out the key.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("EncryptedCodec").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl<C: PropertyCodec> PropertyCodec for EncryptedCodec<C> {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> RedisGraphResult<String> {
+        use aes_gcm::aead::{Aead, AeadCore, OsRng};
+        use base64::Engine;
+
+        let plaintext = self.inner.encode(value)?;
+        let nonce = aes_gcm::Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| RedisGraphError::CodecError("failed to encrypt property".into()))?;
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        Ok(base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, encoded: &str) -> RedisGraphResult<T> {
+        use aes_gcm::aead::Aead;
+        use base64::Engine;
+
+        const NONCE_SIZE: usize = 12;
+
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|error| RedisGraphError::CodecError(error.into()))?;
+        if data.len() < NONCE_SIZE {
+            return Err(RedisGraphError::CodecError(
+                "encrypted property is too short".into(),
+            ));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+        let plaintext = self
+            .cipher
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| RedisGraphError::CodecError("failed to decrypt property".into()))?;
+        match String::from_utf8(plaintext) {
+            Ok(plaintext) => self.inner.decode(&plaintext),
+            Err(_) => Err(RedisGraphError::InvalidUtf8),
+        }
+    }
+}
+
+/// Supplies the key for the fields marked with `#[redisgraph(encrypted)]` in derived
+/// [`ToProperties`](../props/trait.ToProperties.html) and [`Entity`](../entity/trait.Entity.html)
+/// implementations, requires the `encryption` feature.
+///
+/// The fields are encrypted with an [`EncryptedCodec`](struct.EncryptedCodec.html) using the key.
+/// The implementing type is named on the struct with `#[redisgraph(key_provider = "Type")]`:
+///
+/// ```ignore
+/// struct AppKeys;
+///
+/// impl KeyProvider for AppKeys {
+///     fn encryption_key() -> [u8; 32] {
+///         // Load the key from a secret store instead.
+///         [7; 32]
+///     }
+/// }
+///
+/// #[derive(Entity, ToProperties)]
+/// #[redisgraph(key_provider = "AppKeys")]
+/// struct Patient {
+///     #[redisgraph(key)]
+///     name: String,
+///     #[redisgraph(encrypted)]
+///     insurance_number: String,
+/// }
+/// ```
+#[cfg(feature = "encryption")]
+pub trait KeyProvider {
+    /// Returns the 256-bit key the fields are encrypted with.
+    fn encryption_key() -> [u8; 32];
+}
+
+impl Props {
+    /// Sets the property with the given key to the value encoded with the given codec.
+    pub fn set_encoded<K, T, C>(self, key: K, value: &T, codec: &C) -> RedisGraphResult<Self>
+    where
+        K: Into<String>,
+        T: Serialize + ?Sized,
+        C: PropertyCodec,
+    {
+        Ok(self.set(key, codec.encode(value)?))
+    }
+}
+
+impl Node {
+    /// Decodes the property with the given key with the given codec.
+    ///
+    /// Fails with a [`ClientTypeError`](../error/enum.RedisGraphError.html#variant.ClientTypeError)
+    /// if the node doesn't have the property or if it isn't a string.
+    pub fn decode_property<T, C>(&self, key: &str, codec: &C) -> RedisGraphResult<T>
+    where
+        T: DeserializeOwned,
+        C: PropertyCodec,
+    {
+        decode_property(
+            self.properties.get(&RedisString::from(key.to_string())),
+            key,
+            codec,
+        )
+    }
+}
+
+impl Edge {
+    /// Decodes the property with the given key with the given codec.
+    ///
+    /// Fails with a [`ClientTypeError`](../error/enum.RedisGraphError.html#variant.ClientTypeError)
+    /// if the edge doesn't have the property or if it isn't a string.
+    pub fn decode_property<T, C>(&self, key: &str, codec: &C) -> RedisGraphResult<T>
+    where
+        T: DeserializeOwned,
+        C: PropertyCodec,
+    {
+        decode_property(
+            self.properties.get(&RedisString::from(key.to_string())),
+            key,
+            codec,
+        )
+    }
+}
+
+fn decode_property<T: DeserializeOwned, C: PropertyCodec>(
+    property: Option<&Scalar>,
+    key: &str,
+    codec: &C,
+) -> RedisGraphResult<T> {
+    match property {
+        Some(Scalar::String(encoded)) => match std::str::from_utf8(&encoded.0) {
+            Ok(encoded) => codec.decode(encoded),
+            Err(_) => Err(RedisGraphError::InvalidUtf8),
+        },
+        Some(other) => client_type_error!(
+            "failed to decode property {:?}: expected string, found {:?}",
+            key,
+            other
+        ),
+        None => client_type_error!("failed to decode property {:?}: no such property", key),
+    }
+}
@@ -0,0 +1,102 @@
+use redis::{ConnectionLike, Value};
+
+use crate::{RedisGraphError, RedisGraphResult};
+
+/// The names of the Redis commands used to talk to the graph module.
+///
+/// RedisGraph registers its commands as `GRAPH.QUERY`, `GRAPH.DELETE` and so on.
+/// Use this to talk to forks or proxies that expose the same commands under different names,
+/// and pass it to [`Graph::open_with_commands`](../graph/struct.Graph.html#method.open_with_commands).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandNames {
+    /// Executes a query, `GRAPH.QUERY` by default.
+    pub query: String,
+    /// Executes a read-only query, `GRAPH.RO_QUERY` by default.
+    pub ro_query: String,
+    /// Deletes a graph, `GRAPH.DELETE` by default.
+    pub delete: String,
+    /// Executes a query and returns its execution plan with timings, `GRAPH.PROFILE` by default.
+    pub profile: String,
+    /// Returns the execution plan of a query without executing it, `GRAPH.EXPLAIN` by default.
+    pub explain: String,
+    /// Creates and drops constraints, `GRAPH.CONSTRAINT` by default.
+    pub constraint: String,
+    /// Returns the slowest recent queries, `GRAPH.SLOWLOG` by default.
+    pub slowlog: String,
+}
+
+impl CommandNames {
+    /// Returns the command names with the given module prefix, e.g. `FALKOR` for `FALKOR.QUERY`.
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            query: format!("{}.QUERY", prefix),
+            ro_query: format!("{}.RO_QUERY", prefix),
+            delete: format!("{}.DELETE", prefix),
+            profile: format!("{}.PROFILE", prefix),
+            explain: format!("{}.EXPLAIN", prefix),
+            constraint: format!("{}.CONSTRAINT", prefix),
+            slowlog: format!("{}.SLOWLOG", prefix),
+        }
+    }
+
+    /// Returns the command names for the first of the given prefixes
+    /// whose query command is known to the server, using `COMMAND INFO`.
+    ///
+    /// Returns `None` if none of the prefixes is known.
+    pub fn detect<C: ConnectionLike>(
+        conn: &mut C,
+        prefixes: &[&str],
+    ) -> RedisGraphResult<Option<Self>> {
+        for prefix in prefixes {
+            let names = Self::with_prefix(prefix);
+            let info: Vec<Value> = names
+                .command_info()
+                .query(conn)
+                .map_err(RedisGraphError::from)?;
+            if is_known(&info) {
+                return Ok(Some(names));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Same as [`detect`](#method.detect), but over an async connection, e.g. before opening an
+    /// [`AsyncGraph`](../aio/struct.AsyncGraph.html) or a [`GraphClient`](../client/struct.GraphClient.html).
+    /// Requires the `aio` feature.
+    #[cfg(feature = "aio")]
+    pub async fn detect_async<C: redis::aio::ConnectionLike>(
+        conn: &mut C,
+        prefixes: &[&str],
+    ) -> RedisGraphResult<Option<Self>> {
+        for prefix in prefixes {
+            let names = Self::with_prefix(prefix);
+            let info: Vec<Value> = names
+                .command_info()
+                .query_async(conn)
+                .await
+                .map_err(RedisGraphError::from)?;
+            if is_known(&info) {
+                return Ok(Some(names));
+            }
+        }
+        Ok(None)
+    }
+
+    fn command_info(&self) -> redis::Cmd {
+        let mut cmd = redis::cmd("COMMAND");
+        cmd.arg("INFO").arg(&self.query);
+        cmd
+    }
+}
+
+impl Default for CommandNames {
+    /// Returns the command names registered by RedisGraph.
+    fn default() -> Self {
+        Self::with_prefix("GRAPH")
+    }
+}
+
+// `COMMAND INFO` answers with `nil` for unknown commands.
+fn is_known(info: &[Value]) -> bool {
+    info.iter().any(|command| *command != Value::Nil)
+}
@@ -0,0 +1,145 @@
+//! Connected components and communities of the whole graph.
+//!
+//! The methods use the `algo.WCC` and `algo.labelPropagation` procedures if the server
+//! provides them. Otherwise, they fetch the IDs of all nodes and the endpoints of all
+//! relationships and compute the result on the client, which is only feasible for graphs
+//! that fit into memory. Relationships are treated as undirected in both cases.
+//!
+//! ```no_run
+//! # use redisgraph::{Graph, RedisGraphResult};
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! let components = graph.connected_components()?;
+//! for (node, component) in &components {
+//!     println!("node {} is in component {}", node, component);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use redis::ConnectionLike;
+
+use crate::{error::is_unknown_procedure, Graph, RedisGraphResult};
+
+/// The ID of a node, as returned by `id(n)`.
+pub type NodeId = u64;
+
+/// The ID of a connected component or community.
+///
+/// Only meaningful for comparing nodes returned by the same call: the server numbers the
+/// components itself, while the client-side fallback uses the smallest node ID in each one.
+pub type ComponentId = u64;
+
+type Neighbors = HashMap<NodeId, Vec<NodeId>>;
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Returns the weakly connected component of every node.
+    ///
+    /// See the [module documentation](../community/index.html) for how the components are found.
+    pub fn connected_components(&mut self) -> RedisGraphResult<HashMap<NodeId, ComponentId>> {
+        let rows: RedisGraphResult<Vec<(NodeId, ComponentId)>> =
+            self.query("CALL algo.WCC(null) YIELD node, componentId RETURN id(node), componentId");
+        match rows {
+            Err(error) if is_unknown_procedure(&error) => {
+                let (nodes, neighbors) = self.adjacency()?;
+                Ok(components(&nodes, &neighbors))
+            }
+            rows => Ok(rows?.into_iter().collect()),
+        }
+    }
+
+    /// Returns the community of every node found by label propagation, stopping after
+    /// `max_iterations` rounds if the communities haven't settled by then.
+    ///
+    /// See the [module documentation](../community/index.html) for how the communities are found.
+    pub fn label_propagation(
+        &mut self,
+        max_iterations: usize,
+    ) -> RedisGraphResult<HashMap<NodeId, ComponentId>> {
+        let rows: RedisGraphResult<Vec<(NodeId, ComponentId)>> = self.query(&format!(
+            "CALL algo.labelPropagation({{maxIterations: {}}}) YIELD node, communityId \
+             RETURN id(node), communityId",
+            max_iterations
+        ));
+        match rows {
+            Err(error) if is_unknown_procedure(&error) => {
+                let (nodes, neighbors) = self.adjacency()?;
+                Ok(propagate_labels(&nodes, &neighbors, max_iterations))
+            }
+            rows => Ok(rows?.into_iter().collect()),
+        }
+    }
+
+    // Returns the sorted IDs of all nodes and the neighbors of every node.
+    fn adjacency(&mut self) -> RedisGraphResult<(Vec<NodeId>, Neighbors)> {
+        let mut nodes: Vec<NodeId> = self.query("MATCH (n) RETURN id(n)")?;
+        nodes.sort_unstable();
+        let edges: Vec<(NodeId, NodeId)> = self.query("MATCH (a)-[]->(b) RETURN id(a), id(b)")?;
+
+        let mut neighbors = Neighbors::new();
+        for (a, b) in edges {
+            neighbors.entry(a).or_default().push(b);
+            neighbors.entry(b).or_default().push(a);
+        }
+        Ok((nodes, neighbors))
+    }
+}
+
+// Labels every node with the smallest node ID in its component.
+fn components(nodes: &[NodeId], neighbors: &Neighbors) -> HashMap<NodeId, ComponentId> {
+    let mut components = HashMap::with_capacity(nodes.len());
+    // The nodes are sorted, so every search starts at the smallest node of its component.
+    for &start in nodes {
+        match components.entry(start) {
+            Entry::Occupied(_) => continue,
+            Entry::Vacant(entry) => entry.insert(start),
+        };
+        let mut pending = vec![start];
+        while let Some(node) = pending.pop() {
+            for &neighbor in neighbors.get(&node).into_iter().flatten() {
+                if let Entry::Vacant(entry) = components.entry(neighbor) {
+                    entry.insert(start);
+                    pending.push(neighbor);
+                }
+            }
+        }
+    }
+    components
+}
+
+// Starts with every node in its own community and repeatedly moves every node into the
+// community most of its neighbors are in, preferring the smallest ID on ties.
+fn propagate_labels(
+    nodes: &[NodeId],
+    neighbors: &Neighbors,
+    max_iterations: usize,
+) -> HashMap<NodeId, ComponentId> {
+    let mut communities: HashMap<NodeId, ComponentId> =
+        nodes.iter().map(|&node| (node, node)).collect();
+    for _ in 0..max_iterations {
+        let mut changed = false;
+        for node in nodes {
+            let mut counts: HashMap<ComponentId, usize> = HashMap::new();
+            // A neighbor may be missing if it was created after the nodes were fetched.
+            for neighbor in neighbors.get(node).into_iter().flatten() {
+                if let Some(&community) = communities.get(neighbor) {
+                    *counts.entry(community).or_default() += 1;
+                }
+            }
+            let best = counts
+                .into_iter()
+                .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)));
+            if let Some((community, _)) = best {
+                if communities[node] != community {
+                    communities.insert(*node, community);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    communities
+}
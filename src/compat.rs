@@ -0,0 +1,130 @@
+//! A thin layer mirroring the commonly used subset of the [`neo4rs`](https://docs.rs/neo4rs) API,
+//! so code written for Neo4j can be ported with few changes.
+//!
+//! Unlike `neo4rs`, everything here is blocking, so ported code only needs to drop the `.await`s:
+//!
+//! ```no_run
+//! # use redisgraph::{compat::query, result_set::Node, Graph, RedisGraphResult};
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! graph.run(query("CREATE (p:Person {name: $name})").param("name", "Alice"))?;
+//!
+//! let mut result = graph.execute(query("MATCH (p:Person) RETURN p, p.name AS name"))?;
+//! while let Some(row) = result.next()? {
+//!     let person: Node = row.get("p")?;
+//!     let name: String = row.get("name")?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use redis::ConnectionLike;
+
+use crate::{assignments::FromCell, params::CypherValue, Graph, RedisGraphResult, ResultSet};
+
+/// A query with parameters, created with [`query`](fn.query.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    text: String,
+    params: HashMap<String, CypherValue>,
+}
+
+/// Creates a query without parameters.
+pub fn query(text: &str) -> Query {
+    Query::new(text.to_string())
+}
+
+impl Query {
+    /// Creates a query without parameters.
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            params: HashMap::new(),
+        }
+    }
+
+    /// Binds a value to the parameter with the given name.
+    pub fn param<V: Into<CypherValue>>(mut self, key: &str, value: V) -> Self {
+        self.params.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Returns `true` if a value is bound to the parameter with the given name.
+    pub fn has_param_key(&self, key: &str) -> bool {
+        self.params.contains_key(key)
+    }
+}
+
+/// The rows returned by [`Graph::execute`](../graph/struct.Graph.html#method.execute).
+#[derive(Debug)]
+pub struct RowStream {
+    columns: Arc<Columns>,
+    next_row: usize,
+}
+
+#[derive(Debug)]
+struct Columns {
+    names: Vec<String>,
+    result_set: ResultSet,
+}
+
+impl RowStream {
+    /// Returns the next row, or `None` if all rows were returned.
+    ///
+    /// The rows are fetched all at once, so this never fails. It returns a result anyway
+    /// to match `neo4rs`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> RedisGraphResult<Option<Row>> {
+        if self.next_row >= self.columns.result_set.num_rows() {
+            return Ok(None);
+        }
+        let row = Row {
+            columns: Arc::clone(&self.columns),
+            idx: self.next_row,
+        };
+        self.next_row += 1;
+        Ok(Some(row))
+    }
+}
+
+/// A row returned by a [`RowStream`](struct.RowStream.html).
+#[derive(Debug, Clone)]
+pub struct Row {
+    columns: Arc<Columns>,
+    idx: usize,
+}
+
+impl Row {
+    /// Returns the value in the column with the given name, i.e. the name of a returned
+    /// variable or the alias given with `AS`.
+    pub fn get<T: FromCell>(&self, key: &str) -> RedisGraphResult<T> {
+        match self.columns.names.iter().position(|name| name == key) {
+            Some(column_idx) => T::from_cell(&self.columns.result_set, self.idx, column_idx),
+            None => client_type_error!("failed to get value: no column named {:?}", key),
+        }
+    }
+
+    /// Returns the names of the columns.
+    pub fn keys(&self) -> &[String] {
+        &self.columns.names
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes a query without return values, like `neo4rs::Graph::run`.
+    pub fn run(&mut self, query: Query) -> RedisGraphResult<()> {
+        self.mutate_with_params(&query.text, &query.params)
+    }
+
+    /// Executes a query and returns its rows, like `neo4rs::Graph::execute`.
+    pub fn execute(&mut self, query: Query) -> RedisGraphResult<RowStream> {
+        let (names, result_set) =
+            self.query_with_column_names(&self.prepare_query(&query.text, &query.params)?)?;
+        Ok(RowStream {
+            columns: Arc::new(Columns { names, result_set }),
+            next_row: 0,
+        })
+    }
+}
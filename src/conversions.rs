@@ -1,10 +1,11 @@
 use crate::{
     assignments::FromCell,
     client_type_error,
+    params::CypherValue,
     result_set::{Edge, Node, Path, RawPath, Scalar},
     RedisGraphError, RedisGraphResult, RedisString, ResultSet,
 };
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
 impl FromCell for Scalar {
     fn from_cell(
@@ -17,6 +18,17 @@ impl FromCell for Scalar {
     }
 }
 
+impl FromCell for CypherValue {
+    fn from_cell(
+        result_set: &ResultSet,
+        row_idx: usize,
+        column_idx: usize,
+    ) -> RedisGraphResult<Self> {
+        let scalar = result_set.get_scalar(row_idx, column_idx)?;
+        CypherValue::try_from(scalar.clone())
+    }
+}
+
 impl FromCell for () {
     fn from_cell(
         result_set: &ResultSet,
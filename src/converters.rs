@@ -0,0 +1,273 @@
+//! Decoding values into application types that this crate doesn't know about.
+//!
+//! A [`ConverterRegistry`](struct.ConverterRegistry.html) holds converters for patterns of values,
+//! either properties with a given key, optionally only on nodes with a given label, or maps
+//! with a given set of keys. Registering them once lets the application decode such values
+//! wherever they appear, without implementing [`FromCell`](../assignments/trait.FromCell.html)
+//! for every shape they are returned in.
+//!
+//! Set a registry on a graph with [`Graph::set_converters`](../graph/struct.Graph.html#method.set_converters)
+//! to decode cells into [`Converted`](struct.Converted.html) values while decoding query results,
+//! e.g. `graph.query::<Vec<(String, Converted<GeoPoint>)>>(...)`. Cells are matched against the
+//! [map shapes](enum.Pattern.html#variant.MapShape) of the registry, since the key and label of
+//! a returned property are no longer known; use [`node_property`](struct.ConverterRegistry.html#method.node_property)
+//! to convert the properties of returned nodes.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use redisgraph::{converters::{ConverterRegistry, Pattern}, result_set::Scalar};
+//! #[derive(Debug, PartialEq)]
+//! struct GeoPoint {
+//!     lat: f64,
+//!     lon: f64,
+//! }
+//!
+//! let mut converters = ConverterRegistry::new();
+//! converters.register(Pattern::map_with_keys(&["lat", "lon"]), |value: &Scalar| {
+//!     match value {
+//!         Scalar::Map(map) => Ok(GeoPoint {
+//!             lat: map[&"lat".to_string().into()].convert()?,
+//!             lon: map[&"lon".to_string().into()].convert()?,
+//!         }),
+//!         _ => unreachable!(),
+//!     }
+//! });
+//!
+//! let mut map = HashMap::new();
+//! map.insert("lat".to_string().into(), Scalar::Double(52.5));
+//! map.insert("lon".to_string().into(), Scalar::Double(13.4));
+//! let point: GeoPoint = converters.decode(&Scalar::Map(map)).unwrap();
+//! assert_eq!(point, GeoPoint { lat: 52.5, lon: 13.4 });
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{
+    assignments::{FromCell, FromTable},
+    result_set::{Edge, Node, Scalar},
+    RedisGraphResult, RedisString, ResultSet,
+};
+
+thread_local! {
+    // The registry used by `Converted` while a result set is decoded on this thread.
+    static CURRENT: RefCell<Option<Arc<ConverterRegistry>>> = const { RefCell::new(None) };
+}
+
+type Converter<T> = Box<dyn Fn(&Scalar) -> RedisGraphResult<T> + Send + Sync>;
+
+/// The values a converter is registered for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// Properties with the given key, on nodes with the given label or edges with the given
+    /// type, or on any node or edge if `label` is `None`.
+    Property { label: Option<String>, key: String },
+    /// Maps with exactly the given keys, wherever they appear.
+    MapShape(BTreeSet<String>),
+}
+
+impl Pattern {
+    /// Matches the properties with the given key on any node or edge.
+    pub fn property(key: impl Into<String>) -> Self {
+        Self::Property {
+            label: None,
+            key: key.into(),
+        }
+    }
+
+    /// Matches the properties with the given key on nodes with the given label
+    /// or edges with the given type.
+    pub fn labeled_property(label: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::Property {
+            label: Some(label.into()),
+            key: key.into(),
+        }
+    }
+
+    /// Matches maps with exactly the given keys.
+    pub fn map_with_keys(keys: &[&str]) -> Self {
+        Self::MapShape(keys.iter().map(|key| key.to_string()).collect())
+    }
+
+    fn matches_value(&self, value: &Scalar) -> bool {
+        match (self, value) {
+            (Self::MapShape(keys), Scalar::Map(map)) => {
+                map.len() == keys.len()
+                    && keys
+                        .iter()
+                        .all(|key| map.contains_key(&RedisString::from(key.clone())))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_property(&self, labels: &[RedisString], property: &str) -> bool {
+        match self {
+            Self::Property { label, key } => {
+                key == property
+                    && match label {
+                        Some(label) => labels.iter().any(|other| other.0 == label.as_bytes()),
+                        None => true,
+                    }
+            }
+            Self::MapShape(_) => false,
+        }
+    }
+}
+
+struct Registration {
+    pattern: Pattern,
+    target: TypeId,
+    // A `Converter<T>` for the type identified by `target`.
+    convert: Box<dyn Any + Send + Sync>,
+}
+
+/// Converters for application types, see the [module documentation](index.html).
+#[derive(Default)]
+pub struct ConverterRegistry {
+    registrations: Vec<Registration>,
+}
+
+impl ConverterRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a converter into `T` for the values matching the pattern.
+    ///
+    /// If multiple converters into the same type match a value,
+    /// the one registered first is used.
+    pub fn register<T, F>(&mut self, pattern: Pattern, convert: F)
+    where
+        T: 'static,
+        F: Fn(&Scalar) -> RedisGraphResult<T> + Send + Sync + 'static,
+    {
+        let convert: Converter<T> = Box::new(convert);
+        self.registrations.push(Registration {
+            pattern,
+            target: TypeId::of::<T>(),
+            convert: Box::new(convert),
+        });
+    }
+
+    /// Converts the value into `T` with the first converter whose
+    /// [map shape](enum.Pattern.html#variant.MapShape) matches it.
+    pub fn decode<T: 'static>(&self, value: &Scalar) -> RedisGraphResult<T> {
+        match self.find(|pattern| pattern.matches_value(value)) {
+            Some(convert) => convert(value),
+            None => client_type_error!(
+                "no converter into {} registered for {:?}",
+                std::any::type_name::<T>(),
+                value
+            ),
+        }
+    }
+
+    /// Converts the property with the given key into `T`, with the first converter whose
+    /// [property pattern](enum.Pattern.html#variant.Property) matches it, or else like
+    /// [`decode`](#method.decode). A missing property is converted like `null`.
+    pub fn node_property<T: 'static>(&self, node: &Node, key: &str) -> RedisGraphResult<T> {
+        self.property(&node.labels, &node.properties, key)
+    }
+
+    /// Same as [`node_property`](#method.node_property), but for edges, whose type is matched
+    /// against the label of property patterns.
+    pub fn edge_property<T: 'static>(&self, edge: &Edge, key: &str) -> RedisGraphResult<T> {
+        self.property(std::slice::from_ref(&edge.type_name), &edge.properties, key)
+    }
+
+    fn property<T: 'static>(
+        &self,
+        labels: &[RedisString],
+        properties: &HashMap<RedisString, Scalar>,
+        key: &str,
+    ) -> RedisGraphResult<T> {
+        let value = properties
+            .get(&RedisString::from(key.to_string()))
+            .unwrap_or(&Scalar::Nil);
+        match self.find(|pattern| pattern.matches_property(labels, key)) {
+            Some(convert) => convert(value),
+            None => self.decode(value),
+        }
+    }
+
+    /// Converts the result set into `T`, decoding [`Converted`](struct.Converted.html) cells
+    /// with the converters of this registry.
+    ///
+    /// [`Graph::set_converters`](../graph/struct.Graph.html#method.set_converters) does this for
+    /// every query of a graph. Use this for result sets from other handles, e.g. an
+    /// [`AsyncGraph`](../aio/struct.AsyncGraph.html).
+    pub fn decode_table<T: FromTable>(
+        self: &Arc<Self>,
+        result_set: &ResultSet,
+    ) -> RedisGraphResult<T> {
+        // Restores the outer registry when dropped, even if decoding panicked.
+        struct Restore(Option<Arc<ConverterRegistry>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let outer = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = outer);
+            }
+        }
+
+        let outer = CURRENT.with(|current| current.borrow_mut().replace(Arc::clone(self)));
+        let _restore = Restore(outer);
+        T::from_table(result_set)
+    }
+
+    fn find<T: 'static>(&self, matches: impl Fn(&Pattern) -> bool) -> Option<&Converter<T>> {
+        self.registrations
+            .iter()
+            .filter(|registration| registration.target == TypeId::of::<T>())
+            .find(|registration| matches(&registration.pattern))
+            .and_then(|registration| registration.convert.downcast_ref())
+    }
+}
+
+impl fmt::Debug for ConverterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.registrations
+                    .iter()
+                    .map(|registration| &registration.pattern),
+            )
+            .finish()
+    }
+}
+
+/// A cell decoded with the registry that is set for decoding, see the
+/// [module documentation](index.html).
+///
+/// Decoding fails if no registry is set or none of its converters into `T` matches the cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted<T>(pub T);
+
+impl<T> Converted<T> {
+    /// Returns the converted value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: 'static> FromCell for Converted<T> {
+    fn from_cell(
+        result_set: &ResultSet,
+        row_idx: usize,
+        column_idx: usize,
+    ) -> RedisGraphResult<Self> {
+        let scalar = result_set.get_scalar(row_idx, column_idx)?;
+        let registry = CURRENT.with(|current| current.borrow().clone());
+        match registry {
+            Some(registry) => registry.decode(scalar).map(Converted),
+            None => client_type_error!(
+                "failed to decode {}: no converter registry is set",
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+}
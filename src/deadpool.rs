@@ -0,0 +1,82 @@
+//! A [`deadpool`](https://docs.rs/deadpool/0.5) manager for async graphs.
+//!
+//! Services that already pool their other connections with `deadpool` can pool graph handles
+//! the same way: a [`Manager`](struct.Manager.html) opens an
+//! [`AsyncGraph`](../aio/struct.AsyncGraph.html) over a new multiplexed connection whenever the
+//! [`Pool`](type.Pool.html) needs one, and checks with a `PING` that a graph handed back to the
+//! pool is still connected before lending it out again. Graphs whose connection broke are
+//! replaced by new ones.
+//!
+//! Requires the `deadpool` feature, which enables `tokio-comp`.
+//!
+//! ```no_run
+//! # use redisgraph::deadpool::{Manager, Pool};
+//! # async fn example(client: redis::Client) -> Result<(), redisgraph::deadpool::PoolError> {
+//! let pool = Pool::new(Manager::new(client, "MotoGP".to_string()), 16);
+//! let mut graph = pool.get().await?;
+//! let riders: i64 = graph.query("MATCH (r:Rider) RETURN count(r)").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use ::deadpool::managed::{self, RecycleError, RecycleResult};
+use async_trait::async_trait;
+use redis::Client;
+
+use crate::{aio::AsyncGraph, commands::CommandNames, RedisGraphError, RedisGraphResult};
+
+/// A pool of async graphs, created with a [`Manager`](struct.Manager.html).
+pub type Pool = managed::Pool<AsyncGraph, RedisGraphError>;
+
+/// A graph lent out by a [`Pool`](type.Pool.html), which dereferences to the
+/// [`AsyncGraph`](../aio/struct.AsyncGraph.html). It's returned to the pool when dropped.
+pub type Connection = managed::Object<AsyncGraph, RedisGraphError>;
+
+/// The error returned by [`Pool::get`](https://docs.rs/deadpool/0.5/deadpool/managed/struct.Pool.html#method.get).
+pub type PoolError = managed::PoolError<RedisGraphError>;
+
+/// Creates and recycles the graphs of a [`Pool`](type.Pool.html), see the
+/// [module documentation](index.html).
+#[derive(Debug, Clone)]
+pub struct Manager {
+    client: Client,
+    name: String,
+    commands: CommandNames,
+}
+
+impl Manager {
+    /// Creates a manager that opens the graph with the given name over connections from the
+    /// given client, like [`AsyncGraph::connect`](../aio/struct.AsyncGraph.html#method.connect).
+    pub fn new(client: Client, name: String) -> Self {
+        Self::with_commands(client, name, CommandNames::default())
+    }
+
+    /// Same as [`new`](#method.new), but uses the given command names instead of `GRAPH.QUERY` etc.
+    pub fn with_commands(client: Client, name: String, commands: CommandNames) -> Self {
+        Self {
+            client,
+            name,
+            commands,
+        }
+    }
+
+    /// Returns the name of the graph opened by this manager.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[async_trait]
+impl managed::Manager<AsyncGraph, RedisGraphError> for Manager {
+    async fn create(&self) -> RedisGraphResult<AsyncGraph> {
+        let conn = self.client.get_multiplexed_tokio_connection().await?;
+        AsyncGraph::open_with_commands(conn, self.name.clone(), self.commands.clone()).await
+    }
+
+    async fn recycle(&self, graph: &mut AsyncGraph) -> RecycleResult<RedisGraphError> {
+        redis::cmd("PING")
+            .query_async::<_, ()>(graph.connection())
+            .await
+            .map_err(|error| RecycleError::Backend(error.into()))
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use redis::{Connection, ConnectionLike};
+
+use crate::{
+    assignments::FromTable, params::CypherValue, Graph, RedisGraphError, RedisGraphResult,
+};
+
+/// A second database that receives every mutation applied through a
+/// [`DualWriteGraph`](struct.DualWriteGraph.html), e.g. a Neo4j writer during a migration.
+pub trait SecondarySink {
+    /// Applies a mutation that was successfully applied to the primary graph.
+    fn apply(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// How a [`DualWriteGraph`](struct.DualWriteGraph.html) handles mutations that fail on the secondary sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DualWritePolicy {
+    /// The mutation is recorded and succeeds anyway. Take the recorded mutations with
+    /// [`DualWriteGraph::take_failed`](struct.DualWriteGraph.html#method.take_failed) to replay them later.
+    BestEffort,
+    /// The mutation fails with [`SecondaryWriteFailed`](../error/enum.RedisGraphError.html#variant.SecondaryWriteFailed).
+    ///
+    /// It has already been applied to the primary graph at that point.
+    Strict,
+}
+
+/// A mutation that failed on the secondary sink under [`DualWritePolicy::BestEffort`](enum.DualWritePolicy.html#variant.BestEffort).
+#[derive(Debug)]
+pub struct FailedWrite {
+    /// The query of the mutation.
+    pub query: String,
+    /// The parameters of the mutation.
+    pub params: HashMap<String, CypherValue>,
+    /// The error returned by the sink.
+    pub error: Box<dyn Error + Send + Sync>,
+}
+
+/// A graph that applies every mutation to a secondary sink as well, to keep two databases
+/// in sync while migrating between them.
+///
+/// Mutations are applied to the primary graph first and only passed to the sink if they succeed there.
+/// Queries are only executed on the primary graph.
+pub struct DualWriteGraph<S, C = Connection> {
+    primary: Graph<C>,
+    secondary: S,
+    policy: DualWritePolicy,
+    failed: Vec<FailedWrite>,
+}
+
+impl<S: SecondarySink, C: ConnectionLike> DualWriteGraph<S, C> {
+    /// Creates a dual-write graph from the primary graph and the secondary sink.
+    pub fn new(primary: Graph<C>, secondary: S, policy: DualWritePolicy) -> Self {
+        Self {
+            primary,
+            secondary,
+            policy,
+            failed: Vec::new(),
+        }
+    }
+
+    /// Executes the given query on the primary graph and returns its return values.
+    pub fn query<T: FromTable>(&mut self, query: &str) -> RedisGraphResult<T> {
+        self.primary.query(query)
+    }
+
+    /// Same as [`query`](#method.query), but with parameters.
+    pub fn query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.primary.query_with_params(query, params)
+    }
+
+    /// Applies the given mutation to the primary graph and the secondary sink.
+    pub fn mutate(&mut self, query: &str) -> RedisGraphResult<()> {
+        self.mutate_with_params(query, &HashMap::new())
+    }
+
+    /// Same as [`mutate`](#method.mutate), but with parameters.
+    pub fn mutate_with_params(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        self.primary.mutate_with_params(query, params)?;
+        match self.secondary.apply(query, params) {
+            Ok(()) => Ok(()),
+            Err(error) => match self.policy {
+                DualWritePolicy::BestEffort => {
+                    self.failed.push(FailedWrite {
+                        query: query.to_string(),
+                        params: params.clone(),
+                        error,
+                    });
+                    Ok(())
+                }
+                DualWritePolicy::Strict => Err(RedisGraphError::SecondaryWriteFailed(error)),
+            },
+        }
+    }
+
+    /// Returns the mutations that failed on the secondary sink since the last call, oldest first.
+    pub fn take_failed(&mut self) -> Vec<FailedWrite> {
+        std::mem::take(&mut self.failed)
+    }
+
+    /// Returns the policy for mutations that fail on the secondary sink.
+    pub fn policy(&self) -> DualWritePolicy {
+        self.policy
+    }
+
+    /// Sets the policy for mutations that fail on the secondary sink.
+    pub fn set_policy(&mut self, policy: DualWritePolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns the primary graph, e.g. for reads that aren't plain queries.
+    ///
+    /// Mutations applied to it directly are not passed to the secondary sink.
+    pub fn primary(&mut self) -> &mut Graph<C> {
+        &mut self.primary
+    }
+
+    /// Returns the secondary sink.
+    pub fn secondary(&mut self) -> &mut S {
+        &mut self.secondary
+    }
+
+    /// Returns the primary graph and the secondary sink, e.g. once the migration is finished.
+    pub fn into_inner(self) -> (Graph<C>, S) {
+        (self.primary, self.secondary)
+    }
+}
@@ -0,0 +1,208 @@
+//! Storing and reading embeddings, e.g. for vector search.
+//!
+//! Embeddings are stored as `vecf32` values if the server supports them, so they can be used
+//! with vector indexes and [`Graph::knn`](../graph/struct.Graph.html#method.knn), and as arrays
+//! of doubles otherwise. Both are read back the same way.
+//!
+//! ```no_run
+//! # use redisgraph::{Graph, RedisGraphResult};
+//! # fn example(graph: &mut Graph, product: u64) -> RedisGraphResult<()> {
+//! graph.set_embedding(product, "embedding", &[0.1, 0.7, 0.2])?;
+//! for (id, embedding) in graph.get_embeddings("Product", "embedding")? {
+//!     println!("product {} has {} dimensions", id, embedding.len());
+//! }
+//! for (product, distance) in graph.knn("Product", "embedding", &[0.2, 0.6, 0.2], 5)? {
+//!     println!("{:?} is {} away", product, distance);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use redis::ConnectionLike;
+
+use crate::{
+    community::NodeId,
+    error::{is_unknown_function, is_unknown_procedure},
+    params::{escape_identifier, CypherValue},
+    result_set::{Node, Scalar},
+    Graph, RedisGraphResult,
+};
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Stores the embedding in the given property of the node with the given ID.
+    pub fn set_embedding(
+        &mut self,
+        node_id: NodeId,
+        property: &str,
+        embedding: &[f32],
+    ) -> RedisGraphResult<()> {
+        self.set_embeddings(property, &[(node_id, embedding)])
+    }
+
+    /// Stores the embeddings of multiple nodes in the given property, in a single query.
+    pub fn set_embeddings(
+        &mut self,
+        property: &str,
+        embeddings: &[(NodeId, &[f32])],
+    ) -> RedisGraphResult<()> {
+        if embeddings.is_empty() {
+            return Ok(());
+        }
+        let mut params = HashMap::new();
+        params.insert(
+            "ids".to_string(),
+            CypherValue::Array(
+                embeddings
+                    .iter()
+                    .map(|(id, _)| CypherValue::Integer(*id as i64))
+                    .collect(),
+            ),
+        );
+        params.insert(
+            "embeddings".to_string(),
+            CypherValue::Array(
+                embeddings
+                    .iter()
+                    .map(|(_, embedding)| {
+                        CypherValue::Array(
+                            embedding
+                                .iter()
+                                .map(|&value| CypherValue::Double(value.into()))
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            ),
+        );
+
+        let query = |value: &str| {
+            format!(
+                "UNWIND range(0, size($ids) - 1) AS i MATCH (n) WHERE id(n) = $ids[i] SET n.{} = {}",
+                escape_identifier(property),
+                value
+            )
+        };
+        match self.mutate_with_params(&query("vecf32($embeddings[i])"), &params) {
+            Err(error) if is_unknown_function(&error) => {
+                self.mutate_with_params(&query("$embeddings[i]"), &params)
+            }
+            result => result,
+        }
+    }
+
+    /// Returns the embeddings stored in the given property of all nodes with the given label,
+    /// skipping nodes without one.
+    pub fn get_embeddings(
+        &mut self,
+        label: &str,
+        property: &str,
+    ) -> RedisGraphResult<Vec<(NodeId, Vec<f32>)>> {
+        let rows: Vec<(i64, Scalar)> = self.query(&format!(
+            "MATCH (n:{}) WHERE n.{} IS NOT NULL RETURN id(n), n.{}",
+            escape_identifier(label),
+            escape_identifier(property),
+            escape_identifier(property)
+        ))?;
+        rows.into_iter()
+            .map(|(id, embedding)| Ok((id as NodeId, to_f32(&embedding)?)))
+            .collect()
+    }
+
+    /// Returns the embeddings stored in the given property of the nodes with the given IDs,
+    /// in a single query.
+    ///
+    /// Returns one entry per ID, in the same order, which is `None` if there is no node with
+    /// the ID or it has no embedding.
+    pub fn get_embeddings_of(
+        &mut self,
+        property: &str,
+        ids: &[NodeId],
+    ) -> RedisGraphResult<Vec<Option<Vec<f32>>>> {
+        let mut params = HashMap::new();
+        params.insert(
+            "ids".to_string(),
+            CypherValue::Array(
+                ids.iter()
+                    .map(|&id| CypherValue::Integer(id as i64))
+                    .collect(),
+            ),
+        );
+        let embeddings: Vec<Scalar> = self.query_with_params(
+            &format!(
+                "UNWIND $ids AS id OPTIONAL MATCH (n) WHERE id(n) = id RETURN n.{}",
+                escape_identifier(property)
+            ),
+            &params,
+        )?;
+        embeddings
+            .iter()
+            .map(|embedding| match embedding {
+                Scalar::Nil => Ok(None),
+                embedding => to_f32(embedding).map(Some),
+            })
+            .collect()
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Returns the `k` nodes with the given label whose embeddings in the given property are
+    /// closest to the query vector, with their distances, most similar first.
+    ///
+    /// Requires a vector index on the property. The FalkorDB procedure `db.idx.vector.queryNodes`
+    /// is used if the server provides it, and the RedisGraph procedure `db.idx.vector.query` otherwise.
+    pub fn knn(
+        &mut self,
+        label: &str,
+        property: &str,
+        query: &[f32],
+        k: usize,
+    ) -> RedisGraphResult<Vec<(Node, f64)>> {
+        let mut params = HashMap::new();
+        params.insert("label".to_string(), CypherValue::from(label));
+        params.insert("property".to_string(), CypherValue::from(property));
+        params.insert("k".to_string(), CypherValue::Integer(k as i64));
+        params.insert(
+            "query".to_string(),
+            CypherValue::Array(
+                query
+                    .iter()
+                    .map(|&value| CypherValue::Double(value.into()))
+                    .collect(),
+            ),
+        );
+
+        let mut neighbors: Vec<(Node, f64)> = match self.query_with_params(
+            "CALL db.idx.vector.queryNodes($label, $property, $k, vecf32($query)) \
+             YIELD node, score RETURN node, score",
+            &params,
+        ) {
+            Err(error) if is_unknown_procedure(&error) => self.query_with_params(
+                "CALL db.idx.vector.query({type: 'NODE', label: $label, attribute: $property, \
+                 query: vecf32($query), k: $k}) YIELD entity, score RETURN entity, score",
+                &params,
+            )?,
+            result => result?,
+        };
+        neighbors.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        Ok(neighbors)
+    }
+}
+
+// Integers are accepted as well, since arrays written by other clients may contain them.
+fn to_f32(embedding: &Scalar) -> RedisGraphResult<Vec<f32>> {
+    embedding
+        .array_as::<Scalar>()?
+        .into_iter()
+        .map(|value| match value {
+            Scalar::Double(double) => Ok(double as f32),
+            Scalar::Integer(int) => Ok(int as f32),
+            any => client_type_error!(
+                "failed to construct embedding: expected number, found {:?}",
+                any
+            ),
+        })
+        .collect()
+}
@@ -0,0 +1,307 @@
+//! Mapping structs onto nodes.
+//!
+//! An [`Entity`](trait.Entity.html) is a struct stored as a node with a fixed label,
+//! turned into properties with [`ToProperties`](../props/trait.ToProperties.html) and
+//! read back with [`Entity::from_node`](trait.Entity.html#tymethod.from_node).
+//! With the `derive` feature enabled, both can be derived:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # mod example {
+//! # use redisgraph::{Graph, RedisGraphResult};
+//! #[derive(Default, redisgraph::Entity, redisgraph::ToProperties)]
+//! #[redisgraph(label = "Person")]
+//! struct Person {
+//!     name: Option<String>,
+//!     city: Option<String>,
+//! }
+//!
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! // Finds all people living in Berlin.
+//! let berliners = graph.find_like(&Person {
+//!     city: Some("Berlin".to_string()),
+//!     ..Default::default()
+//! })?;
+//! # Ok(())
+//! # }
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use redis::ConnectionLike;
+
+use crate::{
+    import::columnar_set,
+    params::{escape_identifier, CypherValue},
+    pattern::{node, Direction},
+    props::{Props, ToProperties},
+    result_set::Node,
+    soft_delete::DELETED_AT,
+    ttl::to_millis,
+    Graph, RedisGraphError, RedisGraphResult, ResultSet,
+};
+
+/// A struct stored as a node, see the [module documentation](index.html).
+///
+/// With the `derive` feature enabled, this can be derived for structs with named fields.
+/// The label is the struct name unless it is set with `#[redisgraph(label = "...")]`,
+/// and the fields are read with [`Node::get`](../result_set/struct.Node.html#method.get)
+/// using the same `rename` and `skip` attributes as
+/// [`ToProperties`](../props/trait.ToProperties.html). Skipped fields are set to their default.
+///
+/// The field marked with `#[redisgraph(key)]` becomes the [key](#method.key), and relations
+/// are declared on the struct with
+/// `#[redisgraph(relation(rel_type = "WROTE", direction = "outgoing", on_delete = "cascade"))]`,
+/// where `direction` is `outgoing` by default and `on_delete` is `detach` by default.
+/// Lifecycle hooks are methods of the struct returning `RedisGraphResult<()>`, named with
+/// `#[redisgraph(before_save = "validate", after_load = "compute_totals")]` on the struct.
+pub trait Entity: ToProperties + Sized {
+    /// The label of the nodes storing the entity.
+    const LABEL: &'static str;
+
+    /// The relationships of the entity handled specially by
+    /// [`Graph::delete_entity`](../graph/struct.Graph.html#method.delete_entity).
+    const RELATIONS: &'static [Relation] = &[];
+
+    /// Reads the entity from the properties of a node.
+    fn from_node(node: &Node) -> RedisGraphResult<Self>;
+
+    /// Returns the property identifying the entity, and its value.
+    ///
+    /// Entities without a key can't be deleted with
+    /// [`Graph::delete_entity`](../graph/struct.Graph.html#method.delete_entity).
+    fn key(&self) -> Option<(&'static str, CypherValue)> {
+        None
+    }
+
+    /// Called by [`Graph::save_entity`](../graph/struct.Graph.html#method.save_entity) before
+    /// the entity is written, e.g. to validate it or compute derived fields.
+    /// If it fails, the entity is not written.
+    fn before_save(&mut self) -> RedisGraphResult<()> {
+        Ok(())
+    }
+
+    /// Called after the entity was read from a node by the methods of this module,
+    /// e.g. to compute fields that are not stored.
+    fn after_load(&mut self) -> RedisGraphResult<()> {
+        Ok(())
+    }
+}
+
+/// A relationship of an [`Entity`](trait.Entity.html) with a deletion rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Relation {
+    /// The type of the relationships.
+    pub rel_type: &'static str,
+    /// The direction of the relationships, seen from the entity.
+    pub direction: Direction,
+    /// What happens to the relationships and the nodes at their other end
+    /// when the entity is deleted.
+    pub on_delete: OnDelete,
+}
+
+/// What [`Graph::delete_entity`](../graph/struct.Graph.html#method.delete_entity) does with
+/// the relationships of a [`Relation`](struct.Relation.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnDelete {
+    /// Deletes the nodes at the other end of the relationships as well.
+    Cascade,
+    /// Deletes the relationships, keeping the nodes at their other end.
+    /// This is what happens to all relationships that aren't declared.
+    Detach,
+    /// Refuses to delete the entity while it has such relationships.
+    Restrict,
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Returns all entities whose properties equal the ones of the given example,
+    /// leaving out the properties the example doesn't set, like `None` fields.
+    ///
+    /// An example without properties returns all entities of its type.
+    pub fn find_like<E: Entity>(&mut self, example: &E) -> RedisGraphResult<Vec<E>> {
+        let nodes = self.find_nodes(E::LABEL, example)?;
+        nodes.iter().map(load).collect()
+    }
+
+    /// Writes the entity after calling its [`before_save`](../entity/trait.Entity.html#method.before_save) hook.
+    ///
+    /// An entity with a [key](../entity/trait.Entity.html#method.key) is merged on it, so saving
+    /// it again updates the stored properties. An entity without a key is created every time.
+    pub fn save_entity<E: Entity>(&mut self, entity: &mut E) -> RedisGraphResult<()> {
+        entity.before_save()?;
+        match entity.key() {
+            Some((key, value)) => self.merge_node(E::LABEL, &Props::new().set(key, value), entity),
+            None => self.create_node(E::LABEL, entity),
+        }
+        .map(|_| ())
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Writes all given entities in a single query per kind of write, after calling their
+    /// [`before_save`](../entity/trait.Entity.html#method.before_save) hooks.
+    ///
+    /// Entities with a [key](../entity/trait.Entity.html#method.key) are merged on it and
+    /// entities without one are created, like with [`save_entity`](#method.save_entity).
+    /// Unlike there, properties an entity doesn't set, like `None` fields, are removed
+    /// from the stored node. If a hook fails, nothing is written.
+    pub fn save_all<E: Entity>(&mut self, entities: &mut [E]) -> RedisGraphResult<()> {
+        for entity in entities.iter_mut() {
+            entity.before_save()?;
+        }
+
+        let (keyed, unkeyed): (Vec<&E>, Vec<&E>) =
+            entities.iter().partition(|entity| entity.key().is_some());
+        if let Some(first) = keyed.first() {
+            let (key, _) = first.key().unwrap();
+            let keys = keyed
+                .iter()
+                .filter_map(|entity| entity.key())
+                .map(|(_, value)| value)
+                .collect();
+            let mut params = HashMap::new();
+            let set_clause = entity_columns(&keyed, &mut params);
+            params.insert("keys".to_string(), CypherValue::Array(keys));
+            let query = format!(
+                "UNWIND range(0, size($keys) - 1) AS i MERGE (n:{} {{{}: $keys[i]}}){}",
+                escape_identifier(E::LABEL),
+                escape_identifier(key),
+                set_clause
+            );
+            let _: ResultSet = self.query_mutation_with_params(&query, &params)?;
+        }
+        if !unkeyed.is_empty() {
+            let mut params = HashMap::new();
+            let set_clause = entity_columns(&unkeyed, &mut params);
+            let query = format!(
+                "UNWIND range(0, {}) AS i CREATE (n:{}){}",
+                unkeyed.len() - 1,
+                escape_identifier(E::LABEL),
+                set_clause
+            );
+            let _: ResultSet = self.query_mutation_with_params(&query, &params)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the entities stored in the nodes with the given IDs in a single query,
+    /// calling their [`after_load`](../entity/trait.Entity.html#method.after_load) hooks.
+    ///
+    /// Returns one entry per ID, in the same order, which is `None` if there is no node
+    /// with the ID and the label of the entity, or if the node is marked as deleted while
+    /// [soft deletion](#method.set_soft_delete) is enabled.
+    pub fn load_all<E: Entity>(&mut self, ids: &[u64]) -> RedisGraphResult<Vec<Option<E>>> {
+        let mut params = HashMap::new();
+        params.insert(
+            "ids".to_string(),
+            CypherValue::Array(
+                ids.iter()
+                    .map(|&id| CypherValue::Integer(id as i64))
+                    .collect(),
+            ),
+        );
+        let nodes: Vec<Option<Node>> = self.query_with_params(
+            &format!(
+                "UNWIND $ids AS id OPTIONAL MATCH {} WHERE id(`n`) = id{} RETURN `n`",
+                node("n").label(E::LABEL),
+                self.soft_delete_filter(&["n"])
+            ),
+            &params,
+        )?;
+        nodes
+            .iter()
+            .map(|node| node.as_ref().map(load).transpose())
+            .collect()
+    }
+
+    /// Deletes the node storing the given entity, found by its [key](../entity/trait.Entity.html#method.key),
+    /// according to the [relations](../entity/trait.Entity.html#associatedconstant.RELATIONS)
+    /// of the entity. Returns the number of deleted nodes, including cascaded ones.
+    ///
+    /// Fails with [`DeleteRestricted`](../error/enum.RedisGraphError.html#variant.DeleteRestricted)
+    /// without deleting anything if a restricting relationship exists. Restrictions are checked
+    /// before deleting, so a restricting relationship created in between is deleted as well.
+    /// Cascades only go one level deep.
+    ///
+    /// With [soft deletion](#method.set_soft_delete) enabled, the entity and the cascaded nodes
+    /// are only marked as deleted, and all relationships are kept. Entities that are already
+    /// marked are not found, and marked nodes are not counted again.
+    pub fn delete_entity<E: Entity>(&mut self, entity: &E) -> RedisGraphResult<usize> {
+        let (key, value) = match entity.key() {
+            Some(key) => key,
+            None => return client_type_error!("failed to delete entity: {} has no key", E::LABEL),
+        };
+        let matched = format!(
+            "MATCH {} WHERE `n`.{} = $key{}",
+            node("n").label(E::LABEL),
+            escape_identifier(key),
+            self.soft_delete_filter(&["n"])
+        );
+        let mut params = HashMap::new();
+        params.insert("key".to_string(), value);
+
+        for relation in E::RELATIONS {
+            if relation.on_delete == OnDelete::Restrict {
+                let pattern = node("n")
+                    .rel_as("r", relation.rel_type, relation.direction)
+                    .node("m");
+                let count: usize = self.query_with_params(
+                    &format!("{} OPTIONAL MATCH {} RETURN count(`r`)", matched, pattern),
+                    &params,
+                )?;
+                if count > 0 {
+                    return Err(RedisGraphError::DeleteRestricted {
+                        rel_type: relation.rel_type.to_string(),
+                        count,
+                    });
+                }
+            }
+        }
+
+        let mut query = matched;
+        let mut deleted = vec!["`n`".to_string()];
+        for relation in E::RELATIONS {
+            if relation.on_delete == OnDelete::Cascade {
+                let variable = format!("c{}", deleted.len());
+                let pattern = node("n")
+                    .rel(relation.rel_type, relation.direction)
+                    .node(&variable);
+                query.push_str(&format!(" OPTIONAL MATCH {}", pattern));
+                deleted.push(escape_identifier(&variable));
+            }
+        }
+        if self.soft_delete() {
+            params.insert("now".to_string(), to_millis(SystemTime::now()).into());
+            query.push_str(&format!(
+                " WITH [{}] AS marked UNWIND marked AS d WITH DISTINCT d \
+                 WHERE d IS NOT NULL AND d.{1} IS NULL SET d.{1} = $now RETURN count(d)",
+                deleted.join(", "),
+                DELETED_AT
+            ));
+            return self.query_mutation_with_params(&query, &params);
+        }
+        query.push_str(&format!(" DETACH DELETE {}", deleted.join(", ")));
+
+        let result_set: ResultSet = self.query_mutation_with_params(&query, &params)?;
+        Ok(result_set.statistics.count("Nodes deleted"))
+    }
+}
+
+// Reads an entity from a node and calls its `after_load` hook.
+fn load<E: Entity>(node: &Node) -> RedisGraphResult<E> {
+    let mut entity = E::from_node(node)?;
+    entity.after_load()?;
+    Ok(entity)
+}
+
+// Passes the properties of the entities as array parameters, see `columnar_set`.
+fn entity_columns<E: Entity>(entities: &[&E], params: &mut HashMap<String, CypherValue>) -> String {
+    let props: Vec<Props> = entities
+        .iter()
+        .map(|entity| entity.to_properties())
+        .collect();
+    let props: Vec<&Props> = props.iter().collect();
+    columnar_set("n", &props, params)
+}
@@ -1,5 +1,7 @@
 use redis::RedisError;
 
+use crate::registry::ParamType;
+
 /// Common error type for this crate.
 #[derive(Debug)]
 pub enum RedisGraphError {
@@ -15,21 +17,154 @@ pub enum RedisGraphError {
 
     /// Returned if a label name was not found in the graph's internal registry.
     ///
-    /// This error is taken care of by the implementation and should never reach your code.
+    /// This error is taken care of by the implementation and only reaches your code
+    /// if the graph uses a [static schema](../graph/struct.Graph.html#method.use_static_schema).
     LabelNotFound,
     /// Returned if a relationship type name was not found in the graph's internal registry.
     ///
-    /// This error is taken care of by the implementation and should never reach your code.
+    /// This error is taken care of by the implementation and only reaches your code
+    /// if the graph uses a [static schema](../graph/struct.Graph.html#method.use_static_schema).
     RelationshipTypeNotFound,
     /// Returned if a property key name was not found in the graph's internal registry.
     ///
-    /// This error is taken care of by the implementation and should never reach your code.
+    /// This error is taken care of by the implementation and only reaches your code
+    /// if the graph uses a [static schema](../graph/struct.Graph.html#method.use_static_schema).
     PropertyKeyNotFound,
 
     /// Returned if you requested a [`String`](https://doc.rust-lang.org/std/string/struct.String.html) and the database responded with bytes that are invalid UTF-8.
     ///
     /// If you don't care about whether the data is valid UTF-8, consider requesting a [`RedisString`](../result_set/struct.RedisString.html) instead.
     InvalidUtf8,
+
+    /// Returned if a query parameter name is not a valid identifier.
+    ///
+    /// Parameter names must start with a letter or an underscore
+    /// and may only contain letters, digits and underscores.
+    InvalidParameterName(String),
+    /// Returned if the value of the query parameter with the given name is or contains
+    /// a `NaN` or infinite double, which can't be written as a Cypher literal.
+    NonFiniteParameter(String),
+    /// Returned if a literal of a [`QueryBuilder`](../query/struct.QueryBuilder.html) expression,
+    /// formatted as given, is or contains a `NaN` or infinite double.
+    NonFiniteLiteral(String),
+
+    /// Returned if a [`QueryFragment`](../fragment/struct.QueryFragment.html),
+    /// a [registered query](../registry/struct.QueryRegistry.html) or a query whose
+    /// [parameters are validated](../params/fn.validate_params.html) references
+    /// a parameter that has no value.
+    UnboundParameter(String),
+    /// Returned if a [`QueryFragment`](../fragment/struct.QueryFragment.html) binds
+    /// different values to the same parameter.
+    ConflictingParameter(String),
+
+    /// Returned if a value is passed for a parameter that the query doesn't use.
+    UnusedParameter(String),
+    /// Returned if a value passed for a parameter of a
+    /// [registered query](../registry/struct.QueryRegistry.html) has the wrong type.
+    ParameterTypeMismatch {
+        /// The name of the parameter.
+        param: String,
+        /// The declared type of the parameter.
+        expected: ParamType,
+    },
+    /// Returned if a query is registered under a name that is already taken.
+    DuplicateQuery(String),
+    /// Returned if no query is registered under the requested name.
+    UnknownQuery(String),
+
+    /// Returned by [`Graph::execute_script`](../graph/struct.Graph.html#method.execute_script)
+    /// if one of the statements of the script failed.
+    ScriptStatementFailed {
+        /// The zero-based index of the failed statement.
+        index: usize,
+        /// The failed statement.
+        statement: String,
+        /// The error returned for the statement.
+        error: Box<RedisGraphError>,
+    },
+
+    /// Returned by [`Scalar::array_as`](../result_set/enum.Scalar.html#method.array_as)
+    /// if an element of the array can't be converted.
+    ArrayElementMismatch {
+        /// The zero-based index of the element.
+        index: usize,
+        /// The variant of the element, e.g. `"String"`.
+        found: &'static str,
+        /// The error returned for the element.
+        error: Box<RedisGraphError>,
+    },
+
+    /// Returned by the [lifecycle hooks](../entity/trait.Entity.html#method.before_save)
+    /// of an entity, e.g. if validation failed.
+    InvalidEntity(String),
+    /// Returned by [`Graph::delete_entity`](../graph/struct.Graph.html#method.delete_entity)
+    /// if the entity has relationships of a type that restricts deletion.
+    DeleteRestricted {
+        /// The type of the restricting relationships.
+        rel_type: String,
+        /// The number of restricting relationships.
+        count: usize,
+    },
+
+    /// Returned by [`split_create`](../chunk/fn.split_create.html) if a statement
+    /// is not a single `CREATE` clause with comma-separated patterns.
+    UnsplittableStatement(String),
+
+    /// Returned by [`Graph::import_edges`](../graph/struct.Graph.html#method.import_edges)
+    /// if an endpoint's temporary ID is not in the ID map, formatted with `Debug`.
+    UnresolvedImportId(String),
+
+    /// Returned by [`Graph::with_lock`](../graph/struct.Graph.html#method.with_lock)
+    /// if the lock with the given key could not be acquired in time.
+    LockNotAcquired(String),
+
+    /// Returned if a [`NodeRef`](../reference/struct.NodeRef.html) was resolved against a graph
+    /// other than the one it points into.
+    GraphMismatch {
+        /// The name of the graph the reference points into.
+        expected: String,
+        /// The name of the graph the reference was resolved against.
+        found: String,
+    },
+
+    /// Returned by [`Graph::open_with_create_mode`](../graph/struct.Graph.html#method.open_with_create_mode)
+    /// with [`CreateMode::Never`](../graph/enum.CreateMode.html#variant.Never)
+    /// if there is no graph with the given name.
+    GraphNotFound(String),
+
+    /// Returned by [`GraphPool::get`](../pool/struct.GraphPool.html#method.get) if no connection
+    /// became available within the checkout timeout.
+    PoolTimeout,
+    /// Returned by [`GraphPool::get`](../pool/struct.GraphPool.html#method.get) once the pool
+    /// is [shut down](../pool/struct.GraphPool.html#method.shutdown).
+    PoolShutDown,
+
+    /// Returned by [`graphql::node_query`](../graphql/fn.node_query.html) if a GraphQL field
+    /// can't be mapped onto Cypher, with the name of the offending field or argument.
+    UnsupportedGraphqlField(String),
+
+    /// Returned by a [`DualWriteGraph`](../dual_write/struct.DualWriteGraph.html) with the
+    /// [strict policy](../dual_write/enum.DualWritePolicy.html#variant.Strict) if a mutation
+    /// was applied to the primary graph but failed on the secondary sink.
+    SecondaryWriteFailed(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Returned by a [`PropertyCodec`](../codec/trait.PropertyCodec.html) if a value
+    /// can't be encoded into or decoded from a property.
+    CodecError(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl RedisGraphError {
+    /// Returns `true` if the request that failed with this error may or may not have been
+    /// applied, see [`retry::is_ambiguous`](../retry/fn.is_ambiguous.html).
+    pub fn is_ambiguous(&self) -> bool {
+        crate::retry::is_ambiguous(self)
+    }
+
+    /// Returns `true` if the request that failed with this error was rejected without being
+    /// applied, but may succeed later, see [`retry::is_transient`](../retry/fn.is_transient.html).
+    pub fn is_transient(&self) -> bool {
+        crate::retry::is_transient(self)
+    }
 }
 
 impl From<RedisError> for RedisGraphError {
@@ -38,6 +173,24 @@ impl From<RedisError> for RedisGraphError {
     }
 }
 
+// Returns `true` if the request failed because the server doesn't provide a procedure,
+// e.g. one of the `algo.*` procedures on an older version.
+pub(crate) fn is_unknown_procedure(error: &RedisGraphError) -> bool {
+    match error {
+        RedisGraphError::RedisError(error) => error.to_string().contains("not registered"),
+        _ => false,
+    }
+}
+
+// Returns `true` if the request failed because the server doesn't provide a function,
+// e.g. `vecf32` on servers without vector support.
+pub(crate) fn is_unknown_function(error: &RedisGraphError) -> bool {
+    match error {
+        RedisGraphError::RedisError(error) => error.to_string().contains("Unknown function"),
+        _ => false,
+    }
+}
+
 /// Common result type for this crate.
 pub type RedisGraphResult<T> = Result<T, RedisGraphError>;
 
@@ -0,0 +1,75 @@
+use std::time::SystemTime;
+
+use crate::result_set::Statistics;
+
+/// Emitted after a mutation was successfully executed on a [`Graph`](../graph/struct.Graph.html).
+///
+/// Register a listener with [`Graph::on_mutation`](../graph/struct.Graph.html#method.on_mutation).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutationEvent {
+    /// The name of the graph the mutation was executed on.
+    pub graph_name: String,
+    /// The executed query, including the `CYPHER` parameter header if there is one.
+    pub query: String,
+    /// The statistics returned by the database.
+    pub statistics: Statistics,
+    /// The time at which the mutation completed.
+    pub timestamp: SystemTime,
+}
+
+/// A callback registered with [`Graph::on_mutation`](../graph/struct.Graph.html#method.on_mutation).
+pub type MutationListener = Box<dyn Fn(&MutationEvent) + Send + Sync>;
+
+/// Emitted when the connection of a [`Graph`](../graph/struct.Graph.html) fails or is replaced.
+///
+/// Register a listener with [`Graph::on_connection_event`](../graph/struct.Graph.html#method.on_connection_event).
+/// [`GraphPool`](../pool/struct.GraphPool.html), [`GraphClient`](../client/struct.GraphClient.html)
+/// and [`ReconnectingGraph`](../reconnect/struct.ReconnectingGraph.html) have an
+/// `on_connection_event` method as well.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// A request failed with an I/O error, e.g. because the server closed the connection.
+    ///
+    /// Only the first failure is reported until the connection is replaced.
+    Disconnected {
+        /// The name of the graph the request was sent for.
+        graph_name: String,
+        /// The error returned by the connection.
+        error: String,
+        /// The time at which the request failed.
+        timestamp: SystemTime,
+    },
+    /// The connection was replaced with
+    /// [`Graph::reconnect`](../graph/struct.Graph.html#method.reconnect),
+    /// or by a `ReconnectingGraph` after the old one broke.
+    Reconnected {
+        /// The name of the graph.
+        graph_name: String,
+        /// The time at which the connection was replaced.
+        timestamp: SystemTime,
+    },
+    /// A `ReconnectingGraph` failed to open a new connection in place of a broken one.
+    ///
+    /// The next request tries again.
+    ReconnectFailed {
+        /// The name of the graph.
+        graph_name: String,
+        /// The error returned while opening the connection.
+        error: String,
+        /// The time at which opening the connection failed.
+        timestamp: SystemTime,
+    },
+    /// A `GraphPool` failed to lend out a connection, because opening one failed or none
+    /// became available within the checkout timeout.
+    CheckoutFailed {
+        /// The name of the graph the connection was requested for.
+        graph_name: String,
+        /// The error returned by the checkout.
+        error: String,
+        /// The time at which the checkout failed.
+        timestamp: SystemTime,
+    },
+}
+
+/// A callback registered with [`Graph::on_connection_event`](../graph/struct.Graph.html#method.on_connection_event).
+pub type ConnectionListener = Box<dyn Fn(&ConnectionEvent) + Send + Sync>;
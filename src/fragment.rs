@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use redisgraph_cypher::referenced_params;
+
+use crate::{
+    params::{escape_identifier, is_valid_parameter_name, CypherValue},
+    RedisGraphError, RedisGraphResult,
+};
+
+/// A piece of a Cypher query together with the parameters bound for it.
+///
+/// The query text of a fragment can only come from string literals, quoted identifiers
+/// and other fragments, so untrusted input can only become part of a query as a bound parameter.
+/// Parameters referenced as `$name` are tracked, and a fragment with unbound or conflicting
+/// parameters is rejected before it is sent to the database.
+///
+/// ```
+/// use redisgraph::fragment::QueryFragment;
+///
+/// let min_age: Option<i64> = Some(18);
+/// let name_prefix: Option<&str> = None;
+///
+/// let mut conditions = Vec::new();
+/// if let Some(min_age) = min_age {
+///     conditions.push(QueryFragment::new("p.age >= $min_age").bind("min_age", min_age));
+/// }
+/// if let Some(name_prefix) = name_prefix {
+///     conditions.push(QueryFragment::new("p.name STARTS WITH $prefix").bind("prefix", name_prefix));
+/// }
+///
+/// let query = QueryFragment::new("MATCH (p:Person) WHERE")
+///     .append(QueryFragment::join(conditions, " AND "))
+///     .append(QueryFragment::new("RETURN p"));
+/// let (text, params) = query.into_parts().unwrap();
+/// assert_eq!(text, "MATCH (p:Person) WHERE p.age >= $min_age RETURN p");
+/// assert_eq!(params.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QueryFragment {
+    text: String,
+    params: HashMap<String, CypherValue>,
+    conflicts: Vec<String>,
+}
+
+impl QueryFragment {
+    /// Creates a fragment from a string literal.
+    pub fn new(text: &'static str) -> Self {
+        Self::from_trusted(text.to_string())
+    }
+
+    /// Creates a fragment consisting of a single quoted identifier,
+    /// e.g. a property key or label chosen at runtime.
+    pub fn identifier(identifier: &str) -> Self {
+        Self::from_trusted(escape_identifier(identifier))
+    }
+
+    /// Creates a fragment from text that is known not to contain untrusted input.
+    pub(crate) fn from_trusted(text: String) -> Self {
+        Self {
+            text,
+            ..Self::default()
+        }
+    }
+
+    /// Binds a value to the parameter with the given name.
+    ///
+    /// Binding a different value to a parameter that is already bound
+    /// causes [`into_parts`](#method.into_parts) to fail.
+    pub fn bind<V: Into<CypherValue>>(mut self, name: &str, value: V) -> Self {
+        self.insert_param(name.to_string(), value.into());
+        self
+    }
+
+    /// Appends another fragment, separated by a space, and takes over its parameters.
+    pub fn append(mut self, other: QueryFragment) -> Self {
+        if !self.text.is_empty() && !other.text.is_empty() {
+            self.text.push(' ');
+        }
+        self.text.push_str(&other.text);
+        self.conflicts.extend(other.conflicts);
+        for (name, value) in other.params {
+            self.insert_param(name, value);
+        }
+        self
+    }
+
+    /// Appends the property with the given key of the given variable, e.g. ``p.`name` ``,
+    /// separated by a space.
+    ///
+    /// The key is always quoted, the variable only if it isn't a plain identifier.
+    pub fn append_property(self, variable: &str, key: &str) -> Self {
+        let variable = if is_valid_parameter_name(variable) {
+            variable.to_string()
+        } else {
+            escape_identifier(variable)
+        };
+        self.append(Self::from_trusted(format!(
+            "{}.{}",
+            variable,
+            escape_identifier(key)
+        )))
+    }
+
+    /// Concatenates the given fragments with the given separator between them, e.g. `" AND "`.
+    ///
+    /// Empty fragments are left out.
+    pub fn join<I: IntoIterator<Item = QueryFragment>>(
+        fragments: I,
+        separator: &'static str,
+    ) -> Self {
+        let mut joined = Self::default();
+        let fragments = fragments
+            .into_iter()
+            .filter(|fragment| !fragment.is_empty());
+        for (i, fragment) in fragments.enumerate() {
+            if i > 0 {
+                joined.text.push_str(separator);
+            }
+            joined.text.push_str(&fragment.text);
+            joined.conflicts.extend(fragment.conflicts);
+            for (name, value) in fragment.params {
+                joined.insert_param(name, value);
+            }
+        }
+        joined
+    }
+
+    /// Wraps the fragment in parentheses.
+    pub fn parenthesized(mut self) -> Self {
+        self.text = format!("({})", self.text);
+        self
+    }
+
+    /// Returns `true` if the fragment contains no query text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the query text of this fragment.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the parameters bound so far.
+    pub fn params(&self) -> &HashMap<String, CypherValue> {
+        &self.params
+    }
+
+    /// Returns the names of the parameters referenced in the query text without a bound value.
+    pub fn unbound_params(&self) -> Vec<String> {
+        let mut unbound: Vec<String> = referenced_params(&self.text)
+            .into_iter()
+            .filter(|name| !self.params.contains_key(name))
+            .collect();
+        unbound.sort();
+        unbound.dedup();
+        unbound
+    }
+
+    /// Returns the query text and its parameters, ready to be passed to
+    /// [`Graph::query_with_params`](../graph/struct.Graph.html#method.query_with_params).
+    ///
+    /// Fails if a referenced parameter is not bound or a parameter was bound to different values.
+    pub fn into_parts(self) -> RedisGraphResult<(String, HashMap<String, CypherValue>)> {
+        if let Some(name) = self.conflicts.first() {
+            return Err(RedisGraphError::ConflictingParameter(name.clone()));
+        }
+        if let Some(name) = self.unbound_params().into_iter().next() {
+            return Err(RedisGraphError::UnboundParameter(name));
+        }
+        Ok((self.text, self.params))
+    }
+
+    fn insert_param(&mut self, name: String, value: CypherValue) {
+        match self.params.get(&name) {
+            Some(existing) if *existing != value => self.conflicts.push(name),
+            Some(_) => {}
+            None => {
+                self.params.insert(name, value);
+            }
+        }
+    }
+}
@@ -1,43 +1,164 @@
-use redis::{Connection, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use redis::{Connection, ConnectionLike, FromRedisValue, Value};
 
 use crate::{
-    assignments::FromTable,
-    result_set::{Column, FromRedisValueWithGraph, Scalar, Statistics, Take},
-    server_type_error, RedisGraphError, RedisGraphResult, RedisString, ResultSet,
+    aggregate::Aggregate,
+    assignments::{FromRow, FromTable},
+    commands::CommandNames,
+    converters::ConverterRegistry,
+    events::{ConnectionEvent, ConnectionListener, MutationEvent, MutationListener},
+    fragment::QueryFragment,
+    labels::{LabelMatch, Labels},
+    latency::QueryStats,
+    otel,
+    params::{prepare_query, CypherValue},
+    pattern::{node, Direction},
+    profile::{ExecutionPlan, PlanAssertions, ProfileComparison, TimingStats},
+    props::ToProperties,
+    reference::NodeRef,
+    result_set::{
+        parse_column_names, Column, DecodeContext, FromRedisValueWithGraph, Node, RowError, Scalar,
+        Statistics, Take,
+    },
+    retry::RetryPolicy,
+    schema::{ConstraintKind, ConstraintSpec, SchemaMapping},
+    server_type_error,
+    soft_delete::DELETED_AT,
+    RedisGraphError, RedisGraphResult, RedisString, ResultSet,
 };
 
+/// Whether opening a graph creates it if it doesn't exist yet,
+/// passed to [`Graph::open_with_create_mode`](struct.Graph.html#method.open_with_create_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CreateMode {
+    /// Creates the graph with a dummy node that is deleted again, whether it exists or not.
+    /// This is what [`Graph::open`](struct.Graph.html#method.open) does.
+    Always,
+    /// Checks whether the graph exists first and only creates it if it doesn't,
+    /// so opening an existing graph doesn't write to it.
+    IfMissing,
+    /// Fails with [`GraphNotFound`](../error/enum.RedisGraphError.html#variant.GraphNotFound)
+    /// if the graph doesn't exist, e.g. because its name has a typo.
+    Never,
+}
+
 /// Represents a single graph in the database.
-pub struct Graph {
-    conn: Connection,
+///
+/// The graph talks to the database over a `redis::Connection` by default, but accepts any
+/// `redis::ConnectionLike`, e.g. a pooled connection or a mock for tests.
+pub struct Graph<C = Connection> {
+    conn: C,
     name: String,
+    commands: CommandNames,
 
     labels: Vec<RedisString>,
     relationship_types: Vec<RedisString>,
     property_keys: Vec<RedisString>,
+    static_schema: bool,
+    reply_diagnostics: bool,
+    lenient_booleans: bool,
+    validate_params: bool,
+    soft_delete: bool,
+    retry_policy: Option<RetryPolicy<C>>,
+    stats: Option<QueryStats>,
+    converters: Option<Arc<ConverterRegistry>>,
+
+    mutation_listeners: Vec<MutationListener>,
+    connection_listeners: Vec<ConnectionListener>,
+    disconnected: bool,
 }
 
-impl Graph {
+impl<C: ConnectionLike> Graph<C> {
     /// Opens the graph with the given name from the database.
     ///
     /// If the graph does not already exist, creates a new graph with the given name.
-    pub fn open(conn: Connection, name: String) -> RedisGraphResult<Self> {
-        let mut graph = Self {
-            conn,
-            name,
-            labels: Vec::new(),
-            relationship_types: Vec::new(),
-            property_keys: Vec::new(),
+    pub fn open(conn: C, name: String) -> RedisGraphResult<Self> {
+        Self::open_with_commands(conn, name, CommandNames::default())
+    }
+
+    /// Same as [`open`](#method.open), but uses the given command names instead of `GRAPH.QUERY` etc.
+    pub fn open_with_commands(
+        conn: C,
+        name: String,
+        commands: CommandNames,
+    ) -> RedisGraphResult<Self> {
+        Self::open_with_create_mode(conn, name, commands, CreateMode::Always)
+    }
+
+    /// Same as [`open_with_commands`](#method.open_with_commands), but only creates the graph
+    /// as the given mode allows.
+    ///
+    /// Whether the graph exists is checked with `EXISTS`, since graphs are stored in the key
+    /// with their name.
+    pub fn open_with_create_mode(
+        mut conn: C,
+        name: String,
+        commands: CommandNames,
+        mode: CreateMode,
+    ) -> RedisGraphResult<Self> {
+        let create = match mode {
+            CreateMode::Always => true,
+            CreateMode::IfMissing | CreateMode::Never => {
+                let exists: bool = redis::cmd("EXISTS").arg(&name).query(&mut conn)?;
+                if !exists && mode == CreateMode::Never {
+                    return Err(RedisGraphError::GraphNotFound(name));
+                }
+                !exists
+            }
         };
 
+        let mut graph = Self::attach(conn, name, commands, SchemaMapping::default());
+
         // Create a dummy node and delete it again.
         // This ensures that an empty graph is created and `delete()`
         // will succeed if the graph did not already exist.
-        graph.mutate("CREATE (dummy:__DUMMY_LABEL__)")?;
-        graph.mutate("MATCH (dummy:__DUMMY_LABEL__) DELETE dummy")?;
+        if create {
+            graph.mutate("CREATE (dummy:__DUMMY_LABEL__)")?;
+            graph.mutate("MATCH (dummy:__DUMMY_LABEL__) DELETE dummy")?;
+        }
 
         Ok(graph)
     }
 
+    // Creates a handle for a graph that is known to exist, without sending any request.
+    // The names in the mapping are updated as usual when they become outdated.
+    pub(crate) fn attach(
+        conn: C,
+        name: String,
+        commands: CommandNames,
+        mapping: SchemaMapping,
+    ) -> Self {
+        Self {
+            conn,
+            name,
+            commands,
+            labels: mapping.labels,
+            relationship_types: mapping.relationship_types,
+            property_keys: mapping.property_keys,
+            static_schema: false,
+            reply_diagnostics: false,
+            lenient_booleans: true,
+            validate_params: false,
+            soft_delete: false,
+            retry_policy: None,
+            stats: None,
+            converters: None,
+            mutation_listeners: Vec::new(),
+            connection_listeners: Vec::new(),
+            disconnected: false,
+        }
+    }
+
+    // Returns the connection along with the names known so far, and whether it broke.
+    pub(crate) fn detach(self) -> (C, SchemaMapping, bool) {
+        let mapping = self.schema_mapping();
+        (self.conn, mapping, self.disconnected)
+    }
+
     /// Executes the given query and returns its return values.
     ///
     /// Only use this for queries with a `RETURN` statement.
@@ -45,6 +166,17 @@ impl Graph {
         self.query_with_statistics(query).map(|(value, _)| value)
     }
 
+    // Same as `query`, but returns the names of the returned columns along with the result set.
+    pub(crate) fn query_with_column_names(
+        &mut self,
+        query: &str,
+    ) -> RedisGraphResult<(Vec<String>, ResultSet)> {
+        let response: Value = self.request(query)?;
+        let column_names = parse_column_names(&response)?;
+        let result_set = self.get_result_set(response)?;
+        Ok((column_names, result_set))
+    }
+
     /// Same as [`query`](#method.query), but also returns statistics about the query along with its return values.
     pub fn query_with_statistics<T: FromTable>(
         &mut self,
@@ -52,10 +184,73 @@ impl Graph {
     ) -> RedisGraphResult<(T, Statistics)> {
         let response: Value = self.request(query)?;
         let result_set = self.get_result_set(response)?;
-        let value = T::from_table(&result_set)?;
+        let value = self.decode_table(&result_set)?;
         Ok((value, result_set.statistics))
     }
 
+    /// Same as [`query`](#method.query), but sends the query as a read-only query, with
+    /// `GRAPH.RO_QUERY` by default, so the server rejects it if it would modify the graph.
+    ///
+    /// Requires RedisGraph 2.2.8 or later.
+    pub fn ro_query<T: FromTable>(&mut self, query: &str) -> RedisGraphResult<T> {
+        let response: Value = self.ro_request(query)?;
+        let result_set = self.get_result_set(response)?;
+        self.decode_table(&result_set)
+    }
+
+    /// Same as [`ro_query`](#method.ro_query), but passes the given parameters to the query.
+    pub fn ro_query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.ro_query(&self.prepare_query(query, params)?)
+    }
+
+    /// Same as [`query`](#method.query), but skips rows that fail to convert instead of failing entirely.
+    ///
+    /// Returns the converted rows along with an error for every skipped row.
+    /// Use this when a few nodes may have malformed properties.
+    pub fn query_lossy<T: FromRow>(
+        &mut self,
+        query: &str,
+    ) -> RedisGraphResult<(Vec<T>, Vec<RowError>)> {
+        Ok(self.query_result_set(query)?.parse_rows_lossy())
+    }
+
+    /// Executes the given query and returns the raw `--compact` response of the database.
+    ///
+    /// Use this to handle responses this crate can't parse yet.
+    /// Label, relationship type and property key IDs can be resolved with
+    /// [`labels`](#method.labels), [`relationship_types`](#method.relationship_types) and
+    /// [`property_keys`](#method.property_keys) after calling the corresponding `update_*` methods.
+    pub fn raw_query(&mut self, query: &str) -> RedisGraphResult<Value> {
+        self.request(query)
+    }
+
+    /// Executes the given query and returns its result set without converting it to a Rust type.
+    pub fn query_result_set(&mut self, query: &str) -> RedisGraphResult<ResultSet> {
+        let response: Value = self.request(query)?;
+        self.get_result_set(response)
+    }
+
+    /// Same as [`query`](#method.query), but passes the given parameters to the query.
+    ///
+    /// Parameters are referenced in the query as `$name`.
+    pub fn query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        self.query(&self.prepare_query(query, params)?)
+    }
+
+    /// Same as [`query`](#method.query), but takes the query text and parameters from the given fragment.
+    pub fn query_fragment<T: FromTable>(&mut self, fragment: QueryFragment) -> RedisGraphResult<T> {
+        let (query, params) = fragment.into_parts()?;
+        self.query_with_params(&query, &params)
+    }
+
     /// Executes the given query while not returning any values.
     ///
     /// If you want to mutate the graph and retrieve values from it
@@ -68,17 +263,286 @@ impl Graph {
     pub fn mutate_with_statistics(&mut self, query: &str) -> RedisGraphResult<Statistics> {
         let response: Value = self.request(query)?;
         let result_set = self.get_result_set(response)?;
+        self.emit_mutation(query, &result_set.statistics);
         Ok(result_set.statistics)
     }
 
+    /// Registers a callback that is invoked after every successful call to
+    /// [`mutate`](#method.mutate) and its variants, [`create_node`](#method.create_node)
+    /// and [`merge_node`](#method.merge_node).
+    ///
+    /// Use this to e.g. invalidate caches without wrapping every call.
+    /// Mutations performed through [`query`](#method.query) are not reported.
+    pub fn on_mutation<F>(&mut self, listener: F)
+    where
+        F: Fn(&MutationEvent) + Send + Sync + 'static,
+    {
+        self.mutation_listeners.push(Box::new(listener));
+    }
+
+    /// Registers a callback that is invoked when a request fails because of a broken connection
+    /// and when the connection is replaced with [`reconnect`](#method.reconnect).
+    ///
+    /// Use this to e.g. alert on flapping connections.
+    pub fn on_connection_event<F>(&mut self, listener: F)
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.connection_listeners.push(Box::new(listener));
+    }
+
+    /// Replaces the connection to the database, e.g. after the old one was dropped by the server.
+    ///
+    /// The schema and all other state of this handle are kept.
+    pub fn reconnect(&mut self, conn: C) {
+        self.conn = conn;
+        self.disconnected = false;
+        self.emit_connection(ConnectionEvent::Reconnected {
+            graph_name: self.name.clone(),
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    /// Same as [`mutate`](#method.mutate), but passes the given parameters to the query.
+    ///
+    /// Parameters are referenced in the query as `$name`.
+    pub fn mutate_with_params(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        self.mutate(&self.prepare_query(query, params)?)
+    }
+
+    /// Same as [`mutate`](#method.mutate), but takes the query text and parameters from the given fragment.
+    pub fn mutate_fragment(&mut self, fragment: QueryFragment) -> RedisGraphResult<()> {
+        let (query, params) = fragment.into_parts()?;
+        self.mutate_with_params(&query, &params)
+    }
+
+    /// Creates a node with the given labels and properties and returns it.
+    pub fn create_node<L: Into<Labels>, P: ToProperties + ?Sized>(
+        &mut self,
+        labels: L,
+        props: &P,
+    ) -> RedisGraphResult<Node> {
+        let labels: Labels = labels.into();
+        let (map_pattern, params) = props.to_properties().to_map_pattern("p");
+        self.query_mutation_with_params(
+            &format!("CREATE (n{} {}) RETURN n", labels, map_pattern),
+            &params,
+        )
+    }
+
+    /// Finds the node with the given labels and key properties, creating it if it does not exist,
+    /// sets the given properties on it and returns it.
+    ///
+    /// The key properties must not be `null`.
+    pub fn merge_node<L: Into<Labels>, K: ToProperties + ?Sized, P: ToProperties + ?Sized>(
+        &mut self,
+        labels: L,
+        key_props: &K,
+        props: &P,
+    ) -> RedisGraphResult<Node> {
+        let (map_pattern, mut params) = key_props.to_properties().to_map_pattern("k");
+        let (keys, set_params) = props.to_properties().to_params("p");
+        params.extend(set_params);
+
+        let labels: Labels = labels.into();
+        let mut query = format!("MERGE (n{} {})", labels, map_pattern);
+        if !keys.is_empty() {
+            let assignments = keys
+                .iter()
+                .map(|(key, param_name)| format!("n.{} = ${}", key, param_name))
+                .collect::<Vec<String>>();
+            query.push_str(&format!(" SET {}", assignments.join(", ")));
+        }
+        query.push_str(" RETURN n");
+
+        self.query_mutation_with_params(&query, &params)
+    }
+
+    /// Returns all nodes matching the given labels whose properties equal the given ones.
+    ///
+    /// ```no_run
+    /// # use redisgraph::{Graph, RedisGraphResult, labels::{LabelMatch, Labels}, props::Props};
+    /// # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+    /// // Nodes labeled both `Person` and `Employee`.
+    /// let employees = graph.find_nodes(&["Person", "Employee"], &Props::new())?;
+    /// // Nodes labeled `Customer` or `Supplier` named Alice.
+    /// let partners = graph.find_nodes(
+    ///     LabelMatch::Any(Labels::from(&["Customer", "Supplier"])),
+    ///     &Props::new().set("name", "Alice"),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_nodes<L: Into<LabelMatch>, P: ToProperties + ?Sized>(
+        &mut self,
+        labels: L,
+        props: &P,
+    ) -> RedisGraphResult<Vec<Node>> {
+        let labels: LabelMatch = labels.into();
+        let (map_pattern, params) = props.to_properties().to_map_pattern("p");
+        self.query_with_params(
+            &format!(
+                "MATCH (n {}) WHERE {}{} RETURN n",
+                map_pattern,
+                labels.predicate("n"),
+                self.soft_delete_filter(&["n"])
+            ),
+            &params,
+        )
+    }
+
+    /// Returns the nodes connected to the node with the given ID by a relationship of the given type.
+    pub fn neighbors(
+        &mut self,
+        node_id: u64,
+        rel_type: &str,
+        direction: Direction,
+    ) -> RedisGraphResult<Vec<Node>> {
+        let pattern = node("n").rel(rel_type, direction).node("m");
+        self.query(&format!(
+            "MATCH {} WHERE id(n) = {}{} RETURN m",
+            pattern,
+            node_id,
+            self.soft_delete_filter(&["m"])
+        ))
+    }
+
+    /// Returns `true` if the given pattern matches anything, e.g. `(:Person {name: $name})`.
+    ///
+    /// The pattern can be followed by a `WHERE` clause, and built with the
+    /// [`pattern`](../pattern/index.html) module as well. The query stops at the first match,
+    /// using `LIMIT 1` rather than `EXISTS {}`, and is sent as a [read-only query](#method.ro_query).
+    pub fn exists_match<P: fmt::Display + ?Sized>(
+        &mut self,
+        pattern: &P,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<bool> {
+        let rows: Vec<i64> =
+            self.ro_query_with_params(&format!("MATCH {} RETURN 1 LIMIT 1", pattern), params)?;
+        Ok(!rows.is_empty())
+    }
+
+    /// Returns the number of rows the given query returns, without transferring and decoding them.
+    ///
+    /// The query is wrapped in a `CALL {}` subquery, which requires RedisGraph 2.12 or later,
+    /// and sent as a [read-only query](#method.ro_query).
+    pub fn count_rows(&mut self, query: &str) -> RedisGraphResult<u64> {
+        self.count_rows_with_params(query, &HashMap::new())
+    }
+
+    /// Same as [`count_rows`](#method.count_rows), but with parameters.
+    pub fn count_rows_with_params(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<u64> {
+        let query = query.trim().trim_end_matches(';');
+        self.ro_query_with_params(&format!("CALL {{ {} }} RETURN count(*)", query), params)
+    }
+
+    /// Returns helpers for counting nodes and aggregating their properties.
+    pub fn aggregate(&mut self) -> Aggregate<'_, C> {
+        Aggregate::new(self)
+    }
+
+    /// Executes the given query with `GRAPH.PROFILE` and returns its execution plan
+    /// along with the number of records produced and the time spent by each operation.
+    ///
+    /// *The query is actually executed, so mutations will be applied.*
+    pub fn profile(&mut self, query: &str) -> RedisGraphResult<ExecutionPlan> {
+        let mut cmd = redis::cmd(&self.commands.profile);
+        cmd.arg(self.name()).arg(query);
+        let command = self.commands.profile.clone();
+        self.send(&cmd, &command, query)
+    }
+
+    /// Profiles the given query and returns assertions about its execution plan,
+    /// see [`PlanAssertions`](../profile/struct.PlanAssertions.html).
+    ///
+    /// *The query is actually executed, so mutations will be applied.*
+    pub fn explain_analyze(&mut self, query: &str) -> RedisGraphResult<PlanAssertions> {
+        self.profile(query).map(ExecutionPlan::assertions)
+    }
+
+    /// Returns the execution plan of the given query as sent by `GRAPH.EXPLAIN`,
+    /// one line per operation, without executing it.
+    pub fn explain(&mut self, query: &str) -> RedisGraphResult<Vec<String>> {
+        let mut cmd = redis::cmd(&self.commands.explain);
+        cmd.arg(self.name()).arg(query);
+        let command = self.commands.explain.clone();
+        self.send(&cmd, &command, query)
+    }
+
+    /// Creates the given constraint with `GRAPH.CONSTRAINT CREATE`, which requires
+    /// RedisGraph 2.12 or later.
+    ///
+    /// The constraint is built in the background, and a unique constraint
+    /// requires an index on its properties.
+    pub fn create_constraint(&mut self, constraint: &ConstraintSpec) -> RedisGraphResult<()> {
+        let kind = match constraint.kind {
+            ConstraintKind::Unique => "UNIQUE",
+            ConstraintKind::Mandatory => "MANDATORY",
+        };
+        let mut cmd = redis::cmd(&self.commands.constraint);
+        cmd.arg("CREATE")
+            .arg(self.name())
+            .arg(kind)
+            .arg("NODE")
+            .arg(&constraint.label)
+            .arg("PROPERTIES")
+            .arg(constraint.properties.len())
+            .arg(constraint.properties.as_slice());
+        let command = self.commands.constraint.clone();
+        self.send(&cmd, &command, "")
+    }
+
+    /// Profiles two formulations of a query `iterations` times each and returns
+    /// timing statistics for both, based on the execution times reported by the server.
+    ///
+    /// Runs of the two queries are interleaved so that both are equally affected by
+    /// caching and changing server load.
+    ///
+    /// *The queries are actually executed, so mutations will be applied.*
+    ///
+    /// # Panics
+    ///
+    /// Panics if `iterations` is zero.
+    pub fn profile_compare(
+        &mut self,
+        query_a: &str,
+        query_b: &str,
+        iterations: usize,
+    ) -> RedisGraphResult<ProfileComparison> {
+        assert!(
+            iterations > 0,
+            "profile_compare requires at least one iteration"
+        );
+
+        let mut times_a = Vec::<Duration>::with_capacity(iterations);
+        let mut times_b = Vec::<Duration>::with_capacity(iterations);
+        for _ in 0..iterations {
+            times_a.push(self.profile(query_a)?.execution_time());
+            times_b.push(self.profile(query_b)?.execution_time());
+        }
+
+        Ok(ProfileComparison {
+            a: TimingStats::from_durations(&times_a).unwrap(),
+            b: TimingStats::from_durations(&times_b).unwrap(),
+        })
+    }
+
     /// Deletes the entire graph from the database.
     ///
     /// *This action is not easily reversible.*
     pub fn delete(mut self) -> RedisGraphResult<()> {
-        redis::cmd("GRAPH.DELETE")
-            .arg(self.name())
-            .query::<()>(&mut self.conn)
-            .map_err(RedisGraphError::from)
+        let mut cmd = redis::cmd(&self.commands.delete);
+        cmd.arg(self.name());
+        let command = self.commands.delete.clone();
+        self.send(&cmd, &command, "")
     }
 
     /// Updates the internal label names by retrieving them from the database.
@@ -86,6 +550,9 @@ impl Graph {
     /// There is no real need to call this function manually. This implementation
     /// updates the label names automatically when they become outdated.
     pub fn update_labels(&mut self) -> RedisGraphResult<()> {
+        if self.static_schema {
+            return Err(RedisGraphError::LabelNotFound);
+        }
         let refresh_response = self.request("CALL db.labels()")?;
         self.labels = self.get_mapping(refresh_response)?;
         Ok(())
@@ -96,6 +563,9 @@ impl Graph {
     /// There is no real need to call this function manually. This implementation
     /// updates the relationship type names automatically when they become outdated.
     pub fn update_relationship_types(&mut self) -> RedisGraphResult<()> {
+        if self.static_schema {
+            return Err(RedisGraphError::RelationshipTypeNotFound);
+        }
         let refresh_response = self.request("CALL db.relationshipTypes()")?;
         self.relationship_types = self.get_mapping(refresh_response)?;
         Ok(())
@@ -106,16 +576,162 @@ impl Graph {
     /// There is no real need to call this function manually. This implementation
     /// updates the property key names automatically when they become outdated.
     pub fn update_property_keys(&mut self) -> RedisGraphResult<()> {
+        if self.static_schema {
+            return Err(RedisGraphError::PropertyKeyNotFound);
+        }
         let refresh_response = self.request("CALL db.propertyKeys()")?;
         self.property_keys = self.get_mapping(refresh_response)?;
         Ok(())
     }
 
+    /// Uses the given names for decoding labels, relationship types and property keys
+    /// instead of retrieving them with `CALL db.labels()` and the like.
+    ///
+    /// Use this if the procedures are blocked, e.g. by a proxy. Results containing an ID
+    /// that is not in the mapping fail to decode with a
+    /// [`LabelNotFound`](../error/enum.RedisGraphError.html#variant.LabelNotFound) error or similar.
+    pub fn use_static_schema(&mut self, mapping: SchemaMapping) {
+        self.labels = mapping.labels;
+        self.relationship_types = mapping.relationship_types;
+        self.property_keys = mapping.property_keys;
+        self.static_schema = true;
+    }
+
+    /// Returns the label, relationship type and property key names known so far.
+    pub fn schema_mapping(&self) -> SchemaMapping {
+        SchemaMapping {
+            labels: self.labels.clone(),
+            relationship_types: self.relationship_types.clone(),
+            property_keys: self.property_keys.clone(),
+        }
+    }
+
+    /// Enables or disables reply diagnostics.
+    ///
+    /// With diagnostics enabled, every [`ServerTypeError`](../error/enum.RedisGraphError.html#variant.ServerTypeError)
+    /// returned while parsing a result set includes the path of the offending value within the reply,
+    /// its column and a truncated dump of the value. This makes parsing slower, so only enable it while debugging.
+    pub fn set_reply_diagnostics(&mut self, enabled: bool) {
+        self.reply_diagnostics = enabled;
+    }
+
+    /// Returns `true` if reply diagnostics are enabled.
+    pub fn reply_diagnostics(&self) -> bool {
+        self.reply_diagnostics
+    }
+
+    /// Enables or disables checking parameters against the query before sending it.
+    ///
+    /// With validation enabled, [`query_with_params`](#method.query_with_params),
+    /// [`mutate_with_params`](#method.mutate_with_params) and the helpers built on them fail
+    /// as described in [`validate_params`](../params/fn.validate_params.html) if the query
+    /// references a parameter without a value or a value is passed for a parameter it doesn't
+    /// reference. Disabled by default.
+    pub fn set_param_validation(&mut self, enabled: bool) {
+        self.validate_params = enabled;
+    }
+
+    /// Returns `true` if parameters are checked against the query before sending it.
+    pub fn param_validation(&self) -> bool {
+        self.validate_params
+    }
+
+    /// Enables or disables decoding of booleans returned as the integers `0` and `1`.
+    ///
+    /// Some server versions return booleans as integers instead of the strings `"true"` and `"false"`.
+    /// Both are accepted by default. Disable this to reject integer booleans as a
+    /// [`ServerTypeError`](../error/enum.RedisGraphError.html#variant.ServerTypeError).
+    pub fn set_lenient_booleans(&mut self, enabled: bool) {
+        self.lenient_booleans = enabled;
+    }
+
+    /// Returns `true` if booleans returned as integers are accepted.
+    pub fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans
+    }
+
+    /// Sets the registry used to decode [`Converted`](../converters/struct.Converted.html) cells
+    /// in the results of [`query`](#method.query) and the methods built on it, or unsets it with `None`.
+    pub fn set_converters(&mut self, converters: Option<Arc<ConverterRegistry>>) {
+        self.converters = converters;
+    }
+
+    /// Returns the registry used to decode converted cells, if any.
+    pub fn converters(&self) -> Option<&Arc<ConverterRegistry>> {
+        self.converters.as_ref()
+    }
+
+    /// Enables or disables tracking of query counts and latencies.
+    ///
+    /// Disabling tracking discards the stats recorded so far.
+    pub fn set_stats_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.stats = None;
+        } else if self.stats.is_none() {
+            self.stats = Some(QueryStats::default());
+        }
+    }
+
+    /// Returns the query counts and latencies recorded since tracking was enabled,
+    /// or `None` if tracking is disabled.
+    pub fn stats(&self) -> Option<&QueryStats> {
+        self.stats.as_ref()
+    }
+
+    /// Discards the stats recorded so far, if tracking is enabled.
+    pub fn reset_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            *stats = QueryStats::default();
+        }
+    }
+
+    /// Enables or disables soft deletion.
+    ///
+    /// With soft deletion enabled, [`delete_node`](#method.delete_node) only marks nodes as deleted
+    /// by setting their [`DELETED_AT`](../soft_delete/constant.DELETED_AT.html) property, and
+    /// [`find_nodes`](#method.find_nodes) and [`neighbors`](#method.neighbors) leave out
+    /// marked nodes. The same goes for [entities](../entity/index.html): they are marked by
+    /// [`delete_entity`](#method.delete_entity) and left out by [`find_like`](#method.find_like),
+    /// [`load_all`](#method.load_all) and the finders of a [`Session`](../session/struct.Session.html).
+    /// Use [`purge_deleted`](#method.purge_deleted) to remove them for good.
+    /// Queries written by hand are not affected.
+    pub fn set_soft_delete(&mut self, enabled: bool) {
+        self.soft_delete = enabled;
+    }
+
+    /// Returns `true` if soft deletion is enabled.
+    pub fn soft_delete(&self) -> bool {
+        self.soft_delete
+    }
+
+    /// Sets the policy for resending mutations marked as idempotent with
+    /// [`mutate_idempotent_marked`](#method.mutate_idempotent_marked), or disables retries with `None`.
+    ///
+    /// Other requests are never retried, because they may have been applied before the failure.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy<C>>) {
+        self.retry_policy = policy;
+    }
+
+    /// Returns the policy for resending mutations marked as idempotent, if any.
+    pub fn retry_policy(&self) -> Option<&RetryPolicy<C>> {
+        self.retry_policy.as_ref()
+    }
+
     /// Returns the name of this graph.
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// Returns the names of the commands used to talk to the graph module.
+    pub fn command_names(&self) -> &CommandNames {
+        &self.commands
+    }
+
+    /// Returns a reference to the node with the given ID in this graph.
+    pub fn node_ref(&self, id: u64) -> NodeRef {
+        NodeRef::new(self.name.clone(), id)
+    }
+
     /// Returns the graph's internal label names.
     pub fn labels(&self) -> &[RedisString] {
         &self.labels[..]
@@ -131,17 +747,125 @@ impl Graph {
         &self.property_keys[..]
     }
 
-    fn request(&mut self, query: &str) -> RedisGraphResult<Value> {
-        redis::cmd("GRAPH.QUERY")
-            .arg(self.name())
-            .arg(query)
-            .arg("--compact")
-            .query(&mut self.conn)
-            .map_err(RedisGraphError::from)
+    // Returns a condition like ` AND n.deleted_at IS NULL` for every variable if soft deletion is enabled.
+    pub(crate) fn soft_delete_filter(&self, variables: &[&str]) -> String {
+        if !self.soft_delete {
+            return String::new();
+        }
+        variables
+            .iter()
+            .map(|variable| format!(" AND {}.{} IS NULL", variable, DELETED_AT))
+            .collect()
+    }
+
+    // Converts the result set, with the converters of this graph if it has any.
+    fn decode_table<T: FromTable>(&self, result_set: &ResultSet) -> RedisGraphResult<T> {
+        match &self.converters {
+            Some(converters) => converters.decode_table(result_set),
+            None => T::from_table(result_set),
+        }
+    }
+
+    // Prepends the parameters to the query, checking them first if validation is enabled.
+    pub(crate) fn prepare_query(
+        &self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<String> {
+        prepare_query(query, params, self.validate_params)
+    }
+
+    // Like `query_with_params`, but notifies the mutation listeners.
+    pub(crate) fn query_mutation_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        let query = self.prepare_query(query, params)?;
+        let (value, statistics) = self.query_with_statistics(&query)?;
+        self.emit_mutation(&query, &statistics);
+        Ok(value)
+    }
+
+    fn emit_mutation(&self, query: &str, statistics: &Statistics) {
+        if self.mutation_listeners.is_empty() {
+            return;
+        }
+
+        let event = MutationEvent {
+            graph_name: self.name.clone(),
+            query: query.to_string(),
+            statistics: statistics.clone(),
+            timestamp: SystemTime::now(),
+        };
+        for listener in &self.mutation_listeners {
+            listener(&event);
+        }
+    }
+
+    /// Returns the underlying connection, e.g. to issue plain Redis commands.
+    pub fn connection(&mut self) -> &mut C {
+        &mut self.conn
+    }
+
+    pub(crate) fn request(&mut self, query: &str) -> RedisGraphResult<Value> {
+        let command = self.commands.query.clone();
+        self.request_with(&command, query)
+    }
+
+    fn ro_request(&mut self, query: &str) -> RedisGraphResult<Value> {
+        let command = self.commands.ro_query.clone();
+        self.request_with(&command, query)
+    }
+
+    fn request_with(&mut self, command: &str, query: &str) -> RedisGraphResult<Value> {
+        let start = Instant::now();
+        let mut cmd = redis::cmd(command);
+        cmd.arg(self.name()).arg(query).arg("--compact");
+        let response = self.send(&cmd, command, query);
+
+        if let Some(stats) = &mut self.stats {
+            stats.latencies.record(start.elapsed());
+            if response.is_err() {
+                stats.errors += 1;
+            }
+        }
+        response
+    }
+
+    // Sends a command to the database, inside an OpenTelemetry span if the `otel` feature is enabled.
+    fn send<T: FromRedisValue>(
+        &mut self,
+        cmd: &redis::Cmd,
+        command: &str,
+        statement: &str,
+    ) -> RedisGraphResult<T> {
+        let conn = &mut self.conn;
+        let response = otel::traced(&self.name, command, statement, || {
+            cmd.query(conn).map_err(RedisGraphError::from)
+        });
+
+        if let Err(RedisGraphError::RedisError(error)) = &response {
+            if error.is_io_error() && !self.disconnected {
+                self.disconnected = true;
+                self.emit_connection(ConnectionEvent::Disconnected {
+                    graph_name: self.name.clone(),
+                    error: error.to_string(),
+                    timestamp: SystemTime::now(),
+                });
+            }
+        }
+        response
+    }
+
+    fn emit_connection(&self, event: ConnectionEvent) {
+        for listener in &self.connection_listeners {
+            listener(&event);
+        }
     }
 
-    fn get_result_set(&mut self, response: Value) -> RedisGraphResult<ResultSet> {
-        match ResultSet::from_redis_value_with_graph(response.clone(), self) {
+    pub(crate) fn get_result_set(&mut self, response: Value) -> RedisGraphResult<ResultSet> {
+        match ResultSet::from_redis_value_with_graph(response.clone(), &self.context()) {
             Ok(result_set) => Ok(result_set),
             Err(RedisGraphError::LabelNotFound) => {
                 self.update_labels()?;
@@ -160,16 +884,87 @@ impl Graph {
     }
 
     fn get_mapping(&self, response: Value) -> RedisGraphResult<Vec<RedisString>> {
-        let mut result_set = ResultSet::from_redis_value_with_graph(response, self)?;
-        match &mut result_set.columns[0] {
-            Column::Scalars(scalars) => scalars
-                .iter_mut()
-                .map(|scalar| match scalar.take() {
-                    Scalar::String(string) => Ok(string),
-                    _ => server_type_error!("expected strings in first column of result set"),
-                })
-                .collect::<RedisGraphResult<Vec<RedisString>>>(),
-            _ => server_type_error!("expected scalars as first column in result set"),
+        parse_mapping(response, &self.context())
+    }
+
+    // Decodes without requiring the connection to be `Sync`.
+    pub(crate) fn context(&self) -> GraphContext<'_> {
+        GraphContext {
+            labels: &self.labels,
+            relationship_types: &self.relationship_types,
+            property_keys: &self.property_keys,
+            lenient_booleans: self.lenient_booleans,
+            reply_diagnostics: self.reply_diagnostics,
         }
     }
 }
+
+pub(crate) struct GraphContext<'a> {
+    labels: &'a [RedisString],
+    relationship_types: &'a [RedisString],
+    property_keys: &'a [RedisString],
+    lenient_booleans: bool,
+    reply_diagnostics: bool,
+}
+
+impl DecodeContext for GraphContext<'_> {
+    fn labels(&self) -> &[RedisString] {
+        self.labels
+    }
+
+    fn relationship_types(&self) -> &[RedisString] {
+        self.relationship_types
+    }
+
+    fn property_keys(&self) -> &[RedisString] {
+        self.property_keys
+    }
+
+    fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans
+    }
+
+    fn reply_diagnostics(&self) -> bool {
+        self.reply_diagnostics
+    }
+}
+
+// Parses the names returned by `CALL db.labels()` and the like.
+pub(crate) fn parse_mapping(
+    response: Value,
+    context: &dyn DecodeContext,
+) -> RedisGraphResult<Vec<RedisString>> {
+    let mut result_set = ResultSet::from_redis_value_with_graph(response, context)?;
+    match &mut result_set.columns[0] {
+        Column::Scalars(scalars) => scalars
+            .iter_mut()
+            .map(|scalar| match scalar.take() {
+                Scalar::String(string) => Ok(string),
+                _ => server_type_error!("expected strings in first column of result set"),
+            })
+            .collect::<RedisGraphResult<Vec<RedisString>>>(),
+        _ => server_type_error!("expected scalars as first column in result set"),
+    }
+}
+
+impl<C: Sync> DecodeContext for Graph<C> {
+    fn labels(&self) -> &[RedisString] {
+        &self.labels
+    }
+
+    fn relationship_types(&self) -> &[RedisString] {
+        &self.relationship_types
+    }
+
+    fn property_keys(&self) -> &[RedisString] {
+        &self.property_keys
+    }
+
+    fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans
+    }
+
+    fn reply_diagnostics(&self) -> bool {
+        self.reply_diagnostics
+    }
+}
@@ -0,0 +1,181 @@
+//! Resolving simple GraphQL object queries with generated Cypher, requires the `async-graphql` feature.
+//!
+//! A field like `people(city: "Berlin") { name age }` is mapped onto
+//! `` MATCH (`n`:`Person`) WHERE `n`.`city` = $city RETURN `n`.`name` AS `name`, `n`.`age` AS `age` ``:
+//! every argument filters on the property of the same name and every subfield returns the
+//! property of the same name. Nested selections aren't supported.
+//!
+//! The nodes are returned as a GraphQL value, which can be deserialized into the output type of
+//! the field with `async_graphql::from_value`. Fields that weren't selected are missing, so the
+//! output type needs defaults for them.
+//!
+//! [`Graph`](../graph/struct.Graph.html) is blocking, so the resolver below blocks the executor
+//! while the query runs. Servers handling many concurrent requests should run it on a blocking
+//! thread instead, e.g. with `tokio::task::spawn_blocking`.
+//!
+//! ```no_run
+//! # use std::sync::{Arc, Mutex};
+//! # use async_graphql::{Context, Object, SimpleObject};
+//! # use redisgraph::{graphql::node_query, Graph};
+//! # use serde::Deserialize;
+//! #[derive(SimpleObject, Deserialize, Default)]
+//! #[serde(default)]
+//! struct Person {
+//!     name: String,
+//!     age: i64,
+//! }
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     // The arguments are declared for the schema and applied by `node_query`.
+//!     async fn people(&self, ctx: &Context<'_>, city: Option<String>) -> async_graphql::Result<Vec<Person>> {
+//!         let (query, fields) = node_query("Person", ctx.field())
+//!             .map_err(|err| async_graphql::Error::new(format!("{:?}", err)))?;
+//!         let graph = ctx.data::<Arc<Mutex<Graph>>>()?;
+//!         let people = graph
+//!             .lock()
+//!             .unwrap()
+//!             .resolve_nodes(&query, &fields)
+//!             .map_err(|err| async_graphql::Error::new(format!("{:?}", err)))?;
+//!         Ok(async_graphql::from_value(people)?)
+//!     }
+//! }
+//! ```
+
+use async_graphql::{Name, Number, SelectionField, Value};
+
+use redis::ConnectionLike;
+
+use crate::{
+    params::CypherValue,
+    pattern::node,
+    query::{param, BuiltQuery, Expr, QueryBuilder},
+    result_set::{ResultSet, Scalar},
+    Graph, RedisGraphError, RedisGraphResult,
+};
+
+/// The variable the nodes queried by [`node_query`](fn.node_query.html) are bound to.
+pub const NODE_VARIABLE: &str = "n";
+
+/// Builds the query for a GraphQL field selecting nodes with the given label.
+///
+/// Returns the query and the names of the returned fields in the order of the `RETURN` clause,
+/// i.e. the aliases of the subfields if they have one. Introspection fields like `__typename`
+/// are skipped.
+///
+/// Fails with [`UnsupportedGraphqlField`](../error/enum.RedisGraphError.html#variant.UnsupportedGraphqlField)
+/// if a subfield has a selection set itself or if an argument is an object.
+pub fn node_query(
+    label: &str,
+    field: SelectionField<'_>,
+) -> RedisGraphResult<(BuiltQuery, Vec<String>)> {
+    let mut builder = QueryBuilder::new().match_pattern(node(NODE_VARIABLE).label(label));
+
+    let arguments = field
+        .arguments()
+        .map_err(|_| RedisGraphError::UnsupportedGraphqlField(field.name().to_string()))?;
+    for (name, value) in arguments {
+        let value = to_cypher_value(name.as_str(), value)?;
+        builder = builder
+            .filter(Expr::prop(NODE_VARIABLE, name.as_str()).eq(param(name.as_str())))
+            .param(name.as_str(), value);
+    }
+
+    let mut fields = Vec::new();
+    for subfield in field.selection_set() {
+        if subfield.name().starts_with("__") {
+            continue;
+        }
+        if subfield.selection_set().next().is_some() {
+            return Err(RedisGraphError::UnsupportedGraphqlField(
+                subfield.name().to_string(),
+            ));
+        }
+        let output = subfield.alias().unwrap_or_else(|| subfield.name());
+        builder = builder.returns_as(Expr::prop(NODE_VARIABLE, subfield.name()), output);
+        fields.push(output.to_string());
+    }
+
+    Ok((builder.build()?, fields))
+}
+
+fn to_cypher_value(argument: &str, value: Value) -> RedisGraphResult<CypherValue> {
+    Ok(match value {
+        Value::Null => CypherValue::Null,
+        Value::Boolean(boolean) => CypherValue::Boolean(boolean),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => CypherValue::Integer(integer),
+            None => CypherValue::Double(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(string) => CypherValue::String(string),
+        Value::Enum(name) => CypherValue::String(name.to_string()),
+        Value::List(values) => CypherValue::Array(
+            values
+                .into_iter()
+                .map(|value| to_cypher_value(argument, value))
+                .collect::<RedisGraphResult<_>>()?,
+        ),
+        Value::Binary(_) | Value::Object(_) => {
+            return Err(RedisGraphError::UnsupportedGraphqlField(
+                argument.to_string(),
+            ))
+        }
+    })
+}
+
+fn to_graphql_value(scalar: &Scalar) -> RedisGraphResult<Value> {
+    Ok(match scalar {
+        Scalar::Nil => Value::Null,
+        Scalar::Boolean(boolean) => Value::Boolean(*boolean),
+        Scalar::Integer(integer) => Value::Number(Number::from(*integer)),
+        Scalar::Double(double) => Number::from_f64(*double).map_or(Value::Null, Value::Number),
+        Scalar::String(string) => Value::String(String::from_utf8_lossy(&string.0).into_owned()),
+        Scalar::Array(scalars) => Value::List(
+            scalars
+                .iter()
+                .map(to_graphql_value)
+                .collect::<RedisGraphResult<_>>()?,
+        ),
+        Scalar::Map(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = String::from_utf8_lossy(&key.0).into_owned();
+                    Ok((Name::new(key), to_graphql_value(value)?))
+                })
+                .collect::<RedisGraphResult<_>>()?,
+        ),
+        Scalar::Node(_) | Scalar::Edge(_) | Scalar::Path(_) => {
+            return client_type_error!("expected a property, found {:?}", scalar)
+        }
+    })
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes a query built with [`node_query`](../graphql/fn.node_query.html) and returns
+    /// a GraphQL list with an object for every matched node.
+    pub fn resolve_nodes(
+        &mut self,
+        query: &BuiltQuery,
+        fields: &[String],
+    ) -> RedisGraphResult<Value> {
+        let result_set: ResultSet = self.query_with_params(query.query(), query.params())?;
+        let objects = result_set
+            .rows()
+            .map(|row| {
+                let object = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let scalar: Scalar = row.get(i)?;
+                        Ok((Name::new(field), to_graphql_value(&scalar)?))
+                    })
+                    .collect::<RedisGraphResult<_>>()?;
+                Ok(Value::Object(object))
+            })
+            .collect::<RedisGraphResult<_>>()?;
+        Ok(Value::List(objects))
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use redis::ConnectionLike;
+
+use crate::{Graph, RedisGraphResult};
+
+// How long a claimed key blocks replays before the mutation finished, so the key of a process
+// that crashed or lost its connection in the meantime doesn't block them forever.
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes the given mutation unless a mutation with the same deduplication key
+    /// has already been executed on this graph.
+    ///
+    /// Returns `true` if the mutation was executed and `false` if it was skipped.
+    /// Use this when consuming messages that may be delivered more than once.
+    ///
+    /// Executed keys are recorded in plain Redis keys named `{graph name}:dedup:{dedup_key}`
+    /// that never expire; use [`mutate_idempotent_with_ttl`](#method.mutate_idempotent_with_ttl)
+    /// to forget them after a while. If the mutation is rejected, its key is removed again so it can
+    /// be retried. If the outcome is [ambiguous](../error/enum.RedisGraphError.html#method.is_ambiguous),
+    /// e.g. because the connection broke, the key stays claimed until the claim expires, so replays
+    /// arriving in the meantime don't apply the mutation a second time.
+    ///
+    /// The key is claimed for 60 seconds before the mutation is sent and only recorded for good
+    /// once it succeeded. Replays arriving while it's being executed are skipped, and if the
+    /// outcome is lost, e.g. because the process crashed, replays arriving after the claim
+    /// expired are executed again. Messages are thus applied at least once, and twice if the
+    /// process crashed right after the mutation was applied.
+    pub fn mutate_idempotent(&mut self, dedup_key: &str, query: &str) -> RedisGraphResult<bool> {
+        self.mutate_idempotent_inner(dedup_key, query, None)
+    }
+
+    /// Same as [`mutate_idempotent`](#method.mutate_idempotent), but forgets the deduplication key
+    /// after the given time, so replays arriving later are executed again.
+    pub fn mutate_idempotent_with_ttl(
+        &mut self,
+        dedup_key: &str,
+        query: &str,
+        ttl: Duration,
+    ) -> RedisGraphResult<bool> {
+        self.mutate_idempotent_inner(dedup_key, query, Some(ttl))
+    }
+
+    fn mutate_idempotent_inner(
+        &mut self,
+        dedup_key: &str,
+        query: &str,
+        ttl: Option<Duration>,
+    ) -> RedisGraphResult<bool> {
+        let key = format!("{}:dedup:{}", self.name(), dedup_key);
+
+        // Claim the key before executing, so concurrent replays are skipped as well.
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg("pending")
+            .arg("NX")
+            .arg("PX")
+            .arg(CLAIM_LEASE.as_millis() as u64)
+            .query(self.connection())?;
+        if claimed.is_none() {
+            return Ok(false);
+        }
+
+        match self.mutate(query) {
+            Ok(()) => {
+                let mut record = redis::cmd("SET");
+                record.arg(&key).arg(1);
+                if let Some(ttl) = ttl {
+                    record.arg("PX").arg(ttl.as_millis().max(1) as u64);
+                }
+                record.query::<()>(self.connection())?;
+                Ok(true)
+            }
+            Err(error) => {
+                // The original error is more useful than one from releasing the claim,
+                // which expires on its own anyway.
+                if !error.is_ambiguous() {
+                    let _ = redis::cmd("DEL").arg(&key).query::<()>(self.connection());
+                }
+                Err(error)
+            }
+        }
+    }
+}
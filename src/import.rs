@@ -0,0 +1,400 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use redis::ConnectionLike;
+
+use crate::{
+    labels::Labels,
+    params::{escape_identifier, CypherValue},
+    props::Props,
+    Graph, RedisGraphError, RedisGraphResult, ResultSet,
+};
+
+/// A node to be created by [`Graph::import_nodes`](../graph/struct.Graph.html#method.import_nodes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeRecord<K> {
+    /// A client-assigned ID used to refer to the node in [`EdgeRecord`](struct.EdgeRecord.html)s.
+    ///
+    /// It is not stored in the graph.
+    pub temp_id: K,
+    /// The labels of the node.
+    pub labels: Labels,
+    /// The properties of the node.
+    pub props: Props,
+}
+
+/// A relationship to be created by [`Graph::import_edges`](../graph/struct.Graph.html#method.import_edges).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeRecord<K> {
+    /// The temporary ID of the source node.
+    pub src: K,
+    /// The temporary ID of the destination node.
+    pub dst: K,
+    /// The type of the relationship.
+    pub rel_type: String,
+    /// The properties of the relationship.
+    pub props: Props,
+}
+
+/// A node identified by a business key, to be created by
+/// [`Graph::import_keyed`](../graph/struct.Graph.html#method.import_keyed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyedNode {
+    /// The label of the node.
+    pub label: String,
+    /// The value of the key property, unique among the nodes with this label.
+    pub key: CypherValue,
+    /// The other properties of the node.
+    pub props: Props,
+}
+
+/// A relationship between two nodes identified by their business keys, to be created by
+/// [`Graph::import_keyed`](../graph/struct.Graph.html#method.import_keyed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyedEdge {
+    /// The label of the source node.
+    pub src_label: String,
+    /// The key of the source node.
+    pub src_key: CypherValue,
+    /// The label of the destination node.
+    pub dst_label: String,
+    /// The key of the destination node.
+    pub dst_key: CypherValue,
+    /// The type of the relationship.
+    pub rel_type: String,
+    /// The properties of the relationship.
+    pub props: Props,
+}
+
+/// Options for [`Graph::import_keyed`](../graph/struct.Graph.html#method.import_keyed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// The property that stores the business key of every node.
+    pub key_property: String,
+    /// The maximum number of nodes or relationships created per query.
+    pub batch_size: usize,
+}
+
+impl ImportOptions {
+    /// Creates options for nodes whose business key is stored in the given property,
+    /// with batches of 1000 entities.
+    pub fn new(key_property: &str) -> Self {
+        Self {
+            key_property: key_property.to_string(),
+            batch_size: 1000,
+        }
+    }
+}
+
+/// The outcome of [`Graph::import_keyed`](../graph/struct.Graph.html#method.import_keyed).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImportSummary {
+    /// The number of indexes created on the key property.
+    pub indexes_created: usize,
+    /// The number of nodes created.
+    pub nodes_created: usize,
+    /// The number of relationships created.
+    pub relationships_created: usize,
+    /// The number of relationships that were not created because an endpoint was not found.
+    pub unresolved_edges: usize,
+}
+
+/// Maps the temporary IDs of imported nodes to the IDs assigned by the database.
+pub type IdMap<K> = HashMap<K, u64>;
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Creates the given nodes in batches of `batch_size` and returns the ID of every created node
+    /// by its temporary ID.
+    ///
+    /// Use the returned map with [`import_edges`](#method.import_edges) to connect nodes
+    /// created in different batches without looking them up by their properties.
+    /// Maps of several calls can be merged to import nodes in multiple passes.
+    ///
+    /// Batches are executed one after another. If one of them fails, the nodes of
+    /// the previous batches stay in the graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn import_nodes<K, I>(&mut self, nodes: I, batch_size: usize) -> RedisGraphResult<IdMap<K>>
+    where
+        K: Eq + Hash,
+        I: IntoIterator<Item = NodeRecord<K>>,
+    {
+        assert!(batch_size > 0, "batch size must be greater than zero");
+
+        let mut ids = IdMap::new();
+        let mut nodes = nodes.into_iter().peekable();
+        while nodes.peek().is_some() {
+            let batch: Vec<NodeRecord<K>> = nodes.by_ref().take(batch_size).collect();
+
+            let mut patterns = Vec::with_capacity(batch.len());
+            let mut returns = Vec::with_capacity(batch.len());
+            let mut params = HashMap::new();
+            for (i, node) in batch.iter().enumerate() {
+                let (map_pattern, node_params) = node.props.to_map_pattern(&format!("n{}p", i));
+                patterns.push(format!("(n{}{} {})", i, node.labels, map_pattern));
+                returns.push(format!("id(n{})", i));
+                params.extend(node_params);
+            }
+
+            let query = format!(
+                "CREATE {} RETURN {}",
+                patterns.join(", "),
+                returns.join(", ")
+            );
+            let result_set: ResultSet = self.query_mutation_with_params(&query, &params)?;
+            let row = match result_set.rows().next() {
+                Some(row) => row,
+                None => {
+                    return server_type_error!("expected a row with the IDs of the created nodes")
+                }
+            };
+            for (i, node) in batch.into_iter().enumerate() {
+                let id: i64 = row.get(i)?;
+                ids.insert(node.temp_id, id as u64);
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// Creates the given relationships in batches of `batch_size`, resolving their endpoints
+    /// through the ID map returned by [`import_nodes`](#method.import_nodes). Returns the number
+    /// of created relationships.
+    ///
+    /// Fails with [`UnresolvedImportId`](../error/enum.RedisGraphError.html#variant.UnresolvedImportId)
+    /// before executing a batch if one of its endpoints is not in the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn import_edges<K, I>(
+        &mut self,
+        edges: I,
+        ids: &IdMap<K>,
+        batch_size: usize,
+    ) -> RedisGraphResult<usize>
+    where
+        K: Eq + Hash + Debug,
+        I: IntoIterator<Item = EdgeRecord<K>>,
+    {
+        assert!(batch_size > 0, "batch size must be greater than zero");
+
+        let mut created = 0;
+        let mut edges = edges.into_iter().peekable();
+        while edges.peek().is_some() {
+            let batch: Vec<EdgeRecord<K>> = edges.by_ref().take(batch_size).collect();
+
+            // Every node gets a single variable, even if several relationships of the batch use it.
+            let mut nodes: Vec<u64> = Vec::new();
+            let mut resolve = |temp_id: &K| -> RedisGraphResult<String> {
+                let id = *ids
+                    .get(temp_id)
+                    .ok_or_else(|| RedisGraphError::UnresolvedImportId(format!("{:?}", temp_id)))?;
+                let idx = match nodes.iter().position(|&node| node == id) {
+                    Some(idx) => idx,
+                    None => {
+                        nodes.push(id);
+                        nodes.len() - 1
+                    }
+                };
+                Ok(format!("v{}", idx))
+            };
+
+            let mut patterns = Vec::with_capacity(batch.len());
+            let mut params = HashMap::new();
+            for (i, edge) in batch.iter().enumerate() {
+                let src = resolve(&edge.src)?;
+                let dst = resolve(&edge.dst)?;
+                let (map_pattern, edge_params) = edge.props.to_map_pattern(&format!("e{}p", i));
+                patterns.push(format!(
+                    "({})-[:{} {}]->({})",
+                    src,
+                    escape_identifier(&edge.rel_type),
+                    map_pattern,
+                    dst
+                ));
+                params.extend(edge_params);
+            }
+
+            let matches = (0..nodes.len())
+                .map(|idx| format!("(v{})", idx))
+                .collect::<Vec<String>>();
+            let conditions = nodes
+                .iter()
+                .enumerate()
+                .map(|(idx, id)| format!("id(v{}) = {}", idx, id))
+                .collect::<Vec<String>>();
+
+            let query = format!(
+                "MATCH {} WHERE {} CREATE {}",
+                matches.join(", "),
+                conditions.join(" AND "),
+                patterns.join(", ")
+            );
+            let result_set: ResultSet = self.query_mutation_with_params(&query, &params)?;
+            created += result_set.statistics.count("Relationships created");
+        }
+
+        Ok(created)
+    }
+
+    /// Imports nodes identified by business keys and the relationships between them in two phases.
+    ///
+    /// First, an index on the key property is created for every label, unless it already exists.
+    /// Then all nodes are created, and finally the relationships, whose endpoints are looked up
+    /// by label and key. Relationships with an endpoint that doesn't exist are skipped and
+    /// counted in the summary. The nodes don't need to be imported in the same call as the
+    /// relationships between them.
+    ///
+    /// Records with the same labels and type are sent together, in batches of
+    /// [`batch_size`](../import/struct.ImportOptions.html#structfield.batch_size).
+    /// If a batch fails, the batches before it stay in the graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch size is zero.
+    pub fn import_keyed(
+        &mut self,
+        options: &ImportOptions,
+        nodes: Vec<KeyedNode>,
+        edges: Vec<KeyedEdge>,
+    ) -> RedisGraphResult<ImportSummary> {
+        assert!(
+            options.batch_size > 0,
+            "batch size must be greater than zero"
+        );
+
+        let key = escape_identifier(&options.key_property);
+        let mut summary = ImportSummary::default();
+
+        let labels: BTreeSet<&str> = nodes
+            .iter()
+            .map(|node| node.label.as_str())
+            .chain(edges.iter().map(|edge| edge.src_label.as_str()))
+            .chain(edges.iter().map(|edge| edge.dst_label.as_str()))
+            .collect();
+        for label in labels {
+            let query = format!("CREATE INDEX ON :{}({})", escape_identifier(label), key);
+            match self.query_mutation_with_params::<ResultSet>(&query, &HashMap::new()) {
+                Ok(result_set) => {
+                    summary.indexes_created += result_set.statistics.count("Indices created")
+                }
+                Err(RedisGraphError::RedisError(error))
+                    if error.to_string().contains("already indexed") => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        for (label, group) in group_by(nodes, |node| node.label.clone()) {
+            for batch in group.chunks(options.batch_size) {
+                let mut params = HashMap::new();
+                params.insert(
+                    "keys".to_string(),
+                    CypherValue::Array(batch.iter().map(|node| node.key.clone()).collect()),
+                );
+                let props: Vec<&Props> = batch.iter().map(|node| &node.props).collect();
+                let set_clause = columnar_set("n", &props, &mut params);
+
+                let query = format!(
+                    "UNWIND range(0, size($keys) - 1) AS i CREATE (n:{} {{{}: $keys[i]}}){}",
+                    escape_identifier(&label),
+                    key,
+                    set_clause
+                );
+                let result_set: ResultSet = self.query_mutation_with_params(&query, &params)?;
+                summary.nodes_created += result_set.statistics.count("Nodes created");
+            }
+        }
+
+        let edge_groups = group_by(edges, |edge| {
+            (
+                edge.src_label.clone(),
+                edge.dst_label.clone(),
+                edge.rel_type.clone(),
+            )
+        });
+        for ((src_label, dst_label, rel_type), group) in edge_groups {
+            for batch in group.chunks(options.batch_size) {
+                let mut params = HashMap::new();
+                params.insert(
+                    "src".to_string(),
+                    CypherValue::Array(batch.iter().map(|edge| edge.src_key.clone()).collect()),
+                );
+                params.insert(
+                    "dst".to_string(),
+                    CypherValue::Array(batch.iter().map(|edge| edge.dst_key.clone()).collect()),
+                );
+                let props: Vec<&Props> = batch.iter().map(|edge| &edge.props).collect();
+                let set_clause = columnar_set("r", &props, &mut params);
+
+                let query = format!(
+                    "UNWIND range(0, size($src) - 1) AS i \
+                     MATCH (s:{src} {{{key}: $src[i]}}), (d:{dst} {{{key}: $dst[i]}}) \
+                     CREATE (s)-[r:{rel}]->(d){set}",
+                    src = escape_identifier(&src_label),
+                    dst = escape_identifier(&dst_label),
+                    key = key,
+                    rel = escape_identifier(&rel_type),
+                    set = set_clause
+                );
+                let result_set: ResultSet = self.query_mutation_with_params(&query, &params)?;
+                let created = result_set.statistics.count("Relationships created");
+                summary.relationships_created += created;
+                summary.unresolved_edges += batch.len().saturating_sub(created);
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+// Groups the items by key, keeping the order of the first occurrence of every key.
+fn group_by<T, K: Eq + Hash + Clone>(items: Vec<T>, key: impl Fn(&T) -> K) -> Vec<(K, Vec<T>)> {
+    let mut groups: Vec<(K, Vec<T>)> = Vec::new();
+    let mut group_of_key: HashMap<K, usize> = HashMap::new();
+    for item in items {
+        let key = key(&item);
+        match group_of_key.get(&key) {
+            Some(&idx) => groups[idx].1.push(item),
+            None => {
+                group_of_key.insert(key.clone(), groups.len());
+                groups.push((key, vec![item]));
+            }
+        }
+    }
+    groups
+}
+
+// Passes every property as an array parameter indexed by `i`, with `null` where a record
+// doesn't have the property, and returns a `SET` clause assigning them to `variable`.
+pub(crate) fn columnar_set(
+    variable: &str,
+    props: &[&Props],
+    params: &mut HashMap<String, CypherValue>,
+) -> String {
+    let keys: BTreeSet<&String> = props
+        .iter()
+        .flat_map(|props| props.as_map().keys())
+        .collect();
+    let assignments = keys
+        .into_iter()
+        .enumerate()
+        .map(|(idx, key)| {
+            let values = props
+                .iter()
+                .map(|props| props.get(key).cloned().unwrap_or(CypherValue::Null))
+                .collect();
+            params.insert(format!("p{}", idx), CypherValue::Array(values));
+            format!("{}.{} = $p{}[i]", variable, escape_identifier(key), idx)
+        })
+        .collect::<Vec<String>>();
+
+    if assignments.is_empty() {
+        String::new()
+    } else {
+        format!(" SET {}", assignments.join(", "))
+    }
+}
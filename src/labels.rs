@@ -0,0 +1,138 @@
+use std::fmt;
+
+use crate::params::escape_identifier;
+
+/// A set of node labels, e.g. `:Person:Employee`.
+///
+/// Displays as a label list that can be embedded in a node pattern, with every label quoted.
+///
+/// ```
+/// use redisgraph::labels::Labels;
+///
+/// let labels = Labels::from(&["Person", "Employee"]);
+/// assert_eq!(labels.to_string(), ":`Person`:`Employee`");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Labels(Vec<String>);
+
+impl Labels {
+    /// Creates an empty label set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a label to the set. Adding a label that is already in the set has no effect.
+    pub fn with(mut self, label: &str) -> Self {
+        self.insert(label);
+        self
+    }
+
+    /// Adds a label to the set. Adding a label that is already in the set has no effect.
+    pub fn insert(&mut self, label: &str) {
+        if !self.contains(label) {
+            self.0.push(label.to_string());
+        }
+    }
+
+    /// Returns `true` if the set contains the given label.
+    pub fn contains(&self, label: &str) -> bool {
+        self.0.iter().any(|l| l == label)
+    }
+
+    /// Returns an iterator over the labels in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Returns the number of labels in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the set contains no labels.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for Labels {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for label in &self.0 {
+            write!(f, ":{}", escape_identifier(label))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Labels {
+    fn from(label: &str) -> Self {
+        Self::new().with(label)
+    }
+}
+
+impl From<&[&str]> for Labels {
+    fn from(labels: &[&str]) -> Self {
+        labels
+            .iter()
+            .fold(Self::new(), |set, label| set.with(label))
+    }
+}
+
+impl<const N: usize> From<&[&str; N]> for Labels {
+    fn from(labels: &[&str; N]) -> Self {
+        Self::from(&labels[..])
+    }
+}
+
+impl From<Vec<String>> for Labels {
+    fn from(labels: Vec<String>) -> Self {
+        labels
+            .iter()
+            .fold(Self::new(), |set, label| set.with(label))
+    }
+}
+
+impl From<&Labels> for Labels {
+    fn from(labels: &Labels) -> Self {
+        labels.clone()
+    }
+}
+
+/// Determines which nodes a set of labels matches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LabelMatch {
+    /// Matches nodes that have all of the labels.
+    ///
+    /// An empty set matches all nodes.
+    All(Labels),
+    /// Matches nodes that have at least one of the labels.
+    ///
+    /// An empty set matches no nodes.
+    Any(Labels),
+}
+
+impl LabelMatch {
+    /// Returns a predicate like `n:A OR n:B` that checks the labels of the node bound to `variable`.
+    pub fn predicate(&self, variable: &str) -> String {
+        let variable = escape_identifier(variable);
+        match self {
+            Self::All(labels) if labels.is_empty() => "true".to_string(),
+            Self::Any(labels) if labels.is_empty() => "false".to_string(),
+            Self::All(labels) => format!("{}{}", variable, labels),
+            Self::Any(labels) => {
+                let alternatives = labels
+                    .iter()
+                    .map(|label| format!("{}:{}", variable, escape_identifier(label)))
+                    .collect::<Vec<String>>();
+                format!("({})", alternatives.join(" OR "))
+            }
+        }
+    }
+}
+
+impl<L: Into<Labels>> From<L> for LabelMatch {
+    /// Matches nodes that have all of the labels.
+    fn from(labels: L) -> Self {
+        Self::All(labels.into())
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+// Latencies below this many microseconds are counted exactly.
+const LINEAR_BUCKETS: u64 = 64;
+// Every further power of two is split into this many buckets, so recorded
+// latencies are accurate to within about 3%.
+const SUB_BUCKETS: u64 = 32;
+const SUB_BUCKET_BITS: u32 = 5;
+
+/// A compact histogram of query latencies with microsecond resolution,
+/// in the spirit of an HDR histogram.
+///
+/// Memory usage grows logarithmically with the slowest recorded latency,
+/// so it can stay attached to a long-lived [`Graph`](../graph/struct.Graph.html).
+///
+/// ```
+/// use std::time::Duration;
+/// use redisgraph::latency::LatencyHistogram;
+///
+/// let mut histogram = LatencyHistogram::new();
+/// for millis in 1..=100 {
+///     histogram.record(Duration::from_millis(millis));
+/// }
+/// assert_eq!(histogram.count(), 100);
+/// assert_eq!(histogram.max(), Some(Duration::from_millis(100)));
+///
+/// let p50 = histogram.percentile(50.0).unwrap();
+/// assert!(p50 >= Duration::from_millis(50) && p50 <= Duration::from_millis(52));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+    total_micros: u128,
+    min_micros: u64,
+    max_micros: u64,
+}
+
+impl LatencyHistogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a single latency.
+    pub fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+        let idx = bucket_index(micros);
+        if idx >= self.buckets.len() {
+            self.buckets.resize(idx + 1, 0);
+        }
+        self.buckets[idx] += 1;
+
+        if self.count == 0 || micros < self.min_micros {
+            self.min_micros = micros;
+        }
+        self.max_micros = self.max_micros.max(micros);
+        self.total_micros += micros as u128;
+        self.count += 1;
+    }
+
+    /// Returns the number of recorded latencies.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns `true` if no latencies were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the lowest recorded latency.
+    pub fn min(&self) -> Option<Duration> {
+        self.non_empty(self.min_micros)
+    }
+
+    /// Returns the highest recorded latency.
+    pub fn max(&self) -> Option<Duration> {
+        self.non_empty(self.max_micros)
+    }
+
+    /// Returns the arithmetic mean of the recorded latencies.
+    pub fn mean(&self) -> Option<Duration> {
+        self.non_empty((self.total_micros / self.count.max(1) as u128) as u64)
+    }
+
+    /// Returns the latency at the given percentile, e.g. `99.0` for the 99th percentile.
+    ///
+    /// The result is the upper bound of the bucket containing the percentile,
+    /// but never higher than the highest recorded latency.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = ((percentile / 100.0 * self.count as f64).ceil() as u64).clamp(1, self.count);
+        let mut seen = 0;
+        for (idx, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let micros = bucket_upper_bound(idx).min(self.max_micros);
+                return Some(Duration::from_micros(micros));
+            }
+        }
+        self.max()
+    }
+
+    /// Adds all latencies recorded in another histogram to this one.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if other.count == 0 {
+            return;
+        }
+        if other.buckets.len() > self.buckets.len() {
+            self.buckets.resize(other.buckets.len(), 0);
+        }
+        for (bucket, count) in self.buckets.iter_mut().zip(&other.buckets) {
+            *bucket += count;
+        }
+
+        if self.count == 0 || other.min_micros < self.min_micros {
+            self.min_micros = other.min_micros;
+        }
+        self.max_micros = self.max_micros.max(other.max_micros);
+        self.total_micros += other.total_micros;
+        self.count += other.count;
+    }
+
+    fn non_empty(&self, micros: u64) -> Option<Duration> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(Duration::from_micros(micros))
+        }
+    }
+}
+
+fn bucket_index(micros: u64) -> usize {
+    if micros < LINEAR_BUCKETS {
+        return micros as usize;
+    }
+    let magnitude = 63 - micros.leading_zeros();
+    let shift = magnitude - SUB_BUCKET_BITS;
+    let sub_bucket = (micros >> shift) - SUB_BUCKETS;
+    (LINEAR_BUCKETS + (shift as u64 - 1) * SUB_BUCKETS + sub_bucket) as usize
+}
+
+fn bucket_upper_bound(idx: usize) -> u64 {
+    let idx = idx as u64;
+    if idx < LINEAR_BUCKETS {
+        return idx;
+    }
+    let shift = (idx - LINEAR_BUCKETS) / SUB_BUCKETS + 1;
+    let sub_bucket = (idx - LINEAR_BUCKETS) % SUB_BUCKETS;
+    let lower = (SUB_BUCKETS + sub_bucket) << shift;
+    lower.saturating_add((1 << shift) - 1)
+}
+
+/// Query counts and latencies of a [`Graph`](../graph/struct.Graph.html),
+/// see [`Graph::set_stats_enabled`](../graph/struct.Graph.html#method.set_stats_enabled).
+///
+/// Every query sent with the query command counts, including the ones issued
+/// internally, e.g. to refresh the schema. Profiling and deleting the graph are not tracked.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QueryStats {
+    /// The number of queries that failed, including connection errors.
+    pub errors: u64,
+    /// The round-trip latencies of all queries, including failed ones.
+    pub latencies: LatencyHistogram,
+}
+
+impl QueryStats {
+    /// Returns the number of queries sent.
+    pub fn queries(&self) -> u64 {
+        self.latencies.count()
+    }
+}
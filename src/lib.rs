@@ -65,18 +65,108 @@
 //! }
 //! ```
 //!
+//!
+//! ## Talking to many graphs
+//!
+//! A [`Graph`](graph/struct.Graph.html) owns its `redis::Connection`, so every graph that is used
+//! at the same time needs a connection of its own. With the `aio` feature, a
+//! [`GraphClient`](client/struct.GraphClient.html) sends the queries to any number of graphs
+//! over one multiplexed connection instead:
+//!
+//! ```no_run
+//! # #[cfg(feature = "aio")]
+//! # async fn example(conn: redis::aio::MultiplexedConnection) -> redisgraph::RedisGraphResult<()> {
+//! use redisgraph::client::GraphClient;
+//!
+//! let riders = GraphClient::open(conn, "riders".to_string()).await?;
+//! let teams = riders.graph("teams");
+//! // Both can be used from many tasks at once, without waiting for each other.
+//! let rider_count: i64 = riders.query("MATCH (r:Rider) RETURN count(r)").await?;
+//! let team_count: i64 = teams.query("MATCH (t:Team) RETURN count(t)").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! [redisgraph.io]:https://redisgraph.io
 //! [docs.rs/redisgraph]:https://docs.rs/redisgraph
 
 #[macro_use]
 pub mod error;
 
+pub mod aggregate;
+#[cfg(feature = "aio")]
+pub mod aio;
 pub mod assignments;
+pub mod backfill;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod cache;
+pub mod chunk;
+#[cfg(feature = "aio")]
+pub mod client;
+#[cfg(feature = "json")]
+pub mod codec;
+pub mod commands;
+pub mod community;
+pub mod compat;
+pub mod converters;
+#[cfg(feature = "deadpool")]
+pub mod deadpool;
+pub mod dual_write;
+pub mod embedding;
+pub mod entity;
+pub mod events;
+pub mod fragment;
 pub mod graph;
+#[cfg(feature = "async-graphql")]
+pub mod graphql;
+pub mod import;
+pub mod labels;
+pub mod latency;
+pub mod mapping;
+pub mod monitor;
+pub mod params;
+pub mod pattern;
+pub mod pool;
+pub mod profile;
+pub mod projection;
+pub mod props;
+pub mod query;
+#[cfg(feature = "tokio-comp")]
+pub mod reconnect;
+pub mod reference;
+pub mod registry;
+pub mod result_ref;
 pub mod result_set;
+pub mod retry;
+pub mod sampling;
+pub mod sanitize;
+pub mod schema;
+pub mod script;
+pub mod sensitive;
+pub mod session;
+pub mod shortest_path;
+pub mod single_flight;
+pub mod slowlog;
+pub mod soft_delete;
+pub mod stats;
+pub mod testing;
+pub mod traversal;
+pub mod ttl;
+pub mod view;
 
 mod conversions;
+mod idempotency;
+mod lock;
+mod otel;
+mod sequence;
+mod snapshot;
 
+pub use entity::Entity;
 pub use error::{RedisGraphError, RedisGraphResult};
 pub use graph::Graph;
+pub use props::ToProperties;
 pub use result_set::{RedisString, ResultSet};
+
+#[cfg(feature = "derive")]
+pub use redisgraph_derive::{graph_test, queries, Entity, ToProperties};
@@ -0,0 +1,96 @@
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use redis::{ConnectionLike, Script};
+
+use crate::{Graph, RedisGraphError, RedisGraphResult};
+
+/// How long to wait between attempts to acquire a lock that is already held.
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+// Deletes the lock only if it is still held by us, so we never release a lock
+// that expired and was acquired by someone else in the meantime.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+else
+    return 0
+end
+"#;
+
+static TOKEN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a value that identifies the holder of a lock across processes.
+fn lock_token() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}:{}:{}",
+        process::id(),
+        nanos,
+        TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Runs the given closure while holding the lock with the given key,
+    /// so that writers in different processes can coordinate e.g. schema migrations.
+    ///
+    /// The lock is a plain Redis key named `{graph name}:lock:{key}`, set with `SET NX PX`.
+    /// If the lock is held by someone else, this waits for at most `wait` before
+    /// giving up with [`LockNotAcquired`](../error/enum.RedisGraphError.html#variant.LockNotAcquired).
+    /// A `wait` of zero tries to acquire the lock only once.
+    ///
+    /// The lock expires after `ttl` even if the closure is still running, so choose
+    /// a `ttl` well above the expected run time. It is released as soon as the closure returns;
+    /// if the closure panics, the lock is only released when it expires.
+    pub fn with_lock<T, F>(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+        wait: Duration,
+        action: F,
+    ) -> RedisGraphResult<T>
+    where
+        F: FnOnce(&mut Graph<C>) -> RedisGraphResult<T>,
+    {
+        let lock_key = format!("{}:lock:{}", self.name(), key);
+        let token = lock_token();
+        let ttl_millis = ttl.as_millis().max(1) as u64;
+
+        let deadline = Instant::now() + wait;
+        loop {
+            let acquired: Option<String> = redis::cmd("SET")
+                .arg(&lock_key)
+                .arg(&token)
+                .arg("NX")
+                .arg("PX")
+                .arg(ttl_millis)
+                .query(self.connection())?;
+            if acquired.is_some() {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return Err(RedisGraphError::LockNotAcquired(lock_key));
+            }
+            thread::sleep(RETRY_INTERVAL);
+        }
+
+        let result = action(self);
+
+        let release: RedisGraphResult<i64> = Script::new(RELEASE_SCRIPT)
+            .key(&lock_key)
+            .arg(&token)
+            .invoke(self.connection())
+            .map_err(RedisGraphError::from);
+
+        // An error from the closure takes precedence over an error while releasing.
+        let value = result?;
+        release?;
+        Ok(value)
+    }
+}
@@ -0,0 +1,176 @@
+//! Converting rows into structs with column bindings that are configured at runtime.
+//!
+//! [`FromRow`](../assignments/trait.FromRow.html) and the `Entity` derive bind columns at compile
+//! time. When the queries are configurable, e.g. by plugins or reports defined in a config file,
+//! the returned columns aren't known until runtime. A [`ColumnMapping`](struct.ColumnMapping.html)
+//! declares the fields of a struct in code, and the columns they are read from can be changed
+//! with the configuration.
+//!
+//! ```no_run
+//! # use redisgraph::{mapping::ColumnMapping, Graph, RedisGraphResult};
+//! #[derive(Default)]
+//! struct Report {
+//!     label: String,
+//!     value: f64,
+//! }
+//!
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! // E.g. read from the configuration of the report.
+//! let query = "MATCH (t:Team) RETURN t.name AS team, avg(t.points) AS points";
+//! let columns = [("label", "team"), ("value", "points")];
+//!
+//! let mut mapping = ColumnMapping::new()
+//!     .field("label", |report: &mut Report, label| report.label = label)
+//!     .field("value", |report: &mut Report, value| report.value = value);
+//! for (field, column) in &columns {
+//!     mapping = mapping.column(field, column);
+//! }
+//! let reports: Vec<Report> = graph.query_mapped(query, &mapping)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use redis::ConnectionLike;
+
+use crate::{assignments::FromCell, params::CypherValue, Graph, RedisGraphResult, ResultSet};
+
+type Setter<T> =
+    Box<dyn Fn(&mut T, &ResultSet, usize, usize) -> RedisGraphResult<()> + Send + Sync>;
+
+struct Field<T> {
+    name: String,
+    column: String,
+    set: Setter<T>,
+}
+
+/// Bindings of columns to the fields of `T`, see the [module documentation](index.html).
+pub struct ColumnMapping<T> {
+    fields: Vec<Field<T>>,
+    // Fields that columns were bound to without being declared, reported when applied.
+    unknown: Vec<String>,
+}
+
+impl<T> ColumnMapping<T> {
+    /// Creates a mapping without fields.
+    pub fn new() -> Self {
+        Self {
+            fields: Vec::new(),
+            unknown: Vec::new(),
+        }
+    }
+
+    /// Declares a field that is set with the given function, read from the column with the
+    /// same name unless it's bound to another one with [`column`](#method.column).
+    ///
+    /// Use an `Option` for columns that may be `null`.
+    pub fn field<V, F>(mut self, name: &str, set: F) -> Self
+    where
+        V: FromCell,
+        F: Fn(&mut T, V) + Send + Sync + 'static,
+    {
+        self.fields.push(Field {
+            name: name.to_string(),
+            column: name.to_string(),
+            set: Box::new(move |target, result_set, row_idx, column_idx| {
+                set(target, V::from_cell(result_set, row_idx, column_idx)?);
+                Ok(())
+            }),
+        });
+        self
+    }
+
+    /// Reads the field with the given name from the given column.
+    ///
+    /// Binding a field that wasn't declared with [`field`](#method.field) makes
+    /// [`apply`](#method.apply) fail, so typos in the configuration are noticed.
+    pub fn column(mut self, field: &str, column: &str) -> Self {
+        match self.fields.iter_mut().find(|other| other.name == field) {
+            Some(other) => other.column = column.to_string(),
+            None => self.unknown.push(field.to_string()),
+        }
+        self
+    }
+
+    /// Returns the names of the declared fields along with the columns they are read from.
+    pub fn bindings(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.column.as_str()))
+    }
+
+    /// Converts every row of the result set into a `T`, starting from its default value.
+    ///
+    /// `column_names` are the names of the columns of the result set. Fails if a bound column
+    /// is missing or a value can't be converted into the type of its field. Columns that aren't
+    /// bound to a field are ignored.
+    pub fn apply(&self, column_names: &[String], result_set: &ResultSet) -> RedisGraphResult<Vec<T>>
+    where
+        T: Default,
+    {
+        if let Some(field) = self.unknown.first() {
+            return client_type_error!("failed to map columns: no field named {:?}", field);
+        }
+        let column_indices = self
+            .fields
+            .iter()
+            .map(
+                |field| match column_names.iter().position(|name| *name == field.column) {
+                    Some(column_idx) => Ok(column_idx),
+                    None => client_type_error!(
+                        "failed to map columns: no column named {:?} for field {:?}",
+                        field.column,
+                        field.name
+                    ),
+                },
+            )
+            .collect::<RedisGraphResult<Vec<usize>>>()?;
+
+        (0..result_set.num_rows())
+            .map(|row_idx| {
+                let mut target = T::default();
+                for (field, column_idx) in self.fields.iter().zip(&column_indices) {
+                    (field.set)(&mut target, result_set, row_idx, *column_idx)?;
+                }
+                Ok(target)
+            })
+            .collect()
+    }
+}
+
+impl<T> Default for ColumnMapping<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ColumnMapping<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.bindings()).finish()
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes the given query and converts its rows with the given mapping,
+    /// see the [`mapping`](../mapping/index.html) module.
+    pub fn query_mapped<T: Default>(
+        &mut self,
+        query: &str,
+        mapping: &ColumnMapping<T>,
+    ) -> RedisGraphResult<Vec<T>> {
+        let (column_names, result_set) = self.query_with_column_names(query)?;
+        mapping.apply(&column_names, &result_set)
+    }
+
+    /// Same as [`query_mapped`](#method.query_mapped), but passes the given parameters to the query.
+    pub fn query_mapped_with_params<T: Default>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+        mapping: &ColumnMapping<T>,
+    ) -> RedisGraphResult<Vec<T>> {
+        self.query_mapped(&self.prepare_query(query, params)?, mapping)
+    }
+}
@@ -0,0 +1,273 @@
+//! Watching how fast a graph grows.
+//!
+//! A [`GrowthMonitor`](struct.GrowthMonitor.html) keeps the latest samples of the number of
+//! nodes and relationships and the memory used by the graph key, and calls back when one of
+//! them grows faster than a threshold, e.g. to catch a runaway writer before it fills the
+//! server. There is no async runtime in this crate, so
+//! [`spawn`](struct.GrowthMonitor.html#method.spawn) samples on a background thread.
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use redisgraph::{monitor::{GrowthMetric, GrowthMonitor}, Graph};
+//! # fn example(graph: Graph) {
+//! let handle = GrowthMonitor::new(60)
+//!     .on_rate_above(GrowthMetric::Nodes, 1000.0, |alert| {
+//!         eprintln!("{:.0} nodes per second", alert.rate);
+//!     })
+//!     .spawn(graph, Duration::from_secs(10));
+//!
+//! // ...
+//! let samples = handle.samples();
+//! let graph = handle.stop();
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use redis::{Connection, ConnectionLike};
+
+use crate::{Graph, RedisGraphResult};
+
+/// The size of a graph at one point in time, returned by
+/// [`Graph::sample_growth`](../graph/struct.Graph.html#method.sample_growth).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthSample {
+    /// When the sample was taken.
+    pub taken_at: Instant,
+    /// The number of nodes.
+    pub nodes: u64,
+    /// The number of relationships.
+    pub relationships: u64,
+    /// The memory used by the graph key as reported by `MEMORY USAGE`,
+    /// or `None` if the server doesn't report it.
+    pub memory_bytes: Option<u64>,
+}
+
+/// A quantity tracked by a [`GrowthMonitor`](struct.GrowthMonitor.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrowthMetric {
+    /// The number of nodes.
+    Nodes,
+    /// The number of relationships.
+    Relationships,
+    /// The memory used by the graph key in bytes.
+    MemoryBytes,
+}
+
+impl GrowthMetric {
+    fn value(self, sample: &GrowthSample) -> Option<u64> {
+        match self {
+            Self::Nodes => Some(sample.nodes),
+            Self::Relationships => Some(sample.relationships),
+            Self::MemoryBytes => sample.memory_bytes,
+        }
+    }
+}
+
+/// Passed to the callbacks registered with
+/// [`GrowthMonitor::on_rate_above`](struct.GrowthMonitor.html#method.on_rate_above).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowthAlert {
+    /// The metric that grew too fast.
+    pub metric: GrowthMetric,
+    /// The growth per second since the previous sample.
+    pub rate: f64,
+    /// The sample that exceeded the threshold.
+    pub sample: GrowthSample,
+}
+
+struct Threshold {
+    metric: GrowthMetric,
+    per_second: f64,
+    callback: Box<dyn FnMut(&GrowthAlert) + Send>,
+}
+
+/// A ring buffer of [`GrowthSample`](struct.GrowthSample.html)s with threshold callbacks,
+/// see the [module documentation](index.html).
+pub struct GrowthMonitor {
+    samples: VecDeque<GrowthSample>,
+    capacity: usize,
+    thresholds: Vec<Threshold>,
+}
+
+impl GrowthMonitor {
+    /// Creates a monitor that keeps the latest `capacity` samples.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            thresholds: Vec::new(),
+        }
+    }
+
+    /// Calls `callback` whenever the given metric grew by more than `per_second`
+    /// per second since the previous sample.
+    pub fn on_rate_above<F>(mut self, metric: GrowthMetric, per_second: f64, callback: F) -> Self
+    where
+        F: FnMut(&GrowthAlert) + Send + 'static,
+    {
+        self.thresholds.push(Threshold {
+            metric,
+            per_second,
+            callback: Box::new(callback),
+        });
+        self
+    }
+
+    /// Adds a sample, dropping the oldest one if the buffer is full,
+    /// and calls the callbacks of the exceeded thresholds.
+    pub fn record(&mut self, sample: GrowthSample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+
+        for threshold in &mut self.thresholds {
+            if let Some(rate) = latest_rate(&self.samples, threshold.metric) {
+                if rate > threshold.per_second {
+                    (threshold.callback)(&GrowthAlert {
+                        metric: threshold.metric,
+                        rate,
+                        sample,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the buffered samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &GrowthSample> {
+        self.samples.iter()
+    }
+
+    /// Returns the latest sample.
+    pub fn latest(&self) -> Option<&GrowthSample> {
+        self.samples.back()
+    }
+
+    /// Returns the growth of the given metric per second between the two latest samples,
+    /// or `None` if there are fewer than two or the metric is missing from one of them.
+    pub fn rate(&self, metric: GrowthMetric) -> Option<f64> {
+        latest_rate(&self.samples, metric)
+    }
+
+    /// Samples the given graph every `interval` on a background thread.
+    ///
+    /// Samples that fail, e.g. because the connection broke, are skipped.
+    pub fn spawn<C>(self, mut graph: Graph<C>, interval: Duration) -> MonitorHandle<C>
+    where
+        C: ConnectionLike + Send + 'static,
+    {
+        let monitor = Arc::new(Mutex::new(self));
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn({
+            let monitor = Arc::clone(&monitor);
+            move || loop {
+                if let Ok(sample) = graph.sample_growth() {
+                    lock(&monitor).record(sample);
+                }
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    _ => return graph,
+                }
+            }
+        });
+        MonitorHandle {
+            monitor,
+            stop,
+            thread,
+        }
+    }
+}
+
+impl fmt::Debug for GrowthMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GrowthMonitor")
+            .field("samples", &self.samples)
+            .field("capacity", &self.capacity)
+            .field("thresholds", &self.thresholds.len())
+            .finish()
+    }
+}
+
+/// A [`GrowthMonitor`](struct.GrowthMonitor.html) sampling on a background thread,
+/// returned by [`GrowthMonitor::spawn`](struct.GrowthMonitor.html#method.spawn).
+#[derive(Debug)]
+pub struct MonitorHandle<C = Connection> {
+    monitor: Arc<Mutex<GrowthMonitor>>,
+    stop: Sender<()>,
+    thread: JoinHandle<Graph<C>>,
+}
+
+impl<C> MonitorHandle<C> {
+    /// Returns a copy of the buffered samples, oldest first.
+    pub fn samples(&self) -> Vec<GrowthSample> {
+        lock(&self.monitor).samples().copied().collect()
+    }
+
+    /// Returns the growth of the given metric per second between the two latest samples.
+    pub fn rate(&self, metric: GrowthMetric) -> Option<f64> {
+        lock(&self.monitor).rate(metric)
+    }
+
+    /// Stops sampling and returns the graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the callbacks panicked.
+    pub fn stop(self) -> Graph<C> {
+        // Fails if the thread already stopped, which it only does after a panic.
+        let _ = self.stop.send(());
+        self.thread.join().expect("growth monitor thread panicked")
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Returns the current number of nodes and relationships and the memory used by the graph.
+    pub fn sample_growth(&mut self) -> RedisGraphResult<GrowthSample> {
+        let nodes: u64 = self.query("MATCH (n) RETURN count(n)")?;
+        let relationships: u64 = self.query("MATCH ()-[r]->() RETURN count(r)")?;
+        let memory_bytes: Option<u64> = redis::cmd("MEMORY")
+            .arg("USAGE")
+            .arg(self.name())
+            .query(self.connection())
+            .ok()
+            .flatten();
+        Ok(GrowthSample {
+            taken_at: Instant::now(),
+            nodes,
+            relationships,
+            memory_bytes,
+        })
+    }
+}
+
+fn latest_rate(samples: &VecDeque<GrowthSample>, metric: GrowthMetric) -> Option<f64> {
+    let mut latest = samples.iter().rev();
+    let current = latest.next()?;
+    let previous = latest.next()?;
+    let elapsed = current
+        .taken_at
+        .saturating_duration_since(previous.taken_at)
+        .as_secs_f64();
+    if elapsed == 0.0 {
+        return None;
+    }
+    let growth = metric.value(current)? as f64 - metric.value(previous)? as f64;
+    Some(growth / elapsed)
+}
+
+// A panic in a callback doesn't leave the samples in an inconsistent state.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,71 @@
+#[cfg(feature = "otel")]
+use opentelemetry::{
+    global,
+    trace::{Span, SpanKind, Status, Tracer},
+    KeyValue,
+};
+
+#[cfg(feature = "otel")]
+use crate::sanitize::{query_fingerprint, sanitize_query};
+use crate::RedisGraphResult;
+
+/// Sanitized statements longer than this many bytes are truncated in the `db.statement` attribute.
+#[cfg(feature = "otel")]
+const MAX_STATEMENT_LEN: usize = 1024;
+
+/// Runs `request` inside a client span for the given command, recording failures on the span.
+///
+/// The span follows the OpenTelemetry semantic conventions for database clients, and records
+/// the [fingerprint](../sanitize/fn.query_fingerprint.html) of the statement for grouping spans by query shape.
+#[cfg(feature = "otel")]
+pub(crate) fn traced<T>(
+    graph_name: &str,
+    command: &str,
+    statement: &str,
+    request: impl FnOnce() -> RedisGraphResult<T>,
+) -> RedisGraphResult<T> {
+    let fingerprint = format!("{:016x}", query_fingerprint(statement));
+    let statement = sanitize_query(statement);
+    let tracer = global::tracer("redisgraph");
+    let mut span = tracer
+        .span_builder(format!("{} {}", command, graph_name))
+        .with_kind(SpanKind::Client)
+        .with_attributes(vec![
+            KeyValue::new("db.system", "redis"),
+            KeyValue::new("db.name", graph_name.to_string()),
+            KeyValue::new("db.operation", command.to_string()),
+            KeyValue::new("db.statement", truncate(&statement).to_string()),
+            KeyValue::new("db.statement.fingerprint", fingerprint),
+        ])
+        .start(&tracer);
+
+    let result = request();
+    if let Err(error) = &result {
+        span.set_status(Status::error(format!("{:?}", error)));
+    }
+    span.end();
+    result
+}
+
+/// Runs `request`; spans are only emitted with the `otel` feature.
+#[cfg(not(feature = "otel"))]
+pub(crate) fn traced<T>(
+    _graph_name: &str,
+    _command: &str,
+    _statement: &str,
+    request: impl FnOnce() -> RedisGraphResult<T>,
+) -> RedisGraphResult<T> {
+    request()
+}
+
+#[cfg(feature = "otel")]
+fn truncate(statement: &str) -> &str {
+    if statement.len() <= MAX_STATEMENT_LEN {
+        return statement;
+    }
+    let mut end = MAX_STATEMENT_LEN;
+    while !statement.is_char_boundary(end) {
+        end -= 1;
+    }
+    &statement[..end]
+}
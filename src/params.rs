@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+
+use redisgraph_cypher::referenced_params;
+
+use crate::{result_set::Scalar, RedisGraphError, RedisGraphResult, RedisString};
+
+/// A value that can be sent to RedisGraph as a query parameter or property value.
+///
+/// Values can be read from result sets as well, so the same type can be used in both
+/// directions. It converts to a [`Scalar`](../result_set/enum.Scalar.html) without loss,
+/// and back unless the scalar is a node, edge or path, or a string that isn't valid UTF-8.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CypherValue {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Double(f64),
+    String(String),
+    Array(Vec<CypherValue>),
+    /// A map, which may contain arrays and maps in turn.
+    ///
+    /// The entries are ordered by key, so the same map always produces the same query text.
+    Map(BTreeMap<String, CypherValue>),
+}
+
+impl fmt::Display for CypherValue {
+    /// Formats the value as a Cypher literal.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Boolean(boolean) => write!(f, "{}", boolean),
+            Self::Integer(integer) => write!(f, "{}", integer),
+            // `Debug` always includes a decimal point or an exponent,
+            // so the value is parsed as a double again. `NaN` and `inf` aren't valid Cypher,
+            // `build_query` rejects them.
+            Self::Double(double) => write!(f, "{:?}", double),
+            Self::String(string) => write!(f, "\"{}\"", escape_string(string)),
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Self::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if is_valid_parameter_name(key) {
+                        write!(f, "{}: {}", key, value)?;
+                    } else {
+                        write!(f, "{}: {}", escape_identifier(key), value)?;
+                    }
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn escape_string(string: &str) -> String {
+    string.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl From<bool> for CypherValue {
+    fn from(boolean: bool) -> Self {
+        Self::Boolean(boolean)
+    }
+}
+
+macro_rules! impl_from_integer_for_cypher_value {
+    ($t:ty) => {
+        impl From<$t> for CypherValue {
+            fn from(integer: $t) -> Self {
+                Self::Integer(integer as i64)
+            }
+        }
+    };
+}
+
+impl_from_integer_for_cypher_value!(u8);
+impl_from_integer_for_cypher_value!(u16);
+impl_from_integer_for_cypher_value!(u32);
+
+impl_from_integer_for_cypher_value!(i8);
+impl_from_integer_for_cypher_value!(i16);
+impl_from_integer_for_cypher_value!(i32);
+impl_from_integer_for_cypher_value!(i64);
+
+impl From<f32> for CypherValue {
+    fn from(double: f32) -> Self {
+        Self::Double(double as f64)
+    }
+}
+
+impl From<f64> for CypherValue {
+    fn from(double: f64) -> Self {
+        Self::Double(double)
+    }
+}
+
+impl From<&str> for CypherValue {
+    fn from(string: &str) -> Self {
+        Self::String(string.to_string())
+    }
+}
+
+impl From<String> for CypherValue {
+    fn from(string: String) -> Self {
+        Self::String(string)
+    }
+}
+
+impl<T: Into<CypherValue>> From<Vec<T>> for CypherValue {
+    fn from(elements: Vec<T>) -> Self {
+        Self::Array(elements.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<CypherValue>> From<BTreeMap<String, T>> for CypherValue {
+    fn from(entries: BTreeMap<String, T>) -> Self {
+        Self::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Into<CypherValue>> From<HashMap<String, T>> for CypherValue {
+    fn from(entries: HashMap<String, T>) -> Self {
+        Self::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Into<CypherValue>> From<Option<T>> for CypherValue {
+    fn from(option: Option<T>) -> Self {
+        match option {
+            Some(value) => value.into(),
+            None => Self::Null,
+        }
+    }
+}
+
+impl From<CypherValue> for Scalar {
+    fn from(value: CypherValue) -> Self {
+        match value {
+            CypherValue::Null => Self::Nil,
+            CypherValue::Boolean(boolean) => Self::Boolean(boolean),
+            CypherValue::Integer(integer) => Self::Integer(integer),
+            CypherValue::Double(double) => Self::Double(double),
+            CypherValue::String(string) => Self::String(string.into()),
+            CypherValue::Array(elements) => {
+                Self::Array(elements.into_iter().map(Into::into).collect())
+            }
+            CypherValue::Map(entries) => Self::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl TryFrom<Scalar> for CypherValue {
+    type Error = RedisGraphError;
+
+    fn try_from(scalar: Scalar) -> RedisGraphResult<Self> {
+        Ok(match scalar {
+            Scalar::Nil => Self::Null,
+            Scalar::Boolean(boolean) => Self::Boolean(boolean),
+            Scalar::Integer(integer) => Self::Integer(integer),
+            Scalar::Double(double) => Self::Double(double),
+            Scalar::String(RedisString(data)) => {
+                Self::String(String::from_utf8(data).map_err(|_| RedisGraphError::InvalidUtf8)?)
+            }
+            Scalar::Array(scalars) => Self::Array(
+                scalars
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<RedisGraphResult<_>>()?,
+            ),
+            Scalar::Map(entries) => Self::Map(
+                entries
+                    .into_iter()
+                    .map(|(RedisString(key), value)| {
+                        let key =
+                            String::from_utf8(key).map_err(|_| RedisGraphError::InvalidUtf8)?;
+                        Ok((key, Self::try_from(value)?))
+                    })
+                    .collect::<RedisGraphResult<_>>()?,
+            ),
+            Scalar::Node(_) | Scalar::Edge(_) | Scalar::Path(_) => {
+                return client_type_error!(
+                    "failed to construct value: expected a scalar, found {:?}",
+                    scalar
+                )
+            }
+        })
+    }
+}
+
+/// Returns `true` if the given string can be used as a parameter name.
+pub(crate) fn is_valid_parameter_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the query references exactly the given parameters as `$name`.
+///
+/// Fails with [`UnboundParameter`](../error/enum.RedisGraphError.html#variant.UnboundParameter)
+/// if the query references a parameter without a value, e.g. after renaming it in the query but
+/// not in the map, and with [`UnusedParameter`](../error/enum.RedisGraphError.html#variant.UnusedParameter)
+/// if a value is passed for a parameter the query doesn't reference. References in string literals
+/// and quoted identifiers are ignored.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use redisgraph::{params::{validate_params, CypherValue}, RedisGraphError};
+/// let mut params = HashMap::new();
+/// params.insert("name".to_string(), CypherValue::from("Alice"));
+/// assert!(validate_params("MATCH (p {name: $name}) RETURN p", &params).is_ok());
+/// assert!(matches!(
+///     validate_params("MATCH (p {name: $person}) RETURN p", &params),
+///     Err(RedisGraphError::UnboundParameter(name)) if name == "person"
+/// ));
+/// ```
+pub fn validate_params(query: &str, params: &HashMap<String, CypherValue>) -> RedisGraphResult<()> {
+    let mut referenced = referenced_params(query);
+    referenced.sort();
+    referenced.dedup();
+    if let Some(unbound) = referenced.iter().find(|name| !params.contains_key(*name)) {
+        return Err(RedisGraphError::UnboundParameter(unbound.clone()));
+    }
+    let mut unused: Vec<&String> = params
+        .keys()
+        .filter(|name| referenced.binary_search(name).is_err())
+        .collect();
+    unused.sort();
+    match unused.first() {
+        Some(name) => Err(RedisGraphError::UnusedParameter((*name).clone())),
+        None => Ok(()),
+    }
+}
+
+/// Same as [`build_query`], but first checks the parameters against the query as described in
+/// [`validate_params`] if `validate` is set. Used by every `*_with_params` method, so they all
+/// honour the validation setting of their graph.
+pub(crate) fn prepare_query(
+    query: &str,
+    params: &HashMap<String, CypherValue>,
+    validate: bool,
+) -> RedisGraphResult<String> {
+    if validate {
+        validate_params(query, params)?;
+    }
+    build_query(query, params)
+}
+
+/// Prepends the `CYPHER name=value ...` header used by RedisGraph to pass parameters to a query.
+pub(crate) fn build_query(
+    query: &str,
+    params: &HashMap<String, CypherValue>,
+) -> RedisGraphResult<String> {
+    if params.is_empty() {
+        return Ok(query.to_string());
+    }
+
+    // Sort the parameters so the same parameters always produce the same query text.
+    let mut params: Vec<(&String, &CypherValue)> = params.iter().collect();
+    params.sort_by_key(|(name, _)| *name);
+
+    let mut header = String::from("CYPHER");
+    for (name, value) in params {
+        if !is_valid_parameter_name(name) {
+            return Err(RedisGraphError::InvalidParameterName(name.clone()));
+        }
+        if !is_finite(value) {
+            return Err(RedisGraphError::NonFiniteParameter(name.clone()));
+        }
+        header.push_str(&format!(" {}={}", name, value));
+    }
+
+    Ok(format!("{} {}", header, query))
+}
+
+// Returns `false` if the value is or contains a `NaN` or infinite double.
+pub(crate) fn is_finite(value: &CypherValue) -> bool {
+    match value {
+        CypherValue::Double(double) => double.is_finite(),
+        CypherValue::Array(elements) => elements.iter().all(is_finite),
+        CypherValue::Map(entries) => entries.values().all(is_finite),
+        _ => true,
+    }
+}
+
+/// Quotes a label, relationship type or property key so it can be safely embedded in a query.
+pub(crate) fn escape_identifier(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('`', "``"))
+}
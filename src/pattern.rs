@@ -0,0 +1,181 @@
+use std::fmt;
+
+use crate::{labels::Labels, params::escape_identifier};
+
+/// The direction of a relationship in a pattern, relative to the node before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// `(a)-[]->(b)`
+    Outgoing,
+    /// `(a)<-[]-(b)`
+    Incoming,
+    /// `(a)-[]-(b)`
+    Both,
+}
+
+/// A path pattern like `(p:Person)-[:KNOWS]->(q)`, built with [`node`](fn.node.html).
+///
+/// All variables, labels and relationship types are quoted, so they can come from untrusted input.
+///
+/// ```
+/// use redisgraph::pattern::{node, Direction};
+///
+/// let pattern = node("p").label("Person").rel("KNOWS", Direction::Outgoing).node("q");
+/// assert_eq!(pattern.to_string(), "(`p`:`Person`)-[:`KNOWS`]->(`q`)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    start: NodePattern,
+    hops: Vec<(RelationshipPattern, NodePattern)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NodePattern {
+    variable: String,
+    labels: Labels,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RelationshipPattern {
+    variable: String,
+    rel_type: Option<String>,
+    direction: Direction,
+}
+
+/// Starts a pattern with a node bound to the given variable.
+///
+/// Pass an empty string for an anonymous node.
+pub fn node(variable: &str) -> Pattern {
+    Pattern {
+        start: NodePattern::new(variable),
+        hops: Vec::new(),
+    }
+}
+
+impl Pattern {
+    /// Adds a label to the last node of the pattern.
+    pub fn label(mut self, label: &str) -> Self {
+        self.last_node_mut().labels.insert(label);
+        self
+    }
+
+    /// Adds several labels to the last node of the pattern.
+    ///
+    /// The node only matches nodes that have all of the labels.
+    pub fn labels<L: Into<Labels>>(mut self, labels: L) -> Self {
+        let node = self.last_node_mut();
+        for label in labels.into().iter() {
+            node.labels.insert(label);
+        }
+        self
+    }
+
+    /// Adds an anonymous relationship of the given type.
+    ///
+    /// Must be followed by a call to [`node`](#method.node).
+    pub fn rel(self, rel_type: &str, direction: Direction) -> PendingHop {
+        self.rel_as("", rel_type, direction)
+    }
+
+    /// Adds a relationship of the given type bound to the given variable.
+    ///
+    /// Must be followed by a call to [`node`](#method.node).
+    pub fn rel_as(self, variable: &str, rel_type: &str, direction: Direction) -> PendingHop {
+        PendingHop {
+            pattern: self,
+            relationship: RelationshipPattern {
+                variable: variable.to_string(),
+                rel_type: Some(rel_type.to_string()),
+                direction,
+            },
+        }
+    }
+
+    /// Adds an anonymous relationship of any type.
+    ///
+    /// Must be followed by a call to [`node`](#method.node).
+    pub fn any_rel(self, direction: Direction) -> PendingHop {
+        PendingHop {
+            pattern: self,
+            relationship: RelationshipPattern {
+                variable: String::new(),
+                rel_type: None,
+                direction,
+            },
+        }
+    }
+
+    fn last_node_mut(&mut self) -> &mut NodePattern {
+        match self.hops.last_mut() {
+            Some((_, node)) => node,
+            None => &mut self.start,
+        }
+    }
+}
+
+/// A [`Pattern`](struct.Pattern.html) ending in a relationship, waiting for the node on its other side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingHop {
+    pattern: Pattern,
+    relationship: RelationshipPattern,
+}
+
+impl PendingHop {
+    /// Adds the node at the other end of the relationship, bound to the given variable.
+    ///
+    /// Pass an empty string for an anonymous node.
+    pub fn node(mut self, variable: &str) -> Pattern {
+        self.pattern
+            .hops
+            .push((self.relationship, NodePattern::new(variable)));
+        self.pattern
+    }
+}
+
+impl NodePattern {
+    fn new(variable: &str) -> Self {
+        Self {
+            variable: variable.to_string(),
+            labels: Labels::new(),
+        }
+    }
+}
+
+fn variable_pattern(variable: &str) -> String {
+    if variable.is_empty() {
+        String::new()
+    } else {
+        escape_identifier(variable)
+    }
+}
+
+impl fmt::Display for NodePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}{})", variable_pattern(&self.variable), self.labels)
+    }
+}
+
+impl fmt::Display for RelationshipPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rel_type = match &self.rel_type {
+            Some(rel_type) => format!(":{}", escape_identifier(rel_type)),
+            None => String::new(),
+        };
+        let body = format!("[{}{}]", variable_pattern(&self.variable), rel_type);
+        match self.direction {
+            Direction::Outgoing => write!(f, "-{}->", body),
+            Direction::Incoming => write!(f, "<-{}-", body),
+            Direction::Both => write!(f, "-{}-", body),
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+        for (relationship, node) in &self.hops {
+            write!(f, "{}{}", relationship, node)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+//! A pool of connections that hands out graph handles.
+//!
+//! A [`Graph`](../graph/struct.Graph.html) owns its connection, so threads that query at the same
+//! time need a connection each. A [`GraphPool`](struct.GraphPool.html) opens up to a fixed number
+//! of connections to the same database and lends them out as graph handles for any graph name,
+//! waiting up to a timeout for a connection to be returned when all of them are in use.
+//! [`shutdown`](struct.GraphPool.html#method.shutdown) drains the pool, e.g. when the process
+//! is asked to terminate.
+//!
+//! ```no_run
+//! # use std::thread;
+//! # use redisgraph::{pool::GraphPool, RedisGraphResult};
+//! # fn example(client: redis::Client) -> RedisGraphResult<()> {
+//! let pool = GraphPool::new(client, 4);
+//! let workers: Vec<_> = (0..8)
+//!     .map(|_| {
+//!         let pool = pool.clone();
+//!         thread::spawn(move || -> RedisGraphResult<i64> {
+//!             let mut graph = pool.get("MotoGP")?;
+//!             graph.query("MATCH (r:Rider) RETURN count(r)")
+//!         })
+//!     })
+//!     .collect();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+use redis::{Client, Connection};
+
+use crate::{
+    commands::CommandNames,
+    events::{ConnectionEvent, ConnectionListener},
+    schema::SchemaMapping,
+    Graph, RedisGraphError, RedisGraphResult,
+};
+
+/// A pool of connections to the same database, see the [module documentation](index.html).
+///
+/// Clones share the same connections.
+#[derive(Clone)]
+pub struct GraphPool {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    client: Client,
+    commands: CommandNames,
+    max_size: usize,
+    checkout_timeout: Duration,
+    connection_listeners: Vec<ConnectionListener>,
+    state: Mutex<State>,
+    returned: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    idle: Vec<Connection>,
+    // The number of connections that are idle, lent out or being opened.
+    size: usize,
+    // The graphs that were opened through this pool, so handles for them don't create them again.
+    opened: HashSet<String>,
+    mappings: HashMap<String, SchemaMapping>,
+    shut_down: bool,
+}
+
+impl GraphPool {
+    /// Creates a pool of up to `max_size` connections from the given client,
+    /// which are opened when they are needed.
+    ///
+    /// Checkouts wait up to 30 seconds for a connection by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` is zero.
+    pub fn new(client: Client, max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size must be greater than zero");
+        Self {
+            shared: Arc::new(Shared {
+                client,
+                commands: CommandNames::default(),
+                max_size,
+                checkout_timeout: Duration::from_secs(30),
+                connection_listeners: Vec::new(),
+                state: Mutex::new(State::default()),
+                returned: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Sets how long [`get`](#method.get) waits for a connection when all of them are in use.
+    ///
+    /// Must be called before the pool is cloned.
+    pub fn checkout_timeout(mut self, timeout: Duration) -> Self {
+        self.shared_mut().checkout_timeout = timeout;
+        self
+    }
+
+    /// Uses the given command names for all graphs instead of `GRAPH.QUERY` etc.
+    ///
+    /// Must be called before the pool is cloned.
+    pub fn commands(mut self, commands: CommandNames) -> Self {
+        self.shared_mut().commands = commands;
+        self
+    }
+
+    /// Registers a callback that is invoked when a checkout fails, and for the
+    /// [connection events](../graph/struct.Graph.html#method.on_connection_event) of every handle
+    /// lent out by the pool.
+    ///
+    /// Checkouts that fail because the pool was shut down are not reported.
+    /// Must be called before the pool is cloned.
+    pub fn on_connection_event<F>(mut self, listener: F) -> Self
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.shared_mut()
+            .connection_listeners
+            .push(Box::new(listener));
+        self
+    }
+
+    /// Lends out a connection as a handle for the graph with the given name.
+    ///
+    /// The first handle for a graph creates it like [`Graph::open`](../graph/struct.Graph.html#method.open)
+    /// if it doesn't exist yet. The names known by a handle are passed on to the next handle for the
+    /// same graph. Fails with [`PoolTimeout`](../error/enum.RedisGraphError.html#variant.PoolTimeout)
+    /// if no connection became available within the checkout timeout.
+    pub fn get(&self, name: &str) -> RedisGraphResult<PooledGraph> {
+        self.get_with_timeout(name, self.shared.checkout_timeout)
+    }
+
+    /// Same as [`get`](#method.get), but waits up to the given timeout instead of
+    /// the checkout timeout of the pool.
+    pub fn get_with_timeout(&self, name: &str, timeout: Duration) -> RedisGraphResult<PooledGraph> {
+        let result = self.lend(name, timeout);
+        if let Err(error) = &result {
+            if !matches!(error, RedisGraphError::PoolShutDown) {
+                self.shared
+                    .emit_connection(ConnectionEvent::CheckoutFailed {
+                        graph_name: name.to_string(),
+                        error: format!("{:?}", error),
+                        timestamp: SystemTime::now(),
+                    });
+            }
+        }
+        result
+    }
+
+    /// Stops lending out connections and closes them, waiting up to the given timeout for the
+    /// handles that are lent out to be dropped.
+    ///
+    /// Checkouts fail with [`PoolShutDown`](../error/enum.RedisGraphError.html#variant.PoolShutDown)
+    /// from then on, including the ones that are waiting for a connection. Idle connections are
+    /// closed right away and the others when their handles are dropped. Queries that are being
+    /// executed can't be cancelled, so handles that are still lent out at the deadline close
+    /// their connections whenever they are dropped.
+    ///
+    /// Returns `true` if all connections were closed before the deadline.
+    pub fn shutdown(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state();
+        state.shut_down = true;
+        let idle = std::mem::take(&mut state.idle);
+        state.size -= idle.len();
+        drop(idle);
+        self.shared.returned.notify_all();
+
+        while state.size > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            state = self
+                .shared
+                .returned
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        true
+    }
+
+    /// Returns `true` if the pool was [shut down](#method.shutdown).
+    pub fn is_shut_down(&self) -> bool {
+        self.state().shut_down
+    }
+
+    /// Returns the number of idle connections.
+    pub fn idle(&self) -> usize {
+        self.state().idle.len()
+    }
+
+    /// Returns the number of connections that are idle or lent out.
+    pub fn size(&self) -> usize {
+        self.state().size
+    }
+
+    fn lend(&self, name: &str, timeout: Duration) -> RedisGraphResult<PooledGraph> {
+        let conn = self.checkout(timeout)?;
+        let state = self.state();
+        let mapping = state.mappings.get(name).cloned().unwrap_or_default();
+        let opened = state.opened.contains(name);
+        drop(state);
+
+        let mut graph = if opened {
+            Graph::attach(
+                conn,
+                name.to_string(),
+                self.shared.commands.clone(),
+                mapping,
+            )
+        } else {
+            match Graph::open_with_commands(conn, name.to_string(), self.shared.commands.clone()) {
+                Ok(graph) => {
+                    self.state().opened.insert(name.to_string());
+                    graph
+                }
+                Err(error) => {
+                    // The connection is dropped, since it may have broken.
+                    self.release_slot();
+                    return Err(error);
+                }
+            }
+        };
+        if !self.shared.connection_listeners.is_empty() {
+            let shared = Arc::clone(&self.shared);
+            graph.on_connection_event(move |event| shared.emit_connection(event.clone()));
+        }
+        Ok(PooledGraph {
+            graph: Some(graph),
+            shared: Arc::clone(&self.shared),
+        })
+    }
+
+    fn checkout(&self, timeout: Duration) -> RedisGraphResult<Connection> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state();
+        loop {
+            if state.shut_down {
+                return Err(RedisGraphError::PoolShutDown);
+            }
+            if let Some(conn) = state.idle.pop() {
+                return Ok(conn);
+            }
+            if state.size < self.shared.max_size {
+                state.size += 1;
+                drop(state);
+                return self.shared.client.get_connection().map_err(|error| {
+                    self.release_slot();
+                    error.into()
+                });
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RedisGraphError::PoolTimeout);
+            }
+            state = self
+                .shared
+                .returned
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    fn release_slot(&self) {
+        self.shared.release_slot();
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.shared.state()
+    }
+
+    fn shared_mut(&mut self) -> &mut Shared {
+        Arc::get_mut(&mut self.shared).expect("the pool must be configured before it is cloned")
+    }
+}
+
+impl Shared {
+    // Frees the place of a connection that was dropped instead of returned.
+    fn release_slot(&self) {
+        self.state().size -= 1;
+        // Wakes up `shutdown` as well as checkouts.
+        self.returned.notify_all();
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn emit_connection(&self, event: ConnectionEvent) {
+        for listener in &self.connection_listeners {
+            listener(&event);
+        }
+    }
+}
+
+impl fmt::Debug for GraphPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state();
+        f.debug_struct("GraphPool")
+            .field("max_size", &self.shared.max_size)
+            .field("size", &state.size)
+            .field("idle", &state.idle.len())
+            .field("checkout_timeout", &self.shared.checkout_timeout)
+            .field("shut_down", &state.shut_down)
+            .finish()
+    }
+}
+
+/// A graph whose connection is lent out by a [`GraphPool`](struct.GraphPool.html).
+///
+/// Dereferences to the [`Graph`](../graph/struct.Graph.html). The connection is returned to the
+/// pool when this is dropped, unless it broke. Settings made on the graph, e.g. listeners,
+/// are not passed on to the next handle.
+pub struct PooledGraph {
+    graph: Option<Graph>,
+    shared: Arc<Shared>,
+}
+
+impl PooledGraph {
+    /// Takes the graph out of the pool, e.g. to [delete](../graph/struct.Graph.html#method.delete) it.
+    ///
+    /// The pool may open a new connection in its place.
+    pub fn into_inner(mut self) -> Graph {
+        self.shared.release_slot();
+        self.graph.take().unwrap()
+    }
+}
+
+impl Deref for PooledGraph {
+    type Target = Graph;
+
+    fn deref(&self) -> &Graph {
+        self.graph.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledGraph {
+    fn deref_mut(&mut self) -> &mut Graph {
+        self.graph.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledGraph {
+    fn drop(&mut self) {
+        let graph = match self.graph.take() {
+            Some(graph) => graph,
+            None => return,
+        };
+        let name = graph.name().to_string();
+        let (conn, mapping, disconnected) = graph.detach();
+        let mut state = self.shared.state();
+        if disconnected || state.shut_down {
+            drop(state);
+            drop(conn);
+            self.shared.release_slot();
+            return;
+        }
+        state.mappings.insert(name, mapping);
+        state.idle.push(conn);
+        drop(state);
+        self.shared.returned.notify_one();
+    }
+}
@@ -0,0 +1,354 @@
+use std::error::Error;
+use std::fmt;
+use std::str;
+use std::time::Duration;
+
+use redis::{FromRedisValue, RedisResult, Value};
+
+/// The execution plan of a query as reported by `GRAPH.PROFILE`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionPlan {
+    /// The operations of the plan in the order they were reported, root first.
+    pub operations: Vec<Operation>,
+}
+
+/// A single operation in an [`ExecutionPlan`](struct.ExecutionPlan.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    /// The name of the operation, e.g. `Node By Label Scan`.
+    pub name: String,
+    /// Additional information about the operation, e.g. the scanned pattern.
+    pub details: Vec<String>,
+    /// The nesting depth of the operation. The root operation has depth 0.
+    pub depth: usize,
+    /// The number of records produced by the operation.
+    pub records_produced: u64,
+    /// The time spent in the operation, including its children.
+    pub execution_time: Duration,
+}
+
+impl ExecutionPlan {
+    /// Returns the time spent executing the whole plan.
+    ///
+    /// This is the execution time of the root operation, which includes all of its children.
+    pub fn execution_time(&self) -> Duration {
+        self.operations
+            .first()
+            .map(|operation| operation.execution_time)
+            .unwrap_or_default()
+    }
+
+    /// Starts a set of assertions about this plan, see [`PlanAssertions`](struct.PlanAssertions.html).
+    pub fn assertions(self) -> PlanAssertions {
+        PlanAssertions {
+            plan: self,
+            checks: Vec::new(),
+            operation: None,
+        }
+    }
+}
+
+impl FromRedisValue for ExecutionPlan {
+    fn from_redis_value(value: &Value) -> RedisResult<Self> {
+        let lines: Vec<String> = FromRedisValue::from_redis_value(value)?;
+        let operations = lines
+            .iter()
+            .map(|line| parse_operation(line))
+            .collect::<Option<Vec<Operation>>>()
+            .ok_or((
+                redis::ErrorKind::TypeError,
+                "unexpected format of profile output",
+            ))?;
+        Ok(Self { operations })
+    }
+}
+
+// Parses lines like `    Node By Label Scan | (n:Person) | Records produced: 3, Execution time: 0.01 ms`.
+fn parse_operation(line: &str) -> Option<Operation> {
+    let trimmed = line.trim_start();
+    let depth = (line.len() - trimmed.len()) / 4;
+
+    let mut parts: Vec<&str> = trimmed.split(" | ").map(str::trim).collect();
+    let stats = parts.pop()?;
+    if parts.is_empty() {
+        return None;
+    }
+    let name = parts.remove(0).to_string();
+    let details = parts.into_iter().map(str::to_string).collect();
+
+    let mut records_produced = None;
+    let mut execution_time = None;
+    for stat in stats.split(", ") {
+        if let Some(records) = stat.strip_prefix("Records produced: ") {
+            records_produced = records.parse::<u64>().ok();
+        } else if let Some(time) = stat.strip_prefix("Execution time: ") {
+            execution_time = time
+                .trim_end_matches(" ms")
+                .parse::<f64>()
+                .ok()
+                .map(|millis| Duration::from_secs_f64(millis / 1000.0));
+        }
+    }
+
+    Some(Operation {
+        name,
+        details,
+        depth,
+        records_produced: records_produced?,
+        execution_time: execution_time?,
+    })
+}
+
+/// Aggregate timing statistics over several profiled runs of the same query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingStats {
+    /// The number of runs.
+    pub iterations: usize,
+    /// The fastest run.
+    pub min: Duration,
+    /// The slowest run.
+    pub max: Duration,
+    /// The arithmetic mean of all runs.
+    pub mean: Duration,
+    /// The median of all runs.
+    pub median: Duration,
+}
+
+impl TimingStats {
+    /// Computes statistics from the given run times.
+    ///
+    /// Returns `None` if `times` is empty.
+    pub fn from_durations(times: &[Duration]) -> Option<Self> {
+        if times.is_empty() {
+            return None;
+        }
+
+        let mut sorted = times.to_vec();
+        sorted.sort();
+
+        let len = sorted.len();
+        // For odd lengths, both indices point at the middle element.
+        let median = (sorted[(len - 1) / 2] + sorted[len / 2]) / 2;
+        let total: Duration = sorted.iter().sum();
+
+        Some(Self {
+            iterations: len,
+            min: sorted[0],
+            max: sorted[len - 1],
+            mean: total / len as u32,
+            median,
+        })
+    }
+}
+
+/// The result of [`Graph::profile_compare`](../graph/struct.Graph.html#method.profile_compare).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileComparison {
+    /// Timings of the first query.
+    pub a: TimingStats,
+    /// Timings of the second query.
+    pub b: TimingStats,
+}
+
+impl ProfileComparison {
+    /// Returns how many times faster the second query is than the first one, based on the mean.
+    ///
+    /// A value greater than 1 means that the second query is faster.
+    pub fn speedup(&self) -> f64 {
+        self.a.mean.as_secs_f64() / self.b.mean.as_secs_f64()
+    }
+}
+
+/// Assertions about an [`ExecutionPlan`](struct.ExecutionPlan.html), to catch performance
+/// regressions of critical queries in tests, created with
+/// [`Graph::explain_analyze`](../graph/struct.Graph.html#method.explain_analyze).
+///
+/// Limits apply to the operation named by the preceding [`expects_operation`](#method.expects_operation),
+/// or to the whole plan if there is none.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use redisgraph::{Graph, RedisGraphResult};
+/// # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+/// graph
+///     .explain_analyze("MATCH (p:Person) WHERE p.email = 'alice@example.com' RETURN p")?
+///     .forbids_operation("All Node Scan")
+///     .expects_operation("Node By Index Scan")
+///     .max_records(1)
+///     .assert();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanAssertions {
+    plan: ExecutionPlan,
+    checks: Vec<PlanCheck>,
+    operation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PlanCheck {
+    ExpectsOperation(String),
+    ForbidsOperation(String),
+    MaxRecords(Option<String>, u64),
+    MaxExecutionTime(Option<String>, Duration),
+}
+
+impl PlanAssertions {
+    /// Asserts that the plan contains an operation with the given name, e.g. `Node By Index Scan`.
+    pub fn expects_operation(mut self, name: &str) -> Self {
+        self.checks
+            .push(PlanCheck::ExpectsOperation(name.to_string()));
+        self.operation = Some(name.to_string());
+        self
+    }
+
+    /// Asserts that the plan contains no operation with the given name, e.g. `All Node Scan`.
+    pub fn forbids_operation(mut self, name: &str) -> Self {
+        self.checks
+            .push(PlanCheck::ForbidsOperation(name.to_string()));
+        self
+    }
+
+    /// Asserts that the operation produces at most `max` records, or every operation
+    /// if no operation is expected yet.
+    pub fn max_records(mut self, max: u64) -> Self {
+        self.checks
+            .push(PlanCheck::MaxRecords(self.operation.clone(), max));
+        self
+    }
+
+    /// Asserts that the operation, or the whole plan if no operation is expected yet,
+    /// takes at most `max` to execute.
+    pub fn max_execution_time(mut self, max: Duration) -> Self {
+        self.checks
+            .push(PlanCheck::MaxExecutionTime(self.operation.clone(), max));
+        self
+    }
+
+    /// Returns the plan the assertions are about.
+    pub fn plan(&self) -> &ExecutionPlan {
+        &self.plan
+    }
+
+    /// Evaluates the assertions and returns all failed ones.
+    pub fn check(&self) -> Result<(), PlanAssertionError> {
+        let mut failures = Vec::new();
+        for check in &self.checks {
+            match check {
+                PlanCheck::ExpectsOperation(name) => {
+                    if !self
+                        .plan
+                        .operations
+                        .iter()
+                        .any(|operation| operation.name == *name)
+                    {
+                        failures.push(format!("expected operation {:?}", name));
+                    }
+                }
+                PlanCheck::ForbidsOperation(name) => {
+                    if self
+                        .plan
+                        .operations
+                        .iter()
+                        .any(|operation| operation.name == *name)
+                    {
+                        failures.push(format!("unexpected operation {:?}", name));
+                    }
+                }
+                PlanCheck::MaxRecords(name, max) => {
+                    for operation in operations(&self.plan, name) {
+                        if operation.records_produced > *max {
+                            failures.push(format!(
+                                "operation {:?} produced {} records, expected at most {}",
+                                operation.name, operation.records_produced, max
+                            ));
+                        }
+                    }
+                }
+                PlanCheck::MaxExecutionTime(None, max) => {
+                    if self.plan.execution_time() > *max {
+                        failures.push(format!(
+                            "plan took {:?}, expected at most {:?}",
+                            self.plan.execution_time(),
+                            max
+                        ));
+                    }
+                }
+                PlanCheck::MaxExecutionTime(name, max) => {
+                    for operation in operations(&self.plan, name) {
+                        if operation.execution_time > *max {
+                            failures.push(format!(
+                                "operation {:?} took {:?}, expected at most {:?}",
+                                operation.name, operation.execution_time, max
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(PlanAssertionError {
+                failures,
+                plan: self.plan.clone(),
+            })
+        }
+    }
+
+    /// Evaluates the assertions.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the failed assertions and the plan if any assertion fails.
+    pub fn assert(&self) {
+        if let Err(error) = self.check() {
+            panic!("{}", error);
+        }
+    }
+}
+
+// Returns the operations with the given name, or all operations if there is none.
+fn operations<'a>(
+    plan: &'a ExecutionPlan,
+    name: &'a Option<String>,
+) -> impl Iterator<Item = &'a Operation> {
+    plan.operations.iter().filter(move |operation| match name {
+        Some(name) => operation.name == *name,
+        None => true,
+    })
+}
+
+/// The assertions that failed in [`PlanAssertions::check`](struct.PlanAssertions.html#method.check).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanAssertionError {
+    /// A description of every failed assertion.
+    pub failures: Vec<String>,
+    /// The plan the assertions were evaluated against.
+    pub plan: ExecutionPlan,
+}
+
+impl fmt::Display for PlanAssertionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "execution plan assertions failed:")?;
+        for failure in &self.failures {
+            writeln!(f, "- {}", failure)?;
+        }
+        writeln!(f, "plan:")?;
+        for operation in &self.plan.operations {
+            writeln!(
+                f,
+                "{}{} | Records produced: {}, Execution time: {:?}",
+                "    ".repeat(operation.depth),
+                operation.name,
+                operation.records_produced,
+                operation.execution_time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for PlanAssertionError {}
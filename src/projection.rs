@@ -0,0 +1,231 @@
+//! Copying a filtered subgraph into another graph.
+//!
+//! Heavy algorithms can be run on a projection without loading the production graph:
+//!
+//! ```no_run
+//! # use redisgraph::{projection::ProjectionFilter, Graph, RedisGraphResult};
+//! # fn example(production: &mut Graph, analytics: &mut Graph) -> RedisGraphResult<()> {
+//! let filter = ProjectionFilter::new()
+//!     .labels("Person")
+//!     .node_predicate("n.active = true")
+//!     .rel_types(&["KNOWS"]);
+//! let summary = production.project(&filter, analytics)?;
+//! println!("copied {} nodes", summary.nodes_copied);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use redis::ConnectionLike;
+
+use crate::{
+    import::{EdgeRecord, IdMap, NodeRecord},
+    labels::{LabelMatch, Labels},
+    params::CypherValue,
+    props::Props,
+    result_set::{Edge, Node, Scalar},
+    Graph, RedisGraphError, RedisGraphResult, RedisString,
+};
+
+/// Selects the nodes and relationships copied by
+/// [`Graph::project`](../graph/struct.Graph.html#method.project).
+///
+/// By default, everything is copied in batches of 1000 entities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectionFilter {
+    labels: LabelMatch,
+    node_predicate: Option<String>,
+    rel_types: Vec<String>,
+    rel_predicate: Option<String>,
+    batch_size: usize,
+}
+
+impl Default for ProjectionFilter {
+    fn default() -> Self {
+        Self {
+            labels: LabelMatch::All(Labels::new()),
+            node_predicate: None,
+            rel_types: Vec::new(),
+            rel_predicate: None,
+            batch_size: 1000,
+        }
+    }
+}
+
+impl ProjectionFilter {
+    /// Creates a filter that copies everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only copies nodes with the given labels.
+    pub fn labels<L: Into<LabelMatch>>(mut self, labels: L) -> Self {
+        self.labels = labels.into();
+        self
+    }
+
+    /// Only copies nodes matching the given predicate, which refers to the node as `n`,
+    /// e.g. `n.active = true`.
+    pub fn node_predicate(mut self, predicate: &str) -> Self {
+        self.node_predicate = Some(predicate.to_string());
+        self
+    }
+
+    /// Only copies relationships with one of the given types. No types copies all of them.
+    pub fn rel_types(mut self, rel_types: &[&str]) -> Self {
+        self.rel_types = rel_types
+            .iter()
+            .map(|rel_type| rel_type.to_string())
+            .collect();
+        self
+    }
+
+    /// Only copies relationships matching the given predicate, which refers to the
+    /// relationship as `r`, e.g. `r.weight > 0.5`.
+    pub fn rel_predicate(mut self, predicate: &str) -> Self {
+        self.rel_predicate = Some(predicate.to_string());
+        self
+    }
+
+    /// Sets the maximum number of nodes or relationships read and created per query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be greater than zero");
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+/// The outcome of [`Graph::project`](../graph/struct.Graph.html#method.project).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProjectionSummary {
+    /// The number of nodes copied.
+    pub nodes_copied: usize,
+    /// The number of relationships copied.
+    pub relationships_copied: usize,
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Copies the nodes and relationships selected by the filter into `dest`, which is
+    /// usually a new, empty graph. This graph is only read.
+    ///
+    /// A relationship is only copied if both of its endpoints are. Nodes and relationships
+    /// are read and created in batches ordered by their IDs, so a projection of a graph
+    /// that is written to at the same time may miss the latest changes. If a batch fails,
+    /// the batches before it stay in `dest`.
+    pub fn project<D: ConnectionLike>(
+        &mut self,
+        filter: &ProjectionFilter,
+        dest: &mut Graph<D>,
+    ) -> RedisGraphResult<ProjectionSummary> {
+        let mut node_conditions = vec![filter.labels.predicate("n")];
+        node_conditions.extend(filter.node_predicate.iter().map(|p| format!("({})", p)));
+
+        let mut ids = IdMap::new();
+        let mut last_id = -1;
+        loop {
+            let rows: Vec<(i64, Node)> = self.query(&format!(
+                "MATCH (n) WHERE id(n) > {} AND {} RETURN id(n), n ORDER BY id(n) LIMIT {}",
+                last_id,
+                node_conditions.join(" AND "),
+                filter.batch_size
+            ))?;
+            let last_batch = rows.len() < filter.batch_size;
+            if let Some((id, _)) = rows.last() {
+                last_id = *id;
+            }
+
+            let records = rows
+                .into_iter()
+                .map(|(id, node)| {
+                    Ok(NodeRecord {
+                        temp_id: id,
+                        labels: to_labels(&node.labels)?,
+                        props: to_props(node.properties)?,
+                    })
+                })
+                .collect::<RedisGraphResult<Vec<_>>>()?;
+            ids.extend(dest.import_nodes(records, filter.batch_size)?);
+            if last_batch {
+                break;
+            }
+        }
+
+        let mut rel_conditions = Vec::new();
+        let mut params = HashMap::new();
+        if !filter.rel_types.is_empty() {
+            rel_conditions.push("type(r) IN $rel_types".to_string());
+            params.insert(
+                "rel_types".to_string(),
+                CypherValue::from(filter.rel_types.clone()),
+            );
+        }
+        rel_conditions.extend(filter.rel_predicate.iter().map(|p| format!("({})", p)));
+
+        let mut relationships_copied = 0;
+        let mut last_id = -1;
+        loop {
+            let mut conditions = vec![format!("id(r) > {}", last_id)];
+            conditions.extend(rel_conditions.iter().cloned());
+            let rows: Vec<(i64, i64, i64, Edge)> = self.query_with_params(
+                &format!(
+                    "MATCH (a)-[r]->(b) WHERE {} RETURN id(r), id(a), id(b), r ORDER BY id(r) LIMIT {}",
+                    conditions.join(" AND "),
+                    filter.batch_size
+                ),
+                &params,
+            )?;
+            let last_batch = rows.len() < filter.batch_size;
+            if let Some((id, _, _, _)) = rows.last() {
+                last_id = *id;
+            }
+
+            let records = rows
+                .into_iter()
+                .filter(|(_, src, dst, _)| ids.contains_key(src) && ids.contains_key(dst))
+                .map(|(_, src, dst, edge)| {
+                    Ok(EdgeRecord {
+                        src,
+                        dst,
+                        rel_type: to_string(edge.type_name)?,
+                        props: to_props(edge.properties)?,
+                    })
+                })
+                .collect::<RedisGraphResult<Vec<_>>>()?;
+            relationships_copied += dest.import_edges(records, &ids, filter.batch_size)?;
+            if last_batch {
+                break;
+            }
+        }
+
+        Ok(ProjectionSummary {
+            nodes_copied: ids.len(),
+            relationships_copied,
+        })
+    }
+}
+
+fn to_string(string: RedisString) -> RedisGraphResult<String> {
+    String::from_utf8(string.into()).map_err(|_| RedisGraphError::InvalidUtf8)
+}
+
+fn to_labels(labels: &[RedisString]) -> RedisGraphResult<Labels> {
+    let mut set = Labels::new();
+    for label in labels {
+        set.insert(&to_string(label.clone())?);
+    }
+    Ok(set)
+}
+
+fn to_props(properties: HashMap<RedisString, Scalar>) -> RedisGraphResult<Props> {
+    properties
+        .into_iter()
+        .map(|(key, value)| Ok((to_string(key)?, CypherValue::try_from(value)?)))
+        .collect::<RedisGraphResult<HashMap<String, CypherValue>>>()
+        .map(Props::from)
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::params::{escape_identifier, CypherValue};
+
+/// Implemented by types that can be turned into a property map.
+///
+/// With the `derive` feature enabled, this can be derived for structs:
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// #[derive(redisgraph::ToProperties)]
+/// struct Person {
+///     name: String,
+///     #[redisgraph(rename = "birth_year")]
+///     born: i64,
+///     nickname: Option<String>,
+///     #[redisgraph(skip)]
+///     cached_score: f64,
+/// }
+/// ```
+pub trait ToProperties {
+    fn to_properties(&self) -> Props;
+}
+
+impl ToProperties for Props {
+    fn to_properties(&self) -> Props {
+        self.clone()
+    }
+}
+
+impl ToProperties for HashMap<String, CypherValue> {
+    fn to_properties(&self) -> Props {
+        self.clone().into()
+    }
+}
+
+/// Determines what [`Props::set_opt`](struct.Props.html#method.set_opt) does with `None` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NoneHandling {
+    /// Leave the property out of the map. This is the default.
+    #[default]
+    Skip,
+    /// Set the property to `null`.
+    ///
+    /// When the map is used to update an existing node, this removes the property from the node.
+    SetNull,
+}
+
+/// A builder for property maps.
+///
+/// ```
+/// use redisgraph::props::Props;
+///
+/// let nickname: Option<&str> = None;
+/// let props = Props::new()
+///     .set("name", "Alice")
+///     .set("age", 30)
+///     .set_opt("nickname", nickname);
+/// assert_eq!(props.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Props {
+    map: HashMap<String, CypherValue>,
+    none_handling: NoneHandling,
+}
+
+impl Props {
+    /// Creates an empty property map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how subsequent calls to [`set_opt`](#method.set_opt) handle `None` values.
+    pub fn none_handling(mut self, none_handling: NoneHandling) -> Self {
+        self.none_handling = none_handling;
+        self
+    }
+
+    /// Sets the property with the given key to the given value.
+    pub fn set<K: Into<String>, V: Into<CypherValue>>(mut self, key: K, value: V) -> Self {
+        self.map.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the property with the given key to the given value if it is `Some`.
+    ///
+    /// `None` values are either skipped or set to `null`,
+    /// depending on the configured [`NoneHandling`](enum.NoneHandling.html).
+    pub fn set_opt<K: Into<String>, V: Into<CypherValue>>(self, key: K, value: Option<V>) -> Self {
+        match (value, self.none_handling) {
+            (Some(value), _) => self.set(key, value),
+            (None, NoneHandling::SetNull) => self.set(key, CypherValue::Null),
+            (None, NoneHandling::Skip) => self,
+        }
+    }
+
+    /// Returns the value of the property with the given key.
+    pub fn get(&self, key: &str) -> Option<&CypherValue> {
+        self.map.get(key)
+    }
+
+    /// Returns the number of properties in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no properties.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the underlying map.
+    pub fn as_map(&self) -> &HashMap<String, CypherValue> {
+        &self.map
+    }
+
+    /// Consumes the builder, returning the underlying map.
+    pub fn into_map(self) -> HashMap<String, CypherValue> {
+        self.map
+    }
+
+    /// Returns the `(key, parameter name)` pairs of this map along with the parameters,
+    /// naming the parameters `{prefix}0`, `{prefix}1`, ... in order of their keys.
+    pub(crate) fn to_params(
+        &self,
+        prefix: &str,
+    ) -> (Vec<(String, String)>, HashMap<String, CypherValue>) {
+        let mut entries: Vec<(&String, &CypherValue)> = self.map.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+
+        let mut keys = Vec::with_capacity(entries.len());
+        let mut params = HashMap::with_capacity(entries.len());
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            let param_name = format!("{}{}", prefix, i);
+            keys.push((escape_identifier(key), param_name.clone()));
+            params.insert(param_name, value.clone());
+        }
+
+        (keys, params)
+    }
+
+    /// Returns a map literal like `{name: $p0, age: $p1}` referencing the parameters returned along with it.
+    ///
+    /// The literal is empty if there are no properties.
+    pub(crate) fn to_map_pattern(&self, prefix: &str) -> (String, HashMap<String, CypherValue>) {
+        let (keys, params) = self.to_params(prefix);
+        if keys.is_empty() {
+            return (String::new(), params);
+        }
+
+        let entries = keys
+            .iter()
+            .map(|(key, param_name)| format!("{}: ${}", key, param_name))
+            .collect::<Vec<String>>();
+        (format!("{{{}}}", entries.join(", ")), params)
+    }
+}
+
+impl From<Props> for HashMap<String, CypherValue> {
+    fn from(props: Props) -> Self {
+        props.into_map()
+    }
+}
+
+impl From<HashMap<String, CypherValue>> for Props {
+    fn from(map: HashMap<String, CypherValue>) -> Self {
+        Self {
+            map,
+            none_handling: NoneHandling::default(),
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Not;
+
+use crate::{
+    fragment::QueryFragment,
+    labels::Labels,
+    params::{escape_identifier, is_finite, is_valid_parameter_name, CypherValue},
+    pattern::Pattern,
+    RedisGraphError, RedisGraphResult,
+};
+
+/// An expression like `p.age > $min`, used in `WHERE` and `RETURN` clauses built with a
+/// [`QueryBuilder`](struct.QueryBuilder.html).
+///
+/// Variables and property keys are quoted. Values should be passed as parameters,
+/// which are bound on the builder.
+///
+/// ```
+/// use redisgraph::query::{param, Expr};
+///
+/// let expr = Expr::prop("p", "age")
+///     .gt(param("min"))
+///     .and(Expr::prop("p", "name").starts_with(param("q")));
+/// assert_eq!(
+///     expr.to_string(),
+///     "(`p`.`age` > $min) AND (`p`.`name` STARTS WITH $q)"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr(ExprKind);
+
+#[derive(Debug, Clone, PartialEq)]
+enum ExprKind {
+    Variable(String),
+    Property(Box<Expr>, String),
+    Param(String),
+    Value(CypherValue),
+    Binary(Box<Expr>, &'static str, Box<Expr>),
+    Postfix(Box<Expr>, &'static str),
+    Not(Box<Expr>),
+    Call(&'static str, Box<Expr>),
+}
+
+/// Returns an expression referencing the query parameter with the given name, i.e. `$name`.
+pub fn param(name: &str) -> Expr {
+    Expr(ExprKind::Param(name.to_string()))
+}
+
+impl Expr {
+    /// Returns an expression referencing the given variable.
+    pub fn var(variable: &str) -> Self {
+        Self(ExprKind::Variable(variable.to_string()))
+    }
+
+    /// Returns an expression accessing a property of the given variable, e.g. `p.age`.
+    pub fn prop(variable: &str, key: &str) -> Self {
+        Self::var(variable).field(key)
+    }
+
+    /// Returns an expression consisting of the given value as a literal.
+    pub fn value<V: Into<CypherValue>>(value: V) -> Self {
+        Self(ExprKind::Value(value.into()))
+    }
+
+    /// Accesses the property or map entry with the given key, e.g. `row.id`.
+    pub fn field(self, key: &str) -> Self {
+        Self(ExprKind::Property(Box::new(self), key.to_string()))
+    }
+
+    /// `self = other`
+    pub fn eq(self, other: Expr) -> Self {
+        self.binary("=", other)
+    }
+
+    /// `self <> other`
+    pub fn ne(self, other: Expr) -> Self {
+        self.binary("<>", other)
+    }
+
+    /// `self < other`
+    pub fn lt(self, other: Expr) -> Self {
+        self.binary("<", other)
+    }
+
+    /// `self <= other`
+    pub fn le(self, other: Expr) -> Self {
+        self.binary("<=", other)
+    }
+
+    /// `self > other`
+    pub fn gt(self, other: Expr) -> Self {
+        self.binary(">", other)
+    }
+
+    /// `self >= other`
+    pub fn ge(self, other: Expr) -> Self {
+        self.binary(">=", other)
+    }
+
+    /// `self STARTS WITH other`
+    pub fn starts_with(self, other: Expr) -> Self {
+        self.binary("STARTS WITH", other)
+    }
+
+    /// `self ENDS WITH other`
+    pub fn ends_with(self, other: Expr) -> Self {
+        self.binary("ENDS WITH", other)
+    }
+
+    /// `self CONTAINS other`
+    pub fn contains(self, other: Expr) -> Self {
+        self.binary("CONTAINS", other)
+    }
+
+    /// `self IN other`
+    pub fn is_in(self, other: Expr) -> Self {
+        self.binary("IN", other)
+    }
+
+    /// `self IS NULL`
+    pub fn is_null(self) -> Self {
+        Self(ExprKind::Postfix(Box::new(self), "IS NULL"))
+    }
+
+    /// `self IS NOT NULL`
+    pub fn is_not_null(self) -> Self {
+        Self(ExprKind::Postfix(Box::new(self), "IS NOT NULL"))
+    }
+
+    /// `self AND other`
+    pub fn and(self, other: Expr) -> Self {
+        self.binary("AND", other)
+    }
+
+    /// `self OR other`
+    pub fn or(self, other: Expr) -> Self {
+        self.binary("OR", other)
+    }
+
+    /// `count(self)`
+    pub fn count(self) -> Self {
+        Self(ExprKind::Call("count", Box::new(self)))
+    }
+
+    fn binary(self, operator: &'static str, other: Expr) -> Self {
+        Self(ExprKind::Binary(Box::new(self), operator, Box::new(other)))
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(
+            self.0,
+            ExprKind::Binary(..) | ExprKind::Postfix(..) | ExprKind::Not(..)
+        )
+    }
+
+    fn operand_string(&self) -> String {
+        if self.is_compound() {
+            format!("({})", self)
+        } else {
+            self.to_string()
+        }
+    }
+
+    // Checks that the expression can be formatted as valid Cypher.
+    fn check(&self) -> RedisGraphResult<()> {
+        match &self.0 {
+            ExprKind::Param(name) if !is_valid_parameter_name(name) => {
+                Err(RedisGraphError::InvalidParameterName(name.clone()))
+            }
+            ExprKind::Value(value) if !is_finite(value) => {
+                Err(RedisGraphError::NonFiniteLiteral(value.to_string()))
+            }
+            ExprKind::Binary(left, _, right) => {
+                left.check()?;
+                right.check()
+            }
+            ExprKind::Property(operand, _)
+            | ExprKind::Postfix(operand, _)
+            | ExprKind::Not(operand)
+            | ExprKind::Call(_, operand) => operand.check(),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn to_fragment(&self) -> RedisGraphResult<QueryFragment> {
+        self.check()?;
+        Ok(QueryFragment::from_trusted(self.to_string()))
+    }
+}
+
+impl Not for Expr {
+    type Output = Expr;
+
+    /// `NOT self`
+    fn not(self) -> Self::Output {
+        Self(ExprKind::Not(Box::new(self)))
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Formats the expression as Cypher.
+    ///
+    /// Sub-expressions are parenthesized, so the result never depends on operator precedence.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            ExprKind::Variable(variable) => write!(f, "{}", escape_identifier(variable)),
+            ExprKind::Property(operand, key) => {
+                write!(f, "{}.{}", operand.operand_string(), escape_identifier(key))
+            }
+            ExprKind::Param(name) => write!(f, "${}", name),
+            ExprKind::Value(value) => write!(f, "{}", value),
+            ExprKind::Binary(left, operator, right) => write!(
+                f,
+                "{} {} {}",
+                left.operand_string(),
+                operator,
+                right.operand_string()
+            ),
+            ExprKind::Postfix(operand, operator) => {
+                write!(f, "{} {}", operand.operand_string(), operator)
+            }
+            ExprKind::Not(operand) => write!(f, "NOT {}", operand.operand_string()),
+            ExprKind::Call(function, argument) => write!(f, "{}({})", function, argument),
+        }
+    }
+}
+
+/// The order in which rows are sorted by an `ORDER BY` key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// `ASC`
+    Ascending,
+    /// `DESC`
+    Descending,
+}
+
+/// A builder for queries consisting of `UNWIND`, `MATCH`, `WHERE`, `MERGE`, `SET` and `RETURN` clauses.
+///
+/// The clauses are always emitted in a valid order, no matter in which order the methods are called.
+///
+/// ```
+/// use redisgraph::{pattern::node, query::{param, Expr, QueryBuilder, SortOrder}};
+///
+/// let query = QueryBuilder::new()
+///     .match_pattern(node("p").label("Person"))
+///     .filter(Expr::prop("p", "age").ge(param("min")))
+///     .returns(Expr::prop("p", "name"))
+///     .param("min", 18)
+///     .order_by(Expr::prop("p", "name"), SortOrder::Ascending)
+///     .limit(10)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     query.query(),
+///     "MATCH (`p`:`Person`) WHERE `p`.`age` >= $min RETURN `p`.`name` ORDER BY `p`.`name` ASC LIMIT 10"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    unwinds: Vec<(String, String)>,
+    patterns: Vec<Pattern>,
+    conditions: Vec<Expr>,
+    merges: Vec<MergeClause>,
+    assignments: Vec<(Expr, Expr)>,
+    returns: Vec<(Expr, Option<String>)>,
+    distinct: bool,
+    sort_keys: Vec<(Expr, SortOrder)>,
+    skip: Option<u64>,
+    limit: Option<u64>,
+    params: HashMap<String, CypherValue>,
+}
+
+#[derive(Debug, Clone)]
+struct MergeClause {
+    variable: String,
+    labels: Labels,
+    key: Vec<(String, Expr)>,
+}
+
+impl QueryBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an `UNWIND $name AS row` clause that iterates over the array passed as the given parameter.
+    ///
+    /// The closure receives the row variable and returns the clauses to run for every row,
+    /// which are added to this builder.
+    ///
+    /// ```
+    /// use redisgraph::{params::CypherValue, query::{Expr, QueryBuilder}};
+    ///
+    /// let rows = CypherValue::Array(vec![]);
+    /// let query = QueryBuilder::new()
+    ///     .unwind_param("rows", |row| {
+    ///         QueryBuilder::new()
+    ///             .merge_node("n", "Person", vec![("id", row.clone().field("id"))])
+    ///             .set(Expr::prop("n", "name"), row.field("name"))
+    ///     })
+    ///     .param("rows", rows)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     query.query(),
+    ///     "UNWIND $rows AS `row` MERGE (`n`:`Person` {`id`: `row`.`id`}) SET `n`.`name` = `row`.`name`"
+    /// );
+    /// ```
+    pub fn unwind_param<F: FnOnce(Expr) -> QueryBuilder>(mut self, name: &str, body: F) -> Self {
+        let alias = match self.unwinds.len() {
+            0 => "row".to_string(),
+            n => format!("row{}", n + 1),
+        };
+        self.unwinds.push((name.to_string(), alias.clone()));
+        self.extend(body(Expr::var(&alias)))
+    }
+
+    /// Adds a pattern to the `MATCH` clause.
+    pub fn match_pattern(mut self, pattern: Pattern) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Adds a condition to the `WHERE` clause.
+    ///
+    /// Multiple conditions are combined with `AND`.
+    pub fn filter(mut self, condition: Expr) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Adds a `MERGE` clause for a node with the given labels, bound to the given variable
+    /// and identified by the given properties.
+    pub fn merge_node<L: Into<Labels>>(
+        mut self,
+        variable: &str,
+        labels: L,
+        key: Vec<(&str, Expr)>,
+    ) -> Self {
+        let key = key
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        self.merges.push(MergeClause {
+            variable: variable.to_string(),
+            labels: labels.into(),
+            key,
+        });
+        self
+    }
+
+    /// Adds an assignment like `n.name = row.name` to the `SET` clause.
+    pub fn set(mut self, target: Expr, value: Expr) -> Self {
+        self.assignments.push((target, value));
+        self
+    }
+
+    /// Adds an expression to the `RETURN` clause.
+    pub fn returns(mut self, expr: Expr) -> Self {
+        self.returns.push((expr, None));
+        self
+    }
+
+    /// Adds an expression to the `RETURN` clause under the given alias.
+    pub fn returns_as(mut self, expr: Expr, alias: &str) -> Self {
+        self.returns.push((expr, Some(alias.to_string())));
+        self
+    }
+
+    /// Only returns distinct rows, i.e. emits `RETURN DISTINCT`.
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Adds a sort key to the `ORDER BY` clause.
+    ///
+    /// Rows are sorted by the keys in the order they were added.
+    pub fn order_by(mut self, expr: Expr, order: SortOrder) -> Self {
+        self.sort_keys.push((expr, order));
+        self
+    }
+
+    /// Skips the given number of rows.
+    pub fn skip(mut self, skip: u64) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Returns at most the given number of rows.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Binds a value to the parameter with the given name.
+    pub fn param<V: Into<CypherValue>>(mut self, name: &str, value: V) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+
+    fn extend(mut self, other: QueryBuilder) -> Self {
+        self.unwinds.extend(other.unwinds);
+        self.patterns.extend(other.patterns);
+        self.conditions.extend(other.conditions);
+        self.merges.extend(other.merges);
+        self.assignments.extend(other.assignments);
+        self.returns.extend(other.returns);
+        self.distinct |= other.distinct;
+        self.sort_keys.extend(other.sort_keys);
+        self.skip = other.skip.or(self.skip);
+        self.limit = other.limit.or(self.limit);
+        self.params.extend(other.params);
+        self
+    }
+
+    /// Assembles the query.
+    ///
+    /// Fails if a parameter name is not a valid identifier, a referenced parameter is not bound,
+    /// or a literal or parameter value is or contains a `NaN` or infinite double.
+    pub fn build(self) -> RedisGraphResult<BuiltQuery> {
+        let (query, params) = self.build_fragment()?.into_parts()?;
+        Ok(BuiltQuery { query, params })
+    }
+
+    fn build_fragment(self) -> RedisGraphResult<QueryFragment> {
+        let mut query = QueryFragment::default();
+
+        for (name, alias) in &self.unwinds {
+            query = query
+                .append(QueryFragment::new("UNWIND"))
+                .append(param(name).to_fragment()?)
+                .append(QueryFragment::new("AS"))
+                .append(QueryFragment::identifier(alias));
+        }
+
+        if !self.patterns.is_empty() {
+            let patterns = self
+                .patterns
+                .iter()
+                .map(|pattern| QueryFragment::from_trusted(pattern.to_string()));
+            query = query
+                .append(QueryFragment::new("MATCH"))
+                .append(QueryFragment::join(patterns, ", "));
+        }
+
+        if !self.conditions.is_empty() {
+            let conditions = self
+                .conditions
+                .iter()
+                .map(|condition| {
+                    let fragment = condition.to_fragment()?;
+                    Ok(if self.conditions.len() > 1 && condition.is_compound() {
+                        fragment.parenthesized()
+                    } else {
+                        fragment
+                    })
+                })
+                .collect::<RedisGraphResult<Vec<QueryFragment>>>()?;
+            query = query
+                .append(QueryFragment::new("WHERE"))
+                .append(QueryFragment::join(conditions, " AND "));
+        }
+
+        for merge in &self.merges {
+            let key = merge
+                .key
+                .iter()
+                .map(|(name, value)| {
+                    let value = value.to_fragment()?;
+                    Ok(format!("{}: {}", escape_identifier(name), value.text()))
+                })
+                .collect::<RedisGraphResult<Vec<String>>>()?;
+            let key = if key.is_empty() {
+                String::new()
+            } else {
+                format!(" {{{}}}", key.join(", "))
+            };
+            query = query.append(QueryFragment::from_trusted(format!(
+                "MERGE ({}{}{})",
+                escape_identifier(&merge.variable),
+                merge.labels,
+                key
+            )));
+        }
+
+        if !self.assignments.is_empty() {
+            let assignments = self
+                .assignments
+                .iter()
+                .map(|(target, value)| {
+                    Ok(target
+                        .to_fragment()?
+                        .append(QueryFragment::new("="))
+                        .append(value.to_fragment()?))
+                })
+                .collect::<RedisGraphResult<Vec<QueryFragment>>>()?;
+            query = query
+                .append(QueryFragment::new("SET"))
+                .append(QueryFragment::join(assignments, ", "));
+        }
+
+        if !self.returns.is_empty() {
+            let returns = self
+                .returns
+                .iter()
+                .map(|(expr, alias)| {
+                    let fragment = expr.to_fragment()?;
+                    Ok(match alias {
+                        Some(alias) => fragment
+                            .append(QueryFragment::new("AS"))
+                            .append(QueryFragment::identifier(alias)),
+                        None => fragment,
+                    })
+                })
+                .collect::<RedisGraphResult<Vec<QueryFragment>>>()?;
+            query = query.append(QueryFragment::new("RETURN"));
+            if self.distinct {
+                query = query.append(QueryFragment::new("DISTINCT"));
+            }
+            query = query.append(QueryFragment::join(returns, ", "));
+        }
+
+        if !self.sort_keys.is_empty() {
+            let sort_keys = self
+                .sort_keys
+                .iter()
+                .map(|(expr, order)| {
+                    let order = match order {
+                        SortOrder::Ascending => QueryFragment::new("ASC"),
+                        SortOrder::Descending => QueryFragment::new("DESC"),
+                    };
+                    Ok(expr.to_fragment()?.append(order))
+                })
+                .collect::<RedisGraphResult<Vec<QueryFragment>>>()?;
+            query = query
+                .append(QueryFragment::new("ORDER BY"))
+                .append(QueryFragment::join(sort_keys, ", "));
+        }
+
+        if let Some(skip) = self.skip {
+            query = query.append(QueryFragment::from_trusted(format!("SKIP {}", skip)));
+        }
+        if let Some(limit) = self.limit {
+            query = query.append(QueryFragment::from_trusted(format!("LIMIT {}", limit)));
+        }
+
+        for (name, value) in self.params {
+            if !is_valid_parameter_name(&name) {
+                return Err(RedisGraphError::InvalidParameterName(name));
+            }
+            if !is_finite(&value) {
+                return Err(RedisGraphError::NonFiniteParameter(name));
+            }
+            query = query.bind(&name, value);
+        }
+
+        Ok(query)
+    }
+}
+
+/// A query assembled by a [`QueryBuilder`](struct.QueryBuilder.html), with all of its parameters bound.
+///
+/// Pass it to [`Graph::query_with_params`](../graph/struct.Graph.html#method.query_with_params)
+/// like so: `graph.query_with_params(built.query(), built.params())`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltQuery {
+    query: String,
+    params: HashMap<String, CypherValue>,
+}
+
+impl BuiltQuery {
+    /// Returns the query text.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the parameters of the query.
+    pub fn params(&self) -> &HashMap<String, CypherValue> {
+        &self.params
+    }
+
+    /// Returns the query text and its parameters.
+    pub fn into_parts(self) -> (String, HashMap<String, CypherValue>) {
+        (self.query, self.params)
+    }
+}
@@ -0,0 +1,263 @@
+//! An async graph that survives broken connections.
+//!
+//! Once the connection of an [`AsyncGraph`](../aio/struct.AsyncGraph.html) breaks, e.g. because
+//! the server restarted, every further query fails with the same error. A
+//! [`ReconnectingGraph`](struct.ReconnectingGraph.html) opens a new connection from its client
+//! instead, keeping the cached names of the graph.
+//!
+//! Newer versions of the `redis` crate provide a `ConnectionManager` for this, but the version
+//! this crate depends on doesn't, so the reconnection is implemented here.
+//!
+//! Requests that fail are resent at most once, depending on the error, the same way as the
+//! [`retry`](../retry/index.html) module classifies them:
+//!
+//! * After a [transient](../retry/fn.is_transient.html) error, e.g. while the server is still
+//!   loading its dataset, every request is resent, since the server rejected it without
+//!   applying it.
+//! * After an [ambiguous](../retry/fn.is_ambiguous.html) error, e.g. because the connection broke,
+//!   only read-only queries sent with [`ro_query`](struct.ReconnectingGraph.html#method.ro_query)
+//!   are resent over a new connection. Other queries and mutations may or may not have been
+//!   applied, so their error is returned, and the next request opens a new connection.
+//! * Other errors are returned right away.
+//!
+//! Requires the `tokio-comp` feature.
+//!
+//! ```no_run
+//! # use redisgraph::{reconnect::ReconnectingGraph, RedisGraphResult};
+//! # async fn example(client: redis::Client) -> RedisGraphResult<()> {
+//! let mut graph = ReconnectingGraph::open(client, "MotoGP".to_string()).await?;
+//! let riders: i64 = graph.ro_query("MATCH (r:Rider) RETURN count(r)").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use redis::Client;
+
+use crate::{
+    aio::AsyncGraph,
+    assignments::FromTable,
+    events::{ConnectionEvent, ConnectionListener},
+    params::{prepare_query, CypherValue},
+    result_set::Statistics,
+    RedisGraphError, RedisGraphResult, ResultSet,
+};
+
+/// An async graph that reconnects when its connection broke,
+/// see the [module documentation](index.html).
+pub struct ReconnectingGraph {
+    client: Client,
+    graph: AsyncGraph,
+    broken: bool,
+    connection_listeners: Vec<ConnectionListener>,
+}
+
+impl ReconnectingGraph {
+    /// Opens the graph with the given name over a new connection from the given client.
+    ///
+    /// If the graph does not already exist, creates a new graph with the given name.
+    pub async fn open(client: Client, name: String) -> RedisGraphResult<Self> {
+        let graph = AsyncGraph::connect(&client, name).await?;
+        Ok(Self {
+            client,
+            graph,
+            broken: false,
+            connection_listeners: Vec::new(),
+        })
+    }
+
+    /// Executes the given query and returns its return values.
+    ///
+    /// Only use this for queries with a `RETURN` statement. If the connection broke, the error
+    /// is returned since the query may have modified the graph, and the next request opens a
+    /// new connection. Use [`ro_query`](#method.ro_query) for queries that should be resent.
+    /// See the [module documentation](index.html) for the errors after which it is resent.
+    pub async fn query<T: FromTable>(&mut self, query: &str) -> RedisGraphResult<T> {
+        self.query_with_statistics(query)
+            .await
+            .map(|(value, _)| value)
+    }
+
+    /// Same as [`query`](#method.query), but also returns statistics about the query along with its return values.
+    pub async fn query_with_statistics<T: FromTable>(
+        &mut self,
+        query: &str,
+    ) -> RedisGraphResult<(T, Statistics)> {
+        let result_set = self.query_result_set(query).await?;
+        let value = T::from_table(&result_set)?;
+        Ok((value, result_set.statistics))
+    }
+
+    /// Same as [`query`](#method.query), but passes the given parameters to the query.
+    ///
+    /// Parameters are referenced in the query as `$name`.
+    pub async fn query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        let query = prepare_query(query, params, self.graph.param_validation())?;
+        self.query(&query).await
+    }
+
+    /// Executes the given query and returns its result set without converting it to a Rust type.
+    pub async fn query_result_set(&mut self, query: &str) -> RedisGraphResult<ResultSet> {
+        self.ensure_connected().await?;
+        let mut result = self.graph.query_result_set(query).await;
+        if matches!(&result, Err(error) if error.is_transient()) {
+            result = self.graph.query_result_set(query).await;
+        }
+        self.check(result)
+    }
+
+    /// Same as [`query`](#method.query), but sends the query as a read-only query,
+    /// see [`Graph::ro_query`](../graph/struct.Graph.html#method.ro_query).
+    ///
+    /// The server rejects queries that would modify the graph, so the query is resent once
+    /// over a new connection if the connection broke, as well as after transient errors.
+    pub async fn ro_query<T: FromTable>(&mut self, query: &str) -> RedisGraphResult<T> {
+        let result_set = self.ro_query_result_set(query).await?;
+        T::from_table(&result_set)
+    }
+
+    /// Same as [`ro_query`](#method.ro_query), but passes the given parameters to the query.
+    pub async fn ro_query_with_params<T: FromTable>(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        let query = prepare_query(query, params, self.graph.param_validation())?;
+        self.ro_query(&query).await
+    }
+
+    /// Same as [`query_result_set`](#method.query_result_set), but sends the query as a
+    /// [read-only query](#method.ro_query).
+    pub async fn ro_query_result_set(&mut self, query: &str) -> RedisGraphResult<ResultSet> {
+        self.ensure_connected().await?;
+        let result = match self.graph.ro_query_result_set(query).await {
+            Err(error) if error.is_ambiguous() || error.is_transient() => {
+                self.mark_broken(&error);
+                self.ensure_connected().await?;
+                self.graph.ro_query_result_set(query).await
+            }
+            result => result,
+        };
+        self.check(result)
+    }
+
+    /// Executes the given query while not returning any values.
+    ///
+    /// If the connection broke, the error is returned since the mutation may or may not
+    /// have been applied, and the next request opens a new connection. After transient errors,
+    /// the mutation is resent once.
+    pub async fn mutate(&mut self, query: &str) -> RedisGraphResult<()> {
+        self.mutate_with_statistics(query).await.map(|_| ())
+    }
+
+    /// Same as [`mutate`](#method.mutate), but returns statistics about the query.
+    pub async fn mutate_with_statistics(&mut self, query: &str) -> RedisGraphResult<Statistics> {
+        self.ensure_connected().await?;
+        let mut result = self.graph.mutate_with_statistics(query).await;
+        if matches!(&result, Err(error) if error.is_transient()) {
+            result = self.graph.mutate_with_statistics(query).await;
+        }
+        self.check(result)
+    }
+
+    /// Same as [`mutate`](#method.mutate), but passes the given parameters to the query.
+    pub async fn mutate_with_params(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        let query = prepare_query(query, params, self.graph.param_validation())?;
+        self.mutate(&query).await
+    }
+
+    /// Deletes the entire graph from the database.
+    ///
+    /// *This action is not easily reversible.*
+    pub async fn delete(mut self) -> RedisGraphResult<()> {
+        self.ensure_connected().await?;
+        self.graph.delete().await
+    }
+
+    /// Enables or disables checking parameters against the query before sending it,
+    /// see [`Graph::set_param_validation`](../graph/struct.Graph.html#method.set_param_validation).
+    pub fn set_param_validation(&mut self, enabled: bool) {
+        self.graph.set_param_validation(enabled);
+    }
+
+    /// Returns `true` if parameters are checked against the query before sending it.
+    pub fn param_validation(&self) -> bool {
+        self.graph.param_validation()
+    }
+
+    /// Registers a callback that is invoked when a request fails because of a broken connection,
+    /// when a new connection is opened in its place, and when opening one fails.
+    pub fn on_connection_event<F>(&mut self, listener: F)
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.connection_listeners.push(Box::new(listener));
+    }
+
+    /// Returns the underlying graph, e.g. for its slowlog.
+    ///
+    /// Requests sent with it directly don't open a new connection when it broke.
+    pub async fn graph(&mut self) -> RedisGraphResult<&mut AsyncGraph> {
+        self.ensure_connected().await?;
+        Ok(&mut self.graph)
+    }
+
+    // Marks the connection as broken if the request failed because of it.
+    fn check<T>(&mut self, result: RedisGraphResult<T>) -> RedisGraphResult<T> {
+        if let Err(error) = &result {
+            self.mark_broken(error);
+        }
+        result
+    }
+
+    fn mark_broken(&mut self, error: &RedisGraphError) {
+        let broken = error.is_ambiguous();
+        if broken && !self.broken {
+            self.emit_connection(ConnectionEvent::Disconnected {
+                graph_name: self.graph.name().to_string(),
+                error: format!("{:?}", error),
+                timestamp: SystemTime::now(),
+            });
+        }
+        self.broken = broken;
+    }
+
+    async fn ensure_connected(&mut self) -> RedisGraphResult<()> {
+        if self.broken {
+            let conn = match self.client.get_multiplexed_tokio_connection().await {
+                Ok(conn) => conn,
+                Err(error) => {
+                    self.emit_connection(ConnectionEvent::ReconnectFailed {
+                        graph_name: self.graph.name().to_string(),
+                        error: error.to_string(),
+                        timestamp: SystemTime::now(),
+                    });
+                    return Err(error.into());
+                }
+            };
+            self.graph.set_connection(conn);
+            self.broken = false;
+            self.emit_connection(ConnectionEvent::Reconnected {
+                graph_name: self.graph.name().to_string(),
+                timestamp: SystemTime::now(),
+            });
+        }
+        Ok(())
+    }
+
+    fn emit_connection(&self, event: ConnectionEvent) {
+        for listener in &self.connection_listeners {
+            listener(&event);
+        }
+    }
+}
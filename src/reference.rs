@@ -0,0 +1,64 @@
+use redis::ConnectionLike;
+
+use crate::{result_set::Node, Graph, RedisGraphError, RedisGraphResult};
+
+/// A lightweight reference to a node, consisting only of a graph name and a node ID.
+///
+/// Use this to pass nodes around without carrying their labels and properties.
+/// The node can be loaded from the database when it's actually needed.
+///
+/// Node IDs can be retrieved using the `id` function, e.g. `MATCH (n:Person) RETURN id(n)`.
+/// Note that RedisGraph reuses the IDs of deleted nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeRef {
+    graph_name: String,
+    id: u64,
+}
+
+impl NodeRef {
+    /// Creates a reference to the node with the given ID in the graph with the given name.
+    pub fn new(graph_name: String, id: u64) -> Self {
+        Self { graph_name, id }
+    }
+
+    /// Returns the name of the graph this reference points into.
+    pub fn graph_name(&self) -> &str {
+        &self.graph_name
+    }
+
+    /// Returns the ID of the referenced node.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Loads the referenced node from the given graph.
+    ///
+    /// Returns `None` if the node does not exist (anymore).
+    pub fn load<C: ConnectionLike>(&self, graph: &mut Graph<C>) -> RedisGraphResult<Option<Node>> {
+        self.check_graph(graph)?;
+        let mut nodes: Vec<Node> =
+            graph.query(&format!("MATCH (n) WHERE id(n) = {} RETURN n", self.id))?;
+        Ok(nodes.pop())
+    }
+
+    /// Returns `true` if the referenced node exists in the given graph.
+    pub fn exists<C: ConnectionLike>(&self, graph: &mut Graph<C>) -> RedisGraphResult<bool> {
+        self.check_graph(graph)?;
+        let count: i64 = graph.query(&format!(
+            "MATCH (n) WHERE id(n) = {} RETURN count(n)",
+            self.id
+        ))?;
+        Ok(count > 0)
+    }
+
+    fn check_graph<C: ConnectionLike>(&self, graph: &Graph<C>) -> RedisGraphResult<()> {
+        if graph.name() == self.graph_name {
+            Ok(())
+        } else {
+            Err(RedisGraphError::GraphMismatch {
+                expected: self.graph_name.clone(),
+                found: graph.name().to_string(),
+            })
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::{BTreeMap, HashMap};
+
+use redis::ConnectionLike;
+use redisgraph_cypher::referenced_params;
+
+use crate::{
+    assignments::FromTable, params::CypherValue, Graph, RedisGraphError, RedisGraphResult,
+};
+
+/// The type of a parameter declared for a query in a [`QueryRegistry`](struct.QueryRegistry.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamType {
+    Boolean,
+    Integer,
+    /// A double. Integers are accepted as well.
+    Double,
+    String,
+    Array,
+    Map,
+    /// Any value, including `null`.
+    Any,
+}
+
+impl ParamType {
+    /// Returns `true` if the value can be passed for a parameter of this type.
+    ///
+    /// `null` is only accepted by [`Any`](#variant.Any).
+    pub fn accepts(self, value: &CypherValue) -> bool {
+        matches!(
+            (self, value),
+            (Self::Any, _)
+                | (Self::Boolean, CypherValue::Boolean(_))
+                | (Self::Integer, CypherValue::Integer(_))
+                | (Self::Double, CypherValue::Double(_))
+                | (Self::Double, CypherValue::Integer(_))
+                | (Self::String, CypherValue::String(_))
+                | (Self::Array, CypherValue::Array(_))
+                | (Self::Map, CypherValue::Map(_))
+        )
+    }
+}
+
+impl ParamType {
+    /// Returns a value of this type that can stand in for a real argument,
+    /// e.g. `0` for integers or an empty array.
+    pub fn placeholder(self) -> CypherValue {
+        match self {
+            Self::Boolean => CypherValue::Boolean(false),
+            Self::Integer => CypherValue::Integer(0),
+            Self::Double => CypherValue::Double(0.0),
+            Self::String => CypherValue::String(String::new()),
+            Self::Array => CypherValue::Array(Vec::new()),
+            Self::Map => CypherValue::Map(BTreeMap::new()),
+            Self::Any => CypherValue::Null,
+        }
+    }
+}
+
+/// How [`Graph::touch`](../graph/struct.Graph.html#method.touch) sends the registered queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchMode {
+    /// With `GRAPH.EXPLAIN`, which builds the execution plan without executing the query.
+    Explain,
+    /// With `GRAPH.QUERY`, which executes the query as well.
+    ///
+    /// *Registered mutations are applied with the placeholder arguments.*
+    Execute,
+}
+
+/// A query registered in a [`QueryRegistry`](struct.QueryRegistry.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredQuery {
+    /// The name the query is registered under.
+    pub name: String,
+    /// The query text.
+    pub text: String,
+    /// The declared parameters and their types.
+    pub params: Vec<(String, ParamType)>,
+}
+
+impl RegisteredQuery {
+    /// Checks that a value is passed for every declared parameter, that every value has the
+    /// declared type and that no other values are passed.
+    pub fn check_params(&self, params: &HashMap<String, CypherValue>) -> RedisGraphResult<()> {
+        for (name, param_type) in &self.params {
+            match params.get(name) {
+                None => return Err(RedisGraphError::UnboundParameter(name.clone())),
+                Some(value) if !param_type.accepts(value) => {
+                    return Err(RedisGraphError::ParameterTypeMismatch {
+                        param: name.clone(),
+                        expected: *param_type,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        let mut unused: Vec<&String> = params
+            .keys()
+            .filter(|name| !self.params.iter().any(|(declared, _)| declared == *name))
+            .collect();
+        unused.sort();
+        match unused.first() {
+            Some(name) => Err(RedisGraphError::UnusedParameter((*name).clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The name, query text and declared parameters of a query passed to
+/// [`QueryRegistry::from_embedded`](struct.QueryRegistry.html#method.from_embedded).
+pub type EmbeddedQuery<'a> = (&'a str, &'a str, &'a [(&'a str, ParamType)]);
+
+/// A collection of named queries with typed parameters.
+///
+/// Register all queries once at startup and execute them by name with
+/// [`Graph::query_named`](../graph/struct.Graph.html#method.query_named) and
+/// [`Graph::mutate_named`](../graph/struct.Graph.html#method.mutate_named). This keeps all
+/// query text in one place for review, and the names can serve as stable metrics labels.
+///
+/// ```
+/// use redisgraph::registry::{ParamType, QueryRegistry};
+///
+/// let mut registry = QueryRegistry::new();
+/// registry
+///     .register(
+///         "people_older_than",
+///         "MATCH (p:Person) WHERE p.age > $age RETURN p.name",
+///         &[("age", ParamType::Integer)],
+///     )
+///     .unwrap();
+///
+/// assert!(registry.get("people_older_than").is_some());
+/// ```
+///
+/// Query text can also be kept in `.cypher` files that are embedded with `include_str!`,
+/// see [`from_embedded`](#method.from_embedded).
+#[derive(Debug, Clone, Default)]
+pub struct QueryRegistry {
+    queries: HashMap<String, RegisteredQuery>,
+}
+
+impl QueryRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry from the given names, query texts and parameters, e.g. queries
+    /// kept in `.cypher` files that are embedded with `include_str!`.
+    ///
+    /// Fails like [`register`](#method.register) for the first invalid query.
+    ///
+    /// ```
+    /// use redisgraph::registry::{ParamType, QueryRegistry};
+    ///
+    /// let registry = QueryRegistry::from_embedded(&[(
+    ///     "people_older_than",
+    ///     include_str!("../tests/queries/people_older_than.cypher"),
+    ///     &[("min_age", ParamType::Integer)],
+    /// )])
+    /// .unwrap();
+    /// assert!(registry.get("people_older_than").is_some());
+    /// ```
+    pub fn from_embedded(queries: &[EmbeddedQuery]) -> RedisGraphResult<Self> {
+        let mut registry = Self::new();
+        for (name, text, params) in queries {
+            registry.register(name, text, params)?;
+        }
+        Ok(registry)
+    }
+
+    /// Registers a query under the given name.
+    ///
+    /// Fails if the name is already taken, if the query references a parameter that is not
+    /// declared, or if a declared parameter is not referenced by the query.
+    pub fn register(
+        &mut self,
+        name: &str,
+        text: &str,
+        params: &[(&str, ParamType)],
+    ) -> RedisGraphResult<()> {
+        if self.queries.contains_key(name) {
+            return Err(RedisGraphError::DuplicateQuery(name.to_string()));
+        }
+
+        let referenced = referenced_params(text);
+        if let Some(undeclared) = referenced
+            .iter()
+            .find(|referenced| !params.iter().any(|(declared, _)| declared == referenced))
+        {
+            return Err(RedisGraphError::UnboundParameter(undeclared.clone()));
+        }
+        if let Some((unused, _)) = params
+            .iter()
+            .find(|(declared, _)| !referenced.iter().any(|referenced| referenced == declared))
+        {
+            return Err(RedisGraphError::UnusedParameter(unused.to_string()));
+        }
+
+        self.queries.insert(
+            name.to_string(),
+            RegisteredQuery {
+                name: name.to_string(),
+                text: text.to_string(),
+                params: params
+                    .iter()
+                    .map(|(name, param_type)| (name.to_string(), *param_type))
+                    .collect(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the query registered under the given name.
+    pub fn get(&self, name: &str) -> Option<&RegisteredQuery> {
+        self.queries.get(name)
+    }
+
+    /// Returns an iterator over all registered queries in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &RegisteredQuery> {
+        self.queries.values()
+    }
+
+    /// Returns the query registered under the given name with its parameters checked,
+    /// see [`RegisteredQuery::check_params`](struct.RegisteredQuery.html#method.check_params).
+    pub fn prepare(
+        &self,
+        name: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<&RegisteredQuery> {
+        let query = self
+            .get(name)
+            .ok_or_else(|| RedisGraphError::UnknownQuery(name.to_string()))?;
+        query.check_params(params)?;
+        Ok(query)
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes the query registered under the given name with the given parameters
+    /// and returns its return values.
+    ///
+    /// Fails without sending the query if it is not registered or if the parameters
+    /// don't match its declaration.
+    pub fn query_named<T: FromTable>(
+        &mut self,
+        registry: &QueryRegistry,
+        name: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<T> {
+        let query = registry.prepare(name, params)?;
+        self.query_with_params(&query.text, params)
+    }
+
+    /// Same as [`query_named`](#method.query_named), but for mutations.
+    pub fn mutate_named(
+        &mut self,
+        registry: &QueryRegistry,
+        name: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        let query = registry.prepare(name, params)?;
+        self.mutate_with_params(&query.text, params)
+    }
+
+    /// Sends every registered query once with [placeholder](../registry/enum.ParamType.html#method.placeholder)
+    /// arguments, so the server has built and cached the execution plans before the first
+    /// real requests arrive, e.g. right after a deploy.
+    ///
+    /// Fails with the first error returned by the server, which also catches queries with
+    /// syntax errors at startup.
+    pub fn touch(&mut self, registry: &QueryRegistry, mode: TouchMode) -> RedisGraphResult<()> {
+        let mut queries: Vec<&RegisteredQuery> = registry.iter().collect();
+        queries.sort_by(|a, b| a.name.cmp(&b.name));
+        for query in queries {
+            let params = query
+                .params
+                .iter()
+                .map(|(name, param_type)| (name.clone(), param_type.placeholder()))
+                .collect::<HashMap<String, CypherValue>>();
+            let text = self.prepare_query(&query.text, &params)?;
+            match mode {
+                TouchMode::Explain => {
+                    self.explain(&text)?;
+                }
+                TouchMode::Execute => {
+                    self.request(&text)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
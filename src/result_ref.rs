@@ -0,0 +1,468 @@
+//! A borrowed view of a query response that doesn't copy any strings.
+//!
+//! Decoding a [`ResultSet`](../result_set/struct.ResultSet.html) copies every string and property
+//! into owned values. Workloads that read every value once and then discard it, like exporters,
+//! can borrow them from the response instead with
+//! [`Graph::query_with_ref`](../graph/struct.Graph.html#method.query_with_ref).
+//! Label, relationship type and property key names are borrowed from the graph.
+
+use std::collections::HashMap;
+use std::str;
+
+use redis::{ConnectionLike, Value};
+
+use crate::{
+    result_set::{DecodeContext, Edge, Node, RawPath, Scalar},
+    Graph, RedisGraphError, RedisGraphResult, RedisString,
+};
+
+/// A borrowed result set, see the [module documentation](index.html).
+///
+/// Unlike [`ResultSet`](../result_set/struct.ResultSet.html), the cells are stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSetRef<'a> {
+    column_names: Vec<&'a [u8]>,
+    rows: Vec<Vec<ScalarRef<'a>>>,
+    statistics: Vec<&'a str>,
+}
+
+/// A borrowed scalar, the counterpart of [`Scalar`](../result_set/enum.Scalar.html).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScalarRef<'a> {
+    Nil,
+    Boolean(bool),
+    Integer(i64),
+    Double(f64),
+    String(&'a [u8]),
+    Array(Vec<ScalarRef<'a>>),
+    Edge(EdgeRef<'a>),
+    Node(NodeView<'a>),
+    Path(PathRef<'a>),
+    Map(Vec<(&'a [u8], ScalarRef<'a>)>),
+}
+
+/// A borrowed node, the counterpart of [`Node`](../result_set/struct.Node.html).
+///
+/// It's not called `NodeRef` to avoid confusion with [`reference::NodeRef`](../reference/struct.NodeRef.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeView<'a> {
+    /// The labels attached to this node.
+    pub labels: Vec<&'a [u8]>,
+    /// The properties of this node, in the order they were returned.
+    pub properties: Vec<(&'a [u8], ScalarRef<'a>)>,
+}
+
+/// A borrowed edge, the counterpart of [`Edge`](../result_set/struct.Edge.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeRef<'a> {
+    /// The type name of this edge.
+    pub type_name: &'a [u8],
+    /// The properties of this edge, in the order they were returned.
+    pub properties: Vec<(&'a [u8], ScalarRef<'a>)>,
+}
+
+/// A borrowed path, the counterpart of [`RawPath`](../result_set/struct.RawPath.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathRef<'a> {
+    /// Nodes in the path.
+    pub nodes: Vec<NodeView<'a>>,
+    /// Edges in the path.
+    pub edges: Vec<EdgeRef<'a>>,
+}
+
+impl<'a> ResultSetRef<'a> {
+    /// Decodes a response to a `GRAPH.QUERY` command sent with the `--compact` flag.
+    ///
+    /// Fails with [`LabelNotFound`](../error/enum.RedisGraphError.html#variant.LabelNotFound)
+    /// or similar if the graph's names are outdated. In that case, update them and try again
+    /// while the response is still around.
+    pub fn parse(response: &'a Value, graph: &'a dyn DecodeContext) -> RedisGraphResult<Self> {
+        let values = match response {
+            Value::Bulk(values) => values,
+            _ => return server_type_error!("expected array as query response"),
+        };
+        match &values[..] {
+            [statistics] => Ok(Self {
+                column_names: Vec::new(),
+                rows: Vec::new(),
+                statistics: parse_statistics(statistics)?,
+            }),
+            [Value::Bulk(header_row), Value::Bulk(rows), statistics] => {
+                let column_types = header_row
+                    .iter()
+                    .map(|header_cell| match header_cell {
+                        Value::Bulk(header_cell) => match &header_cell[..] {
+                            [Value::Int(column_type), Value::Data(name)] => {
+                                Ok((*column_type, &name[..]))
+                            }
+                            _ => server_type_error!("expected column type and name as header cell"),
+                        },
+                        _ => server_type_error!("expected array as header cell representation"),
+                    })
+                    .collect::<RedisGraphResult<Vec<(i64, &[u8])>>>()?;
+
+                let rows = rows
+                    .iter()
+                    .map(|row| match row {
+                        Value::Bulk(cells) if cells.len() == column_types.len() => cells
+                            .iter()
+                            .zip(&column_types)
+                            .map(|(cell, (column_type, _))| match column_type {
+                                2 => parse_node(cell, graph).map(ScalarRef::Node),
+                                3 => parse_edge(cell, graph).map(ScalarRef::Edge),
+                                _ => parse_scalar(cell, graph),
+                            })
+                            .collect::<RedisGraphResult<Vec<ScalarRef>>>(),
+                        _ => server_type_error!(
+                            "expected array with one cell per column as result row representation"
+                        ),
+                    })
+                    .collect::<RedisGraphResult<Vec<Vec<ScalarRef>>>>()?;
+
+                Ok(Self {
+                    column_names: column_types.into_iter().map(|(_, name)| name).collect(),
+                    rows,
+                    statistics: parse_statistics(statistics)?,
+                })
+            }
+            _ => server_type_error!("expected array of size 1 or 3 as query response"),
+        }
+    }
+
+    /// Returns the number of columns in the result set.
+    pub fn num_columns(&self) -> usize {
+        self.column_names.len()
+    }
+
+    /// Returns the number of rows in the result set.
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the names of the columns.
+    pub fn column_names(&self) -> &[&'a [u8]] {
+        &self.column_names
+    }
+
+    /// Returns an iterator over the rows of the result set.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[ScalarRef<'a>]> {
+        self.rows.iter().map(|row| &row[..])
+    }
+
+    /// Returns the cell at the given position, or `None` if the position is out of bounds.
+    pub fn get(&self, row_idx: usize, column_idx: usize) -> Option<&ScalarRef<'a>> {
+        self.rows.get(row_idx)?.get(column_idx)
+    }
+
+    /// Returns the statistics messages of the response.
+    pub fn statistics(&self) -> &[&'a str] {
+        &self.statistics
+    }
+}
+
+impl ScalarRef<'_> {
+    /// Copies the scalar into an owned [`Scalar`](../result_set/enum.Scalar.html).
+    pub fn to_scalar(&self) -> Scalar {
+        match self {
+            Self::Nil => Scalar::Nil,
+            Self::Boolean(boolean) => Scalar::Boolean(*boolean),
+            Self::Integer(integer) => Scalar::Integer(*integer),
+            Self::Double(double) => Scalar::Double(*double),
+            Self::String(string) => Scalar::String(RedisString(string.to_vec())),
+            Self::Array(scalars) => Scalar::Array(scalars.iter().map(Self::to_scalar).collect()),
+            Self::Edge(edge) => Scalar::Edge(edge.to_edge()),
+            Self::Node(node) => Scalar::Node(node.to_node()),
+            Self::Path(path) => Scalar::Path(RawPath {
+                nodes: path.nodes.iter().map(NodeView::to_node).collect(),
+                edges: path.edges.iter().map(EdgeRef::to_edge).collect(),
+            }),
+            Self::Map(entries) => Scalar::Map(to_owned_properties(entries)),
+        }
+    }
+}
+
+impl<'a> NodeView<'a> {
+    /// Returns the value of the property with the given key.
+    pub fn property(&self, key: &str) -> Option<&ScalarRef<'a>> {
+        find_property(&self.properties, key)
+    }
+
+    /// Copies the node into an owned [`Node`](../result_set/struct.Node.html).
+    pub fn to_node(&self) -> Node {
+        Node {
+            labels: self
+                .labels
+                .iter()
+                .map(|label| RedisString(label.to_vec()))
+                .collect(),
+            properties: to_owned_properties(&self.properties),
+        }
+    }
+}
+
+impl<'a> EdgeRef<'a> {
+    /// Returns the value of the property with the given key.
+    pub fn property(&self, key: &str) -> Option<&ScalarRef<'a>> {
+        find_property(&self.properties, key)
+    }
+
+    /// Copies the edge into an owned [`Edge`](../result_set/struct.Edge.html).
+    pub fn to_edge(&self) -> Edge {
+        Edge {
+            type_name: RedisString(self.type_name.to_vec()),
+            properties: to_owned_properties(&self.properties),
+        }
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes the given query and passes a borrowed view of the response to `f`,
+    /// see the [`result_ref`](../result_ref/index.html) module.
+    ///
+    /// ```no_run
+    /// # use redisgraph::{result_ref::ScalarRef, Graph, RedisGraphResult};
+    /// # fn example(graph: &mut Graph, out: &mut Vec<u8>) -> RedisGraphResult<()> {
+    /// graph.query_with_ref("MATCH (p:Person) RETURN p.name", |result_set| {
+    ///     for row in result_set.rows() {
+    ///         if let ScalarRef::String(name) = row[0] {
+    ///             out.extend_from_slice(name);
+    ///             out.push(b'\n');
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    pub fn query_with_ref<R, F>(&mut self, query: &str, f: F) -> RedisGraphResult<R>
+    where
+        F: FnOnce(&ResultSetRef<'_>) -> RedisGraphResult<R>,
+    {
+        let response = self.request(query)?;
+        loop {
+            match ResultSetRef::parse(&response, &self.context()) {
+                Ok(result_set) => return f(&result_set),
+                Err(RedisGraphError::LabelNotFound) => self.update_labels()?,
+                Err(RedisGraphError::RelationshipTypeNotFound) => {
+                    self.update_relationship_types()?
+                }
+                Err(RedisGraphError::PropertyKeyNotFound) => self.update_property_keys()?,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+fn parse_statistics(statistics: &Value) -> RedisGraphResult<Vec<&str>> {
+    match statistics {
+        Value::Bulk(entries) => entries
+            .iter()
+            .map(|entry| match entry {
+                Value::Data(utf8) => str::from_utf8(utf8).map_err(|_| RedisGraphError::InvalidUtf8),
+                _ => server_type_error!("expected string as statistics entry"),
+            })
+            .collect(),
+        _ => server_type_error!("expected array as statistics list"),
+    }
+}
+
+fn parse_scalar<'a>(
+    value: &'a Value,
+    graph: &'a dyn DecodeContext,
+) -> RedisGraphResult<ScalarRef<'a>> {
+    let (scalar_type, scalar_value) = match value {
+        Value::Bulk(values) => match &values[..] {
+            [Value::Int(scalar_type), scalar_value] => (*scalar_type, scalar_value),
+            _ => {
+                return server_type_error!(
+                    "expected scalar type and value as scalar representation"
+                )
+            }
+        },
+        _ => return server_type_error!("expected array as scalar representation"),
+    };
+    parse_typed_scalar(scalar_type, scalar_value, graph)
+}
+
+fn parse_typed_scalar<'a>(
+    scalar_type: i64,
+    scalar_value: &'a Value,
+    graph: &'a dyn DecodeContext,
+) -> RedisGraphResult<ScalarRef<'a>> {
+    match (scalar_type, scalar_value) {
+        (1, _) => Ok(ScalarRef::Nil),
+        (2, Value::Data(string)) => Ok(ScalarRef::String(string)),
+        (3, Value::Int(integer)) => Ok(ScalarRef::Integer(*integer)),
+        (4, Value::Data(boolean)) => match &boolean[..] {
+            b"true" => Ok(ScalarRef::Boolean(true)),
+            b"false" => Ok(ScalarRef::Boolean(false)),
+            _ => server_type_error!("expected either \"true\" or \"false\" as boolean"),
+        },
+        (4, Value::Int(0)) if graph.lenient_booleans() => Ok(ScalarRef::Boolean(false)),
+        (4, Value::Int(1)) if graph.lenient_booleans() => Ok(ScalarRef::Boolean(true)),
+        (5, Value::Data(double)) => match str::from_utf8(double) {
+            Ok(double) => match double.parse::<f64>() {
+                Ok(double) => Ok(ScalarRef::Double(double)),
+                Err(_) => server_type_error!("expected string representation of double"),
+            },
+            Err(_) => Err(RedisGraphError::InvalidUtf8),
+        },
+        (6, Value::Bulk(elements)) => elements
+            .iter()
+            .map(|element| parse_scalar(element, graph))
+            .collect::<RedisGraphResult<Vec<ScalarRef>>>()
+            .map(ScalarRef::Array),
+        (7, edge) => parse_edge(edge, graph).map(ScalarRef::Edge),
+        (8, node) => parse_node(node, graph).map(ScalarRef::Node),
+        (9, path) => parse_path(path, graph).map(ScalarRef::Path),
+        (10, Value::Bulk(entries)) if entries.len() % 2 == 0 => entries
+            .chunks(2)
+            .map(|entry| match &entry[0] {
+                Value::Data(key) => Ok((&key[..], parse_scalar(&entry[1], graph)?)),
+                _ => server_type_error!("expected binary data as map key"),
+            })
+            .collect::<RedisGraphResult<Vec<(&[u8], ScalarRef)>>>()
+            .map(ScalarRef::Map),
+        (12, Value::Bulk(elements)) => elements
+            .iter()
+            .map(|element| match element {
+                Value::Data(double) => match str::from_utf8(double) {
+                    Ok(double) => match double.parse::<f64>() {
+                        Ok(double) => Ok(ScalarRef::Double(double)),
+                        Err(_) => server_type_error!(
+                            "expected string representation of double as vector element"
+                        ),
+                    },
+                    Err(_) => Err(RedisGraphError::InvalidUtf8),
+                },
+                _ => server_type_error!("expected string representing a double as vector element"),
+            })
+            .collect::<RedisGraphResult<Vec<ScalarRef>>>()
+            .map(ScalarRef::Array),
+        (scalar_type, _) => server_type_error!(
+            "unexpected value for scalar type {} in scalar representation",
+            scalar_type
+        ),
+    }
+}
+
+fn parse_node<'a>(
+    value: &'a Value,
+    graph: &'a dyn DecodeContext,
+) -> RedisGraphResult<NodeView<'a>> {
+    match value {
+        Value::Bulk(values) => match &values[..] {
+            [_, Value::Bulk(label_ids), properties] => {
+                let labels = label_ids
+                    .iter()
+                    .map(|label_id| match label_id {
+                        Value::Int(id) => graph
+                            .labels()
+                            .get(*id as usize)
+                            .map(|label| &label.0[..])
+                            .ok_or(RedisGraphError::LabelNotFound),
+                        _ => server_type_error!("expected integer as label ID"),
+                    })
+                    .collect::<RedisGraphResult<Vec<&[u8]>>>()?;
+                Ok(NodeView {
+                    labels,
+                    properties: parse_properties(properties, graph)?,
+                })
+            }
+            _ => server_type_error!("expected array of size 3 as node representation"),
+        },
+        _ => server_type_error!("expected array as node representation"),
+    }
+}
+
+fn parse_edge<'a>(value: &'a Value, graph: &'a dyn DecodeContext) -> RedisGraphResult<EdgeRef<'a>> {
+    match value {
+        Value::Bulk(values) => match &values[..] {
+            [_, Value::Int(type_id), _, _, properties] => Ok(EdgeRef {
+                type_name: graph
+                    .relationship_types()
+                    .get(*type_id as usize)
+                    .map(|type_name| &type_name.0[..])
+                    .ok_or(RedisGraphError::RelationshipTypeNotFound)?,
+                properties: parse_properties(properties, graph)?,
+            }),
+            _ => server_type_error!("expected array of size 5 as edge representation"),
+        },
+        _ => server_type_error!("expected array as edge representation"),
+    }
+}
+
+fn parse_path<'a>(value: &'a Value, graph: &'a dyn DecodeContext) -> RedisGraphResult<PathRef<'a>> {
+    let (nodes, edges) = match value {
+        Value::Bulk(values) => match &values[..] {
+            [nodes, edges] => (parse_scalar(nodes, graph)?, parse_scalar(edges, graph)?),
+            _ => return server_type_error!("expected array of size 2 as path representation"),
+        },
+        _ => return server_type_error!("expected array as path representation"),
+    };
+
+    let nodes = match nodes {
+        ScalarRef::Array(nodes) => nodes
+            .into_iter()
+            .map(|scalar| match scalar {
+                ScalarRef::Node(node) => Ok(node),
+                other => server_type_error!("unexpected non-node in path nodes array, {:?}", other),
+            })
+            .collect::<RedisGraphResult<Vec<NodeView>>>()?,
+        other => return server_type_error!("expected path nodes to be an array, not {:?}", other),
+    };
+    let edges = match edges {
+        ScalarRef::Array(edges) => edges
+            .into_iter()
+            .map(|scalar| match scalar {
+                ScalarRef::Edge(edge) => Ok(edge),
+                other => server_type_error!("unexpected non-edge in path edges array, {:?}", other),
+            })
+            .collect::<RedisGraphResult<Vec<EdgeRef>>>()?,
+        other => return server_type_error!("expected path edges to be an array, not {:?}", other),
+    };
+    Ok(PathRef { nodes, edges })
+}
+
+fn parse_properties<'a>(
+    value: &'a Value,
+    graph: &'a dyn DecodeContext,
+) -> RedisGraphResult<Vec<(&'a [u8], ScalarRef<'a>)>> {
+    match value {
+        Value::Bulk(properties) => properties
+            .iter()
+            .map(|property| match property {
+                Value::Bulk(property) => match &property[..] {
+                    [Value::Int(key_id), Value::Int(property_type), property_value] => {
+                        let key = graph
+                            .property_keys()
+                            .get(*key_id as usize)
+                            .map(|key| &key.0[..])
+                            .ok_or(RedisGraphError::PropertyKeyNotFound)?;
+                        let value = parse_typed_scalar(*property_type, property_value, graph)?;
+                        Ok((key, value))
+                    }
+                    _ => server_type_error!("expected array of size 3 as property representation"),
+                },
+                _ => server_type_error!("expected array as property representation"),
+            })
+            .collect(),
+        _ => server_type_error!("expected array as properties representation"),
+    }
+}
+
+fn find_property<'p, 'a>(
+    properties: &'p [(&'a [u8], ScalarRef<'a>)],
+    key: &str,
+) -> Option<&'p ScalarRef<'a>> {
+    properties
+        .iter()
+        .find(|(property_key, _)| *property_key == key.as_bytes())
+        .map(|(_, value)| value)
+}
+
+fn to_owned_properties(properties: &[(&[u8], ScalarRef)]) -> HashMap<RedisString, Scalar> {
+    properties
+        .iter()
+        .map(|(key, value)| (RedisString(key.to_vec()), value.to_scalar()))
+        .collect()
+}
@@ -1,21 +1,59 @@
 use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter;
 use std::mem;
 use std::str;
 
 use num::FromPrimitive;
 use redis::{FromRedisValue, Value};
 
-use crate::{server_type_error, Graph, RedisGraphError, RedisGraphResult};
+use crate::{
+    assignments::{FromCell, FromRow},
+    server_type_error, RedisGraphError, RedisGraphResult,
+};
 use std::convert::TryFrom;
 
+#[cfg(feature = "ndarray")]
+use ndarray::Array2;
+#[cfg(feature = "ndarray")]
+use num::Float;
+#[cfg(feature = "rayon")]
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+
+/// The names and options needed to decode a response, provided by the graph that returned it.
+///
+/// Implemented by [`Graph`](../graph/struct.Graph.html) and [`AsyncGraph`](../aio/struct.AsyncGraph.html).
+pub trait DecodeContext: Sync {
+    /// Returns the label names, where the name at index `i` belongs to the label with ID `i`.
+    fn labels(&self) -> &[RedisString];
+
+    /// Returns the relationship type names, where the name at index `i` belongs to the
+    /// relationship type with ID `i`.
+    fn relationship_types(&self) -> &[RedisString];
+
+    /// Returns the property key names, where the name at index `i` belongs to the property key with ID `i`.
+    fn property_keys(&self) -> &[RedisString];
+
+    /// Returns `true` if booleans sent as the integers `0` and `1` are accepted,
+    /// see [`Graph::set_lenient_booleans`](../graph/struct.Graph.html#method.set_lenient_booleans).
+    fn lenient_booleans(&self) -> bool;
+
+    /// Returns `true` if decoding errors include the offending part of the reply,
+    /// see [`Graph::set_reply_diagnostics`](../graph/struct.Graph.html#method.set_reply_diagnostics).
+    fn reply_diagnostics(&self) -> bool;
+}
+
 /// Implemented by types that can be contructed from a
-/// Redis [`Value`](https://docs.rs/redis/0.15.1/redis/enum.Value.html) and a [`Graph`](../graph/struct.Graph.html)
+/// Redis [`Value`](https://docs.rs/redis/0.15.1/redis/enum.Value.html) and the
+/// [`DecodeContext`](trait.DecodeContext.html) of a graph, e.g. a [`Graph`](../graph/struct.Graph.html)
 pub trait FromRedisValueWithGraph: Sized {
-    fn from_redis_value_with_graph(value: Value, graph: &Graph) -> RedisGraphResult<Self>;
+    fn from_redis_value_with_graph(value: Value, graph: &dyn DecodeContext) -> RedisGraphResult<Self>;
 }
 
 impl<T: FromRedisValue> FromRedisValueWithGraph for T {
-    fn from_redis_value_with_graph(value: Value, _graph: &Graph) -> RedisGraphResult<T> {
+    fn from_redis_value_with_graph(value: Value, _graph: &dyn DecodeContext) -> RedisGraphResult<T> {
         T::from_redis_value(&value).map_err(RedisGraphError::from)
     }
 }
@@ -35,6 +73,18 @@ pub struct ResultSet {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Statistics(pub Vec<String>);
 
+impl Statistics {
+    /// Returns the value of the counter with the given name, e.g. `"Nodes created"`,
+    /// or 0 if the response did not include it.
+    pub fn count(&self, name: &str) -> usize {
+        self.0
+            .iter()
+            .filter_map(|entry| entry.strip_prefix(name)?.strip_prefix(':'))
+            .filter_map(|count| count.trim().parse::<usize>().ok())
+            .sum()
+    }
+}
+
 impl ResultSet {
     /// Returns the number of rows in the result set.
     pub fn num_columns(&self) -> usize {
@@ -49,6 +99,137 @@ impl ResultSet {
         }
     }
 
+    /// Returns an iterator over the rows of the result set.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = Row<'_>> + DoubleEndedIterator {
+        (0..self.num_rows()).map(move |idx| Row {
+            result_set: self,
+            idx,
+        })
+    }
+
+    /// Returns a parallel iterator over the rows of the result set.
+    ///
+    /// Use this for CPU-heavy post-processing of large results.
+    #[cfg(feature = "rayon")]
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = Row<'_>> {
+        (0..self.num_rows())
+            .into_par_iter()
+            .map(move |idx| Row {
+                result_set: self,
+                idx,
+            })
+    }
+
+    /// Returns the cells of the result set in row-major order, i.e. a vector of cells for every row.
+    ///
+    /// Nodes and relationships are wrapped in [`Scalar::Node`](enum.Scalar.html#variant.Node)
+    /// and [`Scalar::Edge`](enum.Scalar.html#variant.Edge).
+    pub fn transpose(&self) -> Vec<Vec<Scalar>> {
+        transpose(self.num_rows(), self.columns.clone())
+    }
+
+    /// Same as [`transpose`](#method.transpose), but moves the cells instead of cloning them.
+    pub fn into_rows(self) -> Vec<Vec<Scalar>> {
+        transpose(self.num_rows(), self.columns)
+    }
+
+    /// Converts an all-numeric result set into a matrix with a row for every row of the result set.
+    ///
+    /// Integers and doubles are cast into `T`, and `null` becomes NaN.
+    /// Requires the `ndarray` feature.
+    #[cfg(feature = "ndarray")]
+    pub fn to_array2<T: Float>(&self) -> RedisGraphResult<Array2<T>> {
+        let shape = (self.num_rows(), self.num_columns());
+        let mut cells = Vec::with_capacity(shape.0 * shape.1);
+        for row_idx in 0..shape.0 {
+            for column_idx in 0..shape.1 {
+                let cell = match self.get_scalar(row_idx, column_idx)? {
+                    Scalar::Integer(int) => T::from(*int),
+                    Scalar::Double(double) => T::from(*double),
+                    Scalar::Nil => Some(T::nan()),
+                    any => {
+                        return client_type_error!(
+                            "failed to construct matrix: expected number, found {:?} at row {}, column {}",
+                            any,
+                            row_idx,
+                            column_idx
+                        )
+                    }
+                };
+                match cell {
+                    Some(cell) => cells.push(cell),
+                    None => {
+                        return client_type_error!(
+                            "failed to construct matrix: value at row {}, column {} is out of range",
+                            row_idx,
+                            column_idx
+                        )
+                    }
+                }
+            }
+        }
+        Ok(Array2::from_shape_vec(shape, cells).expect("cells match the shape"))
+    }
+
+    /// Converts every row, collecting the rows that fail to convert instead of aborting.
+    ///
+    /// Returns the successfully converted rows in order, and an error for every other row.
+    pub fn parse_rows_lossy<T: FromRow>(&self) -> (Vec<T>, Vec<RowError>) {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        for row in self.rows() {
+            match row.parse() {
+                Ok(value) => values.push(value),
+                Err(error) => errors.push(RowError {
+                    row_idx: row.idx(),
+                    error,
+                }),
+            }
+        }
+        (values, errors)
+    }
+
+    /// Groups the rows by the values in the given column.
+    ///
+    /// The values are constructed from the remaining columns, so for a query like
+    /// `MATCH (p:Parent)-[:HAS]->(c:Child) RETURN p.name, c.name, c.age`,
+    /// `group_by_column::<String, (String, i64)>(0)` returns the names and ages of the children by parent name.
+    /// Within each group, the rows keep their order.
+    pub fn group_by_column<K, V>(&self, key_idx: usize) -> RedisGraphResult<HashMap<K, Vec<V>>>
+    where
+        K: FromCell + Eq + Hash,
+        V: FromRow,
+    {
+        if key_idx >= self.num_columns() {
+            return client_type_error!(
+                "failed to group rows: column index out of bounds: the len is {:?} but the index is {:?}",
+                self.num_columns(),
+                key_idx,
+            );
+        }
+
+        let values = ResultSet {
+            columns: self
+                .columns
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != key_idx)
+                .map(|(_, column)| column.clone())
+                .collect(),
+            statistics: Statistics(Vec::new()),
+        };
+
+        let mut groups: HashMap<K, Vec<V>> = HashMap::new();
+        for row_idx in 0..self.num_rows() {
+            groups
+                .entry(K::from_cell(self, row_idx, key_idx)?)
+                .or_default()
+                .push(V::from_row(&values, row_idx)?);
+        }
+
+        Ok(groups)
+    }
+
     /// Returns the scalar at the given position.
     ///
     /// Returns an error if the value at the given position is not a scalar
@@ -173,6 +354,44 @@ impl ResultSet {
     }
 }
 
+/// A view of a single row of a [`ResultSet`](struct.ResultSet.html).
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    result_set: &'a ResultSet,
+    idx: usize,
+}
+
+impl<'a> Row<'a> {
+    /// Returns the index of this row in the result set.
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    /// Returns the result set this row belongs to.
+    pub fn result_set(&self) -> &'a ResultSet {
+        self.result_set
+    }
+
+    /// Converts the cell in the given column of this row.
+    pub fn get<T: FromCell>(&self, column_idx: usize) -> RedisGraphResult<T> {
+        T::from_cell(self.result_set, self.idx, column_idx)
+    }
+
+    /// Converts the whole row, e.g. into a tuple.
+    pub fn parse<T: FromRow>(&self) -> RedisGraphResult<T> {
+        T::from_row(self.result_set, self.idx)
+    }
+}
+
+/// A row that failed to convert, returned by [`ResultSet::parse_rows_lossy`](struct.ResultSet.html#method.parse_rows_lossy).
+#[derive(Debug)]
+pub struct RowError {
+    /// The index of the row in the result set.
+    pub row_idx: usize,
+    /// The error returned while converting the row.
+    pub error: RedisGraphError,
+}
+
 /// A single column of the result set.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Column {
@@ -195,6 +414,28 @@ impl Column {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Converts this column into scalars, wrapping nodes and relationships
+    /// in [`Scalar::Node`](enum.Scalar.html#variant.Node) and [`Scalar::Edge`](enum.Scalar.html#variant.Edge).
+    pub fn into_scalars(self) -> Vec<Scalar> {
+        match self {
+            Self::Scalars(cells) => cells,
+            Self::Nodes(cells) => cells.into_iter().map(Scalar::Node).collect(),
+            Self::Relations(cells) => cells.into_iter().map(Scalar::Edge).collect(),
+        }
+    }
+}
+
+fn transpose(num_rows: usize, columns: Vec<Column>) -> Vec<Vec<Scalar>> {
+    let mut rows: Vec<Vec<Scalar>> = (0..num_rows)
+        .map(|_| Vec::with_capacity(columns.len()))
+        .collect();
+    for column in columns {
+        for (row, cell) in rows.iter_mut().zip(column.into_scalars()) {
+            row.push(cell);
+        }
+    }
+    rows
 }
 
 #[derive(num_derive::FromPrimitive)]
@@ -206,7 +447,7 @@ enum ColumnType {
 }
 
 impl FromRedisValueWithGraph for ResultSet {
-    fn from_redis_value_with_graph(value: Value, graph: &Graph) -> RedisGraphResult<Self> {
+    fn from_redis_value_with_graph(value: Value, graph: &dyn DecodeContext) -> RedisGraphResult<Self> {
         match value {
             Value::Bulk(mut values) => {
                 match values.len() {
@@ -217,78 +458,9 @@ impl FromRedisValueWithGraph for ResultSet {
 
                         match header_row {
                             Value::Bulk(header_row) => {
-                                let column_count = header_row.len();
-                                let mut columns = Vec::<Column>::with_capacity(column_count);
-
-                                // `result_table[0][1]` is row 0, column 1
-                                let mut result_table: Vec<Vec<Value>> = match result_rows {
-                                    Value::Bulk(rows) => rows
-                                        .into_iter()
-                                        .map(|row| match row {
-                                            Value::Bulk(row) => Ok(row),
-                                            _ => server_type_error!(
-                                                "expected array as result row representation",
-                                            ),
-                                        })
-                                        .collect::<RedisGraphResult<Vec<Vec<Value>>>>(),
-                                    _ => server_type_error!(
-                                        "expected array as result table representation",
-                                    ),
-                                }?;
-
-                                for i in 0..column_count {
-                                    match &header_row[i] {
-                                        Value::Bulk(header_cell) => {
-                                            let column_type_i64 = match header_cell[0] {
-                                                Value::Int(column_type_i64) => column_type_i64,
-                                                _ => {
-                                                    return server_type_error!(
-                                                        "expected integer as column type",
-                                                    )
-                                                }
-                                            };
-
-                                            let column = match ColumnType::from_i64(column_type_i64) {
-                                                Some(ColumnType::Unknown) => server_type_error!("column type is unknown"),
-                                                Some(ColumnType::Scalar) => Ok(Column::Scalars(
-                                                    result_table
-                                                        .iter_mut()
-                                                        .map(|row| {
-                                                            Scalar::from_redis_value_with_graph(row[i].take(), graph)
-                                                                .map_err(RedisGraphError::from)
-                                                        })
-                                                        .collect::<RedisGraphResult<Vec<Scalar>>>()?,
-                                                )),
-                                                Some(ColumnType::Node) => Ok(Column::Nodes(
-                                                    result_table
-                                                        .iter_mut()
-                                                        .map(|row| {
-                                                            Node::from_redis_value_with_graph(row[i].take(), graph)
-                                                                .map_err(RedisGraphError::from)
-                                                        })
-                                                        .collect::<RedisGraphResult<Vec<Node>>>()?,
-                                                )),
-                                                Some(ColumnType::Relation) => Ok(Column::Relations(
-                                                    result_table
-                                                        .iter_mut()
-                                                        .map(|row| {
-                                                            Edge::from_redis_value_with_graph(row[i].take(), graph)
-                                                                .map_err(RedisGraphError::from)
-                                                        })
-                                                        .collect::<RedisGraphResult<Vec<Edge>>>()?,
-                                                )),
-                                                None => server_type_error!("expected integer between 0 and 3 as column type")
-                                            }?;
-
-                                            columns.push(column);
-                                        }
-                                        _ => {
-                                            return server_type_error!(
-                                                "expected array as header cell representation",
-                                            )
-                                        }
-                                    }
-                                }
+                                let raw_columns =
+                                    split_columns(header_row.len(), result_rows, graph)?;
+                                let columns = parse_columns(&header_row, raw_columns, graph)?;
 
                                 if let Some(first_column) = columns.get(0) {
                                     if !columns
@@ -301,47 +473,306 @@ impl FromRedisValueWithGraph for ResultSet {
                                     }
                                 }
 
-                                let statistics = parse_statistics(statistics)?;
+                                let statistics = parse_statistics(statistics, 2, graph)?;
 
                                 Ok(Self {
                                     columns,
                                     statistics,
                                 })
                             }
-                            _ => server_type_error!("expected array as header row representation",),
+                            header_row => reply_error(
+                                graph,
+                                "expected array as header row representation",
+                                &[0],
+                                None,
+                                &header_row,
+                            ),
                         }
                     }
                     1 => {
-                        let statistics = parse_statistics(values[0].take())?;
+                        let statistics = parse_statistics(values[0].take(), 0, graph)?;
 
                         Ok(Self {
                             columns: Vec::new(),
                             statistics,
                         })
                     }
-                    _ => server_type_error!(
+                    _ => reply_error(
+                        graph,
                         "expected array of size 3 or 1 as result set representation",
+                        &[],
+                        None,
+                        &Value::Bulk(values),
                     ),
                 }
             }
-            _ => server_type_error!("expected array as result set representation"),
+            value => reply_error(
+                graph,
+                "expected array as result set representation",
+                &[],
+                None,
+                &value,
+            ),
+        }
+    }
+}
+
+/// Replies are dumped up to this many characters in diagnostics.
+const DIAGNOSTICS_MAX_DUMP_LEN: usize = 256;
+
+/// Returns a server type error with the given message.
+///
+/// If reply diagnostics are enabled, the message is extended with the path of the offending value
+/// within the reply, e.g. `reply[1][4][0]` for row 4, column 0, and a truncated dump of the value.
+fn reply_error<T>(
+    graph: &dyn DecodeContext,
+    message: &str,
+    path: &[usize],
+    column: Option<usize>,
+    value: &Value,
+) -> RedisGraphResult<T> {
+    if !graph.reply_diagnostics() {
+        return server_type_error!("{}", message);
+    }
+
+    let path: String = path.iter().map(|index| format!("[{}]", index)).collect();
+    let column = match column {
+        Some(column) => format!(", column {}", column),
+        None => String::new(),
+    };
+    let mut dump = format!("{:?}", value);
+    if dump.len() > DIAGNOSTICS_MAX_DUMP_LEN {
+        let mut end = DIAGNOSTICS_MAX_DUMP_LEN;
+        while !dump.is_char_boundary(end) {
+            end -= 1;
         }
+        dump.truncate(end);
+        dump.push_str("...");
+    }
+    server_type_error!("{} (at reply{}{}: {})", message, path, column, dump)
+}
+
+/// Splits the result rows into columns of raw values.
+///
+/// `raw_columns[1][0]` is row 0, column 1.
+fn split_columns(
+    column_count: usize,
+    result_rows: Value,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<Vec<Vec<Value>>> {
+    let rows = match result_rows {
+        Value::Bulk(rows) => rows,
+        result_rows => {
+            return reply_error(
+                graph,
+                "expected array as result table representation",
+                &[1],
+                None,
+                &result_rows,
+            )
+        }
+    };
+
+    let mut raw_columns: Vec<Vec<Value>> = (0..column_count)
+        .map(|_| Vec::with_capacity(rows.len()))
+        .collect();
+    for (row_idx, row) in rows.into_iter().enumerate() {
+        let row = match row {
+            Value::Bulk(row) => row,
+            row => {
+                return reply_error(
+                    graph,
+                    "expected array as result row representation",
+                    &[1, row_idx],
+                    None,
+                    &row,
+                )
+            }
+        };
+        for (column_idx, cell) in row.into_iter().enumerate() {
+            match raw_columns.get_mut(column_idx) {
+                Some(raw_column) => raw_column.push(cell),
+                None => {
+                    return reply_error(
+                        graph,
+                        "result row has more cells than there are columns",
+                        &[1, row_idx, column_idx],
+                        Some(column_idx),
+                        &cell,
+                    )
+                }
+            }
+        }
+    }
+
+    Ok(raw_columns)
+}
+
+/// Results with fewer rows than this are parsed on a single thread.
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_ROWS: usize = 1024;
+
+#[cfg(not(feature = "rayon"))]
+fn parse_columns(
+    header_row: &[Value],
+    raw_columns: Vec<Vec<Value>>,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<Vec<Column>> {
+    header_row
+        .iter()
+        .zip(raw_columns)
+        .enumerate()
+        .map(|(column_idx, (header_cell, cells))| {
+            parse_column(column_idx, header_cell, cells, graph)
+        })
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+fn parse_columns(
+    header_row: &[Value],
+    raw_columns: Vec<Vec<Value>>,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<Vec<Column>> {
+    header_row
+        .par_iter()
+        .zip(raw_columns)
+        .enumerate()
+        .map(|(column_idx, (header_cell, cells))| {
+            parse_column(column_idx, header_cell, cells, graph)
+        })
+        .collect()
+}
+
+fn parse_column(
+    column_idx: usize,
+    header_cell: &Value,
+    cells: Vec<Value>,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<Column> {
+    let header_error = |message| {
+        reply_error(
+            graph,
+            message,
+            &[0, column_idx],
+            Some(column_idx),
+            header_cell,
+        )
+    };
+
+    let column_type_i64 = match header_cell {
+        Value::Bulk(header_cell) => match header_cell.first() {
+            Some(Value::Int(column_type_i64)) => *column_type_i64,
+            _ => return header_error("expected integer as column type"),
+        },
+        _ => return header_error("expected array as header cell representation"),
+    };
+
+    match ColumnType::from_i64(column_type_i64) {
+        Some(ColumnType::Unknown) => header_error("column type is unknown"),
+        Some(ColumnType::Scalar) => parse_cells(column_idx, cells, graph).map(Column::Scalars),
+        Some(ColumnType::Node) => parse_cells(column_idx, cells, graph).map(Column::Nodes),
+        Some(ColumnType::Relation) => parse_cells(column_idx, cells, graph).map(Column::Relations),
+        None => header_error("expected integer between 0 and 3 as column type"),
+    }
+}
+
+// Returns the names of the columns of a query response, or nothing if it has no return values.
+pub(crate) fn parse_column_names(value: &Value) -> RedisGraphResult<Vec<String>> {
+    match value {
+        Value::Bulk(values) if values.len() == 3 => match &values[0] {
+            Value::Bulk(header_row) => header_row
+                .iter()
+                .map(|header_cell| match header_cell {
+                    Value::Bulk(header_cell) => match header_cell.get(1) {
+                        Some(Value::Data(name)) => Ok(String::from_utf8_lossy(name).into_owned()),
+                        _ => server_type_error!("expected string as column name"),
+                    },
+                    _ => server_type_error!("expected array as header cell representation"),
+                })
+                .collect(),
+            _ => server_type_error!("expected array as header row representation"),
+        },
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn parse_cell<T: FromRedisValueWithGraph>(
+    row_idx: usize,
+    column_idx: usize,
+    cell: Value,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<T> {
+    if !graph.reply_diagnostics() {
+        return T::from_redis_value_with_graph(cell, graph);
     }
+
+    let dump = cell.clone();
+    match T::from_redis_value_with_graph(cell, graph) {
+        Err(RedisGraphError::ServerTypeError(message)) => reply_error(
+            graph,
+            &message,
+            &[1, row_idx, column_idx],
+            Some(column_idx),
+            &dump,
+        ),
+        result => result,
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn parse_cells<T: FromRedisValueWithGraph>(
+    column_idx: usize,
+    cells: Vec<Value>,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<Vec<T>> {
+    cells
+        .into_iter()
+        .enumerate()
+        .map(|(row_idx, cell)| parse_cell(row_idx, column_idx, cell, graph))
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+fn parse_cells<T: FromRedisValueWithGraph + Send>(
+    column_idx: usize,
+    cells: Vec<Value>,
+    graph: &dyn DecodeContext,
+) -> RedisGraphResult<Vec<T>> {
+    cells
+        .into_par_iter()
+        .with_min_len(PARALLEL_MIN_ROWS)
+        .enumerate()
+        .map(|(row_idx, cell)| parse_cell(row_idx, column_idx, cell, graph))
+        .collect()
 }
 
-fn parse_statistics(value: Value) -> RedisGraphResult<Statistics> {
+fn parse_statistics(value: Value, reply_idx: usize, graph: &dyn DecodeContext) -> RedisGraphResult<Statistics> {
     match value {
         Value::Bulk(statistics) => statistics
             .into_iter()
-            .map(|entry| match entry {
+            .enumerate()
+            .map(|(i, entry)| match entry {
                 Value::Data(utf8) => {
                     String::from_utf8(utf8).map_err(|_| RedisGraphError::InvalidUtf8)
                 }
-                _ => server_type_error!("expected string as statistics entry"),
+                entry => reply_error(
+                    graph,
+                    "expected string as statistics entry",
+                    &[reply_idx, i],
+                    None,
+                    &entry,
+                ),
             })
             .collect::<RedisGraphResult<Vec<String>>>()
             .map(Statistics),
-        _ => server_type_error!("expected array as statistics list"),
+        value => reply_error(
+            graph,
+            "expected array as statistics list",
+            &[reply_idx],
+            None,
+            &value,
+        ),
     }
 }
 
@@ -357,6 +788,7 @@ pub enum Scalar {
     Edge(Edge),
     Node(Node),
     Path(RawPath),
+    Map(HashMap<RedisString, Scalar>),
 }
 
 /// Implemented for Redis types with a nil-like variant.
@@ -377,6 +809,59 @@ impl Take for Scalar {
     }
 }
 
+impl Scalar {
+    /// Converts the elements of an array, e.g. the result of `collect()`, into a `Vec<T>`.
+    ///
+    /// If an element can't be converted, returns
+    /// [`ArrayElementMismatch`](../enum.RedisGraphError.html#variant.ArrayElementMismatch)
+    /// with the index and variant of the first such element.
+    pub fn array_as<T: FromCell>(&self) -> RedisGraphResult<Vec<T>> {
+        let elements = match self {
+            Self::Array(elements) => elements,
+            any => return client_type_error!(
+                "failed to convert array: expected array, found {:?}",
+                any
+            ),
+        };
+        elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| {
+                element.convert().map_err(|error| RedisGraphError::ArrayElementMismatch {
+                    index,
+                    found: element.variant_name(),
+                    error: Box::new(error),
+                })
+            })
+            .collect()
+    }
+
+    /// Converts the scalar into `T` as if it were the only cell of a result set,
+    /// e.g. to read the values of a map in a [converter](../converters/index.html).
+    pub fn convert<T: FromCell>(&self) -> RedisGraphResult<T> {
+        let cell = ResultSet {
+            columns: vec![Column::Scalars(vec![self.clone()])],
+            statistics: Statistics(Vec::new()),
+        };
+        T::from_cell(&cell, 0, 0)
+    }
+
+    fn variant_name(&self) -> &'static str {
+        match self {
+            Self::Nil => "Nil",
+            Self::Boolean(_) => "Boolean",
+            Self::Integer(_) => "Integer",
+            Self::Double(_) => "Double",
+            Self::String(_) => "String",
+            Self::Array(_) => "Array",
+            Self::Edge(_) => "Edge",
+            Self::Node(_) => "Node",
+            Self::Path(_) => "Path",
+            Self::Map(_) => "Map",
+        }
+    }
+}
+
 /// A string returned by Redis.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RedisString(pub Vec<u8>);
@@ -411,10 +896,12 @@ enum ScalarType {
     Edge = 7,
     Node = 8,
     Path = 9,
+    Map = 10,
+    VectorF32 = 12,
 }
 
 impl FromRedisValueWithGraph for Scalar {
-    fn from_redis_value_with_graph(value: Value, graph: &Graph) -> RedisGraphResult<Self> {
+    fn from_redis_value_with_graph(value: Value, graph: &dyn DecodeContext) -> RedisGraphResult<Self> {
         match value {
             Value::Bulk(mut values) => {
                 if values.len() == 2 {
@@ -438,6 +925,8 @@ impl FromRedisValueWithGraph for Scalar {
                                     b"false" => Ok(Scalar::Boolean(false)),
                                     _ => server_type_error!("expected either \"true\" or \"false\" as scalar value (scalar type is boolean)")
                                 }
+                                Value::Int(0) if graph.lenient_booleans() => Ok(Scalar::Boolean(false)),
+                                Value::Int(1) if graph.lenient_booleans() => Ok(Scalar::Boolean(true)),
                                 _ => server_type_error!("expected binary data as scalar value (scalar type is boolean)")
                             },
                             Some(ScalarType::Double) => match scalar_value {
@@ -475,7 +964,44 @@ impl FromRedisValueWithGraph for Scalar {
                                 Ok(path) => Ok(Scalar::Path(path)),
                                 Err(e) => Err(e),
                             },
-                            None => server_type_error!("expected integer between 0 and 9 (scalar type) as first element of scalar array, got {}", scalar_type_int)
+                            Some(ScalarType::Map) => match scalar_value {
+                                Value::Bulk(entries) => {
+                                    if entries.len() % 2 != 0 {
+                                        return server_type_error!("expected alternating keys and values for map");
+                                    }
+                                    let mut map = HashMap::with_capacity(entries.len() / 2);
+                                    let mut entries = entries.into_iter();
+                                    while let (Some(key), Some(value)) = (entries.next(), entries.next()) {
+                                        match key {
+                                            Value::Data(key_data) => {
+                                                let value = Self::from_redis_value_with_graph(value, graph)?;
+                                                map.insert(RedisString(key_data), value);
+                                            }
+                                            _ => return server_type_error!("expected binary data as map key")
+                                        }
+                                    }
+                                    Ok(Scalar::Map(map))
+                                },
+                                _ => server_type_error!("expected array as scalar value (scalar type is map)")
+                            },
+                            Some(ScalarType::VectorF32) => match scalar_value {
+                                Value::Bulk(elements) => elements
+                                    .into_iter()
+                                    .map(|elem| match elem {
+                                        Value::Data(double_data) => match str::from_utf8(&double_data[..]) {
+                                            Ok(double_string) => match double_string.parse::<f64>() {
+                                                Ok(double) => Ok(Scalar::Double(double)),
+                                                Err(_) => server_type_error!("expected string representation of double as vector element")
+                                            },
+                                            Err(_) => Err(RedisGraphError::InvalidUtf8),
+                                        }
+                                        _ => server_type_error!("expected string representing a double as vector element")
+                                    })
+                                    .collect::<RedisGraphResult<Vec<Scalar>>>()
+                                    .map(Scalar::Array),
+                                _ => server_type_error!("expected array as scalar value (scalar type is vectorf32)")
+                            },
+                            None => server_type_error!("expected integer between 0 and 12 (scalar type) as first element of scalar array, got {}", scalar_type_int)
                         },
                         _ => server_type_error!("expected integer representing scalar type as first element of scalar array")
                     }
@@ -497,8 +1023,20 @@ pub struct Node {
     pub properties: HashMap<RedisString, Scalar>,
 }
 
+impl Node {
+    /// Returns the property with the given key converted into `T`.
+    ///
+    /// A missing property is converted like `null`, so request an `Option` for optional properties.
+    pub fn get<T: FromCell>(&self, key: &str) -> RedisGraphResult<T> {
+        match self.properties.get(&RedisString::from(key.to_string())) {
+            Some(value) => value.convert(),
+            None => Scalar::Nil.convert(),
+        }
+    }
+}
+
 impl FromRedisValueWithGraph for Node {
-    fn from_redis_value_with_graph(value: Value, graph: &Graph) -> RedisGraphResult<Self> {
+    fn from_redis_value_with_graph(value: Value, graph: &dyn DecodeContext) -> RedisGraphResult<Self> {
         match value {
             Value::Bulk(mut values) => {
                 if values.len() == 3 {
@@ -545,8 +1083,20 @@ pub struct Edge {
     pub properties: HashMap<RedisString, Scalar>,
 }
 
+impl Edge {
+    /// Returns the property with the given key converted into `T`.
+    ///
+    /// A missing property is converted like `null`, so request an `Option` for optional properties.
+    pub fn get<T: FromCell>(&self, key: &str) -> RedisGraphResult<T> {
+        match self.properties.get(&RedisString::from(key.to_string())) {
+            Some(value) => value.convert(),
+            None => Scalar::Nil.convert(),
+        }
+    }
+}
+
 impl FromRedisValueWithGraph for Edge {
-    fn from_redis_value_with_graph(value: Value, graph: &Graph) -> RedisGraphResult<Self> {
+    fn from_redis_value_with_graph(value: Value, graph: &dyn DecodeContext) -> RedisGraphResult<Self> {
         match value {
             Value::Bulk(mut values) => {
                 if values.len() == 5 {
@@ -698,6 +1248,93 @@ impl Path {
     pub fn len(&self) -> usize {
         self.bifoldl(|acc, _| acc, |acc, _| acc + 1, 0)
     }
+
+    /// Returns the first node of the path.
+    pub fn start(&self) -> &Node {
+        match self {
+            Path::Cons(start, _, _) | Path::End(start, _, _) => start,
+        }
+    }
+
+    /// Returns the last node of the path.
+    pub fn end(&self) -> &Node {
+        let mut segment = self;
+        loop {
+            match segment {
+                Path::Cons(_, _, rest) => segment = rest,
+                Path::End(_, _, end) => return end,
+            }
+        }
+    }
+
+    /// Iterates over the nodes of the path, from the start to the end.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.segments()
+            .map(Path::start)
+            .chain(iter::once(self.end()))
+    }
+
+    /// Iterates over the edges of the path, from the start to the end.
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.segments().map(|segment| match segment {
+            Path::Cons(_, edge, _) | Path::End(_, edge, _) => edge,
+        })
+    }
+
+    fn segments(&self) -> impl Iterator<Item = &Path> {
+        iter::successors(Some(self), |segment| match segment {
+            Path::Cons(_, _, rest) => Some(&**rest),
+            Path::End(..) => None,
+        })
+    }
+}
+
+/// Builds a [`Path`] or [`RawPath`] one step at a time, e.g. for tests.
+///
+/// ```
+/// # use redisgraph::result_set::{Edge, Node, PathBuilder};
+/// # fn person() -> Node { Node { labels: Vec::new(), properties: Default::default() } }
+/// # fn knows() -> Edge { Edge { type_name: "KNOWS".to_string().into(), properties: Default::default() } }
+/// let path = PathBuilder::new(person())
+///     .step(knows(), person())
+///     .step(knows(), person())
+///     .build()
+///     .unwrap();
+/// assert_eq!(path.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathBuilder {
+    path: RawPath,
+}
+
+impl PathBuilder {
+    /// Starts a path at the given node.
+    pub fn new(start: Node) -> Self {
+        Self {
+            path: RawPath {
+                nodes: vec![start],
+                edges: Vec::new(),
+            },
+        }
+    }
+
+    /// Appends an edge and the node it leads to.
+    pub fn step(mut self, edge: Edge, node: Node) -> Self {
+        self.path.edges.push(edge);
+        self.path.nodes.push(node);
+        self
+    }
+
+    /// Builds the path, or returns `None` if no step was added,
+    /// since a [`Path`] has at least one edge.
+    pub fn build(self) -> Option<Path> {
+        Path::try_from(self.path).ok()
+    }
+
+    /// Builds the path as a [`RawPath`], which may consist of the start node only.
+    pub fn build_raw(self) -> RawPath {
+        self.path
+    }
 }
 
 impl TryFrom<RawPath> for Path {
@@ -722,7 +1359,7 @@ impl TryFrom<RawPath> for Path {
 }
 
 impl FromRedisValueWithGraph for RawPath {
-    fn from_redis_value_with_graph(value: Value, graph: &Graph) -> RedisGraphResult<Self> {
+    fn from_redis_value_with_graph(value: Value, graph: &dyn DecodeContext) -> RedisGraphResult<Self> {
         match value {
             Value::Bulk(mut values) => {
                 if values.len() == 2 {
@@ -774,7 +1411,7 @@ impl FromRedisValueWithGraph for RawPath {
 }
 
 fn parse_properties(
-    graph: &Graph,
+    graph: &dyn DecodeContext,
     properties: Value,
 ) -> RedisGraphResult<HashMap<RedisString, Scalar>> {
     let graph_property_keys = graph.property_keys();
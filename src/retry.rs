@@ -0,0 +1,180 @@
+//! Resending mutations after ambiguous network failures.
+//!
+//! When a request fails because the connection broke, the mutation may or may not have been
+//! applied, so resending it is only safe if applying it twice has the same effect as applying
+//! it once, e.g. for `MERGE` or `SET` on existing nodes. Nothing is retried automatically:
+//! callers mark such mutations with [`Graph::mutate_idempotent_marked`](../graph/struct.Graph.html#method.mutate_idempotent_marked),
+//! and only those are resent according to the graph's [`RetryPolicy`](struct.RetryPolicy.html).
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use redis::Client;
+//! # use redisgraph::{retry::RetryPolicy, Graph, RedisGraphResult};
+//! # fn example(client: Client, graph: &mut Graph) -> RedisGraphResult<()> {
+//! graph.set_retry_policy(Some(
+//!     RetryPolicy::new(3, Duration::from_millis(50)).reconnect_with(move || client.get_connection()),
+//! ));
+//!
+//! // Resent up to three times if the connection breaks.
+//! graph.mutate_idempotent_marked("MERGE (:Person {name: 'Alice'})")?;
+//! // Never resent.
+//! graph.mutate("CREATE (:Person {name: 'Bob'})")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use redis::{Connection, ConnectionLike, ErrorKind, RedisResult};
+
+use crate::{params::CypherValue, Graph, RedisGraphError, RedisGraphResult};
+
+type Reconnect<C> = Arc<dyn Fn() -> RedisResult<C> + Send + Sync>;
+
+/// How often and how fast mutations marked as idempotent are resent,
+/// set with [`Graph::set_retry_policy`](../graph/struct.Graph.html#method.set_retry_policy).
+///
+/// `C` is the connection type of the graph, which [`reconnect_with`](#method.reconnect_with)
+/// opens new connections of.
+pub struct RetryPolicy<C = Connection> {
+    retries: usize,
+    backoff: Duration,
+    reconnect: Option<Reconnect<C>>,
+}
+
+impl RetryPolicy {
+    /// Creates a policy that resends a mutation up to `retries` times, waiting `backoff`
+    /// before the first retry and twice as long before every further one.
+    pub fn new(retries: usize, backoff: Duration) -> Self {
+        Self::for_connection(retries, backoff)
+    }
+}
+
+impl<C> RetryPolicy<C> {
+    /// Same as [`new`](#method.new), but for graphs over another connection type.
+    pub fn for_connection(retries: usize, backoff: Duration) -> Self {
+        Self {
+            retries,
+            backoff,
+            reconnect: None,
+        }
+    }
+
+    /// Opens a new connection with the given function before every retry, since a broken
+    /// connection usually stays broken.
+    ///
+    /// If opening the connection fails, the old one is kept and the retry is sent anyway.
+    pub fn reconnect_with<F>(mut self, reconnect: F) -> Self
+    where
+        F: Fn() -> RedisResult<C> + Send + Sync + 'static,
+    {
+        self.reconnect = Some(Arc::new(reconnect));
+        self
+    }
+
+    /// Returns the maximum number of retries.
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// Returns the time to wait before the first retry.
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+}
+
+impl Default for RetryPolicy {
+    /// 2 retries, starting after 100 ms, without reconnecting.
+    fn default() -> Self {
+        Self::new(2, Duration::from_millis(100))
+    }
+}
+
+impl<C> Clone for RetryPolicy<C> {
+    fn clone(&self) -> Self {
+        Self {
+            retries: self.retries,
+            backoff: self.backoff,
+            reconnect: self.reconnect.clone(),
+        }
+    }
+}
+
+impl<C> fmt::Debug for RetryPolicy<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("retries", &self.retries)
+            .field("backoff", &self.backoff)
+            .field("reconnect", &self.reconnect.is_some())
+            .finish()
+    }
+}
+
+/// Returns `true` if the request that failed with the given error may or may not have been
+/// applied, because the connection broke or timed out.
+pub fn is_ambiguous(error: &RedisGraphError) -> bool {
+    match error {
+        RedisGraphError::RedisError(error) => {
+            error.is_io_error() || error.is_timeout() || error.is_connection_dropped()
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if the request that failed with the given error was rejected without being
+/// applied, but may succeed if it's sent again later, e.g. because the server is still loading
+/// its dataset or a cluster is failing over.
+pub fn is_transient(error: &RedisGraphError) -> bool {
+    match error {
+        RedisGraphError::RedisError(error) => matches!(
+            error.kind(),
+            ErrorKind::BusyLoadingError
+                | ErrorKind::TryAgain
+                | ErrorKind::ClusterDown
+                | ErrorKind::MasterDown
+        ),
+        _ => false,
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Same as [`mutate`](#method.mutate), but marks the mutation as idempotent, so it is resent
+    /// according to the [retry policy](#method.set_retry_policy) if it fails
+    /// [ambiguously](../retry/fn.is_ambiguous.html).
+    ///
+    /// Only mark mutations that have the same effect when applied twice.
+    pub fn mutate_idempotent_marked(&mut self, query: &str) -> RedisGraphResult<()> {
+        self.mutate_idempotent_marked_with_params(query, &HashMap::new())
+    }
+
+    /// Same as [`mutate_idempotent_marked`](#method.mutate_idempotent_marked), but with parameters.
+    pub fn mutate_idempotent_marked_with_params(
+        &mut self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<()> {
+        let policy = self.retry_policy().cloned();
+        let mut retries = 0;
+        loop {
+            match self.mutate_with_params(query, params) {
+                Err(error) if is_ambiguous(&error) => match &policy {
+                    Some(policy) if retries < policy.retries => {
+                        thread::sleep(policy.backoff * 2u32.saturating_pow(retries as u32));
+                        retries += 1;
+                        if let Some(reconnect) = &policy.reconnect {
+                            if let Ok(conn) = reconnect() {
+                                self.reconnect(conn);
+                            }
+                        }
+                    }
+                    _ => return Err(error),
+                },
+                result => return result,
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Characterizing big result sets cheaply before processing them in full.
+//!
+//! [`ResultSet::head`](../result_set/struct.ResultSet.html#method.head) and
+//! [`ResultSet::sample`](../result_set/struct.ResultSet.html#method.sample) cut a result set down
+//! to a few rows, and [`ResultSet::summary`](../result_set/struct.ResultSet.html#method.summary)
+//! describes every column in a single pass.
+//!
+//! ```
+//! use redisgraph::{
+//!     result_set::{Column, Scalar, Statistics},
+//!     sampling::ValueType,
+//!     ResultSet,
+//! };
+//!
+//! let result_set = ResultSet {
+//!     columns: vec![Column::Scalars(vec![
+//!         Scalar::Integer(3),
+//!         Scalar::Nil,
+//!         Scalar::Double(1.5),
+//!     ])],
+//!     statistics: Statistics(Vec::new()),
+//! };
+//! let summary = &result_set.summary()[0];
+//! assert_eq!(summary.value_type, Some(ValueType::Mixed));
+//! assert_eq!(summary.nulls, 1);
+//! assert_eq!((summary.min, summary.max), (Some(1.5), Some(3.0)));
+//! assert_eq!(result_set.head(1).num_rows(), 1);
+//! ```
+
+use crate::{
+    result_set::{Column, Scalar},
+    ResultSet,
+};
+
+/// The type of the values in a column, see [`ColumnSummary`](struct.ColumnSummary.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    Boolean,
+    Integer,
+    Double,
+    String,
+    Array,
+    Node,
+    Edge,
+    Path,
+    Map,
+    /// The column contains values of more than one type, not counting `null`.
+    Mixed,
+}
+
+/// A description of a column, returned by
+/// [`ResultSet::summary`](../result_set/struct.ResultSet.html#method.summary).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSummary {
+    /// The type of the values, or `None` if the column is empty or only contains `null`.
+    pub value_type: Option<ValueType>,
+    /// The number of `null` values.
+    pub nulls: usize,
+    /// The smallest integer or double in the column, if any.
+    pub min: Option<f64>,
+    /// The largest integer or double in the column, if any.
+    pub max: Option<f64>,
+}
+
+impl ColumnSummary {
+    fn new() -> Self {
+        Self {
+            value_type: None,
+            nulls: 0,
+            min: None,
+            max: None,
+        }
+    }
+
+    fn record(&mut self, value_type: Option<ValueType>, number: Option<f64>) {
+        let value_type = match value_type {
+            Some(value_type) => value_type,
+            None => {
+                self.nulls += 1;
+                return;
+            }
+        };
+        self.value_type = match self.value_type {
+            Some(other) if other != value_type => Some(ValueType::Mixed),
+            _ => Some(value_type),
+        };
+        if let Some(number) = number.filter(|number| !number.is_nan()) {
+            self.min = Some(self.min.map_or(number, |min| min.min(number)));
+            self.max = Some(self.max.map_or(number, |max| max.max(number)));
+        }
+    }
+}
+
+impl ResultSet {
+    /// Returns a result set with the first `n` rows of this one, or all of them if there are fewer.
+    ///
+    /// The statistics are kept.
+    pub fn head(&self, n: usize) -> ResultSet {
+        let n = n.min(self.num_rows());
+        self.select_rows(&(0..n).collect::<Vec<_>>())
+    }
+
+    /// Returns a result set with `n` rows of this one chosen at random, or all of them if there
+    /// are fewer. The rows keep their order.
+    ///
+    /// The same seed always chooses the same rows of a result set of the same size.
+    pub fn sample(&self, n: usize, seed: u64) -> ResultSet {
+        let num_rows = self.num_rows();
+        let n = n.min(num_rows);
+        // Reservoir sampling, so every subset of `n` rows is equally likely.
+        let mut rng = SplitMix64(seed);
+        let mut chosen: Vec<usize> = (0..n).collect();
+        for row_idx in n..num_rows {
+            let slot = (rng.next() % (row_idx as u64 + 1)) as usize;
+            if slot < n {
+                chosen[slot] = row_idx;
+            }
+        }
+        chosen.sort_unstable();
+        self.select_rows(&chosen)
+    }
+
+    /// Describes every column: the type of its values, how many of them are `null` and,
+    /// for numeric values, their minimum and maximum.
+    pub fn summary(&self) -> Vec<ColumnSummary> {
+        self.columns
+            .iter()
+            .map(|column| {
+                let mut summary = ColumnSummary::new();
+                match column {
+                    Column::Scalars(cells) => {
+                        for cell in cells {
+                            let (value_type, number) = scalar_type(cell);
+                            summary.record(value_type, number);
+                        }
+                    }
+                    Column::Nodes(cells) => {
+                        for _ in cells {
+                            summary.record(Some(ValueType::Node), None);
+                        }
+                    }
+                    Column::Relations(cells) => {
+                        for _ in cells {
+                            summary.record(Some(ValueType::Edge), None);
+                        }
+                    }
+                }
+                summary
+            })
+            .collect()
+    }
+
+    fn select_rows(&self, row_indices: &[usize]) -> ResultSet {
+        fn select<T: Clone>(cells: &[T], row_indices: &[usize]) -> Vec<T> {
+            row_indices.iter().map(|idx| cells[*idx].clone()).collect()
+        }
+
+        ResultSet {
+            columns: self
+                .columns
+                .iter()
+                .map(|column| match column {
+                    Column::Scalars(cells) => Column::Scalars(select(cells, row_indices)),
+                    Column::Nodes(cells) => Column::Nodes(select(cells, row_indices)),
+                    Column::Relations(cells) => Column::Relations(select(cells, row_indices)),
+                })
+                .collect(),
+            statistics: self.statistics.clone(),
+        }
+    }
+}
+
+fn scalar_type(scalar: &Scalar) -> (Option<ValueType>, Option<f64>) {
+    match scalar {
+        Scalar::Nil => (None, None),
+        Scalar::Boolean(_) => (Some(ValueType::Boolean), None),
+        Scalar::Integer(integer) => (Some(ValueType::Integer), Some(*integer as f64)),
+        Scalar::Double(double) => (Some(ValueType::Double), Some(*double)),
+        Scalar::String(_) => (Some(ValueType::String), None),
+        Scalar::Array(_) => (Some(ValueType::Array), None),
+        Scalar::Edge(_) => (Some(ValueType::Edge), None),
+        Scalar::Node(_) => (Some(ValueType::Node), None),
+        Scalar::Path(_) => (Some(ValueType::Path), None),
+        Scalar::Map(_) => (Some(ValueType::Map), None),
+    }
+}
+
+// A small, fast generator, see https://prng.di.unimi.it/splitmix64.c.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
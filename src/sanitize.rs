@@ -0,0 +1,194 @@
+/// Replaces every string and number literal in a query with `?`, so the query can be
+/// logged or reported without leaking the data embedded in it.
+///
+/// Identifiers, including quoted ones, and parameter names are kept. Parameters inlined by
+/// [`Graph::query_with_params`](../graph/struct.Graph.html#method.query_with_params)
+/// end up in a `CYPHER name=value` prefix, so their values are replaced as well.
+///
+/// ```
+/// use redisgraph::sanitize::sanitize_query;
+///
+/// assert_eq!(
+///     sanitize_query("CYPHER id=42 MATCH (p:Person { name: 'Alice', `ssn 2`: \"123-45\" }) WHERE p.id = $id RETURN p LIMIT 10"),
+///     "CYPHER id=? MATCH (p:Person { name: ?, `ssn 2`: ? }) WHERE p.id = $id RETURN p LIMIT ?",
+/// );
+/// ```
+pub fn sanitize_query(query: &str) -> String {
+    let mut sanitized = String::with_capacity(query.len());
+    let mut chars = query.chars().peekable();
+    let mut in_word = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                // Skip to the closing quote, honoring backslash escapes.
+                while let Some(next) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == c {
+                        break;
+                    }
+                }
+                sanitized.push('?');
+            }
+            '`' => {
+                sanitized.push(c);
+                for next in chars.by_ref() {
+                    sanitized.push(next);
+                    if next == '`' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_digit() && !in_word => {
+                // Digits, decimal points and exponents, e.g. `1.5e-3`, but not the `..` of a range.
+                let mut previous = c;
+                while let Some(&next) = chars.peek() {
+                    let continues = next.is_ascii_alphanumeric()
+                        || next == '_'
+                        || (next == '.' && !is_range_ahead(&chars))
+                        || ((next == '-' || next == '+') && (previous == 'e' || previous == 'E'));
+                    if !continues {
+                        break;
+                    }
+                    previous = next;
+                    chars.next();
+                }
+                sanitized.push('?');
+            }
+            _ => sanitized.push(c),
+        }
+        in_word = c.is_alphanumeric() || c == '_' || c == '$';
+    }
+
+    sanitized
+}
+
+// Returns `true` if the next two characters are `..`.
+fn is_range_ahead(chars: &std::iter::Peekable<std::str::Chars>) -> bool {
+    let mut lookahead = chars.clone();
+    lookahead.next() == Some('.') && lookahead.next() == Some('.')
+}
+
+/// Returns the shape of a query: the query [sanitized](fn.sanitize_query.html), without the
+/// `CYPHER name=value` prefix of inlined parameters, with runs of whitespace collapsed
+/// into a single space, and with lists of literals collapsed into a single literal.
+///
+/// ```
+/// use redisgraph::sanitize::normalize_query;
+///
+/// assert_eq!(
+///     normalize_query("CYPHER ids=[1, 2] MATCH (p:Person)\n    WHERE id(p) IN $ids AND p.age IN [18, 19, 20]  RETURN p"),
+///     "MATCH (p:Person) WHERE id(p) IN $ids AND p.age IN [?] RETURN p",
+/// );
+/// ```
+pub fn normalize_query(query: &str) -> String {
+    let sanitized = sanitize_query(query);
+    let body = strip_cypher_prefix(&sanitized);
+    let mut normalized = String::with_capacity(body.len());
+    let mut chars = body.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => {
+                normalized.push(c);
+                for next in chars.by_ref() {
+                    normalized.push(next);
+                    if next == '`' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                while matches!(chars.peek(), Some(next) if next.is_whitespace()) {
+                    chars.next();
+                }
+                normalized.push(' ');
+            }
+            ']' => {
+                normalized.push(c);
+                collapse_literal_list(&mut normalized);
+            }
+            _ => normalized.push(c),
+        }
+    }
+
+    normalized
+}
+
+/// Returns a fingerprint of the [shape](fn.normalize_query.html) of a query, which is equal for
+/// queries that only differ in their literals, parameter values and whitespace.
+///
+/// The fingerprint is the same in every process, so it can be used to group queries across
+/// instances, e.g. in metrics or slowlog entries.
+///
+/// ```
+/// use redisgraph::sanitize::query_fingerprint;
+///
+/// assert_eq!(
+///     query_fingerprint("MATCH (p:Person {name: 'Alice'}) RETURN p"),
+///     query_fingerprint("MATCH (p:Person {name: 'Bob'})\nRETURN p"),
+/// );
+/// assert_ne!(
+///     query_fingerprint("MATCH (p:Person) RETURN p"),
+///     query_fingerprint("MATCH (p:Company) RETURN p"),
+/// );
+/// ```
+pub fn query_fingerprint(query: &str) -> u64 {
+    // FNV-1a, which unlike `DefaultHasher` doesn't depend on the process or Rust version.
+    normalize_query(query)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+// Skips the `CYPHER name=value ...` prefix added by `build_query`.
+fn strip_cypher_prefix(query: &str) -> &str {
+    let query = query.trim_start();
+    match query.get(..7) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("CYPHER ") => {}
+        _ => return query,
+    }
+
+    let mut rest = &query[7..];
+    loop {
+        let candidate = rest.trim_start();
+        let name_len = candidate
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(candidate.len());
+        if name_len == 0 || !candidate[name_len..].starts_with('=') {
+            return candidate;
+        }
+
+        // The value ends at the first whitespace outside of lists and maps.
+        let value = &candidate[name_len + 1..];
+        let mut depth = 0;
+        let mut end = value.len();
+        for (idx, c) in value.char_indices() {
+            match c {
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth -= 1,
+                c if c.is_whitespace() && depth <= 0 => {
+                    end = idx;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        rest = &value[end..];
+    }
+}
+
+// Replaces a list of literals like `[?, ?, ?]` at the end of `normalized` with `[?]`.
+fn collapse_literal_list(normalized: &mut String) {
+    let start = match normalized.rfind('[') {
+        Some(start) => start,
+        None => return,
+    };
+    let items = &normalized[start + 1..normalized.len() - 1];
+    if !items.trim().is_empty() && items.split(',').all(|item| item.trim() == "?") {
+        normalized.truncate(start);
+        normalized.push_str("[?]");
+    }
+}
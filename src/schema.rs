@@ -0,0 +1,389 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use redis::ConnectionLike;
+
+use crate::{
+    error::is_unknown_procedure, params::escape_identifier, result_set::Scalar, Graph,
+    RedisGraphResult, RedisString,
+};
+
+/// The label, relationship type and property key names of a graph, indexed by their IDs.
+///
+/// RedisGraph only sends IDs instead of names in its compact result format,
+/// so nodes and relationships can only be decoded with this mapping.
+/// It is normally retrieved by calling the `db.labels()`, `db.relationshipTypes()`
+/// and `db.propertyKeys()` procedures. In environments where these procedures are blocked,
+/// provide the mapping yourself through
+/// [`Graph::use_static_schema`](../graph/struct.Graph.html#method.use_static_schema),
+/// e.g. one exported with [`Graph::schema_mapping`](../graph/struct.Graph.html#method.schema_mapping)
+/// from an environment where they are allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SchemaMapping {
+    /// The label names, where the name at index `i` belongs to the label with ID `i`.
+    pub labels: Vec<RedisString>,
+    /// The relationship type names, where the name at index `i` belongs to the relationship type with ID `i`.
+    pub relationship_types: Vec<RedisString>,
+    /// The property key names, where the name at index `i` belongs to the property key with ID `i`.
+    pub property_keys: Vec<RedisString>,
+}
+
+impl SchemaMapping {
+    /// Creates a mapping from the given names, ordered by their IDs.
+    pub fn from_names(
+        labels: &[&str],
+        relationship_types: &[&str],
+        property_keys: &[&str],
+    ) -> Self {
+        fn to_redis_strings(names: &[&str]) -> Vec<RedisString> {
+            names
+                .iter()
+                .map(|name| RedisString::from(name.to_string()))
+                .collect()
+        }
+
+        Self {
+            labels: to_redis_strings(labels),
+            relationship_types: to_redis_strings(relationship_types),
+            property_keys: to_redis_strings(property_keys),
+        }
+    }
+}
+
+/// The labels, relationship types, indexes and constraints of a graph.
+///
+/// Declare the schema an application expects, with the builder methods or the
+/// [`schema!`](../macro.schema.html) macro, and compare it against the one read with
+/// [`Graph::graph_schema`](../graph/struct.Graph.html#method.graph_schema) on startup:
+///
+/// ```no_run
+/// # use redisgraph::{schema::{ConstraintKind, GraphSchema}, Graph, RedisGraphResult};
+/// # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+/// let expected = GraphSchema::new()
+///     .with_label("Person")
+///     .with_relationship_type("KNOWS")
+///     .with_index("Person", "name")
+///     .with_constraint(ConstraintKind::Unique, "Person", &["email"]);
+/// let drift = graph.graph_schema()?.diff(&expected);
+/// if !drift.missing.is_empty() {
+///     panic!("schema drift:\n{}", drift);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GraphSchema {
+    /// The node labels.
+    pub labels: BTreeSet<String>,
+    /// The relationship types.
+    pub relationship_types: BTreeSet<String>,
+    /// The indexed properties.
+    pub indexes: BTreeSet<IndexSpec>,
+    /// The constraints.
+    pub constraints: BTreeSet<ConstraintSpec>,
+}
+
+/// An index on a property of the nodes with a label.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IndexSpec {
+    /// The label of the indexed nodes.
+    pub label: String,
+    /// The indexed property.
+    pub property: String,
+}
+
+/// The kind of a [`ConstraintSpec`](struct.ConstraintSpec.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConstraintKind {
+    /// The combination of the properties is unique among the nodes with the label.
+    Unique,
+    /// Every node with the label has the properties.
+    Mandatory,
+}
+
+/// A constraint on the properties of the nodes with a label.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConstraintSpec {
+    /// The kind of the constraint.
+    pub kind: ConstraintKind,
+    /// The label of the constrained nodes.
+    pub label: String,
+    /// The constrained properties, in the order they were declared.
+    pub properties: Vec<String>,
+}
+
+impl GraphSchema {
+    /// Creates an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node label.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.labels.insert(label.to_string());
+        self
+    }
+
+    /// Adds a relationship type.
+    pub fn with_relationship_type(mut self, rel_type: &str) -> Self {
+        self.relationship_types.insert(rel_type.to_string());
+        self
+    }
+
+    /// Adds an index on a property of the nodes with the given label.
+    pub fn with_index(mut self, label: &str, property: &str) -> Self {
+        self.indexes.insert(IndexSpec {
+            label: label.to_string(),
+            property: property.to_string(),
+        });
+        self
+    }
+
+    /// Adds a constraint on properties of the nodes with the given label.
+    pub fn with_constraint(
+        mut self,
+        kind: ConstraintKind,
+        label: &str,
+        properties: &[&str],
+    ) -> Self {
+        self.constraints.insert(ConstraintSpec {
+            kind,
+            label: label.to_string(),
+            properties: properties
+                .iter()
+                .map(|property| property.to_string())
+                .collect(),
+        });
+        self
+    }
+
+    /// Returns `true` if the schema has no labels, relationship types, indexes or constraints.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+            && self.relationship_types.is_empty()
+            && self.indexes.is_empty()
+            && self.constraints.is_empty()
+    }
+
+    /// Compares this schema, usually the live one, against the expected one.
+    pub fn diff(&self, expected: &GraphSchema) -> SchemaDrift {
+        SchemaDrift {
+            missing: difference(expected, self),
+            unexpected: difference(self, expected),
+        }
+    }
+}
+
+/// The differences between two schemas, returned by [`GraphSchema::diff`](struct.GraphSchema.html#method.diff).
+///
+/// Displays as one line per difference, e.g. `missing index :Person(name)`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SchemaDrift {
+    /// The parts of the expected schema that the live one lacks.
+    pub missing: GraphSchema,
+    /// The parts of the live schema that were not expected, e.g. labels created by other
+    /// applications. Usually only worth a warning.
+    pub unexpected: GraphSchema,
+}
+
+impl SchemaDrift {
+    /// Returns `true` if the schemas are equal.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (prefix, schema) in &[("missing", &self.missing), ("unexpected", &self.unexpected)] {
+            for label in &schema.labels {
+                writeln!(f, "{} label :{}", prefix, label)?;
+            }
+            for rel_type in &schema.relationship_types {
+                writeln!(f, "{} relationship type :{}", prefix, rel_type)?;
+            }
+            for index in &schema.indexes {
+                writeln!(f, "{} index :{}({})", prefix, index.label, index.property)?;
+            }
+            for constraint in &schema.constraints {
+                writeln!(
+                    f,
+                    "{} {:?} constraint :{}({})",
+                    prefix,
+                    constraint.kind,
+                    constraint.label,
+                    constraint.properties.join(", ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Returns the parts of `a` that are not in `b`.
+fn difference(a: &GraphSchema, b: &GraphSchema) -> GraphSchema {
+    GraphSchema {
+        labels: a.labels.difference(&b.labels).cloned().collect(),
+        relationship_types: a
+            .relationship_types
+            .difference(&b.relationship_types)
+            .cloned()
+            .collect(),
+        indexes: a.indexes.difference(&b.indexes).cloned().collect(),
+        constraints: a.constraints.difference(&b.constraints).cloned().collect(),
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Reads the labels, relationship types, indexes and constraints of the graph.
+    ///
+    /// Constraints are only supported by RedisGraph 2.12 and later,
+    /// older servers report none.
+    pub fn graph_schema(&mut self) -> RedisGraphResult<GraphSchema> {
+        let labels: Vec<String> = self.query("CALL db.labels() YIELD label RETURN label")?;
+        let relationship_types: Vec<String> = self
+            .query("CALL db.relationshipTypes() YIELD relationshipType RETURN relationshipType")?;
+
+        let mut indexes = BTreeSet::new();
+        let rows: Vec<(String, Scalar)> =
+            self.query("CALL db.indexes() YIELD label, properties RETURN label, properties")?;
+        for (label, properties) in rows {
+            for property in properties.array_as::<String>()? {
+                indexes.insert(IndexSpec {
+                    label: label.clone(),
+                    property,
+                });
+            }
+        }
+
+        let mut constraints = BTreeSet::new();
+        let rows: RedisGraphResult<Vec<(String, String, Scalar)>> = self.query(
+            "CALL db.constraints() YIELD type, label, properties RETURN type, label, properties",
+        );
+        let rows = match rows {
+            Err(error) if is_unknown_procedure(&error) => Vec::new(),
+            rows => rows?,
+        };
+        for (kind, label, properties) in rows {
+            let kind = match kind.as_str() {
+                "UNIQUE" => ConstraintKind::Unique,
+                "MANDATORY" => ConstraintKind::Mandatory,
+                any => return server_type_error!("unknown constraint type {:?}", any),
+            };
+            constraints.insert(ConstraintSpec {
+                kind,
+                label,
+                properties: properties.array_as()?,
+            });
+        }
+
+        Ok(GraphSchema {
+            labels: labels.into_iter().collect(),
+            relationship_types: relationship_types.into_iter().collect(),
+            indexes,
+            constraints,
+        })
+    }
+
+    /// Creates the indexes and constraints of the given schema that don't exist yet,
+    /// and returns them.
+    ///
+    /// The properties of unique constraints are indexed as well, since the server requires it.
+    /// Labels and relationship types can't be created without nodes or relationships,
+    /// so they are only part of the schema for [drift detection](struct.GraphSchema.html#method.diff).
+    /// Applying the same schema again does nothing.
+    pub fn apply_schema(&mut self, schema: &GraphSchema) -> RedisGraphResult<GraphSchema> {
+        let mut expected = schema.clone();
+        for constraint in &schema.constraints {
+            if constraint.kind == ConstraintKind::Unique {
+                for property in &constraint.properties {
+                    expected = expected.with_index(&constraint.label, property);
+                }
+            }
+        }
+
+        let missing = self.graph_schema()?.diff(&expected).missing;
+        for index in &missing.indexes {
+            self.mutate(&format!(
+                "CREATE INDEX ON :{}({})",
+                escape_identifier(&index.label),
+                escape_identifier(&index.property)
+            ))?;
+        }
+        for constraint in &missing.constraints {
+            self.create_constraint(constraint)?;
+        }
+        Ok(GraphSchema {
+            indexes: missing.indexes,
+            constraints: missing.constraints,
+            ..GraphSchema::default()
+        })
+    }
+}
+
+/// Declares a [`GraphSchema`](schema/struct.GraphSchema.html) with labels and their indexed,
+/// unique and required properties, followed by a list of relationship types.
+///
+/// Every `unique` list declares one constraint over all of its properties, while every
+/// `required` property gets a mandatory constraint of its own. Names that aren't valid
+/// Rust identifiers can be added with the builder methods of `GraphSchema`.
+///
+/// ```
+/// use redisgraph::schema::{ConstraintKind, GraphSchema};
+///
+/// let schema = redisgraph::schema! {
+///     :Person {
+///         indexes: [name],
+///         unique: [email],
+///         required: [name, email],
+///     }
+///     :Company {}
+///     [:KNOWS, :WORKS_AT]
+/// };
+/// assert_eq!(
+///     schema,
+///     GraphSchema::new()
+///         .with_label("Person")
+///         .with_index("Person", "name")
+///         .with_constraint(ConstraintKind::Unique, "Person", &["email"])
+///         .with_constraint(ConstraintKind::Mandatory, "Person", &["name"])
+///         .with_constraint(ConstraintKind::Mandatory, "Person", &["email"])
+///         .with_label("Company")
+///         .with_relationship_type("KNOWS")
+///         .with_relationship_type("WORKS_AT")
+/// );
+/// ```
+#[macro_export]
+macro_rules! schema {
+    (
+        $( : $label:ident { $( $kind:ident : [ $( $property:ident ),* $(,)? ] ),* $(,)? } )*
+        $( [ $( : $rel_type:ident ),* $(,)? ] )?
+    ) => {{
+        let schema = $crate::schema::GraphSchema::new();
+        $(
+            let schema = schema.with_label(stringify!($label));
+            $( let schema = $crate::schema!(@$kind schema, $label, [$( $property ),*]); )*
+        )*
+        $( $( let schema = schema.with_relationship_type(stringify!($rel_type)); )* )?
+        schema
+    }};
+    (@indexes $schema:expr, $label:ident, [$( $property:ident ),*]) => {
+        $schema $( .with_index(stringify!($label), stringify!($property)) )*
+    };
+    (@unique $schema:expr, $label:ident, [$( $property:ident ),*]) => {
+        $schema.with_constraint(
+            $crate::schema::ConstraintKind::Unique,
+            stringify!($label),
+            &[$( stringify!($property) ),*],
+        )
+    };
+    (@required $schema:expr, $label:ident, [$( $property:ident ),*]) => {
+        $schema $(
+            .with_constraint(
+                $crate::schema::ConstraintKind::Mandatory,
+                stringify!($label),
+                &[stringify!($property)],
+            )
+        )*
+    };
+}
@@ -0,0 +1,93 @@
+use redis::ConnectionLike;
+
+use crate::{result_set::Statistics, Graph, RedisGraphError, RedisGraphResult};
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes a script of semicolon-separated statements, e.g. an exported schema or a migration file.
+    ///
+    /// The statements are split with [`split_statements`](../script/fn.split_statements.html)
+    /// and executed one after another as mutations. Returns the statistics of every statement.
+    ///
+    /// Execution stops at the first failing statement, which is reported as a
+    /// [`ScriptStatementFailed`](../error/enum.RedisGraphError.html#variant.ScriptStatementFailed) error.
+    /// The statements before it are not rolled back.
+    pub fn execute_script(&mut self, script: &str) -> RedisGraphResult<Vec<Statistics>> {
+        let mut statistics = Vec::new();
+        for (index, statement) in split_statements(script).into_iter().enumerate() {
+            match self.mutate_with_statistics(&statement) {
+                Ok(stats) => statistics.push(stats),
+                Err(error) => {
+                    return Err(RedisGraphError::ScriptStatementFailed {
+                        index,
+                        statement,
+                        error: Box::new(error),
+                    })
+                }
+            }
+        }
+        Ok(statistics)
+    }
+}
+
+/// Splits a script into its semicolon-separated statements.
+///
+/// Semicolons inside string literals and quoted identifiers don't end a statement.
+/// `//` line comments are removed, and empty statements are left out.
+///
+/// ```
+/// use redisgraph::script::split_statements;
+///
+/// let script = "CREATE (:A { name: 'a;b' });\n// seed data\nCREATE (:B);;";
+/// assert_eq!(
+///     split_statements(script),
+///     vec!["CREATE (:A { name: 'a;b' })", "CREATE (:B)"]
+/// );
+/// ```
+pub fn split_statements(script: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = script.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                current.push(c);
+                if c == '\\' && q != '`' {
+                    if let Some(escaped) = chars.next() {
+                        current.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' | '`' => {
+                    quote = Some(c);
+                    current.push(c);
+                }
+                '/' if chars.peek() == Some(&'/') => {
+                    while let Some(&next) = chars.peek() {
+                        if next == '\n' {
+                            break;
+                        }
+                        chars.next();
+                    }
+                }
+                ';' => push_statement(&mut statements, &mut current),
+                _ => current.push(c),
+            },
+        }
+    }
+    push_statement(&mut statements, &mut current);
+
+    statements
+}
+
+fn push_statement(statements: &mut Vec<String>, current: &mut String) {
+    let statement = current.trim();
+    if !statement.is_empty() {
+        statements.push(statement.to_string());
+    }
+    current.clear();
+}
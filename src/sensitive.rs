@@ -0,0 +1,173 @@
+//! Keeping sensitive property values out of `Debug` output and logs.
+//!
+//! Wrap fields of entity types in [`Sensitive`](struct.Sensitive.html) to mask them when the entity
+//! is printed with `{:?}`, and print returned nodes and edges with
+//! [`Node::redacted`](../result_set/struct.Node.html#method.redacted) and
+//! [`Edge::redacted`](../result_set/struct.Edge.html#method.redacted).
+//!
+//! ```
+//! use redisgraph::sensitive::Sensitive;
+//!
+//! #[derive(Debug)]
+//! struct Person {
+//!     name: String,
+//!     email: Sensitive<String>,
+//! }
+//!
+//! let person = Person {
+//!     name: "Alice".to_string(),
+//!     email: Sensitive::new("alice@example.com".to_string()),
+//! };
+//! assert_eq!(
+//!     format!("{:?}", person),
+//!     r#"Person { name: "Alice", email: <redacted> }"#
+//! );
+//! assert_eq!(person.email.expose(), "alice@example.com");
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    assignments::FromCell,
+    params::CypherValue,
+    result_set::{Edge, Node, Scalar},
+    RedisGraphResult, RedisString, ResultSet,
+};
+
+/// Printed in place of masked values.
+pub const REDACTED: &str = "<redacted>";
+
+/// A value that is masked in `Debug` and `Display` output.
+///
+/// It converts into a [`CypherValue`](../params/enum.CypherValue.html) and can be read from result sets
+/// like the wrapped type, so it can be used for fields of types deriving
+/// [`ToProperties`](../props/trait.ToProperties.html) or read with [`Graph::query`](../graph/struct.Graph.html#method.query).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Sensitive<T>(T);
+
+impl<T> Sensitive<T> {
+    /// Wraps the value.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Sensitive<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T: Into<CypherValue>> From<Sensitive<T>> for CypherValue {
+    fn from(sensitive: Sensitive<T>) -> Self {
+        sensitive.0.into()
+    }
+}
+
+impl<T: FromCell> FromCell for Sensitive<T> {
+    fn from_cell(
+        result_set: &ResultSet,
+        row_idx: usize,
+        column_idx: usize,
+    ) -> RedisGraphResult<Self> {
+        T::from_cell(result_set, row_idx, column_idx).map(Self)
+    }
+}
+
+/// Prints a node or an edge with some property values masked,
+/// returned by [`Node::redacted`](../result_set/struct.Node.html#method.redacted)
+/// and [`Edge::redacted`](../result_set/struct.Edge.html#method.redacted).
+pub struct Redacted<'a, T> {
+    entity: &'a T,
+    keys: &'a [&'a str],
+}
+
+impl Node {
+    /// Returns a wrapper whose `Debug` output masks the values of the properties with the given keys.
+    pub fn redacted<'a>(&'a self, keys: &'a [&'a str]) -> Redacted<'a, Node> {
+        Redacted { entity: self, keys }
+    }
+}
+
+impl Edge {
+    /// Returns a wrapper whose `Debug` output masks the values of the properties with the given keys.
+    pub fn redacted<'a>(&'a self, keys: &'a [&'a str]) -> Redacted<'a, Edge> {
+        Redacted { entity: self, keys }
+    }
+}
+
+impl fmt::Debug for Redacted<'_, Node> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("labels", &self.entity.labels)
+            .field(
+                "properties",
+                &RedactedProperties {
+                    properties: &self.entity.properties,
+                    keys: self.keys,
+                },
+            )
+            .finish()
+    }
+}
+
+impl fmt::Debug for Redacted<'_, Edge> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Edge")
+            .field("type_name", &self.entity.type_name)
+            .field(
+                "properties",
+                &RedactedProperties {
+                    properties: &self.entity.properties,
+                    keys: self.keys,
+                },
+            )
+            .finish()
+    }
+}
+
+struct RedactedProperties<'a> {
+    properties: &'a HashMap<RedisString, Scalar>,
+    keys: &'a [&'a str],
+}
+
+impl fmt::Debug for RedactedProperties<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (key, value) in self.properties {
+            if self
+                .keys
+                .iter()
+                .any(|redacted| redacted.as_bytes() == &key.0[..])
+            {
+                map.entry(key, &Sensitive(value));
+            } else {
+                map.entry(key, value);
+            }
+        }
+        map.finish()
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+use redis::ConnectionLike;
+
+use crate::{params::CypherValue, Graph, RedisGraphResult};
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Increments the sequence with the given name and returns its new value,
+    /// starting at 1 for a new sequence.
+    ///
+    /// Sequences are stored in the graph itself as nodes labeled `_Seq`, so applications can
+    /// mint sequential business IDs without another datastore. Since queries on a graph are
+    /// executed one at a time, concurrent callers never receive the same value.
+    pub fn next_id(&mut self, sequence_name: &str) -> RedisGraphResult<i64> {
+        let mut params = HashMap::new();
+        params.insert(
+            "name".to_string(),
+            CypherValue::String(sequence_name.to_string()),
+        );
+        self.query_mutation_with_params(
+            "MERGE (s:_Seq {name: $name}) SET s.val = coalesce(s.val, 0) + 1 RETURN s.val",
+            &params,
+        )
+    }
+}
@@ -0,0 +1,220 @@
+//! Loading and updating entities with an identity map and dirty tracking.
+//!
+//! A [`Session`](struct.Session.html) caches every [entity](../entity/trait.Entity.html) it
+//! loads by node ID, so loading the same node again doesn't send a query and returns the same
+//! instance. [`flush`](struct.Session.html#method.flush) compares every entity with the
+//! properties it was loaded with and only writes the properties that changed.
+//!
+//! ```no_run
+//! # #[cfg(feature = "derive")]
+//! # mod example {
+//! # use redisgraph::{session::Session, Graph, RedisGraphResult};
+//! #[derive(redisgraph::Entity, redisgraph::ToProperties)]
+//! struct Account {
+//!     owner: String,
+//!     balance: i64,
+//! }
+//!
+//! # fn example(graph: &mut Graph, from: u64, to: u64) -> RedisGraphResult<()> {
+//! let mut session = Session::new(graph);
+//! session.load::<Account>(from)?.unwrap().balance -= 10;
+//! session.load::<Account>(to)?.unwrap().balance += 10;
+//! // Sends `SET n.balance = ...` for both accounts, leaving the owners untouched.
+//! session.flush()?;
+//! # Ok(())
+//! # }
+//! # }
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+
+use redis::{Connection, ConnectionLike};
+
+use crate::{
+    entity::Entity,
+    params::{escape_identifier, CypherValue},
+    pattern::node,
+    props::Props,
+    result_set::Node,
+    Graph, RedisGraphResult, ResultSet,
+};
+
+/// A unit of work over a graph, see the [module documentation](index.html).
+pub struct Session<'a, C = Connection> {
+    graph: &'a mut Graph<C>,
+    entities: HashMap<u64, Tracked>,
+}
+
+struct Tracked {
+    entity: Box<dyn TrackedEntity>,
+    // The properties the entity had when it was loaded or last flushed.
+    snapshot: HashMap<String, CypherValue>,
+}
+
+// Entities of different types, stored in the same identity map.
+trait TrackedEntity {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn properties(&self) -> HashMap<String, CypherValue>;
+    fn before_save(&mut self) -> RedisGraphResult<()>;
+}
+
+impl<E: Entity + 'static> TrackedEntity for E {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn properties(&self) -> HashMap<String, CypherValue> {
+        self.to_properties().into_map()
+    }
+
+    fn before_save(&mut self) -> RedisGraphResult<()> {
+        Entity::before_save(self)
+    }
+}
+
+impl<'a, C: ConnectionLike> Session<'a, C> {
+    /// Starts a session over the given graph.
+    pub fn new(graph: &'a mut Graph<C>) -> Self {
+        Self {
+            graph,
+            entities: HashMap::new(),
+        }
+    }
+
+    /// Returns the graph, e.g. to send queries that don't involve entities.
+    pub fn graph(&mut self) -> &mut Graph<C> {
+        self.graph
+    }
+
+    /// Returns the entity stored in the node with the given ID, or `None` if there is no
+    /// such node with the label of the entity, or if the node is marked as deleted while
+    /// [soft deletion](../graph/struct.Graph.html#method.set_soft_delete) is enabled.
+    ///
+    /// The node is only read the first time it is loaded, later calls return the same instance.
+    /// Fails if the node was already loaded as a different type.
+    pub fn load<E: Entity + 'static>(&mut self, id: u64) -> RedisGraphResult<Option<&mut E>> {
+        if !self.entities.contains_key(&id) {
+            let nodes: Vec<Node> = self.graph.query(&format!(
+                "MATCH {} WHERE id(`n`) = {}{} RETURN `n`",
+                node("n").label(E::LABEL),
+                id,
+                self.graph.soft_delete_filter(&["n"])
+            ))?;
+            match nodes.first() {
+                Some(node) => self.track::<E>(id, node)?,
+                None => return Ok(None),
+            }
+        }
+        self.get(id)
+    }
+
+    /// Loads all entities whose properties equal the ones of the given example,
+    /// like [`Graph::find_like`](../graph/struct.Graph.html#method.find_like), and returns
+    /// their node IDs. Entities that were already loaded are not replaced, and entities marked
+    /// as deleted are left out while soft deletion is enabled.
+    pub fn find_like<E: Entity + 'static>(&mut self, example: &E) -> RedisGraphResult<Vec<u64>> {
+        let (map_pattern, params) = example.to_properties().to_map_pattern("p");
+        let rows: Vec<(u64, Node)> = self.graph.query_with_params(
+            &format!(
+                "MATCH (`n`:{} {}) WHERE true{} RETURN id(`n`), `n`",
+                escape_identifier(E::LABEL),
+                map_pattern,
+                self.graph.soft_delete_filter(&["n"])
+            ),
+            &params,
+        )?;
+        let mut ids = Vec::with_capacity(rows.len());
+        for (id, node) in rows {
+            if !self.entities.contains_key(&id) {
+                self.track::<E>(id, &node)?;
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Returns an entity that was already loaded, without sending a query.
+    pub fn get<E: Entity + 'static>(&mut self, id: u64) -> RedisGraphResult<Option<&mut E>> {
+        match self.entities.get_mut(&id) {
+            Some(tracked) => match tracked.entity.as_any_mut().downcast_mut::<E>() {
+                Some(entity) => Ok(Some(entity)),
+                None => client_type_error!(
+                    "failed to get entity: node {} was loaded as a different type than {}",
+                    id,
+                    E::LABEL
+                ),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Writes the properties that changed since the entities were loaded or last flushed,
+    /// calling the [`before_save`](../entity/trait.Entity.html#method.before_save) hook of
+    /// every changed entity first. Removed properties are set to `null`.
+    ///
+    /// Returns the number of written entities. Entities are written one query at a time, so
+    /// if one of them fails, the ones before it stay written and the rest stay dirty.
+    pub fn flush(&mut self) -> RedisGraphResult<usize> {
+        let mut ids: Vec<u64> = self.entities.keys().copied().collect();
+        ids.sort_unstable();
+
+        let mut written = 0;
+        for id in ids {
+            let tracked = self.entities.get_mut(&id).unwrap();
+            if tracked.entity.properties() == tracked.snapshot {
+                continue;
+            }
+            tracked.entity.before_save()?;
+            let properties = tracked.entity.properties();
+
+            let mut keys: Vec<&String> = properties
+                .keys()
+                .chain(tracked.snapshot.keys())
+                .filter(|key| properties.get(*key) != tracked.snapshot.get(*key))
+                .collect();
+            keys.sort();
+            keys.dedup();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let mut changes = HashMap::new();
+            for key in keys {
+                let value = properties.get(key).cloned().unwrap_or(CypherValue::Null);
+                changes.insert(key.clone(), value);
+            }
+            let (assignments, params) = Props::from(changes).to_params("p");
+            let assignments = assignments
+                .iter()
+                .map(|(key, param_name)| format!("`n`.{} = ${}", key, param_name))
+                .collect::<Vec<String>>();
+            let _: ResultSet = self.graph.query_mutation_with_params(
+                &format!(
+                    "MATCH (`n`) WHERE id(`n`) = {} SET {}",
+                    id,
+                    assignments.join(", ")
+                ),
+                &params,
+            )?;
+
+            tracked.snapshot = properties;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    fn track<E: Entity + 'static>(&mut self, id: u64, node: &Node) -> RedisGraphResult<()> {
+        let mut entity = E::from_node(node)?;
+        entity.after_load()?;
+        let snapshot = entity.to_properties().into_map();
+        self.entities.insert(
+            id,
+            Tracked {
+                entity: Box::new(entity),
+                snapshot,
+            },
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Weighted shortest paths between two nodes.
+//!
+//! [`Graph::shortest_path`](../graph/struct.Graph.html#method.shortest_path) uses the
+//! `algo.SPpaths` procedure if the server provides it, and otherwise falls back to a plain
+//! Cypher query that compares every path between the two nodes, which is only feasible
+//! for small graphs.
+//!
+//! ```no_run
+//! # use redisgraph::{Graph, RedisGraphResult};
+//! # fn example(graph: &mut Graph, berlin: u64, paris: u64) -> RedisGraphResult<()> {
+//! if let Some(route) = graph.shortest_path(berlin, paris, "distance")? {
+//!     println!("{} km over {} roads", route.cost, route.path.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use redis::ConnectionLike;
+
+use crate::{
+    error::is_unknown_procedure,
+    params::{escape_identifier, CypherValue},
+    result_set::RawPath,
+    Graph, RedisGraphResult,
+};
+
+/// A path returned by [`Graph::shortest_path`](../graph/struct.Graph.html#method.shortest_path).
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedPath {
+    /// The path from the source to the target node.
+    ///
+    /// This is a [`RawPath`](../result_set/struct.RawPath.html) since the path
+    /// has no edges if the source and the target node are the same.
+    pub path: RawPath,
+    /// The sum of the weights of the edges in the path.
+    pub cost: f64,
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Returns the path from the node with ID `source` to the node with ID `target` along
+    /// outgoing relationships with the lowest sum of the given weight property, or `None`
+    /// if the target can't be reached.
+    ///
+    /// Relationships without the weight property should be avoided: the Cypher fallback
+    /// skips them, while `algo.SPpaths` handles them as the server sees fit.
+    /// See the [module documentation](../shortest_path/index.html) for how the path is found.
+    pub fn shortest_path(
+        &mut self,
+        source: u64,
+        target: u64,
+        weight: &str,
+    ) -> RedisGraphResult<Option<WeightedPath>> {
+        let mut params = HashMap::new();
+        params.insert("weight".to_string(), CypherValue::from(weight));
+        let endpoints = format!(
+            "MATCH (s), (t) WHERE id(s) = {} AND id(t) = {}",
+            source, target
+        );
+
+        let procedure = format!(
+            "{} CALL algo.SPpaths({{sourceNode: s, targetNode: t, weightProp: $weight}}) \
+             YIELD path, pathWeight \
+             RETURN path, toFloat(pathWeight) AS cost ORDER BY cost LIMIT 1",
+            endpoints
+        );
+        let rows: Vec<(RawPath, f64)> = match self.query_with_params(&procedure, &params) {
+            Err(error) if is_unknown_procedure(&error) => {
+                let weight = escape_identifier(weight);
+                self.query(&format!(
+                    "MATCH p = (s)-[*0..]->(t) WHERE id(s) = {} AND id(t) = {} \
+                     AND all(e IN relationships(p) WHERE e.{} IS NOT NULL) \
+                     WITH p, reduce(cost = 0.0, e IN relationships(p) | cost + e.{}) AS cost \
+                     RETURN p, cost ORDER BY cost LIMIT 1",
+                    source, target, weight, weight
+                ))?
+            }
+            rows => rows?,
+        };
+        Ok(rows
+            .into_iter()
+            .next()
+            .map(|(path, cost)| WeightedPath { path, cost }))
+    }
+}
@@ -0,0 +1,141 @@
+//! Coalescing identical concurrent reads into a single request.
+//!
+//! The layer wraps a blocking graph shared between threads; async services can use
+//! [`GraphClient::query_coalesced`](../client/struct.GraphClient.html#method.query_coalesced)
+//! instead. Dashboards with many widgets tend to send the same aggregation queries at the same
+//! time; with [`SingleFlight`](struct.SingleFlight.html), a query that is already being executed
+//! with the same parameters is not sent again, and every caller receives the same result.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use std::thread;
+//! # use redisgraph::{single_flight::SingleFlight, Graph};
+//! # fn example(graph: Graph) {
+//! let graph = Arc::new(SingleFlight::new(graph));
+//! let widgets: Vec<_> = (0..8)
+//!     .map(|_| {
+//!         let graph = Arc::clone(&graph);
+//!         thread::spawn(move || graph.query("MATCH (o:Order) RETURN count(o)"))
+//!     })
+//!     .collect();
+//! for widget in widgets {
+//!     let result_set = widget.join().unwrap();
+//! }
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+use redis::{Connection, ConnectionLike};
+
+use crate::{params::CypherValue, Graph, RedisGraphResult, ResultSet};
+
+/// A graph shared between threads that coalesces identical concurrent reads,
+/// see the [module documentation](index.html).
+pub struct SingleFlight<C = Connection> {
+    graph: Mutex<Graph<C>>,
+    in_flight: Mutex<HashMap<String, Arc<Flight>>>,
+}
+
+// A query that is being executed. `result` is `None` until the query finished,
+// and `Some(None)` if it failed.
+#[derive(Default)]
+struct Flight {
+    result: Mutex<Option<Option<Arc<ResultSet>>>>,
+    done: Condvar,
+}
+
+impl<C: ConnectionLike> SingleFlight<C> {
+    /// Wraps the given graph.
+    pub fn new(graph: Graph<C>) -> Self {
+        Self {
+            graph: Mutex::new(graph),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Executes the given query, or waits for the result of an identical query that is
+    /// already being executed.
+    ///
+    /// Only use this for reads: a mutation sent while the same mutation is in flight is skipped.
+    /// If the query fails, the callers that waited for it send it themselves, so every
+    /// caller receives its own error.
+    pub fn query(&self, query: &str) -> RedisGraphResult<Arc<ResultSet>> {
+        self.query_with_params(query, &HashMap::new())
+    }
+
+    /// Same as [`query`](#method.query), but with parameters. Queries are only coalesced
+    /// if their parameters are equal as well.
+    pub fn query_with_params(
+        &self,
+        query: &str,
+        params: &HashMap<String, CypherValue>,
+    ) -> RedisGraphResult<Arc<ResultSet>> {
+        let key = self.graph().prepare_query(query, params)?;
+
+        let mut in_flight = lock(&self.in_flight);
+        if let Some(flight) = in_flight.get(&key).cloned() {
+            drop(in_flight);
+            let mut result = lock(&flight.result);
+            while result.is_none() {
+                result = flight
+                    .done
+                    .wait(result)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            if let Some(Some(result_set)) = &*result {
+                return Ok(Arc::clone(result_set));
+            }
+            drop(result);
+            return self.graph().query_result_set(&key).map(Arc::new);
+        }
+        let flight = Arc::new(Flight::default());
+        in_flight.insert(key.clone(), Arc::clone(&flight));
+        drop(in_flight);
+
+        let mut landing = Landing {
+            single_flight: self,
+            key: &key,
+            flight,
+            result: None,
+        };
+        let result = self.graph().query_result_set(&key).map(Arc::new);
+        landing.result = result.as_ref().ok().cloned();
+        result
+    }
+
+    /// Returns exclusive access to the graph, e.g. for mutations.
+    pub fn graph(&self) -> MutexGuard<'_, Graph<C>> {
+        lock(&self.graph)
+    }
+
+    /// Returns the wrapped graph.
+    pub fn into_inner(self) -> Graph<C> {
+        self.graph
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Publishes the result of a flight to the waiting callers when dropped,
+// so they are woken up even if executing the query panicked.
+struct Landing<'a, C> {
+    single_flight: &'a SingleFlight<C>,
+    key: &'a str,
+    flight: Arc<Flight>,
+    result: Option<Arc<ResultSet>>,
+}
+
+impl<C> Drop for Landing<'_, C> {
+    fn drop(&mut self) {
+        lock(&self.single_flight.in_flight).remove(self.key);
+        *lock(&self.flight.result) = Some(self.result.take());
+        self.flight.done.notify_all();
+    }
+}
+
+// A panic while holding one of the locks doesn't leave the data in an inconsistent state.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,287 @@
+//! Reading the slowlog and alerting on query shapes that are slow again and again.
+//!
+//! The server keeps the slowest recent queries of every graph in its slowlog, returned by
+//! [`Graph::slowlog`](../graph/struct.Graph.html#method.slowlog) and
+//! [`AsyncGraph::slowlog`](../aio/struct.AsyncGraph.html#method.slowlog). A single slow query is
+//! rarely worth waking someone up for, so a [`SlowQueryMonitor`](struct.SlowQueryMonitor.html)
+//! groups the entries by their [fingerprint](../sanitize/fn.query_fingerprint.html) and calls
+//! back once a query shape exceeded a latency threshold a number of times within a window.
+//!
+//! With the `aio` feature, the monitor can poll an async graph in the background with
+//! [`run`](struct.SlowQueryMonitor.html#method.run), or with
+//! [`spawn`](struct.SlowQueryMonitor.html#method.spawn) on tokio with the `tokio-comp` feature.
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use redisgraph::{slowlog::SlowQueryMonitor, Graph, RedisGraphResult};
+//! # fn example(mut graph: Graph) -> RedisGraphResult<()> {
+//! let mut monitor = SlowQueryMonitor::new(Duration::from_millis(200), 5, Duration::from_secs(300))
+//!     .on_alert(|alert| {
+//!         eprintln!("{} was slow {} times", alert.query, alert.occurrences);
+//!     });
+//! loop {
+//!     monitor.record(&graph.slowlog()?);
+//!     std::thread::sleep(Duration::from_secs(10));
+//! }
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+#[cfg(feature = "aio")]
+use std::future::Future;
+#[cfg(feature = "tokio-comp")]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redis::ConnectionLike;
+
+#[cfg(feature = "aio")]
+use crate::aio::AsyncGraph;
+use crate::{
+    sanitize::{query_fingerprint, sanitize_query},
+    Graph, RedisGraphResult,
+};
+
+type AlertCallback = Box<dyn FnMut(&SlowQueryAlert) + Send>;
+
+/// A query in the slowlog of a graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SlowlogEntry {
+    /// When the query was executed, with a resolution of one second.
+    pub timestamp: SystemTime,
+    /// The command the query was sent with, e.g. `GRAPH.QUERY`.
+    pub command: String,
+    /// The query, including its `CYPHER` parameter prefix.
+    pub query: String,
+    /// How long the server took to execute the query.
+    pub duration: Duration,
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Returns the entries of the slowlog of this graph.
+    pub fn slowlog(&mut self) -> RedisGraphResult<Vec<SlowlogEntry>> {
+        let mut cmd = redis::cmd(&self.command_names().slowlog);
+        cmd.arg(self.name());
+        let rows = cmd.query(self.connection())?;
+        parse_slowlog(rows)
+    }
+}
+
+#[cfg(feature = "aio")]
+impl AsyncGraph {
+    /// Returns the entries of the slowlog of this graph.
+    pub async fn slowlog(&mut self) -> RedisGraphResult<Vec<SlowlogEntry>> {
+        let mut cmd = redis::cmd(&self.command_names().slowlog);
+        cmd.arg(self.name());
+        let rows = cmd.query_async(self.connection()).await?;
+        parse_slowlog(rows)
+    }
+}
+
+// Every entry is an array of the timestamp in seconds, the command, the query
+// and the duration in milliseconds, all sent as strings.
+fn parse_slowlog(rows: Vec<(u64, String, String, f64)>) -> RedisGraphResult<Vec<SlowlogEntry>> {
+    rows.into_iter()
+        .map(|(timestamp, command, query, millis)| {
+            if !(millis >= 0.0 && millis.is_finite()) {
+                return server_type_error!(
+                    "expected non-negative slowlog duration, got {}",
+                    millis
+                );
+            }
+            Ok(SlowlogEntry {
+                timestamp: UNIX_EPOCH + Duration::from_secs(timestamp),
+                command,
+                query,
+                duration: Duration::from_secs_f64(millis / 1000.0),
+            })
+        })
+        .collect()
+}
+
+/// A query shape that was slow too often, passed to the callback of a
+/// [`SlowQueryMonitor`](struct.SlowQueryMonitor.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowQueryAlert {
+    /// The [fingerprint](../sanitize/fn.query_fingerprint.html) of the query shape.
+    pub fingerprint: u64,
+    /// One of the slow queries of this shape, [sanitized](../sanitize/fn.sanitize_query.html).
+    pub query: String,
+    /// How often queries of this shape exceeded the threshold within the window.
+    pub occurrences: usize,
+    /// The longest duration among these queries.
+    pub max_duration: Duration,
+}
+
+/// Calls back when queries of the same shape exceed a latency threshold too often,
+/// see the [module documentation](index.html).
+pub struct SlowQueryMonitor {
+    threshold: Duration,
+    occurrences: usize,
+    window: Duration,
+    // The timestamps and durations of the slow queries within the window, by fingerprint.
+    slow: HashMap<u64, VecDeque<(SystemTime, Duration)>>,
+    // The entries that were already recorded, since every poll returns them again.
+    seen: HashSet<SlowlogEntry>,
+    callbacks: Vec<AlertCallback>,
+}
+
+impl SlowQueryMonitor {
+    /// Creates a monitor that alerts once queries of the same shape took longer than
+    /// `threshold` at least `occurrences` times within `window`.
+    ///
+    /// After an alert, the shape needs `occurrences` further slow queries to alert again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `occurrences` is zero.
+    pub fn new(threshold: Duration, occurrences: usize, window: Duration) -> Self {
+        assert!(occurrences > 0, "occurrences must be greater than zero");
+        Self {
+            threshold,
+            occurrences,
+            window,
+            slow: HashMap::new(),
+            seen: HashSet::new(),
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Calls the given callback for every alert.
+    pub fn on_alert<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&SlowQueryAlert) + Send + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Records the given slowlog entries, skipping the ones that were already recorded,
+    /// and calls back for the query shapes that were slow too often.
+    pub fn record(&mut self, entries: &[SlowlogEntry]) {
+        let latest = match entries.iter().map(|entry| entry.timestamp).max() {
+            Some(latest) => latest,
+            None => return,
+        };
+        let window_start = latest.checked_sub(self.window).unwrap_or(UNIX_EPOCH);
+        self.seen.retain(|entry| entry.timestamp >= window_start);
+
+        let mut recorded = HashMap::new();
+        for entry in entries {
+            if entry.duration <= self.threshold
+                || entry.timestamp < window_start
+                || !self.seen.insert(entry.clone())
+            {
+                continue;
+            }
+            let fingerprint = query_fingerprint(&entry.query);
+            self.slow
+                .entry(fingerprint)
+                .or_default()
+                .push_back((entry.timestamp, entry.duration));
+            recorded.insert(fingerprint, &entry.query);
+        }
+
+        for slow in self.slow.values_mut() {
+            slow.retain(|(timestamp, _)| *timestamp >= window_start);
+        }
+        for (fingerprint, query) in recorded {
+            let slow = self.slow.get_mut(&fingerprint).unwrap();
+            if slow.len() < self.occurrences {
+                continue;
+            }
+            let alert = SlowQueryAlert {
+                fingerprint,
+                query: sanitize_query(query),
+                occurrences: slow.len(),
+                max_duration: slow.iter().map(|(_, duration)| *duration).max().unwrap(),
+            };
+            slow.clear();
+            for callback in &mut self.callbacks {
+                callback(&alert);
+            }
+        }
+        self.slow.retain(|_, slow| !slow.is_empty());
+    }
+
+    /// Polls the slowlog of the given graph every `interval`, waiting with the given
+    /// sleep function of the async runtime in between. Requires the `aio` feature.
+    ///
+    /// Never completes; drop the future to stop polling. Polls that fail,
+    /// e.g. because the connection broke, are skipped.
+    #[cfg(feature = "aio")]
+    pub async fn run<S, F>(mut self, mut graph: AsyncGraph, interval: Duration, mut sleep: S)
+    where
+        S: FnMut(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        loop {
+            self.poll(&mut graph).await;
+            sleep(interval).await;
+        }
+    }
+
+    /// Polls the slowlog of the given graph every `interval` on the tokio runtime.
+    /// Requires the `tokio-comp` feature.
+    ///
+    /// Polls that fail, e.g. because the connection broke, are skipped.
+    #[cfg(feature = "tokio-comp")]
+    pub fn spawn(mut self, mut graph: AsyncGraph, interval: Duration) -> SlowQueryHandle {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let task = tokio::spawn({
+            let stopped = Arc::clone(&stopped);
+            async move {
+                while !stopped.load(Ordering::SeqCst) {
+                    self.poll(&mut graph).await;
+                    tokio::time::delay_for(interval).await;
+                }
+                graph
+            }
+        });
+        SlowQueryHandle { stopped, task }
+    }
+
+    #[cfg(feature = "aio")]
+    async fn poll(&mut self, graph: &mut AsyncGraph) {
+        if let Ok(entries) = graph.slowlog().await {
+            self.record(&entries);
+        }
+    }
+}
+
+/// A [`SlowQueryMonitor`](struct.SlowQueryMonitor.html) polling on the tokio runtime,
+/// returned by [`SlowQueryMonitor::spawn`](struct.SlowQueryMonitor.html#method.spawn).
+#[cfg(feature = "tokio-comp")]
+pub struct SlowQueryHandle {
+    stopped: Arc<AtomicBool>,
+    task: tokio::task::JoinHandle<AsyncGraph>,
+}
+
+#[cfg(feature = "tokio-comp")]
+impl SlowQueryHandle {
+    /// Stops polling after the current interval and returns the graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a callback panicked.
+    pub async fn stop(self) -> AsyncGraph {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.task.await.expect("a slow query callback panicked")
+    }
+}
+
+impl fmt::Debug for SlowQueryMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SlowQueryMonitor")
+            .field("threshold", &self.threshold)
+            .field("occurrences", &self.occurrences)
+            .field("window", &self.window)
+            .field("shapes", &self.slow.len())
+            .field("callbacks", &self.callbacks.len())
+            .finish()
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use crate::{
+    result_set::{Column, Edge, Node, Scalar},
+    RedisString, ResultSet,
+};
+
+impl ResultSet {
+    /// Returns a deterministic, human-readable text form of the result set,
+    /// suitable for snapshot tests (e.g. with `insta`).
+    ///
+    /// Every row is printed on its own line. Properties are sorted by key, doubles are
+    /// rounded to 12 significant digits, and statistics containing execution times are left out,
+    /// so the snapshot only changes when the data does.
+    ///
+    /// ```
+    /// use redisgraph::{result_set::{Column, Scalar, Statistics}, ResultSet};
+    ///
+    /// let result_set = ResultSet {
+    ///     columns: vec![
+    ///         Column::Scalars(vec![Scalar::Integer(1)]),
+    ///         Column::Scalars(vec![Scalar::Double(0.1 + 0.2)]),
+    ///     ],
+    ///     statistics: Statistics(vec![
+    ///         "Nodes created: 1".to_string(),
+    ///         "Query internal execution time: 0.1 milliseconds".to_string(),
+    ///     ]),
+    /// };
+    /// assert_eq!(
+    ///     result_set.to_debug_snapshot(),
+    ///     "row 0: [1, 0.3]\nstatistics: [\"Nodes created: 1\"]\n"
+    /// );
+    /// ```
+    pub fn to_debug_snapshot(&self) -> String {
+        let mut snapshot = String::new();
+        for row_idx in 0..self.num_rows() {
+            let cells: Vec<String> = self
+                .columns
+                .iter()
+                .map(|column| match column {
+                    Column::Scalars(cells) => format_scalar(&cells[row_idx]),
+                    Column::Nodes(cells) => format_node(&cells[row_idx]),
+                    Column::Relations(cells) => format_edge(&cells[row_idx]),
+                })
+                .collect();
+            snapshot.push_str(&format!("row {}: [{}]\n", row_idx, cells.join(", ")));
+        }
+
+        let statistics: Vec<String> = self
+            .statistics
+            .0
+            .iter()
+            .filter(|entry| !entry.contains("execution time"))
+            .map(|entry| format!("{:?}", entry))
+            .collect();
+        snapshot.push_str(&format!("statistics: [{}]\n", statistics.join(", ")));
+
+        snapshot
+    }
+}
+
+fn format_scalar(scalar: &Scalar) -> String {
+    match scalar {
+        Scalar::Nil => "null".to_string(),
+        Scalar::Boolean(boolean) => boolean.to_string(),
+        Scalar::Integer(integer) => integer.to_string(),
+        Scalar::Double(double) => format_double(*double),
+        Scalar::String(string) => format_string(string),
+        Scalar::Array(elements) => {
+            let elements: Vec<String> = elements.iter().map(format_scalar).collect();
+            format!("[{}]", elements.join(", "))
+        }
+        Scalar::Edge(edge) => format_edge(edge),
+        Scalar::Node(node) => format_node(node),
+        Scalar::Path(path) => {
+            let mut parts = Vec::new();
+            for (i, node) in path.nodes.iter().enumerate() {
+                parts.push(format_node(node));
+                if let Some(edge) = path.edges.get(i) {
+                    parts.push(format_edge(edge));
+                }
+            }
+            format!("<{}>", parts.join(", "))
+        }
+        Scalar::Map(entries) => {
+            let mut entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", format_string(key), format_scalar(value)))
+                .collect();
+            entries.sort();
+            format!("{{{}}}", entries.join(", "))
+        }
+    }
+}
+
+fn format_double(double: f64) -> String {
+    if !double.is_finite() {
+        return double.to_string();
+    }
+    let rounded: f64 = format!("{:.11e}", double).parse().unwrap_or(double);
+    // Avoid printing `-0.0`.
+    let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+    format!("{:?}", rounded)
+}
+
+fn format_string(string: &RedisString) -> String {
+    format!("{:?}", String::from_utf8_lossy(&string.0))
+}
+
+fn format_properties(properties: &HashMap<RedisString, Scalar>) -> String {
+    if properties.is_empty() {
+        return String::new();
+    }
+    let mut properties: Vec<(&RedisString, &Scalar)> = properties.iter().collect();
+    properties.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+    let properties: Vec<String> = properties
+        .into_iter()
+        .map(|(key, value)| {
+            format!(
+                "{}: {}",
+                String::from_utf8_lossy(&key.0),
+                format_scalar(value)
+            )
+        })
+        .collect();
+    format!(" {{{}}}", properties.join(", "))
+}
+
+fn format_node(node: &Node) -> String {
+    let labels: String = node
+        .labels
+        .iter()
+        .map(|label| format!(":{}", String::from_utf8_lossy(&label.0)))
+        .collect();
+    format!("({}{})", labels, format_properties(&node.properties))
+}
+
+fn format_edge(edge: &Edge) -> String {
+    format!(
+        "[:{}{}]",
+        String::from_utf8_lossy(&edge.type_name.0),
+        format_properties(&edge.properties)
+    )
+}
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use redis::ConnectionLike;
+
+use crate::{labels::Labels, params::CypherValue, ttl::to_millis, Graph, RedisGraphResult};
+
+/// The property marking a node as deleted while
+/// [soft deletion](../graph/struct.Graph.html#method.set_soft_delete) is enabled.
+///
+/// Holds the time of deletion in milliseconds since the Unix epoch.
+pub const DELETED_AT: &str = "deleted_at";
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Deletes the node with the given ID along with its relationships.
+    ///
+    /// With [soft deletion](#method.set_soft_delete) enabled, the node is only marked as deleted
+    /// and its relationships are kept.
+    pub fn delete_node(&mut self, node_id: u64) -> RedisGraphResult<()> {
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), CypherValue::Integer(node_id as i64));
+        if self.soft_delete() {
+            params.insert("now".to_string(), to_millis(SystemTime::now()).into());
+            self.mutate_with_params(
+                &format!(
+                    "MATCH (n) WHERE id(n) = $id AND n.{0} IS NULL SET n.{0} = $now",
+                    DELETED_AT
+                ),
+                &params,
+            )
+        } else {
+            self.mutate_with_params("MATCH (n) WHERE id(n) = $id DETACH DELETE n", &params)
+        }
+    }
+
+    /// Removes all nodes with the given labels that are marked as deleted, along with their
+    /// relationships, and returns the number of removed nodes.
+    ///
+    /// Works regardless of whether [soft deletion](#method.set_soft_delete) is enabled.
+    pub fn purge_deleted<L: Into<Labels>>(&mut self, labels: L) -> RedisGraphResult<usize> {
+        let labels: Labels = labels.into();
+        let statistics = self.mutate_with_statistics(&format!(
+            "MATCH (n{}) WHERE n.{} IS NOT NULL DETACH DELETE n",
+            labels, DELETED_AT
+        ))?;
+        Ok(statistics.count("Nodes deleted"))
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+
+use redis::ConnectionLike;
+
+use crate::{
+    params::escape_identifier,
+    reference::NodeRef,
+    result_set::{Edge, Node, Scalar},
+    Graph, RedisGraphError, RedisGraphResult, RedisString,
+};
+
+/// The number of nodes per label and relationships per type inspected by
+/// [`Graph::sample_statistics`](../graph/struct.Graph.html#method.sample_statistics).
+pub const DEFAULT_SAMPLE_SIZE: usize = 1000;
+
+/// Statistics about the contents of a graph, returned by
+/// [`Graph::sample_statistics`](../graph/struct.Graph.html#method.sample_statistics).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphStats {
+    /// Statistics for every label, ordered by label ID.
+    pub labels: Vec<EntityStats>,
+    /// Statistics for every relationship type, ordered by type ID.
+    pub relationship_types: Vec<EntityStats>,
+}
+
+/// Statistics about the nodes with a label or the relationships of a type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityStats {
+    /// The label or relationship type.
+    pub name: String,
+    /// The exact number of nodes or relationships.
+    pub count: u64,
+    /// The number of nodes or relationships the property presence was computed from.
+    pub sampled: usize,
+    /// For every property key found in the sample, the fraction of sampled entities
+    /// that have the property, between 0 and 1.
+    pub property_presence: BTreeMap<String, f64>,
+}
+
+/// The highest-degree nodes for a relationship type, returned by
+/// [`Graph::hotspots`](../graph/struct.Graph.html#method.hotspots).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeHotspots {
+    /// The relationship type.
+    pub rel_type: String,
+    /// The nodes with the most relationships of the type, highest degree first and by ID
+    /// for equal degrees.
+    pub nodes: Vec<Hotspot>,
+}
+
+/// A node with many relationships of a type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotspot {
+    /// The node.
+    pub node: NodeRef,
+    /// The number of relationships of the type the node has, in either direction.
+    pub degree: u64,
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Collects statistics about the graph that help reasoning about query plans:
+    /// the number of nodes per label, the number of relationships per type and how often
+    /// properties are present, inspecting up to [`DEFAULT_SAMPLE_SIZE`](../stats/constant.DEFAULT_SAMPLE_SIZE.html)
+    /// entities per label or type.
+    pub fn sample_statistics(&mut self) -> RedisGraphResult<GraphStats> {
+        self.sample_statistics_with_size(DEFAULT_SAMPLE_SIZE)
+    }
+
+    /// Same as [`sample_statistics`](#method.sample_statistics), but inspects up to
+    /// `sample_size` entities per label or type.
+    ///
+    /// Labels and relationship types are re-read from the database first, unless the graph
+    /// uses a [static schema](#method.use_static_schema).
+    pub fn sample_statistics_with_size(
+        &mut self,
+        sample_size: usize,
+    ) -> RedisGraphResult<GraphStats> {
+        match self.update_labels() {
+            Ok(()) | Err(RedisGraphError::LabelNotFound) => {}
+            Err(error) => return Err(error),
+        }
+        match self.update_relationship_types() {
+            Ok(()) | Err(RedisGraphError::RelationshipTypeNotFound) => {}
+            Err(error) => return Err(error),
+        }
+
+        let mut labels = Vec::new();
+        for label in to_strings(self.labels()) {
+            let pattern = format!("(n:{})", escape_identifier(&label));
+            let count: u64 = self.query(&format!("MATCH {} RETURN count(n)", pattern))?;
+            let sample: Vec<Node> =
+                self.query(&format!("MATCH {} RETURN n LIMIT {}", pattern, sample_size))?;
+            let properties = sample.iter().map(|node| &node.properties);
+            labels.push(entity_stats(label, count, sample.len(), properties));
+        }
+
+        let mut relationship_types = Vec::new();
+        for rel_type in to_strings(self.relationship_types()) {
+            let pattern = format!("()-[r:{}]->()", escape_identifier(&rel_type));
+            let count: u64 = self.query(&format!("MATCH {} RETURN count(r)", pattern))?;
+            let sample: Vec<Edge> =
+                self.query(&format!("MATCH {} RETURN r LIMIT {}", pattern, sample_size))?;
+            let properties = sample.iter().map(|edge| &edge.properties);
+            relationship_types.push(entity_stats(rel_type, count, sample.len(), properties));
+        }
+
+        Ok(GraphStats {
+            labels,
+            relationship_types,
+        })
+    }
+
+    /// Returns the `k` nodes with the highest degree for every relationship type,
+    /// to find supernodes that slow down traversals.
+    ///
+    /// Relationships are counted in either direction. Every relationship type is scanned
+    /// completely, so this is expensive on large graphs.
+    pub fn hotspots(&mut self, k: usize) -> RedisGraphResult<Vec<TypeHotspots>> {
+        match self.update_relationship_types() {
+            Ok(()) | Err(RedisGraphError::RelationshipTypeNotFound) => {}
+            Err(error) => return Err(error),
+        }
+
+        let mut hotspots = Vec::new();
+        for rel_type in to_strings(self.relationship_types()) {
+            let degrees: Vec<(u64, u64)> = self.query(&format!(
+                "MATCH (n)-[r:{}]-() RETURN id(n) AS node, count(r) AS degree \
+                 ORDER BY degree DESC, node LIMIT {}",
+                escape_identifier(&rel_type),
+                k
+            ))?;
+            let nodes = degrees
+                .into_iter()
+                .map(|(id, degree)| Hotspot {
+                    node: NodeRef::new(self.name().to_string(), id),
+                    degree,
+                })
+                .collect();
+            hotspots.push(TypeHotspots { rel_type, nodes });
+        }
+        Ok(hotspots)
+    }
+}
+
+fn to_strings(names: &[RedisString]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| String::from_utf8_lossy(&name.0).into_owned())
+        .collect()
+}
+
+fn entity_stats<'a>(
+    name: String,
+    count: u64,
+    sampled: usize,
+    properties: impl Iterator<Item = &'a HashMap<RedisString, Scalar>>,
+) -> EntityStats {
+    let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+    for properties in properties {
+        for key in properties.keys() {
+            *occurrences
+                .entry(String::from_utf8_lossy(&key.0).into_owned())
+                .or_default() += 1;
+        }
+    }
+    EntityStats {
+        name,
+        count,
+        sampled,
+        property_presence: occurrences
+            .into_iter()
+            .map(|(key, occurrences)| (key, occurrences as f64 / sampled as f64))
+            .collect(),
+    }
+}
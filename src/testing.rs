@@ -0,0 +1,180 @@
+//! Helpers for integration tests against a real database.
+
+#[cfg(feature = "testcontainers")]
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use redis::Connection;
+#[cfg(feature = "testcontainers")]
+use testcontainers::{
+    core::{IntoContainerPort, WaitFor},
+    runners::SyncRunner,
+    Container, GenericImage,
+};
+
+use crate::Graph;
+
+/// Connects to the test database at the URI in the `TEST_REDIS_URI` environment variable,
+/// `redis://127.0.0.1` by default.
+///
+/// # Panics
+///
+/// Panics if the database can't be reached.
+pub fn test_connection() -> Connection {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    redis::Client::open(uri.as_str())
+        .and_then(|client| client.get_connection())
+        .expect("failed to connect to the test database")
+}
+
+/// Runs the given closure against a temporary graph in the [test database](fn.test_connection.html).
+///
+/// Every call uses a graph with a [unique name](fn.unique_graph_name.html), so tests using this
+/// can run in parallel, even across processes. The graph is deleted afterwards, even if the closure panics.
+///
+/// ```no_run
+/// use redisgraph::testing::with_test_graph;
+///
+/// with_test_graph(|graph| {
+///     graph.mutate("CREATE (:Person)").unwrap();
+///     let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+///     assert_eq!(count, 1);
+/// });
+/// ```
+pub fn with_test_graph<F, R>(test: F) -> R
+where
+    F: FnOnce(&mut Graph) -> R,
+{
+    run_graph_test("test_graph", None, test)
+}
+
+/// Runs the given test against a temporary graph. Used by the `graph_test` attribute macro.
+#[doc(hidden)]
+pub fn run_graph_test<F, R>(test_name: &str, fixture: Option<&str>, test: F) -> R
+where
+    F: FnOnce(&mut Graph) -> R,
+{
+    let mut graph = Graph::open(test_connection(), unique_graph_name(test_name))
+        .expect("failed to open the test graph");
+
+    // Delete the graph even if the test panics, and only then resume the panic.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if let Some(fixture) = fixture {
+            graph
+                .execute_script(fixture)
+                .expect("failed to execute the fixture");
+        }
+        test(&mut graph)
+    }));
+    let deleted = graph.delete();
+    match result {
+        Ok(value) => {
+            deleted.expect("failed to delete the test graph");
+            value
+        }
+        Err(panic) => panic::resume_unwind(panic),
+    }
+}
+
+/// Returns a graph name starting with the given prefix that is unique across processes,
+/// e.g. `my_test_4242_1600000000000000000_0`.
+///
+/// Characters in the prefix other than ASCII letters and digits are replaced with underscores.
+pub fn unique_graph_name(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let prefix: String = prefix
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos())
+        .unwrap_or(0);
+    format!(
+        "{}_{}_{}_{}",
+        prefix,
+        std::process::id(),
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// The image started by [`redisgraph_container`](fn.redisgraph_container.html).
+#[cfg(feature = "testcontainers")]
+pub const DEFAULT_IMAGE: (&str, &str) = ("redislabs/redisgraph", "latest");
+
+/// A graph in a database running in a Docker container.
+///
+/// Dereferences to the [`Graph`](../graph/struct.Graph.html). The container is stopped and removed when this is dropped.
+#[cfg(feature = "testcontainers")]
+pub struct ContainerGraph {
+    // Dropped before the container, so the connection is closed first.
+    graph: Graph,
+    container: Container<GenericImage>,
+}
+
+#[cfg(feature = "testcontainers")]
+impl ContainerGraph {
+    /// Returns the port the database in the container can be reached at on the host.
+    pub fn host_port(&self) -> u16 {
+        self.container
+            .get_host_port_ipv4(6379.tcp())
+            .expect("failed to get the port of the RedisGraph container")
+    }
+}
+
+#[cfg(feature = "testcontainers")]
+impl Deref for ContainerGraph {
+    type Target = Graph;
+
+    fn deref(&self) -> &Graph {
+        &self.graph
+    }
+}
+
+#[cfg(feature = "testcontainers")]
+impl DerefMut for ContainerGraph {
+    fn deref_mut(&mut self) -> &mut Graph {
+        &mut self.graph
+    }
+}
+
+/// Starts a RedisGraph Docker container and opens a graph named `test_graph` in it.
+///
+/// # Panics
+///
+/// Panics if the container can't be started or the database can't be reached.
+#[cfg(feature = "testcontainers")]
+pub fn redisgraph_container() -> ContainerGraph {
+    redisgraph_container_with_image(DEFAULT_IMAGE.0, DEFAULT_IMAGE.1)
+}
+
+/// Same as [`redisgraph_container`](fn.redisgraph_container.html), but starts the given image,
+/// e.g. `("falkordb/falkordb", "latest")`.
+///
+/// The image must run a Redis server with the graph module loaded on port 6379.
+#[cfg(feature = "testcontainers")]
+pub fn redisgraph_container_with_image(name: &str, tag: &str) -> ContainerGraph {
+    let container = GenericImage::new(name, tag)
+        .with_exposed_port(6379.tcp())
+        .with_wait_for(WaitFor::message_on_stdout("Ready to accept connections"))
+        .start()
+        .expect("failed to start the RedisGraph container");
+
+    let host = container
+        .get_host()
+        .expect("failed to get the host of the RedisGraph container");
+    let port = container
+        .get_host_port_ipv4(6379.tcp())
+        .expect("failed to get the port of the RedisGraph container");
+    let conn = redis::Client::open(format!("redis://{}:{}", host, port))
+        .and_then(|client| client.get_connection())
+        .expect("failed to connect to the RedisGraph container");
+    let graph = Graph::open(conn, "test_graph".to_string())
+        .expect("failed to open a graph in the RedisGraph container");
+
+    ContainerGraph { graph, container }
+}
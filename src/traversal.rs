@@ -0,0 +1,194 @@
+//! A small traversal DSL in the style of TinkerPop's Gremlin, compiled to Cypher.
+//!
+//! ```
+//! use redisgraph::traversal::v;
+//!
+//! let query = v()
+//!     .has_label("Person")
+//!     .has("name", "Alice")
+//!     .out("KNOWS")
+//!     .limit(10)
+//!     .to_query()
+//!     .unwrap();
+//! assert_eq!(
+//!     query.query(),
+//!     "MATCH (`v0`:`Person`)-[:`KNOWS`]->(`v1`) WHERE `v0`.`name` = $p0 RETURN `v1` LIMIT 10"
+//! );
+//! ```
+//!
+//! Execute traversals with [`Graph::traverse`](../graph/struct.Graph.html#method.traverse).
+
+use redis::ConnectionLike;
+
+use crate::{
+    assignments::FromTable,
+    params::CypherValue,
+    pattern::{node, Direction, Pattern},
+    query::{param, BuiltQuery, Expr, QueryBuilder},
+    Graph, RedisGraphResult,
+};
+
+/// A traversal started with [`v`](fn.v.html).
+///
+/// Every step after the start continues from the vertices reached by the previous steps.
+/// Vertex `n` of the traversal is bound to the variable `v{n}` and the `n`-th value passed
+/// to [`has`](#method.has) to the parameter `p{n}`.
+#[derive(Debug, Clone)]
+pub struct Traversal {
+    pattern: Pattern,
+    vertices: usize,
+    conditions: Vec<Expr>,
+    params: Vec<CypherValue>,
+    dedup: bool,
+    skip: Option<u64>,
+    limit: Option<u64>,
+    projection: Projection,
+}
+
+#[derive(Debug, Clone)]
+enum Projection {
+    Vertices,
+    Values(String),
+    Count,
+}
+
+/// Starts a traversal at all vertices of the graph, like `g.V()`.
+pub fn v() -> Traversal {
+    Traversal {
+        pattern: node("v0"),
+        vertices: 1,
+        conditions: Vec::new(),
+        params: Vec::new(),
+        dedup: false,
+        skip: None,
+        limit: None,
+        projection: Projection::Vertices,
+    }
+}
+
+impl Traversal {
+    /// Only keeps vertices with the given label.
+    pub fn has_label(mut self, label: &str) -> Self {
+        self.pattern = self.pattern.label(label);
+        self
+    }
+
+    /// Only keeps vertices whose given property equals the given value.
+    pub fn has<V: Into<CypherValue>>(mut self, key: &str, value: V) -> Self {
+        let name = format!("p{}", self.params.len());
+        self.conditions
+            .push(Expr::prop(&self.current(), key).eq(param(&name)));
+        self.params.push(value.into());
+        self
+    }
+
+    /// Only keeps vertices that don't have the given property.
+    pub fn has_not(mut self, key: &str) -> Self {
+        self.conditions
+            .push(Expr::prop(&self.current(), key).is_null());
+        self
+    }
+
+    /// Moves to the vertices at the end of outgoing relationships of the given type.
+    pub fn out(self, rel_type: &str) -> Self {
+        self.hop(rel_type, Direction::Outgoing)
+    }
+
+    /// Moves to the vertices at the start of incoming relationships of the given type.
+    pub fn in_(self, rel_type: &str) -> Self {
+        self.hop(rel_type, Direction::Incoming)
+    }
+
+    /// Moves to the vertices on the other side of relationships of the given type
+    /// in either direction.
+    pub fn both(self, rel_type: &str) -> Self {
+        self.hop(rel_type, Direction::Both)
+    }
+
+    /// Removes duplicate results, i.e. emits `RETURN DISTINCT`.
+    pub fn dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    /// Skips the given number of results.
+    pub fn skip(mut self, skip: u64) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Returns at most the given number of results.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns the given property of the vertices at the end of the traversal
+    /// instead of the vertices themselves.
+    pub fn values(mut self, key: &str) -> Self {
+        self.projection = Projection::Values(key.to_string());
+        self
+    }
+
+    /// Returns the number of vertices at the end of the traversal instead of the vertices themselves.
+    pub fn count(mut self) -> Self {
+        self.projection = Projection::Count;
+        self
+    }
+
+    /// Compiles the traversal to Cypher.
+    pub fn to_query(&self) -> RedisGraphResult<BuiltQuery> {
+        let current = self.current();
+        let mut builder = QueryBuilder::new().match_pattern(self.pattern.clone());
+        for condition in &self.conditions {
+            builder = builder.filter(condition.clone());
+        }
+        builder = match &self.projection {
+            Projection::Vertices => builder.returns(Expr::var(&current)),
+            Projection::Values(key) => builder.returns(Expr::prop(&current, key)),
+            Projection::Count => builder.returns(Expr::var(&current).count()),
+        };
+        if self.dedup {
+            builder = builder.distinct();
+        }
+        if let Some(skip) = self.skip {
+            builder = builder.skip(skip);
+        }
+        if let Some(limit) = self.limit {
+            builder = builder.limit(limit);
+        }
+        for (i, value) in self.params.iter().enumerate() {
+            builder = builder.param(&format!("p{}", i), value.clone());
+        }
+        builder.build()
+    }
+
+    fn current(&self) -> String {
+        format!("v{}", self.vertices - 1)
+    }
+
+    fn hop(mut self, rel_type: &str, direction: Direction) -> Self {
+        let next = format!("v{}", self.vertices);
+        self.pattern = self.pattern.rel(rel_type, direction).node(&next);
+        self.vertices += 1;
+        self
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes a [`Traversal`](../traversal/struct.Traversal.html) and returns its results.
+    ///
+    /// ```no_run
+    /// # use redisgraph::{result_set::Node, traversal::v, Graph, RedisGraphResult};
+    /// # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+    /// let friends: Vec<Node> = graph.traverse(&v().has_label("Person").out("KNOWS"))?;
+    /// let names: Vec<String> = graph.traverse(&v().has_label("Person").values("name"))?;
+    /// let people: u64 = graph.traverse(&v().has_label("Person").count())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn traverse<T: FromTable>(&mut self, traversal: &Traversal) -> RedisGraphResult<T> {
+        let query = traversal.to_query()?;
+        self.query_with_params(query.query(), query.params())
+    }
+}
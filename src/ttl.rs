@@ -0,0 +1,92 @@
+//! Helpers for expiring nodes, which RedisGraph doesn't support natively.
+//!
+//! Expiring nodes store the time they expire at in the [`EXPIRES_AT`](constant.EXPIRES_AT.html)
+//! property, in milliseconds since the Unix epoch, and are deleted by calling
+//! [`Graph::purge_expired`](../graph/struct.Graph.html#method.purge_expired) periodically.
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use redisgraph::{props::Props, Graph, RedisGraphResult};
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! let session = Props::new()
+//!     .set("token", "abc")
+//!     .expires_in(Duration::from_secs(3600));
+//! graph.create_node("Session", &session)?;
+//!
+//! // Later, e.g. from a scheduled job:
+//! let purged = graph.purge_expired("Session")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redis::ConnectionLike;
+
+use crate::{labels::Labels, params::CypherValue, props::Props, Graph, RedisGraphResult};
+
+/// The property holding the expiration time of a node.
+pub const EXPIRES_AT: &str = "expires_at";
+
+/// The number of nodes deleted per query by [`Graph::purge_expired`](../graph/struct.Graph.html#method.purge_expired).
+pub const PURGE_BATCH_SIZE: usize = 1000;
+
+/// Returns the expiration time for something that expires after `ttl`,
+/// in milliseconds since the Unix epoch.
+pub fn expires_at(ttl: Duration) -> i64 {
+    to_millis(SystemTime::now() + ttl)
+}
+
+// Times before the epoch are negative, rather than all mapped to the epoch itself.
+pub(crate) fn to_millis(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => since_epoch.as_millis() as i64,
+        Err(error) => -(error.duration().as_millis() as i64),
+    }
+}
+
+impl Props {
+    /// Sets the [`EXPIRES_AT`](../ttl/constant.EXPIRES_AT.html) property so the node expires after `ttl`.
+    pub fn expires_in(self, ttl: Duration) -> Self {
+        self.set(EXPIRES_AT, expires_at(ttl))
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Sets the node with the given ID to expire after `ttl`, replacing any earlier expiration time.
+    pub fn set_expiry(&mut self, node_id: u64, ttl: Duration) -> RedisGraphResult<()> {
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), CypherValue::Integer(node_id as i64));
+        params.insert(EXPIRES_AT.to_string(), expires_at(ttl).into());
+        self.mutate_with_params(
+            &format!("MATCH (n) WHERE id(n) = $id SET n.{0} = ${0}", EXPIRES_AT),
+            &params,
+        )
+    }
+
+    /// Deletes all nodes with the given labels that have expired, along with their relationships,
+    /// and returns the number of deleted nodes.
+    ///
+    /// Nodes are deleted in batches of [`PURGE_BATCH_SIZE`](../ttl/constant.PURGE_BATCH_SIZE.html),
+    /// so the database isn't blocked for long. Expiration times are compared to the local clock.
+    pub fn purge_expired<L: Into<Labels>>(&mut self, labels: L) -> RedisGraphResult<usize> {
+        let labels: Labels = labels.into();
+        let mut params = HashMap::new();
+        params.insert("now".to_string(), to_millis(SystemTime::now()).into());
+        let query = format!(
+            "MATCH (n{}) WHERE n.{} <= $now WITH n LIMIT {} DETACH DELETE n",
+            labels, EXPIRES_AT, PURGE_BATCH_SIZE
+        );
+
+        let mut purged = 0;
+        loop {
+            let query = self.prepare_query(&query, &params)?;
+            let deleted = self.mutate_with_statistics(&query)?.count("Nodes deleted");
+            purged += deleted;
+            if deleted < PURGE_BATCH_SIZE {
+                return Ok(purged);
+            }
+        }
+    }
+}
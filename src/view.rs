@@ -0,0 +1,192 @@
+//! Materialized views, i.e. query results stored in the graph for cheap repeated reads.
+//!
+//! Every row of a view is stored as a node with the [`VIEW_LABEL`](constant.VIEW_LABEL.html) label,
+//! the returned columns as properties and the view name in the `_view` property. Only scalar
+//! columns can be stored, so return properties instead of nodes or relationships.
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use redisgraph::{Graph, RedisGraphResult};
+//! # fn example(graph: &mut Graph) -> RedisGraphResult<()> {
+//! let mut view = graph.materialize(
+//!     "friend_counts",
+//!     "MATCH (p:Person)-[:KNOWS]->(f) RETURN p.name AS name, count(f) AS friends",
+//!     Duration::from_secs(300),
+//! )?;
+//!
+//! // Reads the stored rows, and re-runs the query first if they are older than five minutes.
+//! let counts: Vec<(String, i64)> = graph.read_view(&mut view)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::{Duration, Instant, SystemTime};
+
+use redis::ConnectionLike;
+
+use crate::{
+    assignments::FromTable,
+    params::{escape_identifier, CypherValue},
+    result_set::Column,
+    ttl::to_millis,
+    Graph, RedisGraphResult,
+};
+
+/// The label of the nodes storing the rows of materialized views.
+pub const VIEW_LABEL: &str = "_View";
+
+/// A handle to a view created with [`Graph::materialize`](../graph/struct.Graph.html#method.materialize).
+#[derive(Debug, Clone)]
+pub struct MaterializedView {
+    name: String,
+    query: String,
+    columns: Vec<String>,
+    refresh_interval: Duration,
+    refreshed_at: Instant,
+    generation: i64,
+}
+
+impl MaterializedView {
+    /// Returns the name of the view.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the query the view stores the result of.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the names of the stored columns.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the time the view was last refreshed through this handle.
+    pub fn refreshed_at(&self) -> Instant {
+        self.refreshed_at
+    }
+
+    /// Returns `true` if the view was refreshed longer than its refresh interval ago.
+    pub fn is_stale(&self) -> bool {
+        self.refreshed_at.elapsed() >= self.refresh_interval
+    }
+}
+
+impl<C: ConnectionLike> Graph<C> {
+    /// Executes the query and stores its result as the view with the given name,
+    /// replacing an earlier result stored under that name.
+    ///
+    /// Reading the view with [`read_view`](#method.read_view) re-runs the query once the result
+    /// is older than `refresh_interval`. Refreshing on a schedule instead is a matter of calling
+    /// [`refresh_view`](#method.refresh_view) periodically.
+    pub fn materialize(
+        &mut self,
+        view_name: &str,
+        query: &str,
+        refresh_interval: Duration,
+    ) -> RedisGraphResult<MaterializedView> {
+        let mut view = MaterializedView {
+            name: view_name.to_string(),
+            query: query.to_string(),
+            columns: Vec::new(),
+            refresh_interval,
+            refreshed_at: Instant::now(),
+            generation: 0,
+        };
+        self.refresh_view(&mut view)?;
+        Ok(view)
+    }
+
+    /// Re-runs the query of the view and replaces the stored result.
+    ///
+    /// The new rows are created before the old ones are deleted, and reads only return
+    /// the newest rows, so concurrent readers never see a partially refreshed view.
+    pub fn refresh_view(&mut self, view: &mut MaterializedView) -> RedisGraphResult<()> {
+        let (columns, result_set) = self.query_with_column_names(&view.query)?;
+        if columns.is_empty() {
+            return client_type_error!(
+                "failed to materialize view {:?}: the query doesn't return anything",
+                view.name
+            );
+        }
+
+        let generation = to_millis(SystemTime::now()).max(view.generation + 1);
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), view.name.as_str().into());
+        params.insert("generation".to_string(), CypherValue::Integer(generation));
+        let mut create_params = params.clone();
+        let mut properties = Vec::new();
+        for (idx, (name, column)) in columns.iter().zip(result_set.columns).enumerate() {
+            let values = match column {
+                Column::Scalars(scalars) => scalars
+                    .into_iter()
+                    .map(CypherValue::try_from)
+                    .collect::<RedisGraphResult<Vec<CypherValue>>>()?,
+                _ => {
+                    return client_type_error!(
+                        "failed to materialize view {:?}: column {:?} doesn't contain scalars",
+                        view.name,
+                        name
+                    )
+                }
+            };
+            create_params.insert(format!("c{}", idx), CypherValue::Array(values));
+            properties.push(format!(", {}: $c{}[i]", escape_identifier(name), idx));
+        }
+
+        let create = format!(
+            "UNWIND range(0, size($c0) - 1) AS i \
+             CREATE (:{} {{_view: $name, _generation: $generation, _row: i{}}})",
+            VIEW_LABEL,
+            properties.concat()
+        );
+        self.mutate(&self.prepare_query(&create, &create_params)?)?;
+        let delete = format!(
+            "MATCH (r:{} {{_view: $name}}) WHERE r._generation < $generation DELETE r",
+            VIEW_LABEL
+        );
+        self.mutate(&self.prepare_query(&delete, &params)?)?;
+
+        view.columns = columns;
+        view.refreshed_at = Instant::now();
+        view.generation = generation;
+        Ok(())
+    }
+
+    /// Returns the stored rows of the view in their original order,
+    /// refreshing it first if it is [stale](../view/struct.MaterializedView.html#method.is_stale).
+    pub fn read_view<T: FromTable>(&mut self, view: &mut MaterializedView) -> RedisGraphResult<T> {
+        if view.is_stale() {
+            self.refresh_view(view)?;
+        }
+
+        let returns = view
+            .columns
+            .iter()
+            .map(|name| format!("r.{}", escape_identifier(name)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let query = format!(
+            "MATCH (r:{0} {{_view: $name}}) WITH max(r._generation) AS generation \
+             MATCH (r:{0} {{_view: $name}}) WHERE r._generation = generation \
+             RETURN {1} ORDER BY r._row",
+            VIEW_LABEL, returns
+        );
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), view.name.as_str().into());
+        self.query_with_params(&query, &params)
+    }
+
+    /// Deletes the stored rows of the view.
+    pub fn drop_view(&mut self, view: MaterializedView) -> RedisGraphResult<()> {
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), view.name.as_str().into());
+        self.mutate_with_params(
+            &format!("MATCH (r:{} {{_view: $name}}) DELETE r", VIEW_LABEL),
+            &params,
+        )
+    }
+}
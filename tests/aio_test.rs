@@ -0,0 +1,106 @@
+#![cfg(feature = "tokio-comp")]
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use redisgraph::{
+    aio::AsyncGraph, commands::CommandNames, params::CypherValue, slowlog::SlowQueryMonitor,
+    testing::unique_graph_name,
+};
+
+async fn open_test_graph() -> AsyncGraph {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    let client = redis::Client::open(uri.as_str()).unwrap();
+    AsyncGraph::connect(&client, unique_graph_name("aio"))
+        .await
+        .expect("failed to connect to the test database")
+}
+
+fn assert_send<F: Future + Send>(_future: F) {}
+
+#[test]
+fn test_futures_are_send() {
+    // Never polled, only checks that the futures can be spawned on a multi-threaded runtime.
+    let _ = || async {
+        let mut graph = open_test_graph().await;
+        assert_send(graph.query::<i64>("RETURN 1"));
+        assert_send(graph.mutate("CREATE ()"));
+        assert_send(graph.delete());
+    };
+}
+
+#[tokio::test]
+async fn test_async_graph() {
+    let mut graph = open_test_graph().await;
+    graph
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})-[:RIDES]->(:Team {name: 'Yamaha'})")
+        .await
+        .unwrap();
+
+    let (names, statistics): (Vec<String>, _) = graph
+        .query_with_statistics("MATCH (r:Rider)-[:RIDES]->(t:Team) RETURN r.name")
+        .await
+        .unwrap();
+    assert_eq!(names, vec!["Valentino Rossi"]);
+    assert!(statistics
+        .0
+        .iter()
+        .any(|stat| stat.contains("execution time")));
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), CypherValue::from("Yamaha"));
+    let team: redisgraph::result_set::Node = graph
+        .query_with_params("MATCH (t:Team {name: $name}) RETURN t", &params)
+        .await
+        .unwrap();
+    assert_eq!(team.labels, vec!["Team".to_string().into()]);
+
+    assert!(graph.mutate("MATCH (").await.is_err());
+    graph.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_detect_command_names_async() {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    let client = redis::Client::open(uri.as_str()).unwrap();
+    let mut conn = client
+        .get_multiplexed_tokio_connection()
+        .await
+        .expect("failed to connect to the test database");
+    let names = CommandNames::detect_async(&mut conn, &["NOT_A_MODULE", "GRAPH"])
+        .await
+        .unwrap();
+    assert_eq!(names, Some(CommandNames::default()));
+
+    let graph = AsyncGraph::open_with_commands(conn, unique_graph_name("aio"), names.unwrap())
+        .await
+        .unwrap();
+    graph.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_blocking_decode() {
+    let mut graph = open_test_graph().await;
+    graph.set_blocking_decode_threshold(Some(0));
+    assert_eq!(graph.blocking_decode_threshold(), Some(0));
+    graph
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+        .await
+        .unwrap();
+    // The label and property key names are fetched after the first attempt to decode.
+    let rider: redisgraph::result_set::Node =
+        graph.query("MATCH (r:Rider) RETURN r").await.unwrap();
+    assert_eq!(rider.labels, vec!["Rider".to_string().into()]);
+    graph.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_slow_query_monitor_spawn() {
+    let graph = open_test_graph().await;
+    let monitor = SlowQueryMonitor::new(Duration::from_secs(60), 1, Duration::from_secs(60))
+        .on_alert(|alert| panic!("unexpected alert for {}", alert.query));
+    let handle = monitor.spawn(graph, Duration::from_millis(10));
+    tokio::time::delay_for(Duration::from_millis(50)).await;
+    handle.stop().await.delete().await.unwrap();
+}
@@ -1,5 +1,7 @@
 mod common;
 
+use std::collections::{BTreeMap, HashMap};
+
 use redisgraph::RedisGraphResult;
 use serial_test::serial;
 
@@ -64,3 +66,66 @@ fn test_out_of_bounds() {
         assert!(out_of_bounds_result.is_err());
     });
 }
+
+#[test]
+#[serial]
+fn test_btree_map() {
+    with_graph(|graph| {
+        graph
+            .mutate("CREATE ({ id: 2, name: 'two' }), ({ id: 1, name: 'one' })")
+            .unwrap();
+        let map: BTreeMap<i64, String> = graph.query("MATCH (n) RETURN n.id, n.name").unwrap();
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![(1, "one".to_string()), (2, "two".to_string())]
+        );
+    });
+}
+
+#[test]
+#[serial]
+fn test_hash_map() {
+    with_graph(|graph| {
+        graph
+            .mutate("CREATE ({ id: 1, name: 'foo' }), ({ id: 2, name: 'bar' })")
+            .unwrap();
+        let map: HashMap<i64, String> = graph.query("MATCH (n) RETURN n.id, n.name").unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&2], "bar");
+    });
+}
+
+#[test]
+#[serial]
+fn test_hash_map_column_count() {
+    with_graph(|graph| {
+        let map_result: RedisGraphResult<HashMap<i64, String>> = graph.query("RETURN 42");
+        assert!(map_result.is_err());
+    });
+}
+
+#[test]
+#[serial]
+fn test_array() {
+    with_graph(|graph| {
+        let array: [f64; 3] = graph.query("RETURN 0.5, 1.0, -1.0").unwrap();
+        assert_eq!(array, [0.5, 1.0, -1.0]);
+        let out_of_bounds_result: RedisGraphResult<[f64; 2]> = graph.query("RETURN 0.5, 1.0, -1.0");
+        assert!(out_of_bounds_result.is_err());
+    });
+}
+
+#[test]
+#[serial]
+fn test_array_vec() {
+    with_graph(|graph| {
+        graph
+            .mutate("CREATE ({ x: 0.5, y: 1.0, z: -1.0 }), ({ x: 1.5, y: 2.5 })")
+            .unwrap();
+        let array_vec: Vec<[Option<f64>; 3]> = graph
+            .query("MATCH (n) RETURN n.x, n.y, n.z ORDER BY n.x")
+            .unwrap();
+        assert_eq!(array_vec[0], [Some(0.5), Some(1.0), Some(-1.0)]);
+        assert_eq!(array_vec[1], [Some(1.5), Some(2.5), None]);
+    });
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "async-std-comp")]
+
+use redisgraph::{
+    aio::{detached_connection, AsyncGraph},
+    testing::unique_graph_name,
+};
+
+#[test]
+fn test_async_std() {
+    async_std::task::block_on(async {
+        let uri =
+            std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+        let client = redis::Client::open(uri.as_str()).unwrap();
+        let conn = detached_connection(&client)
+            .await
+            .expect("failed to connect to the test database");
+        let mut graph = AsyncGraph::open(conn, unique_graph_name("async_std"))
+            .await
+            .unwrap();
+
+        graph
+            .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+            .await
+            .unwrap();
+        let names: Vec<String> = graph.query("MATCH (r:Rider) RETURN r.name").await.unwrap();
+        assert_eq!(names, vec!["Valentino Rossi"]);
+        graph.delete().await.unwrap();
+    });
+}
+
+#[test]
+fn test_detached_connection_refused() {
+    async_std::task::block_on(async {
+        let client = redis::Client::open("redis://127.0.0.1:1").unwrap();
+        assert!(detached_connection(&client).await.is_err());
+    });
+}
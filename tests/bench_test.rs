@@ -0,0 +1,34 @@
+#![cfg(feature = "bench")]
+
+use std::time::Duration;
+
+use redisgraph::bench::LatencyStats;
+
+#[test]
+fn test_latency_stats_empty() {
+    assert_eq!(LatencyStats::from_durations(&[]), None);
+}
+
+#[test]
+fn test_latency_stats_percentiles() {
+    let latencies: Vec<Duration> = (1..=1000).rev().map(Duration::from_millis).collect();
+    let stats = LatencyStats::from_durations(&latencies).unwrap();
+
+    assert_eq!(stats.count, 1000);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.max, Duration::from_millis(1000));
+    assert_eq!(stats.mean, Duration::from_micros(500_500));
+    assert_eq!(stats.p50, Duration::from_millis(500));
+    assert_eq!(stats.p90, Duration::from_millis(900));
+    assert_eq!(stats.p99, Duration::from_millis(990));
+    assert_eq!(stats.p999, Duration::from_millis(999));
+}
+
+#[test]
+fn test_latency_stats_single() {
+    let stats = LatencyStats::from_durations(&[Duration::from_millis(7)]).unwrap();
+
+    assert_eq!(stats.p50, Duration::from_millis(7));
+    assert_eq!(stats.p999, Duration::from_millis(7));
+    assert_eq!(stats.mean, Duration::from_millis(7));
+}
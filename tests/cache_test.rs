@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use redisgraph::{
+    cache::{QueryCacheBackend, RedisCache},
+    result_set::Node,
+    testing::test_connection,
+    Graph, RedisGraphResult,
+};
+use redisgraph_derive::graph_test;
+
+#[derive(Default)]
+struct MemoryCache {
+    values: HashMap<String, Vec<u8>>,
+    puts: usize,
+}
+
+impl QueryCacheBackend for MemoryCache {
+    fn get(&mut self, key: &str) -> RedisGraphResult<Option<Vec<u8>>> {
+        Ok(self.values.get(key).cloned())
+    }
+
+    fn put(&mut self, key: &str, value: &[u8], _ttl: Duration) -> RedisGraphResult<()> {
+        self.puts += 1;
+        self.values.insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+}
+
+#[graph_test]
+fn test_query_cached(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person {name: 'Alice', age: 30})")
+        .unwrap();
+    let mut cache = MemoryCache::default();
+    let query = "MATCH (p:Person) RETURN p.name, p.age";
+    let ttl = Duration::from_secs(60);
+
+    let people: Vec<(String, i64)> = graph.query_cached(&mut cache, query, ttl).unwrap();
+    assert_eq!(people, vec![("Alice".to_string(), 30)]);
+    assert_eq!(cache.puts, 1);
+
+    // The cached response is returned even though the graph changed.
+    graph
+        .mutate("CREATE (:Person {name: 'Bob', age: 40})")
+        .unwrap();
+    let people: Vec<(String, i64)> = graph.query_cached(&mut cache, query, ttl).unwrap();
+    assert_eq!(people, vec![("Alice".to_string(), 30)]);
+    assert_eq!(cache.puts, 1);
+}
+
+#[graph_test]
+fn test_redis_cache(graph: &mut Graph) {
+    graph.mutate("CREATE (:Person {name: 'Alice'})").unwrap();
+    let mut cache = RedisCache::with_prefix(test_connection(), "redisgraph-cache-test:");
+    let query = "MATCH (p:Person) RETURN p";
+    let ttl = Duration::from_millis(200);
+
+    let first: Vec<Node> = graph.query_cached(&mut cache, query, ttl).unwrap();
+    let second: Vec<Node> = graph.query_cached(&mut cache, query, ttl).unwrap();
+    assert_eq!(first, second);
+    assert!(cache
+        .get(&format!("{}:{}", graph.name(), query))
+        .unwrap()
+        .is_some());
+
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(cache
+        .get(&format!("{}:{}", graph.name(), query))
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,79 @@
+use redisgraph::{
+    chunk::{split_create, ChunkOptions},
+    RedisGraphError,
+};
+
+#[test]
+fn test_split_create_by_entities() {
+    let options = ChunkOptions {
+        max_entities: 2,
+        ..ChunkOptions::default()
+    };
+    let chunks = split_create("create (:A { v: 'x, y' }), (:B), (:C)", &options).unwrap();
+    assert_eq!(
+        chunks,
+        vec![
+            ("CREATE (:A { v: 'x, y' }), (:B)".to_string(), 2),
+            ("CREATE (:C)".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_split_create_by_bytes() {
+    let options = ChunkOptions {
+        max_bytes: 20,
+        ..ChunkOptions::default()
+    };
+    let chunks = split_create(
+        "CREATE (:Aaaa), (:Bbbb), (:Cccccccccccccccccccccc)",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        chunks,
+        vec![
+            ("CREATE (:Aaaa)".to_string(), 1),
+            ("CREATE (:Bbbb)".to_string(), 1),
+            ("CREATE (:Cccccccccccccccccccccc)".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_split_create_keeps_connected_patterns() {
+    let options = ChunkOptions {
+        max_entities: 1,
+        ..ChunkOptions::default()
+    };
+    let chunks = split_create(
+        "CREATE (`a b`:A), (c:C), (d:D), ( `a b` )-[r:R]->(d), (c)-[:S { r: 1 }]->(:E)",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        chunks,
+        vec![
+            (
+                "CREATE (`a b`:A), (d:D), ( `a b` )-[r:R]->(d)".to_string(),
+                3
+            ),
+            ("CREATE (c:C), (c)-[:S { r: 1 }]->(:E)".to_string(), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_split_create_rejects_other_clauses() {
+    for statement in &[
+        "MATCH (a) CREATE (b)",
+        "CREATE (a) RETURN a",
+        "CREATE p = (a)-[:R]->(b)",
+        "CREATED (a)",
+    ] {
+        match split_create(statement, &ChunkOptions::default()) {
+            Err(RedisGraphError::UnsplittableStatement(s)) => assert_eq!(&s, statement),
+            other => panic!("expected unsplittable statement, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "tokio-comp")]
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use redisgraph::{client::GraphClient, events::ConnectionEvent, testing::unique_graph_name};
+
+async fn open_test_client() -> GraphClient {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    let client = redis::Client::open(uri.as_str()).unwrap();
+    GraphClient::connect(&client, unique_graph_name("client"))
+        .await
+        .expect("failed to connect to the test database")
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn assert_send<F: Future + Send>(_future: F) {}
+
+#[test]
+fn test_client_is_send_sync() {
+    assert_send_sync::<GraphClient>();
+    // Never polled, only checks that the mappings aren't locked across an await.
+    let _ = || async {
+        let client = open_test_client().await;
+        assert_send(client.query::<i64>("RETURN 1"));
+        assert_send(client.update_labels());
+        assert_send(client.query_coalesced("RETURN 1"));
+    };
+}
+
+#[tokio::test]
+async fn test_client_clones() {
+    let client = open_test_client().await;
+    client
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'}), (:Rider {name: 'Marc Marquez'})")
+        .await
+        .unwrap();
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .query::<Vec<String>>("MATCH (r:Rider) RETURN r.name ORDER BY r.name")
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+    for reader in readers {
+        assert_eq!(
+            reader.await.unwrap(),
+            vec!["Marc Marquez".to_string(), "Valentino Rossi".to_string()]
+        );
+    }
+
+    // The clone decodes the new label with the names fetched by the original.
+    let clone = client.clone();
+    client
+        .mutate("CREATE (:Team {name: 'Yamaha'})")
+        .await
+        .unwrap();
+    client.update_labels().await.unwrap();
+    let team: redisgraph::result_set::Node = clone.query("MATCH (t:Team) RETURN t").await.unwrap();
+    assert_eq!(team.labels, vec!["Team".to_string().into()]);
+
+    client.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_graph_override() {
+    let client = open_test_client().await;
+    let tenant = client.graph(&unique_graph_name("tenant"));
+    assert_ne!(tenant.name(), client.name());
+
+    client.mutate("CREATE (:Rider)").await.unwrap();
+    tenant.mutate("CREATE (:Team), (:Team)").await.unwrap();
+    let riders: i64 = client
+        .query("MATCH (r:Rider) RETURN count(r)")
+        .await
+        .unwrap();
+    let teams: i64 = tenant
+        .query("MATCH (t:Team) RETURN count(t)")
+        .await
+        .unwrap();
+    assert_eq!((riders, teams), (1, 2));
+
+    // Another client of the same graph uses the names the first one fetched.
+    let team: redisgraph::result_set::Node = client
+        .graph(tenant.name())
+        .query("MATCH (t:Team) RETURN t LIMIT 1")
+        .await
+        .unwrap();
+    assert_eq!(team.labels, vec!["Team".to_string().into()]);
+
+    tenant.delete().await.unwrap();
+    client.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_blocking_decode() {
+    let mut client = open_test_client().await;
+    client.set_blocking_decode_threshold(Some(0));
+    client
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+        .await
+        .unwrap();
+    let names: Vec<String> = client.query("MATCH (r:Rider) RETURN r.name").await.unwrap();
+    assert_eq!(names, vec!["Valentino Rossi"]);
+    client.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_query_coalesced() {
+    let client = open_test_client().await;
+    client
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+        .await
+        .unwrap();
+
+    let other = client.clone();
+    let query = "MATCH (r:Rider) RETURN r.name";
+    let (first, second) = tokio::join!(client.query_coalesced(query), other.query_coalesced(query));
+    let (first, second) = (first.unwrap(), second.unwrap());
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(first.columns.len(), 1);
+
+    // The flight has landed, so the next query is sent again.
+    let third = client.query_coalesced(query).await.unwrap();
+    assert!(!Arc::ptr_eq(&first, &third));
+    assert!(client
+        .query_coalesced("MATCH (r:Rider) SET r.x = 1")
+        .await
+        .is_err());
+
+    client.delete().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_connection_events() {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    let redis_client = redis::Client::open(uri.as_str()).unwrap();
+    let client = open_test_client().await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    client
+        .clone()
+        .on_connection_event(move |event| sink.lock().unwrap().push(event.clone()));
+
+    // Kills the connection of the client, but not this one.
+    redis::cmd("CLIENT")
+        .arg("KILL")
+        .arg("TYPE")
+        .arg("normal")
+        .arg("SKIPME")
+        .arg("yes")
+        .query::<()>(&mut redis_client.get_connection().unwrap())
+        .unwrap();
+    assert!(client.query::<i64>("RETURN 1").await.is_err());
+    assert!(client
+        .graph("other")
+        .query::<i64>("RETURN 1")
+        .await
+        .is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        ConnectionEvent::Disconnected { graph_name, .. } => assert_eq!(graph_name, client.name()),
+        other => panic!("expected disconnected event, got {:?}", other),
+    }
+}
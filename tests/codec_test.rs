@@ -0,0 +1,96 @@
+#![cfg(feature = "json")]
+
+use std::collections::HashMap;
+
+use redisgraph::{
+    codec::{JsonCodec, PropertyCodec},
+    params::CypherValue,
+    props::Props,
+    result_set::{Node, Scalar},
+    RedisGraphError,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+fn addresses() -> Vec<Address> {
+    vec![
+        Address {
+            street: "Main Street 1".to_string(),
+            city: "Springfield".to_string(),
+        },
+        Address {
+            street: "Elm Street 13".to_string(),
+            city: "Springwood".to_string(),
+        },
+    ]
+}
+
+#[test]
+fn test_json_codec() {
+    let encoded = JsonCodec.encode(&addresses()).unwrap();
+    assert_eq!(
+        JsonCodec.decode::<Vec<Address>>(&encoded).unwrap(),
+        addresses()
+    );
+
+    match JsonCodec.decode::<Address>("{}") {
+        Err(RedisGraphError::CodecError(_)) => {}
+        other => panic!("expected codec error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encoded_properties() {
+    let props = Props::new()
+        .set_encoded("addresses", &addresses(), &JsonCodec)
+        .unwrap();
+    let encoded = match props.get("addresses") {
+        Some(CypherValue::String(encoded)) => encoded.clone(),
+        other => panic!("expected string property, got {:?}", other),
+    };
+
+    let mut properties = HashMap::new();
+    properties.insert(
+        "addresses".to_string().into(),
+        Scalar::String(encoded.into()),
+    );
+    properties.insert("age".to_string().into(), Scalar::Integer(30));
+    let node = Node {
+        labels: Vec::new(),
+        properties,
+    };
+
+    let decoded: Vec<Address> = node.decode_property("addresses", &JsonCodec).unwrap();
+    assert_eq!(decoded, addresses());
+    assert!(node.decode_property::<i64, _>("age", &JsonCodec).is_err());
+    assert!(node
+        .decode_property::<i64, _>("missing", &JsonCodec)
+        .is_err());
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn test_encrypted_codec() {
+    use redisgraph::codec::EncryptedCodec;
+
+    let codec = EncryptedCodec::new(&[1; 32]);
+    let encrypted = codec.encode(&addresses()).unwrap();
+    assert!(!encrypted.contains("Springfield"));
+    assert_ne!(codec.encode(&addresses()).unwrap(), encrypted);
+    assert_eq!(
+        codec.decode::<Vec<Address>>(&encrypted).unwrap(),
+        addresses()
+    );
+
+    match EncryptedCodec::new(&[2; 32]).decode::<Vec<Address>>(&encrypted) {
+        Err(RedisGraphError::CodecError(_)) => {}
+        other => panic!("expected codec error, got {:?}", other),
+    }
+    assert!(codec.decode::<Vec<Address>>("AAAA").is_err());
+    assert!(!format!("{:?}", codec).contains('1'));
+}
@@ -0,0 +1,60 @@
+use redisgraph::Graph;
+use redisgraph_derive::graph_test;
+
+fn person_id(graph: &mut Graph, name: &str) -> u64 {
+    graph
+        .query(&format!(
+            "MATCH (p:Person {{name: '{}'}}) RETURN id(p)",
+            name
+        ))
+        .unwrap()
+}
+
+#[graph_test]
+fn test_connected_components(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:Person {name: 'A'})-[:KNOWS]->(b:Person {name: 'B'}), \
+             (c:Person {name: 'C'})-[:KNOWS]->(b), \
+             (:Person {name: 'D'})-[:KNOWS]->(:Person {name: 'E'}), \
+             (:Person {name: 'F'})",
+        )
+        .unwrap();
+    let ids: Vec<u64> = ["A", "B", "C", "D", "E", "F"]
+        .iter()
+        .map(|name| person_id(graph, name))
+        .collect();
+
+    let components = graph.connected_components().unwrap();
+    assert_eq!(components.len(), 6);
+    assert_eq!(components[&ids[0]], components[&ids[1]]);
+    assert_eq!(components[&ids[0]], components[&ids[2]]);
+    assert_eq!(components[&ids[3]], components[&ids[4]]);
+    assert_ne!(components[&ids[0]], components[&ids[3]]);
+    assert_ne!(components[&ids[0]], components[&ids[5]]);
+    assert_ne!(components[&ids[3]], components[&ids[5]]);
+}
+
+#[graph_test]
+fn test_label_propagation(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:Person {name: 'A'}), (b:Person {name: 'B'}), (c:Person {name: 'C'}), \
+             (d:Person {name: 'D'}), (e:Person {name: 'E'}), (f:Person {name: 'F'}), \
+             (a)-[:KNOWS]->(b), (b)-[:KNOWS]->(c), (c)-[:KNOWS]->(a), \
+             (d)-[:KNOWS]->(e), (e)-[:KNOWS]->(f), (f)-[:KNOWS]->(d)",
+        )
+        .unwrap();
+    let ids: Vec<u64> = ["A", "B", "C", "D", "E", "F"]
+        .iter()
+        .map(|name| person_id(graph, name))
+        .collect();
+
+    let communities = graph.label_propagation(10).unwrap();
+    assert_eq!(communities.len(), 6);
+    assert_eq!(communities[&ids[0]], communities[&ids[1]]);
+    assert_eq!(communities[&ids[1]], communities[&ids[2]]);
+    assert_eq!(communities[&ids[3]], communities[&ids[4]]);
+    assert_eq!(communities[&ids[4]], communities[&ids[5]]);
+    assert_ne!(communities[&ids[0]], communities[&ids[3]]);
+}
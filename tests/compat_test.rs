@@ -0,0 +1,34 @@
+use redisgraph::{compat::query, result_set::Node, Graph, RedisGraphError};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_query_params() {
+    let query = query("MATCH (p:Person {name: $name}) RETURN p").param("name", "Alice");
+    assert!(query.has_param_key("name"));
+    assert!(!query.has_param_key("age"));
+}
+
+#[graph_test]
+fn test_run_and_execute(graph: &mut Graph) {
+    graph
+        .run(
+            query("CREATE (:Person {name: $name, age: $age})")
+                .param("name", "Alice")
+                .param("age", 30),
+        )
+        .unwrap();
+
+    let mut result = graph
+        .execute(query("MATCH (p:Person) RETURN p, p.age AS age"))
+        .unwrap();
+    let row = result.next().unwrap().unwrap();
+    assert_eq!(row.keys(), ["p".to_string(), "age".to_string()]);
+    let person: Node = row.get("p").unwrap();
+    assert_eq!(person.labels.len(), 1);
+    assert_eq!(row.get::<i64>("age").unwrap(), 30);
+    assert!(matches!(
+        row.get::<i64>("name"),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+    assert!(result.next().unwrap().is_none());
+}
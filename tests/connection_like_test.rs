@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::io;
+
+use redis::{ConnectionLike, ErrorKind, RedisResult, Value};
+use redisgraph::{
+    chunk::ChunkOptions, compat, mapping::ColumnMapping, params::CypherValue, reference::NodeRef,
+    single_flight::SingleFlight, Graph, RedisGraphError,
+};
+use redisgraph_derive::queries;
+
+queries! {
+    dir = "tests/queries";
+
+    fn create_person(name: &str, age: i64);
+}
+
+// Answers every query with a single integer and every other command with `OK`,
+// and records the commands it received.
+// Queries with the labels `:Invalid`, `:Loading` or `:Broken` fail as if they were rejected,
+// sent while the server was loading, or sent over a broken connection.
+#[derive(Default)]
+struct MockConnection {
+    commands: Vec<String>,
+}
+
+impl ConnectionLike for MockConnection {
+    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+        let cmd = String::from_utf8_lossy(cmd).into_owned();
+        self.commands.push(cmd.clone());
+        if cmd.contains(":Invalid") {
+            return Err((ErrorKind::ResponseError, "errMsg: syntax error").into());
+        } else if cmd.contains(":Loading") {
+            return Err((ErrorKind::BusyLoadingError, "dataset is loading").into());
+        } else if cmd.contains(":Broken") {
+            return Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset").into());
+        }
+        if !cmd.contains("GRAPH.") {
+            return Ok(Value::Okay);
+        }
+        let statistics = Value::Bulk(vec![Value::Data(
+            b"Query internal execution time: 0.1 milliseconds".to_vec(),
+        )]);
+        let response = if cmd.contains("RETURN") {
+            Value::Bulk(vec![
+                Value::Bulk(vec![Value::Bulk(vec![
+                    Value::Int(1),
+                    Value::Data(b"answer".to_vec()),
+                ])]),
+                Value::Bulk(vec![Value::Bulk(vec![Value::Bulk(vec![
+                    Value::Int(3),
+                    Value::Int(42),
+                ])])]),
+                statistics,
+            ])
+        } else {
+            Value::Bulk(vec![statistics])
+        };
+        Ok(response)
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        _cmd: &[u8],
+        _offset: usize,
+        _count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        Err((
+            ErrorKind::ClientError,
+            "pipelines are not supported by the mock",
+        )
+            .into())
+    }
+
+    fn get_db(&self) -> i64 {
+        0
+    }
+
+    fn check_connection(&mut self) -> bool {
+        true
+    }
+
+    fn is_open(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_graph_over_mock_connection() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    let answer: i64 = graph.query("RETURN 42 AS answer").unwrap();
+    assert_eq!(answer, 42);
+    graph.mutate("CREATE (:Rider)").unwrap();
+
+    let commands = &graph.connection().commands;
+    assert_eq!(commands.len(), 4);
+    assert!(commands[0].contains("GRAPH.QUERY"));
+    assert!(commands[0].contains("CREATE (dummy:__DUMMY_LABEL__)"));
+    assert!(commands[3].contains("CREATE (:Rider)"));
+}
+
+#[test]
+fn test_helpers_over_mock_connection() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    assert!(NodeRef::new("mock".to_string(), 7)
+        .exists(&mut graph)
+        .unwrap());
+
+    let graph = SingleFlight::new(graph);
+    let result_set = graph.query("RETURN 42 AS answer").unwrap();
+    assert_eq!(result_set.num_rows(), 1);
+    let mut graph = graph.into_inner();
+    let commands = &graph.connection().commands;
+    assert!(commands.last().unwrap().contains("RETURN 42 AS answer"));
+}
+
+#[test]
+fn test_generated_queries_over_mock_connection() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    create_person(&mut graph, "Alice", 30).unwrap();
+    let commands = &graph.connection().commands;
+    assert!(commands
+        .last()
+        .unwrap()
+        .contains("CREATE (:Person { name: $name, age: $age })"));
+}
+
+#[test]
+fn test_read_only_queries_over_mock_connection() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    let answer: i64 = graph.ro_query("RETURN 42 AS answer").unwrap();
+    assert_eq!(answer, 42);
+    assert_eq!(graph.count_rows("MATCH (r:Rider) RETURN r").unwrap(), 42);
+    assert!(graph.exists_match("(:Rider)", &HashMap::new()).unwrap());
+
+    let commands = &graph.connection().commands;
+    assert_eq!(commands.len(), 5);
+    assert!(commands[2..]
+        .iter()
+        .all(|command| command.contains("GRAPH.RO_QUERY")));
+}
+
+#[test]
+fn test_mutate_chunked_only_retries_transient_errors() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    let options = ChunkOptions {
+        max_entities: 1,
+        retries: 2,
+        ..ChunkOptions::default()
+    };
+    let report = graph
+        .mutate_chunked(
+            "CREATE (:Rider), (:Invalid), (:Loading), (:Broken)",
+            &options,
+        )
+        .unwrap();
+    let attempts: Vec<usize> = report.chunks.iter().map(|chunk| chunk.attempts).collect();
+    assert_eq!(attempts, vec![1, 1, 3, 1]);
+    assert_eq!(report.failures().count(), 3);
+    let ambiguous: Vec<usize> = report.ambiguous().map(|chunk| chunk.entities).collect();
+    assert_eq!(ambiguous, vec![1]);
+    assert!(report.chunks[3].is_ambiguous());
+    assert!(!report.chunks[1].is_ambiguous());
+}
+
+#[test]
+fn test_non_finite_parameters_are_rejected() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    let sent = graph.connection().commands.len();
+    for value in [
+        CypherValue::from(f64::NAN),
+        CypherValue::from(f64::INFINITY),
+        CypherValue::from(vec![1.0, f64::NEG_INFINITY]),
+    ] {
+        let mut params = HashMap::new();
+        params.insert("score".to_string(), value);
+        let result = graph.mutate_with_params("CREATE (:Rider {score: $score})", &params);
+        assert!(matches!(
+            result,
+            Err(RedisGraphError::NonFiniteParameter(name)) if name == "score"
+        ));
+    }
+    assert_eq!(graph.connection().commands.len(), sent);
+}
+
+#[test]
+fn test_helpers_validate_params() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    graph.set_param_validation(true);
+    let sent = graph.connection().commands.len();
+    let is_unbound = |result: Result<_, RedisGraphError>| matches!(result, Err(RedisGraphError::UnboundParameter(name)) if name == "nmae");
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), CypherValue::from("Valentino"));
+    let execute = graph.execute(compat::query("MATCH (r {name: $nmae}) RETURN r.name"));
+    assert!(is_unbound(execute.map(|_| ())));
+    let mapped = graph.query_mapped_with_params(
+        "MATCH (r {name: $nmae}) RETURN r.name",
+        &params,
+        &ColumnMapping::<()>::new(),
+    );
+    assert!(is_unbound(mapped.map(|_| ())));
+    assert_eq!(graph.connection().commands.len(), sent);
+
+    let graph = SingleFlight::new(graph);
+    let coalesced = graph.query_with_params("MATCH (r {name: $nmae}) RETURN r.name", &params);
+    assert!(is_unbound(coalesced.map(|_| ())));
+    assert_eq!(graph.into_inner().connection().commands.len(), sent);
+}
+
+#[test]
+fn test_mutate_idempotent_keeps_ambiguous_claims() {
+    let mut graph = Graph::open(MockConnection::default(), "mock".to_string()).unwrap();
+    let deleted = |graph: &mut Graph<MockConnection>| {
+        graph
+            .connection()
+            .commands
+            .iter()
+            .filter(|command| command.contains("\r\nDEL\r\n"))
+            .count()
+    };
+
+    let rejected = graph.mutate_idempotent("message-1", "CREATE (:Invalid)");
+    assert!(matches!(rejected, Err(RedisGraphError::RedisError(_))));
+    assert_eq!(deleted(&mut graph), 1);
+
+    // The mutation may have been applied, so the claim is left to expire.
+    let broken = graph.mutate_idempotent("message-2", "CREATE (:Broken)");
+    assert!(broken.unwrap_err().is_ambiguous());
+    assert_eq!(deleted(&mut graph), 1);
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use redisgraph::{
+    assignments::FromTable,
+    converters::{Converted, ConverterRegistry, Pattern},
+    result_set::{Column, Edge, Node, Scalar, Statistics},
+    Graph, RedisGraphError, RedisGraphResult, ResultSet,
+};
+use redisgraph_derive::graph_test;
+
+#[derive(Debug, PartialEq)]
+struct GeoPoint {
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(Debug, PartialEq)]
+struct Celsius(f64);
+
+fn geo_point(value: &Scalar) -> RedisGraphResult<GeoPoint> {
+    match value {
+        Scalar::Map(map) => Ok(GeoPoint {
+            lat: map[&"lat".to_string().into()].convert()?,
+            lon: map[&"lon".to_string().into()].convert()?,
+        }),
+        _ => unreachable!(),
+    }
+}
+
+fn location(lat: f64, lon: f64) -> Scalar {
+    let mut map = HashMap::new();
+    map.insert("lat".to_string().into(), Scalar::Double(lat));
+    map.insert("lon".to_string().into(), Scalar::Double(lon));
+    Scalar::Map(map)
+}
+
+fn converters() -> ConverterRegistry {
+    let mut converters = ConverterRegistry::new();
+    converters.register(Pattern::map_with_keys(&["lat", "lon"]), geo_point);
+    converters.register(Pattern::labeled_property("Sensor", "reading"), |value| {
+        value.convert().map(Celsius)
+    });
+    converters.register(Pattern::property("reading"), |value| {
+        value
+            .convert()
+            .map(|fahrenheit: f64| Celsius((fahrenheit - 32.0) / 1.8))
+    });
+    converters
+}
+
+#[test]
+fn test_decode() {
+    let converters = converters();
+    assert_eq!(
+        converters
+            .decode::<GeoPoint>(&location(52.5, 13.4))
+            .unwrap(),
+        GeoPoint {
+            lat: 52.5,
+            lon: 13.4
+        }
+    );
+
+    let mut map = HashMap::new();
+    map.insert("lat".to_string().into(), Scalar::Double(52.5));
+    assert!(matches!(
+        converters.decode::<GeoPoint>(&Scalar::Map(map)),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+    assert!(converters.decode::<Celsius>(&Scalar::Double(20.0)).is_err());
+    assert!(ConverterRegistry::new()
+        .decode::<GeoPoint>(&location(52.5, 13.4))
+        .is_err());
+}
+
+#[test]
+fn test_decode_property() {
+    let converters = converters();
+    let mut properties = HashMap::new();
+    properties.insert("reading".to_string().into(), Scalar::Double(212.0));
+    properties.insert("location".to_string().into(), location(52.5, 13.4));
+
+    let sensor = Node {
+        labels: vec!["Sensor".to_string().into()],
+        properties: properties.clone(),
+    };
+    assert_eq!(
+        converters
+            .node_property::<Celsius>(&sensor, "reading")
+            .unwrap(),
+        Celsius(212.0)
+    );
+    assert_eq!(
+        converters
+            .node_property::<GeoPoint>(&sensor, "location")
+            .unwrap(),
+        GeoPoint {
+            lat: 52.5,
+            lon: 13.4
+        }
+    );
+    assert!(converters
+        .node_property::<GeoPoint>(&sensor, "missing")
+        .is_err());
+
+    let measured = Edge {
+        type_name: "MEASURED".to_string().into(),
+        properties,
+    };
+    assert_eq!(
+        converters
+            .edge_property::<Celsius>(&measured, "reading")
+            .unwrap(),
+        Celsius(100.0)
+    );
+}
+
+#[test]
+fn test_decode_table() {
+    let converters = Arc::new(converters());
+    let result_set = ResultSet {
+        columns: vec![
+            Column::Scalars(vec![location(52.5, 13.4), location(48.1, 11.6)]),
+            Column::Scalars(vec![
+                Scalar::String("Berlin".to_string().into()),
+                Scalar::String("Munich".to_string().into()),
+            ]),
+        ],
+        statistics: Statistics(Vec::new()),
+    };
+    let rows: Vec<(Converted<GeoPoint>, String)> = converters.decode_table(&result_set).unwrap();
+    assert_eq!(
+        rows[1],
+        (
+            Converted(GeoPoint {
+                lat: 48.1,
+                lon: 11.6
+            }),
+            "Munich".to_string()
+        )
+    );
+
+    // Without a registry, converted cells can't be decoded.
+    let unset: RedisGraphResult<Vec<(Converted<GeoPoint>, String)>> =
+        FromTable::from_table(&result_set);
+    assert!(matches!(unset, Err(RedisGraphError::ClientTypeError(_))));
+}
+
+#[graph_test]
+fn test_query_with_converters(graph: &mut Graph) {
+    graph.set_converters(Some(Arc::new(converters())));
+    let point: Converted<GeoPoint> = graph.query("RETURN {lat: 52.5, lon: 13.4}").unwrap();
+    assert_eq!(
+        point.into_inner(),
+        GeoPoint {
+            lat: 52.5,
+            lon: 13.4
+        }
+    );
+
+    graph.set_converters(None);
+    assert!(graph
+        .query::<Converted<GeoPoint>>("RETURN {lat: 52.5, lon: 13.4}")
+        .is_err());
+}
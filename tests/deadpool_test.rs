@@ -0,0 +1,47 @@
+#![cfg(feature = "deadpool")]
+
+use redisgraph::{
+    deadpool::{Manager, Pool, PoolError},
+    testing::unique_graph_name,
+};
+
+fn test_client() -> redis::Client {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    redis::Client::open(uri.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_get_refused() {
+    let client = redis::Client::open("redis://127.0.0.1:1").unwrap();
+    let pool = Pool::new(Manager::new(client, unique_graph_name("deadpool")), 2);
+    match pool.get().await {
+        Err(PoolError::Backend(_)) => {}
+        Err(other) => panic!("expected a backend error, got {:?}", other),
+        Ok(_) => panic!("expected a backend error"),
+    }
+    assert_eq!(pool.status().size, 0);
+}
+
+#[tokio::test]
+async fn test_pool_recycles_graphs() {
+    let manager = Manager::new(test_client(), unique_graph_name("deadpool"));
+    assert!(manager.name().starts_with("deadpool"));
+    let pool = Pool::new(manager, 2);
+
+    let mut graph = pool
+        .get()
+        .await
+        .expect("failed to connect to the test database");
+    graph
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+        .await
+        .unwrap();
+    drop(graph);
+    assert_eq!(pool.status().available, 1);
+
+    let mut graph = pool.get().await.unwrap();
+    let names: Vec<String> = graph.query("MATCH (r:Rider) RETURN r.name").await.unwrap();
+    assert_eq!(names, vec!["Valentino Rossi"]);
+    assert_eq!(pool.status().size, 1);
+    graph.mutate("MATCH (r:Rider) DELETE r").await.unwrap();
+}
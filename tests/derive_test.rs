@@ -0,0 +1,397 @@
+#![cfg(feature = "derive")]
+
+use std::collections::HashMap;
+
+use redisgraph::{
+    entity::{OnDelete, Relation},
+    params::CypherValue,
+    pattern::Direction,
+    result_set::{Node, Scalar},
+    Entity, Graph, RedisGraphError, RedisGraphResult, ToProperties,
+};
+use redisgraph_derive::graph_test;
+
+#[derive(ToProperties)]
+struct Person {
+    name: String,
+    #[redisgraph(rename = "birth_year")]
+    born: i64,
+    nickname: Option<String>,
+    #[redisgraph(skip)]
+    #[allow(dead_code)]
+    cached_score: f64,
+}
+
+#[derive(Debug, Default, PartialEq, Entity, ToProperties)]
+#[redisgraph(label = "Person")]
+struct PersonExample {
+    name: Option<String>,
+    #[redisgraph(rename = "birth_year")]
+    born: Option<i64>,
+    #[redisgraph(skip)]
+    cached_score: f64,
+}
+
+#[derive(Entity, ToProperties)]
+#[redisgraph(relation(rel_type = "WROTE", on_delete = "cascade"))]
+#[redisgraph(relation(rel_type = "FOLLOWS", direction = "incoming", on_delete = "restrict"))]
+struct Author {
+    #[redisgraph(key)]
+    handle: String,
+}
+
+#[derive(Debug, Default, Entity, ToProperties)]
+#[redisgraph(before_save = "validate", after_load = "compute_total")]
+struct Order {
+    #[redisgraph(key)]
+    number: String,
+    quantity: i64,
+    unit_price: i64,
+    #[redisgraph(skip)]
+    total: i64,
+}
+
+impl Order {
+    fn validate(&mut self) -> RedisGraphResult<()> {
+        if self.quantity <= 0 {
+            return Err(RedisGraphError::InvalidEntity(format!(
+                "order {} has no items",
+                self.number
+            )));
+        }
+        Ok(())
+    }
+
+    fn compute_total(&mut self) -> RedisGraphResult<()> {
+        self.total = self.quantity * self.unit_price;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "encryption")]
+struct TestKeys;
+
+#[cfg(feature = "encryption")]
+impl redisgraph::codec::KeyProvider for TestKeys {
+    fn encryption_key() -> [u8; 32] {
+        [7; 32]
+    }
+}
+
+#[cfg(feature = "encryption")]
+#[derive(Debug, PartialEq, Entity, ToProperties)]
+#[redisgraph(key_provider = "TestKeys")]
+struct Patient {
+    #[redisgraph(key)]
+    name: String,
+    #[redisgraph(encrypted, rename = "insurance")]
+    insurance_number: String,
+    #[redisgraph(encrypted)]
+    diagnosis: Option<String>,
+}
+
+fn alice() -> Person {
+    Person {
+        name: "Alice".to_string(),
+        born: 1990,
+        nickname: None,
+        cached_score: 0.5,
+    }
+}
+
+#[test]
+fn test_derive_to_properties() {
+    let props = alice().to_properties();
+    assert_eq!(props.len(), 2);
+    assert_eq!(props.get("name"), Some(&CypherValue::from("Alice")));
+    assert_eq!(props.get("birth_year"), Some(&CypherValue::Integer(1990)));
+    assert_eq!(props.get("nickname"), None);
+    assert_eq!(props.get("cached_score"), None);
+}
+
+#[graph_test]
+fn test_create_node_from_struct(graph: &mut Graph) {
+    let node = graph.create_node(&["Person"], &alice()).unwrap();
+    assert_eq!(
+        node.properties.get(&"birth_year".to_string().into()),
+        Some(&Scalar::Integer(1990))
+    );
+}
+
+#[test]
+fn test_derive_entity() {
+    assert_eq!(PersonExample::LABEL, "Person");
+
+    let mut properties = HashMap::new();
+    properties.insert(
+        "name".to_string().into(),
+        Scalar::String("Alice".to_string().into()),
+    );
+    properties.insert("cached_score".to_string().into(), Scalar::Double(0.5));
+    let node = Node {
+        labels: vec!["Person".to_string().into()],
+        properties,
+    };
+    assert_eq!(
+        PersonExample::from_node(&node).unwrap(),
+        PersonExample {
+            name: Some("Alice".to_string()),
+            born: None,
+            cached_score: 0.0,
+        }
+    );
+}
+
+#[graph_test]
+fn test_find_like(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (:Person {name: 'Alice', birth_year: 1990}), \
+             (:Person {name: 'Bob', birth_year: 1990}), \
+             (:Robot {name: 'Alice'})",
+        )
+        .unwrap();
+
+    let alices = graph
+        .find_like(&PersonExample {
+            name: Some("Alice".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(alices.len(), 1);
+    assert_eq!(alices[0].born, Some(1990));
+
+    let born_1990 = graph
+        .find_like(&PersonExample {
+            born: Some(1990),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(born_1990.len(), 2);
+    assert_eq!(graph.find_like(&PersonExample::default()).unwrap().len(), 2);
+}
+
+#[test]
+fn test_derive_entity_relations() {
+    assert_eq!(
+        Author::RELATIONS,
+        &[
+            Relation {
+                rel_type: "WROTE",
+                direction: Direction::Outgoing,
+                on_delete: OnDelete::Cascade,
+            },
+            Relation {
+                rel_type: "FOLLOWS",
+                direction: Direction::Incoming,
+                on_delete: OnDelete::Restrict,
+            },
+        ]
+    );
+    let author = Author {
+        handle: "alice".to_string(),
+    };
+    assert_eq!(author.key(), Some(("handle", CypherValue::from("alice"))));
+    assert_eq!(PersonExample::default().key(), None);
+}
+
+#[graph_test]
+fn test_delete_entity(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:Author {handle: 'alice'})-[:WROTE]->(:Post), (a)-[:WROTE]->(:Post), \
+             (a)-[:LIKES]->(:Post {title: 'kept'}), \
+             (b:Author {handle: 'bob'})<-[:FOLLOWS]-(:Author {handle: 'carol'})",
+        )
+        .unwrap();
+
+    let alice = Author {
+        handle: "alice".to_string(),
+    };
+    assert_eq!(graph.delete_entity(&alice).unwrap(), 3);
+    let posts: i64 = graph.query("MATCH (p:Post) RETURN count(p)").unwrap();
+    assert_eq!(posts, 1);
+
+    let bob = Author {
+        handle: "bob".to_string(),
+    };
+    match graph.delete_entity(&bob) {
+        Err(RedisGraphError::DeleteRestricted { rel_type, count }) => {
+            assert_eq!(rel_type, "FOLLOWS");
+            assert_eq!(count, 1);
+        }
+        any => panic!("expected restricted deletion, found {:?}", any),
+    }
+    assert!(graph.delete_entity(&PersonExample::default()).is_err());
+}
+
+#[graph_test]
+fn test_soft_delete_entity(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (a:Author {handle: 'alice'})-[:WROTE]->(:Post), (a)-[:WROTE]->(:Post)")
+        .unwrap();
+    graph.set_soft_delete(true);
+
+    let alice = Author {
+        handle: "alice".to_string(),
+    };
+    assert_eq!(graph.delete_entity(&alice).unwrap(), 3);
+    assert_eq!(graph.delete_entity(&alice).unwrap(), 0);
+    let (nodes, rels): (i64, i64) = graph
+        .query("MATCH (a:Author)-[r:WROTE]->(p:Post) WHERE p.deleted_at IS NOT NULL RETURN count(DISTINCT p), count(r)")
+        .unwrap();
+    assert_eq!((nodes, rels), (2, 2));
+
+    assert!(graph.find_like(&alice).unwrap().is_empty());
+    let id: i64 = graph.query("MATCH (a:Author) RETURN id(a)").unwrap();
+    let loaded: Vec<Option<Author>> = graph.load_all(&[id as u64]).unwrap();
+    assert!(loaded[0].is_none());
+
+    graph.set_soft_delete(false);
+    assert_eq!(graph.find_like(&alice).unwrap().len(), 1);
+}
+
+#[test]
+fn test_derive_entity_hooks() {
+    let mut order = Order {
+        number: "A1".to_string(),
+        quantity: 3,
+        unit_price: 5,
+        total: 0,
+    };
+    order.before_save().unwrap();
+    order.after_load().unwrap();
+    assert_eq!(order.total, 15);
+
+    order.quantity = 0;
+    assert!(matches!(
+        order.before_save(),
+        Err(RedisGraphError::InvalidEntity(_))
+    ));
+    assert!(PersonExample::default().before_save().is_ok());
+}
+
+#[graph_test]
+fn test_save_entity(graph: &mut Graph) {
+    let mut order = Order {
+        number: "A1".to_string(),
+        quantity: 3,
+        unit_price: 5,
+        total: 0,
+    };
+    graph.save_entity(&mut order).unwrap();
+    order.quantity = 4;
+    graph.save_entity(&mut order).unwrap();
+
+    // Non-optional fields are always part of the example.
+    let orders = graph.find_like(&Order { total: 0, ..order }).unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].total, 20);
+
+    let mut empty = Order {
+        number: "A2".to_string(),
+        ..Default::default()
+    };
+    assert!(graph.save_entity(&mut empty).is_err());
+    let count: i64 = graph.query("MATCH (o:Order) RETURN count(o)").unwrap();
+    assert_eq!(count, 1);
+}
+
+#[graph_test]
+fn test_save_all_load_all(graph: &mut Graph) {
+    let order = |number: &str, quantity| Order {
+        number: number.to_string(),
+        quantity,
+        unit_price: 2,
+        total: 0,
+    };
+    let mut orders = vec![order("A1", 1), order("A2", 2)];
+    graph.save_all(&mut orders).unwrap();
+    orders[1].quantity = 5;
+    graph.save_all(&mut orders).unwrap();
+    let mut people = vec![PersonExample::default(), PersonExample::default()];
+    graph.save_all(&mut people).unwrap();
+
+    let ids: Vec<i64> = graph
+        .query("MATCH (o:Order) RETURN id(o) ORDER BY o.number")
+        .unwrap();
+    let ids: Vec<u64> = ids.into_iter().map(|id| id as u64).collect();
+    let loaded: Vec<Option<Order>> = graph.load_all(&[ids[1], 1000, ids[0]]).unwrap();
+    assert_eq!(loaded[0].as_ref().unwrap().total, 10);
+    assert!(loaded[1].is_none());
+    assert_eq!(loaded[2].as_ref().unwrap().number, "A1");
+    let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(count, 2);
+
+    let mut invalid = vec![order("A3", 1), order("A4", 0)];
+    assert!(graph.save_all(&mut invalid).is_err());
+    let count: i64 = graph.query("MATCH (o:Order) RETURN count(o)").unwrap();
+    assert_eq!(count, 2);
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_derive_encrypted_fields() {
+    use redisgraph::codec::{EncryptedCodec, PropertyCodec};
+
+    let patient = Patient {
+        name: "Alice".to_string(),
+        insurance_number: "A123456789".to_string(),
+        diagnosis: None,
+    };
+    let props = patient.to_properties();
+    assert_eq!(props.len(), 2);
+    let encrypted = match props.get("insurance") {
+        Some(CypherValue::String(encrypted)) => encrypted.clone(),
+        other => panic!("expected an encrypted string, got {:?}", other),
+    };
+    assert!(!encrypted.contains("A123456789"));
+    let codec = EncryptedCodec::new(&[7; 32]);
+    assert_eq!(codec.decode::<String>(&encrypted).unwrap(), "A123456789");
+
+    let mut properties = HashMap::new();
+    properties.insert(
+        "name".to_string().into(),
+        Scalar::String("Alice".to_string().into()),
+    );
+    properties.insert(
+        "insurance".to_string().into(),
+        Scalar::String(encrypted.into()),
+    );
+    properties.insert(
+        "diagnosis".to_string().into(),
+        Scalar::String(codec.encode("flu").unwrap().into()),
+    );
+    let node = Node {
+        labels: vec!["Patient".to_string().into()],
+        properties,
+    };
+    assert_eq!(
+        Patient::from_node(&node).unwrap(),
+        Patient {
+            diagnosis: Some("flu".to_string()),
+            ..patient
+        }
+    );
+}
+
+#[cfg(feature = "encryption")]
+#[graph_test]
+fn test_save_encrypted_entity(graph: &mut Graph) {
+    let mut patient = Patient {
+        name: "Alice".to_string(),
+        insurance_number: "A123456789".to_string(),
+        diagnosis: Some("flu".to_string()),
+    };
+    graph.save_entity(&mut patient).unwrap();
+
+    let (insurance, diagnosis): (String, String) = graph
+        .query("MATCH (p:Patient) RETURN p.insurance, p.diagnosis")
+        .unwrap();
+    assert!(!insurance.contains("A123456789"));
+    assert!(!diagnosis.contains("flu"));
+
+    let id: i64 = graph.query("MATCH (p:Patient) RETURN id(p)").unwrap();
+    let loaded: Vec<Option<Patient>> = graph.load_all(&[id as u64]).unwrap();
+    assert_eq!(loaded[0].as_ref(), Some(&patient));
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use redisgraph::{
+    dual_write::{DualWriteGraph, DualWritePolicy, SecondarySink},
+    params::CypherValue,
+    testing::{test_connection, unique_graph_name},
+    Graph, RedisGraphError,
+};
+
+#[derive(Default)]
+struct RecordingSink {
+    applied: Vec<String>,
+    fail: bool,
+}
+
+impl SecondarySink for RecordingSink {
+    fn apply(
+        &mut self,
+        query: &str,
+        _params: &HashMap<String, CypherValue>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.fail {
+            return Err("secondary unavailable".into());
+        }
+        self.applied.push(query.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_dual_write() {
+    let graph = Graph::open(test_connection(), unique_graph_name("dual_write")).unwrap();
+    let mut graph = DualWriteGraph::new(graph, RecordingSink::default(), DualWritePolicy::Strict);
+
+    graph.mutate("CREATE (:Person {name: 'Alice'})").unwrap();
+    assert_eq!(
+        graph.secondary().applied,
+        vec!["CREATE (:Person {name: 'Alice'})".to_string()]
+    );
+
+    // Mutations that fail on the primary graph aren't passed on.
+    assert!(graph.mutate("CREATE (").is_err());
+    assert_eq!(graph.secondary().applied.len(), 1);
+
+    graph.secondary().fail = true;
+    assert!(matches!(
+        graph.mutate("CREATE (:Person {name: 'Bob'})"),
+        Err(RedisGraphError::SecondaryWriteFailed(_))
+    ));
+
+    graph.set_policy(DualWritePolicy::BestEffort);
+    graph.mutate("CREATE (:Person {name: 'Carol'})").unwrap();
+    let failed = graph.take_failed();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].query, "CREATE (:Person {name: 'Carol'})");
+    assert!(graph.take_failed().is_empty());
+
+    let people: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(people, 3);
+
+    let (graph, _) = graph.into_inner();
+    graph.delete().unwrap();
+}
@@ -0,0 +1,63 @@
+use redisgraph::Graph;
+use redisgraph_derive::graph_test;
+
+#[graph_test]
+fn test_embeddings(graph: &mut Graph) {
+    let ids: [i64; 3] = graph
+        .query("CREATE (a:Product), (b:Product), (c:Product {embedding: [1, 2]}) RETURN id(a), id(b), id(c)")
+        .unwrap();
+    let ids = ids.map(|id| id as u64);
+
+    graph
+        .set_embedding(ids[0], "embedding", &[0.5, 0.25])
+        .unwrap();
+    let mut embeddings = graph.get_embeddings("Product", "embedding").unwrap();
+    embeddings.sort_by_key(|(id, _)| *id);
+    assert_eq!(
+        embeddings,
+        vec![(ids[0], vec![0.5, 0.25]), (ids[2], vec![1.0, 2.0])]
+    );
+
+    graph
+        .set_embeddings("embedding", &[(ids[0], &[1.5]), (ids[1], &[0.0, 1.0])])
+        .unwrap();
+    let embeddings = graph
+        .get_embeddings_of("embedding", &[ids[1], ids[0], 1000])
+        .unwrap();
+    assert_eq!(
+        embeddings,
+        vec![Some(vec![0.0, 1.0]), Some(vec![1.5]), None]
+    );
+}
+
+#[graph_test]
+fn test_knn(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE VECTOR INDEX FOR (p:Product) ON (p.embedding) \
+             OPTIONS {dimension: 2, similarityFunction: 'euclidean'}",
+        )
+        .unwrap();
+    let ids: [i64; 3] = graph
+        .query("CREATE (a:Product {name: 'a'}), (b:Product {name: 'b'}), (c:Product {name: 'c'}) RETURN id(a), id(b), id(c)")
+        .unwrap();
+    let ids = ids.map(|id| id as u64);
+    graph
+        .set_embeddings(
+            "embedding",
+            &[
+                (ids[0], &[0.0, 0.0]),
+                (ids[1], &[1.0, 1.0]),
+                (ids[2], &[5.0, 5.0]),
+            ],
+        )
+        .unwrap();
+
+    let neighbors = graph.knn("Product", "embedding", &[0.9, 0.9], 2).unwrap();
+    let names: Vec<String> = neighbors
+        .iter()
+        .map(|(node, _)| node.get("name").unwrap())
+        .collect();
+    assert_eq!(names, vec!["b", "a"]);
+    assert!(neighbors[0].1 <= neighbors[1].1);
+}
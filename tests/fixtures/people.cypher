@@ -0,0 +1,3 @@
+// Three people, two of which know each other.
+CREATE (:Person { name: 'Alice' })-[:KNOWS]->(:Person { name: 'Bob' });
+CREATE (:Person { name: 'Carol; the third' });
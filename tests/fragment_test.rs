@@ -0,0 +1,50 @@
+use redisgraph::{fragment::QueryFragment, params::CypherValue, RedisGraphError};
+
+#[test]
+fn test_fragment_composition() {
+    let conditions = vec![
+        QueryFragment::new("p.age >= $min").bind("min", 18),
+        QueryFragment::default(),
+        QueryFragment::new("p.name = $name").bind("name", "Alice"),
+    ];
+    let query = QueryFragment::new("MATCH (p) WHERE")
+        .append(QueryFragment::join(conditions, " OR ").parenthesized())
+        .append(QueryFragment::new("RETURN"))
+        .append_property("p", "weird`key");
+
+    let (text, params) = query.into_parts().unwrap();
+    assert_eq!(
+        text,
+        "MATCH (p) WHERE (p.age >= $min OR p.name = $name) RETURN p.`weird``key`"
+    );
+    assert_eq!(params["min"], CypherValue::Integer(18));
+    assert_eq!(params["name"], CypherValue::from("Alice"));
+}
+
+#[test]
+fn test_fragment_append_property() {
+    let query = QueryFragment::new("RETURN").append_property("not a variable", "name");
+    assert_eq!(query.text(), "RETURN `not a variable`.`name`");
+}
+
+#[test]
+fn test_fragment_unbound_params() {
+    let fragment = QueryFragment::new("RETURN $a, '$not_a_param', $b").bind("a", 1);
+    assert_eq!(fragment.unbound_params(), vec!["b".to_string()]);
+    match fragment.into_parts() {
+        Err(RedisGraphError::UnboundParameter(name)) => assert_eq!(name, "b"),
+        other => panic!("expected unbound parameter, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_fragment_conflicting_params() {
+    let fragment = QueryFragment::new("RETURN $a")
+        .bind("a", 1)
+        .append(QueryFragment::new("+ $a").bind("a", 1))
+        .append(QueryFragment::new("+ $a").bind("a", 2));
+    match fragment.into_parts() {
+        Err(RedisGraphError::ConflictingParameter(name)) => assert_eq!(name, "a"),
+        other => panic!("expected conflicting parameter, got {:?}", other),
+    }
+}
@@ -1,15 +1,695 @@
-mod common;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use redisgraph::Graph;
-use serial_test::serial;
-
-use common::*;
+use redis::Value;
+use redisgraph::{
+    chunk::ChunkOptions,
+    commands::CommandNames,
+    events::ConnectionEvent,
+    graph::CreateMode,
+    import::{EdgeRecord, ImportOptions, KeyedEdge, KeyedNode, NodeRecord},
+    labels::Labels,
+    params::CypherValue,
+    pattern::{node, Direction},
+    props::Props,
+    reference::NodeRef,
+    registry::{ParamType, QueryRegistry, TouchMode},
+    result_set::{FromRedisValueWithGraph, Node, Scalar},
+    schema::SchemaMapping,
+    testing::{test_connection, unique_graph_name},
+    ttl::EXPIRES_AT,
+    Graph, RedisGraphError, RedisString, ResultSet,
+};
+use redisgraph_derive::graph_test;
 
 #[test]
-#[serial]
 fn test_open_delete() {
-    let conn = get_connection();
+    let conn = test_connection();
+
+    let graph = Graph::open(conn, unique_graph_name("test_open_delete_graph")).unwrap();
+    graph.delete().unwrap();
+}
+
+#[graph_test]
+fn test_node_ref(graph: &mut Graph) {
+    let id: u64 = graph
+        .query("CREATE (n:Person { name: 'Alice' }) RETURN id(n)")
+        .unwrap();
+    let node_ref = graph.node_ref(id);
+    assert!(node_ref.exists(graph).unwrap());
+    let node = node_ref.load(graph).unwrap().unwrap();
+    assert_eq!(node.labels, vec!["Person".to_string().into()]);
+
+    graph.mutate("MATCH (n) DELETE n").unwrap();
+    assert!(!node_ref.exists(graph).unwrap());
+    assert_eq!(node_ref.load(graph).unwrap(), None);
+
+    let foreign_ref = NodeRef::new("other_graph".to_string(), id);
+    assert!(foreign_ref.exists(graph).is_err());
+}
+
+#[graph_test]
+fn test_with_lock(graph: &mut Graph) {
+    let value = graph
+        .with_lock(
+            "migration",
+            Duration::from_secs(5),
+            Duration::from_secs(1),
+            |graph| graph.query::<i64>("RETURN 42"),
+        )
+        .unwrap();
+    assert_eq!(value, 42);
+
+    // The lock was released, so it can be acquired again.
+    let mut other = Graph::open(test_connection(), graph.name().to_string()).unwrap();
+    let nested = graph.with_lock(
+        "migration",
+        Duration::from_secs(5),
+        Duration::from_secs(1),
+        |_| {
+            // Gives up after the wait, long before the lock expires.
+            let started = Instant::now();
+            let result = other.with_lock(
+                "migration",
+                Duration::from_secs(5),
+                Duration::from_millis(200),
+                |_| Ok(()),
+            );
+            assert!(started.elapsed() < Duration::from_secs(2));
+            result
+        },
+    );
+    assert!(matches!(nested, Err(RedisGraphError::LockNotAcquired(_))));
+}
+
+#[graph_test]
+fn test_mutate_idempotent(graph: &mut Graph) {
+    assert!(graph
+        .mutate_idempotent("message-1", "CREATE (:Event)")
+        .unwrap());
+    assert!(!graph
+        .mutate_idempotent("message-1", "CREATE (:Event)")
+        .unwrap());
+    let count: i64 = graph.query("MATCH (n:Event) RETURN count(n)").unwrap();
+    assert_eq!(count, 1);
+
+    // Failed mutations can be retried.
+    assert!(graph
+        .mutate_idempotent("message-2", "INVALID CYPHER")
+        .is_err());
+    assert!(graph
+        .mutate_idempotent("message-2", "CREATE (:Event)")
+        .unwrap());
+
+    // Executed keys are kept for good.
+    let ttl: i64 = redis::cmd("PTTL")
+        .arg(format!("{}:dedup:message-1", graph.name()))
+        .query(&mut test_connection())
+        .unwrap();
+    assert_eq!(ttl, -1);
+
+    redis::cmd("DEL")
+        .arg(format!("{}:dedup:message-1", graph.name()))
+        .arg(format!("{}:dedup:message-2", graph.name()))
+        .query::<()>(&mut test_connection())
+        .unwrap();
+}
+
+#[graph_test]
+fn test_mutate_idempotent_abandoned_claim(graph: &mut Graph) {
+    // A claim left behind by a process that crashed before recording the outcome.
+    let key = format!("{}:dedup:message-1", graph.name());
+    redis::cmd("SET")
+        .arg(&key)
+        .arg("pending")
+        .arg("PX")
+        .arg(100)
+        .query::<()>(&mut test_connection())
+        .unwrap();
+    assert!(!graph
+        .mutate_idempotent("message-1", "CREATE (:Event)")
+        .unwrap());
+
+    thread::sleep(Duration::from_millis(150));
+    assert!(graph
+        .mutate_idempotent("message-1", "CREATE (:Event)")
+        .unwrap());
+    let count: i64 = graph.query("MATCH (n:Event) RETURN count(n)").unwrap();
+    assert_eq!(count, 1);
+
+    redis::cmd("DEL")
+        .arg(&key)
+        .query::<()>(&mut test_connection())
+        .unwrap();
+}
+
+#[graph_test]
+fn test_on_mutation(graph: &mut Graph) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    graph.on_mutation(move |event| sink.lock().unwrap().push(event.query.clone()));
+
+    graph.mutate("CREATE (:Event)").unwrap();
+    graph.query::<i64>("RETURN 1").unwrap();
+    graph.create_node("Event", &Props::new()).unwrap();
+    assert!(graph.mutate("INVALID CYPHER").is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0], "CREATE (:Event)");
+}
+
+#[graph_test]
+fn test_on_connection_event(graph: &mut Graph) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    graph.on_connection_event(move |event| sink.lock().unwrap().push(event.clone()));
+
+    graph.reconnect(test_connection());
+    graph.query::<i64>("RETURN 1").unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        ConnectionEvent::Reconnected { graph_name, .. } => assert_eq!(graph_name, graph.name()),
+        other => panic!("expected reconnected event, got {:?}", other),
+    }
+}
 
-    let graph = Graph::open(conn, "test_open_delete_graph".to_string()).unwrap();
+#[graph_test]
+fn test_aggregate(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person { age: 20 }), (:Person { age: 30 }), (:Dog { age: 5 })")
+        .unwrap();
+
+    let mut aggregate = graph.aggregate();
+    assert_eq!(aggregate.count("Person").unwrap(), 2);
+    assert_eq!(aggregate.count(&[][..]).unwrap(), 3);
+    assert_eq!(aggregate.sum::<i64, _>("Person", "age").unwrap(), 50);
+    assert_eq!(aggregate.avg("Person", "age").unwrap(), Some(25.0));
+    assert_eq!(aggregate.avg("Cat", "age").unwrap(), None);
+    assert_eq!(
+        aggregate.min::<Option<i64>, _>("Person", "age").unwrap(),
+        Some(20)
+    );
+    assert_eq!(aggregate.max::<Option<i64>, _>("Cat", "age").unwrap(), None);
+}
+
+#[test]
+fn test_command_names() {
+    let names = CommandNames::with_prefix("FALKOR");
+    assert_eq!(names.query, "FALKOR.QUERY");
+    assert_eq!(names.ro_query, "FALKOR.RO_QUERY");
+    assert_eq!(names.delete, "FALKOR.DELETE");
+    assert_eq!(CommandNames::default().profile, "GRAPH.PROFILE");
+}
+
+#[test]
+fn test_detect_command_names() {
+    let mut conn = test_connection();
+    let names = CommandNames::detect(&mut conn, &["NOT_A_MODULE", "GRAPH"])
+        .unwrap()
+        .unwrap();
+    assert_eq!(names, CommandNames::default());
+
+    let graph =
+        Graph::open_with_commands(conn, unique_graph_name("test_detect_graph"), names).unwrap();
+    assert_eq!(graph.command_names().query, "GRAPH.QUERY");
+    graph.delete().unwrap();
+}
+
+#[test]
+fn test_create_modes() {
+    let name = unique_graph_name("test_create_modes");
+    match Graph::open_with_create_mode(
+        test_connection(),
+        name.clone(),
+        CommandNames::default(),
+        CreateMode::Never,
+    ) {
+        Err(RedisGraphError::GraphNotFound(missing)) => assert_eq!(missing, name),
+        _ => panic!("expected GraphNotFound"),
+    }
+
+    let mut graph = Graph::open_with_create_mode(
+        test_connection(),
+        name.clone(),
+        CommandNames::default(),
+        CreateMode::IfMissing,
+    )
+    .unwrap();
+    graph.mutate("CREATE (:Person {name: 'Alice'})").unwrap();
+
+    let mut existing = Graph::open_with_create_mode(
+        test_connection(),
+        name,
+        CommandNames::default(),
+        CreateMode::Never,
+    )
+    .unwrap();
+    let count: i64 = existing.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(count, 1);
     graph.delete().unwrap();
 }
+
+#[test]
+fn test_schema_mapping_from_names() {
+    let mapping = SchemaMapping::from_names(&["Person"], &[], &["name", "age"]);
+    assert_eq!(
+        mapping.labels,
+        vec![RedisString::from("Person".to_string())]
+    );
+    assert!(mapping.relationship_types.is_empty());
+    assert_eq!(mapping.property_keys.len(), 2);
+}
+
+#[graph_test]
+fn test_static_schema(graph: &mut Graph) {
+    graph.mutate("CREATE (:Person { name: 'Alice' })").unwrap();
+    let _: Vec<Node> = graph.query("MATCH (n) RETURN n").unwrap();
+    let mapping = graph.schema_mapping();
+    assert_eq!(
+        mapping.labels,
+        vec![RedisString::from("Person".to_string())]
+    );
+
+    graph.use_static_schema(mapping);
+    let nodes: Vec<Node> = graph.query("MATCH (n:Person) RETURN n").unwrap();
+    assert_eq!(nodes.len(), 1);
+
+    graph.mutate("CREATE (:Dog)").unwrap();
+    match graph.query::<Vec<Node>>("MATCH (n:Dog) RETURN n") {
+        Err(RedisGraphError::LabelNotFound) => {}
+        other => panic!("expected label not found, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_raw_query(graph: &mut Graph) {
+    match graph.raw_query("RETURN 1").unwrap() {
+        Value::Bulk(parts) => assert_eq!(parts.len(), 3),
+        other => panic!("expected bulk response, got {:?}", other),
+    }
+
+    let result_set = graph.query_result_set("RETURN 1, 'a'").unwrap();
+    assert_eq!(result_set.num_columns(), 2);
+    assert_eq!(result_set.num_rows(), 1);
+}
+
+#[graph_test]
+fn test_reply_diagnostics(graph: &mut Graph) {
+    let reply = || {
+        Value::Bulk(vec![
+            Value::Bulk(vec![Value::Bulk(vec![
+                Value::Int(1),
+                Value::Data(b"n".to_vec()),
+            ])]),
+            Value::Bulk(vec![
+                Value::Bulk(vec![Value::Bulk(vec![Value::Int(3), Value::Int(1)])]),
+                Value::Bulk(vec![Value::Okay]),
+            ]),
+            Value::Bulk(vec![]),
+        ])
+    };
+
+    match ResultSet::from_redis_value_with_graph(reply(), graph) {
+        Err(RedisGraphError::ServerTypeError(message)) => assert!(!message.contains("reply[")),
+        other => panic!("expected server type error, got {:?}", other),
+    }
+
+    graph.set_reply_diagnostics(true);
+    match ResultSet::from_redis_value_with_graph(reply(), graph) {
+        Err(RedisGraphError::ServerTypeError(message)) => {
+            assert!(message.ends_with("(at reply[1][1][0], column 0: Okay)"))
+        }
+        other => panic!("expected server type error, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_lenient_booleans(graph: &mut Graph) {
+    let reply = |value| {
+        Value::Bulk(vec![
+            Value::Bulk(vec![Value::Bulk(vec![
+                Value::Int(1),
+                Value::Data(b"b".to_vec()),
+            ])]),
+            Value::Bulk(vec![Value::Bulk(vec![Value::Bulk(vec![
+                Value::Int(4),
+                value,
+            ])])]),
+            Value::Bulk(vec![]),
+        ])
+    };
+
+    assert!(graph.lenient_booleans());
+    let result_set = ResultSet::from_redis_value_with_graph(reply(Value::Int(1)), graph).unwrap();
+    assert_eq!(result_set.get_scalar(0, 0).unwrap(), &Scalar::Boolean(true));
+    let result_set =
+        ResultSet::from_redis_value_with_graph(reply(Value::Data(b"false".to_vec())), graph)
+            .unwrap();
+    assert_eq!(
+        result_set.get_scalar(0, 0).unwrap(),
+        &Scalar::Boolean(false)
+    );
+
+    graph.set_lenient_booleans(false);
+    match ResultSet::from_redis_value_with_graph(reply(Value::Int(0)), graph) {
+        Err(RedisGraphError::ServerTypeError(_)) => {}
+        other => panic!("expected server type error, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_exists_match(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person { name: 'Alice' })-[:KNOWS]->(:Person { name: 'Bob' })")
+        .unwrap();
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), CypherValue::from("Alice"));
+    assert!(graph
+        .exists_match("(p:Person) WHERE p.name = $name", &params)
+        .unwrap());
+    params.insert("name".to_string(), CypherValue::from("Carol"));
+    assert!(!graph
+        .exists_match("(:Person {name: $name})", &params)
+        .unwrap());
+
+    let knows = node("a").rel("KNOWS", Direction::Outgoing).node("b");
+    assert!(graph.exists_match(&knows, &HashMap::new()).unwrap());
+    let likes = node("a").rel("LIKES", Direction::Both).node("b");
+    assert!(!graph.exists_match(&likes, &HashMap::new()).unwrap());
+}
+
+#[graph_test]
+fn test_count_rows(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person { age: 20 }), (:Person { age: 30 }), (:Person { age: 40 })")
+        .unwrap();
+
+    assert_eq!(graph.count_rows("MATCH (p:Person) RETURN p").unwrap(), 3);
+    assert_eq!(
+        graph
+            .count_rows("MATCH (p:Person) RETURN p ORDER BY p.age LIMIT 2;")
+            .unwrap(),
+        2
+    );
+    let mut params = HashMap::new();
+    params.insert("min".to_string(), CypherValue::from(25));
+    assert_eq!(
+        graph
+            .count_rows_with_params("MATCH (p:Person) WHERE p.age > $min RETURN p", &params)
+            .unwrap(),
+        2
+    );
+}
+
+#[graph_test]
+fn test_query_lossy(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person { age: 30 }), (:Person { age: 'unknown' })")
+        .unwrap();
+    let (ages, errors) = graph
+        .query_lossy::<i64>("MATCH (p:Person) RETURN p.age")
+        .unwrap();
+    assert_eq!(ages, vec![30]);
+    assert_eq!(errors.len(), 1);
+}
+
+#[graph_test(fixture = "tests/fixtures/people.cypher")]
+fn test_graph_test_fixture(graph: &mut Graph) {
+    let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(count, 3);
+}
+
+#[graph_test]
+fn test_stats(graph: &mut Graph) {
+    assert!(graph.stats().is_none());
+
+    graph.set_stats_enabled(true);
+    graph.mutate("CREATE (:Person)").unwrap();
+    graph
+        .query::<ResultSet>("MATCH (p:Person) RETURN p")
+        .unwrap();
+    assert!(graph.mutate("NOT CYPHER").is_err());
+
+    let stats = graph.stats().unwrap();
+    assert!(stats.queries() >= 3);
+    assert_eq!(stats.errors, 1);
+    assert!(stats.latencies.percentile(50.0).is_some());
+
+    graph.reset_stats();
+    assert_eq!(graph.stats().unwrap().queries(), 0);
+    graph.set_stats_enabled(false);
+    assert!(graph.stats().is_none());
+}
+
+#[graph_test]
+fn test_mutate_rows_chunked(graph: &mut Graph) {
+    let rows = (0..10).map(CypherValue::from).collect();
+    let options = ChunkOptions {
+        max_entities: 3,
+        ..ChunkOptions::default()
+    };
+    let report = graph
+        .mutate_rows_chunked(
+            "UNWIND $rows AS row CREATE (:Item { n: row })",
+            "rows",
+            rows,
+            &options,
+        )
+        .unwrap();
+    assert!(report.is_success());
+    assert_eq!(report.chunks.len(), 4);
+
+    let count: i64 = graph.query("MATCH (i:Item) RETURN count(i)").unwrap();
+    assert_eq!(count, 10);
+}
+
+#[graph_test]
+fn test_import_with_temp_ids(graph: &mut Graph) {
+    let nodes = (0..5).map(|i| NodeRecord {
+        temp_id: format!("person-{}", i),
+        labels: Labels::from("Person"),
+        props: Props::new().set("n", i),
+    });
+    let ids = graph.import_nodes(nodes, 2).unwrap();
+    assert_eq!(ids.len(), 5);
+
+    let edges = vec![
+        EdgeRecord {
+            src: "person-0".to_string(),
+            dst: "person-4".to_string(),
+            rel_type: "KNOWS".to_string(),
+            props: Props::new().set("since", 2020),
+        },
+        EdgeRecord {
+            src: "person-4".to_string(),
+            dst: "person-1".to_string(),
+            rel_type: "KNOWS".to_string(),
+            props: Props::new(),
+        },
+    ];
+    assert_eq!(graph.import_edges(edges, &ids, 10).unwrap(), 2);
+
+    let n: i64 = graph
+        .query("MATCH (:Person { n: 0 })-[:KNOWS]->(:Person)-[:KNOWS]->(p:Person) RETURN p.n")
+        .unwrap();
+    assert_eq!(n, 1);
+
+    let unknown = vec![EdgeRecord {
+        src: "person-0".to_string(),
+        dst: "nobody".to_string(),
+        rel_type: "KNOWS".to_string(),
+        props: Props::new(),
+    }];
+    match graph.import_edges(unknown, &ids, 10) {
+        Err(RedisGraphError::UnresolvedImportId(id)) => assert_eq!(id, "\"nobody\""),
+        other => panic!("expected unresolved import id, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_import_keyed(graph: &mut Graph) {
+    let nodes = vec![
+        KeyedNode {
+            label: "Person".to_string(),
+            key: "alice".into(),
+            props: Props::new().set("age", 30),
+        },
+        KeyedNode {
+            label: "Person".to_string(),
+            key: "bob".into(),
+            props: Props::new(),
+        },
+        KeyedNode {
+            label: "City".to_string(),
+            key: "berlin".into(),
+            props: Props::new().set("population", 3_600_000),
+        },
+    ];
+    let edge = |src: &str, dst_label: &str, dst: &str, rel_type: &str| KeyedEdge {
+        src_label: "Person".to_string(),
+        src_key: src.into(),
+        dst_label: dst_label.to_string(),
+        dst_key: dst.into(),
+        rel_type: rel_type.to_string(),
+        props: Props::new(),
+    };
+    let edges = vec![
+        edge("alice", "Person", "bob", "KNOWS"),
+        edge("alice", "City", "berlin", "LIVES_IN"),
+        edge("bob", "City", "paris", "LIVES_IN"),
+    ];
+
+    let summary = graph
+        .import_keyed(&ImportOptions::new("key"), nodes, edges)
+        .unwrap();
+    assert_eq!(summary.indexes_created, 2);
+    assert_eq!(summary.nodes_created, 3);
+    assert_eq!(summary.relationships_created, 2);
+    assert_eq!(summary.unresolved_edges, 1);
+
+    let age: i64 = graph
+        .query("MATCH (p:Person)-[:LIVES_IN]->(:City { key: 'berlin' }) RETURN p.age")
+        .unwrap();
+    assert_eq!(age, 30);
+}
+
+#[graph_test]
+fn test_backfill(graph: &mut Graph) {
+    graph
+        .mutate("UNWIND range(1, 25) AS i CREATE (:Person { name: 'P' + toString(i) })")
+        .unwrap();
+
+    let mut reported = Vec::new();
+    let progress = graph
+        .backfill_with_progress(
+            "MATCH (n:Person) WHERE n.name_lower IS NULL",
+            "n.name_lower = toLower(n.name)",
+            10,
+            Duration::from_millis(1),
+            |progress| reported.push(progress.batch_properties_set),
+        )
+        .unwrap();
+    assert_eq!(reported, vec![10, 10, 5, 0]);
+    assert_eq!(progress.batches, 4);
+    assert_eq!(progress.properties_set, 25);
+
+    let names: Vec<String> = graph
+        .query("MATCH (n:Person) WHERE n.name_lower = 'p7' RETURN n.name")
+        .unwrap();
+    assert_eq!(names, vec!["P7".to_string()]);
+}
+
+#[graph_test]
+fn test_purge_expired(graph: &mut Graph) {
+    graph
+        .create_node("Session", &Props::new().set("n", 1).set(EXPIRES_AT, 0))
+        .unwrap();
+    graph
+        .create_node(
+            "Session",
+            &Props::new()
+                .set("n", 2)
+                .expires_in(Duration::from_secs(3600)),
+        )
+        .unwrap();
+    let id: i64 = graph
+        .query("CREATE (s:Session { n: 3 }) RETURN id(s)")
+        .unwrap();
+
+    assert_eq!(graph.purge_expired("Session").unwrap(), 1);
+    graph.set_expiry(id as u64, Duration::from_secs(0)).unwrap();
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(graph.purge_expired("Session").unwrap(), 1);
+
+    let remaining: Vec<i64> = graph.query("MATCH (s:Session) RETURN s.n").unwrap();
+    assert_eq!(remaining, vec![2]);
+}
+
+#[graph_test]
+fn test_soft_delete(graph: &mut Graph) {
+    let ids: Vec<i64> = graph
+        .query("UNWIND range(1, 3) AS i CREATE (p:Person { n: i }) RETURN id(p)")
+        .unwrap();
+
+    graph.set_soft_delete(true);
+    graph.delete_node(ids[0] as u64).unwrap();
+    assert_eq!(graph.find_nodes("Person", &Props::new()).unwrap().len(), 2);
+    let all: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(all, 3);
+
+    graph.set_soft_delete(false);
+    assert_eq!(graph.find_nodes("Person", &Props::new()).unwrap().len(), 3);
+    graph.delete_node(ids[1] as u64).unwrap();
+    assert_eq!(graph.purge_deleted("Person").unwrap(), 1);
+
+    let remaining: Vec<i64> = graph.query("MATCH (p:Person) RETURN p.n").unwrap();
+    assert_eq!(remaining, vec![3]);
+}
+
+#[graph_test]
+fn test_next_id(graph: &mut Graph) {
+    assert_eq!(graph.next_id("orders").unwrap(), 1);
+    assert_eq!(graph.next_id("orders").unwrap(), 2);
+    assert_eq!(graph.next_id("invoices").unwrap(), 1);
+    assert_eq!(graph.next_id("orders").unwrap(), 3);
+}
+
+#[graph_test]
+fn test_query_named(graph: &mut Graph) {
+    let mut registry = QueryRegistry::new();
+    registry
+        .register(
+            "create_person",
+            "CREATE (:Person { name: $name })",
+            &[("name", ParamType::String)],
+        )
+        .unwrap();
+    registry
+        .register("count_people", "MATCH (p:Person) RETURN count(p)", &[])
+        .unwrap();
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), CypherValue::from("Alice"));
+    graph
+        .mutate_named(&registry, "create_person", &params)
+        .unwrap();
+
+    let count: i64 = graph
+        .query_named(&registry, "count_people", &HashMap::new())
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[graph_test]
+fn test_touch(graph: &mut Graph) {
+    let mut registry = QueryRegistry::new();
+    registry
+        .register(
+            "create_person",
+            "CREATE (:Person { name: $name, age: $age })",
+            &[("name", ParamType::String), ("age", ParamType::Integer)],
+        )
+        .unwrap();
+    registry
+        .register("count_people", "MATCH (p:Person) RETURN count(p)", &[])
+        .unwrap();
+
+    graph.touch(&registry, TouchMode::Explain).unwrap();
+    let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(count, 0);
+
+    graph.touch(&registry, TouchMode::Execute).unwrap();
+    let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(count, 1);
+
+    assert!(!graph
+        .explain("MATCH (p:Person) RETURN p")
+        .unwrap()
+        .is_empty());
+}
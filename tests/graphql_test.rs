@@ -0,0 +1,101 @@
+#![cfg(feature = "async-graphql")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context as TaskContext, Poll, Waker};
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use redisgraph::graphql::node_query;
+
+#[derive(SimpleObject)]
+struct Person {
+    name: String,
+    age: i64,
+    friends: Vec<String>,
+    address: Address,
+}
+
+#[derive(SimpleObject)]
+struct Address {
+    city: String,
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    // Resolves to the generated query instead of running it, the selection set only serves
+    // as input for `node_query`.
+    async fn people(
+        &self,
+        ctx: &Context<'_>,
+        city: Option<String>,
+        min_age: Option<i64>,
+    ) -> async_graphql::Result<Person> {
+        let _ = (city, min_age);
+        let (query, fields) = node_query("Person", ctx.field())
+            .map_err(|err| async_graphql::Error::new(format!("{:?}", err)))?;
+        let mut params: Vec<String> = query
+            .params()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        params.sort();
+        Err(async_graphql::Error::new(format!(
+            "{}|{}|{}",
+            query.query(),
+            params.join(","),
+            fields.join(",")
+        )))
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = TaskContext::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn generate(request: &str) -> String {
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let response = block_on(schema.execute(request));
+    response.errors[0].message.clone()
+}
+
+#[test]
+fn test_node_query() {
+    assert_eq!(
+        generate(r#"{ people(city: "Berlin") { name years: age __typename } }"#),
+        "MATCH (`n`:`Person`) WHERE `n`.`city` = $city RETURN `n`.`name` AS `name`, `n`.`age` AS `years`\
+         |city=\"Berlin\"|name,years"
+    );
+}
+
+#[test]
+fn test_node_query_without_arguments() {
+    assert_eq!(
+        generate("{ people { friends } }"),
+        "MATCH (`n`:`Person`) RETURN `n`.`friends` AS `friends`||friends"
+    );
+}
+
+#[test]
+fn test_node_query_multiple_arguments() {
+    assert_eq!(
+        generate("{ people(city: \"Berlin\", minAge: 30) { name } }"),
+        "MATCH (`n`:`Person`) WHERE (`n`.`city` = $city) AND (`n`.`minAge` = $minAge) \
+         RETURN `n`.`name` AS `name`|city=\"Berlin\",minAge=30|name"
+    );
+}
+
+#[test]
+fn test_node_query_nested_selection() {
+    assert_eq!(
+        generate("{ people { name address { city } } }"),
+        "UnsupportedGraphqlField(\"address\")"
+    );
+}
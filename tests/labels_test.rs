@@ -0,0 +1,40 @@
+use redisgraph::{
+    labels::{LabelMatch, Labels},
+    pattern::node,
+    props::Props,
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_labels_dedup() {
+    let labels = Labels::from(&["A", "B"]).with("A");
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels.to_string(), ":`A`:`B`");
+    assert_eq!(node("n").labels(&labels).to_string(), "(`n`:`A`:`B`)");
+}
+
+#[test]
+fn test_label_match_predicates() {
+    assert_eq!(LabelMatch::from(&["A", "B"]).predicate("n"), "`n`:`A`:`B`");
+    assert_eq!(
+        LabelMatch::Any(Labels::from(&["A", "B"])).predicate("n"),
+        "(`n`:`A` OR `n`:`B`)"
+    );
+    assert_eq!(LabelMatch::All(Labels::new()).predicate("n"), "true");
+    assert_eq!(LabelMatch::Any(Labels::new()).predicate("n"), "false");
+}
+
+#[graph_test]
+fn test_find_nodes_any_all(graph: &mut Graph) {
+    graph.create_node(&["A", "B"], &Props::new()).unwrap();
+    graph.create_node("A", &Props::new()).unwrap();
+    graph.create_node("C", &Props::new()).unwrap();
+
+    let all = graph.find_nodes(&["A", "B"], &Props::new()).unwrap();
+    assert_eq!(all.len(), 1);
+    let any = graph
+        .find_nodes(LabelMatch::Any(Labels::from(&["B", "C"])), &Props::new())
+        .unwrap();
+    assert_eq!(any.len(), 2);
+}
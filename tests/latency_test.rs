@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use redisgraph::latency::LatencyHistogram;
+
+#[test]
+fn test_histogram_empty() {
+    let histogram = LatencyHistogram::new();
+    assert!(histogram.is_empty());
+    assert_eq!(histogram.min(), None);
+    assert_eq!(histogram.mean(), None);
+    assert_eq!(histogram.percentile(99.0), None);
+}
+
+#[test]
+fn test_histogram_exact_below_64_micros() {
+    let mut histogram = LatencyHistogram::new();
+    for micros in 0..64 {
+        histogram.record(Duration::from_micros(micros));
+    }
+    assert_eq!(histogram.percentile(50.0), Some(Duration::from_micros(31)));
+    assert_eq!(histogram.percentile(100.0), Some(Duration::from_micros(63)));
+    assert_eq!(histogram.min(), Some(Duration::from_micros(0)));
+}
+
+#[test]
+fn test_histogram_relative_error() {
+    let mut histogram = LatencyHistogram::new();
+    for millis in 1..=1000 {
+        histogram.record(Duration::from_millis(millis));
+    }
+    assert_eq!(histogram.count(), 1000);
+    assert_eq!(histogram.mean(), Some(Duration::from_micros(500_500)));
+    assert_eq!(histogram.max(), Some(Duration::from_millis(1000)));
+
+    for &(percentile, expected) in &[(50.0, 500), (90.0, 900), (99.0, 990)] {
+        let actual = histogram.percentile(percentile).unwrap().as_micros() as f64;
+        let expected = Duration::from_millis(expected).as_micros() as f64;
+        assert!(actual >= expected && actual <= expected * 1.04);
+    }
+}
+
+#[test]
+fn test_histogram_merge() {
+    let mut fast = LatencyHistogram::new();
+    fast.record(Duration::from_micros(10));
+    let mut slow = LatencyHistogram::new();
+    slow.record(Duration::from_secs(2));
+
+    fast.merge(&slow);
+    assert_eq!(fast.count(), 2);
+    assert_eq!(fast.min(), Some(Duration::from_micros(10)));
+    assert_eq!(fast.max(), Some(Duration::from_secs(2)));
+    assert_eq!(fast.percentile(100.0), Some(Duration::from_secs(2)));
+}
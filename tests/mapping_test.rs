@@ -0,0 +1,102 @@
+use redisgraph::{
+    mapping::ColumnMapping,
+    result_set::{Column, Scalar, Statistics},
+    Graph, RedisGraphError, ResultSet,
+};
+use redisgraph_derive::graph_test;
+
+#[derive(Debug, Default, PartialEq)]
+struct Report {
+    label: String,
+    value: Option<i64>,
+}
+
+fn report_mapping() -> ColumnMapping<Report> {
+    ColumnMapping::new()
+        .field("label", |report: &mut Report, label| report.label = label)
+        .field("value", |report: &mut Report, value| report.value = value)
+}
+
+fn result_set() -> ResultSet {
+    ResultSet {
+        columns: vec![
+            Column::Scalars(vec![Scalar::Integer(3), Scalar::Nil]),
+            Column::Scalars(vec![
+                Scalar::String("Yamaha".to_string().into()),
+                Scalar::String("Honda".to_string().into()),
+            ]),
+        ],
+        statistics: Statistics(Vec::new()),
+    }
+}
+
+#[test]
+fn test_column_mapping() {
+    let names = vec!["points".to_string(), "team".to_string()];
+    let mapping = report_mapping()
+        .column("label", "team")
+        .column("value", "points");
+    assert_eq!(
+        mapping.bindings().collect::<Vec<_>>(),
+        vec![("label", "team"), ("value", "points")]
+    );
+    assert_eq!(
+        mapping.apply(&names, &result_set()).unwrap(),
+        vec![
+            Report {
+                label: "Yamaha".to_string(),
+                value: Some(3),
+            },
+            Report {
+                label: "Honda".to_string(),
+                value: None,
+            },
+        ]
+    );
+
+    // Without bindings, the fields are read from the columns with their names.
+    assert!(matches!(
+        report_mapping().apply(&names, &result_set()),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+    let typo = report_mapping()
+        .column("lable", "team")
+        .column("value", "points");
+    assert!(matches!(
+        typo.apply(&names, &result_set()),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+    let wrong_type = report_mapping()
+        .column("label", "points")
+        .column("value", "team");
+    assert!(wrong_type.apply(&names, &result_set()).is_err());
+}
+
+#[graph_test]
+fn test_query_mapped(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Team {name: 'Yamaha', points: 3}), (:Team {name: 'Honda'})")
+        .unwrap();
+    let mapping = report_mapping()
+        .column("label", "t.name")
+        .column("value", "t.points");
+    let reports = graph
+        .query_mapped(
+            "MATCH (t:Team) RETURN t.name, t.points ORDER BY t.name",
+            &mapping,
+        )
+        .unwrap();
+    assert_eq!(
+        reports,
+        vec![
+            Report {
+                label: "Honda".to_string(),
+                value: None,
+            },
+            Report {
+                label: "Yamaha".to_string(),
+                value: Some(3),
+            },
+        ]
+    );
+}
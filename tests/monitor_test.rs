@@ -0,0 +1,51 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use redisgraph::{
+    monitor::{GrowthMetric, GrowthMonitor, GrowthSample},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+fn sample(start: Instant, secs: u64, nodes: u64) -> GrowthSample {
+    GrowthSample {
+        taken_at: start + Duration::from_secs(secs),
+        nodes,
+        relationships: 0,
+        memory_bytes: None,
+    }
+}
+
+#[test]
+fn test_growth_monitor() {
+    let alerts = Arc::new(Mutex::new(Vec::new()));
+    let mut monitor = GrowthMonitor::new(2).on_rate_above(GrowthMetric::Nodes, 50.0, {
+        let alerts = Arc::clone(&alerts);
+        move |alert| alerts.lock().unwrap().push(alert.rate)
+    });
+    let start = Instant::now();
+
+    monitor.record(sample(start, 0, 100));
+    assert_eq!(monitor.rate(GrowthMetric::Nodes), None);
+    monitor.record(sample(start, 10, 200));
+    assert_eq!(monitor.rate(GrowthMetric::Nodes), Some(10.0));
+    monitor.record(sample(start, 20, 1200));
+    assert_eq!(monitor.rate(GrowthMetric::Nodes), Some(100.0));
+    assert_eq!(monitor.rate(GrowthMetric::Relationships), Some(0.0));
+    assert_eq!(monitor.rate(GrowthMetric::MemoryBytes), None);
+
+    let nodes: Vec<u64> = monitor.samples().map(|sample| sample.nodes).collect();
+    assert_eq!(nodes, vec![200, 1200]);
+    assert_eq!(monitor.latest().unwrap().nodes, 1200);
+    assert_eq!(*alerts.lock().unwrap(), vec![100.0]);
+}
+
+#[graph_test]
+fn test_sample_growth(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person)-[:KNOWS]->(:Person)")
+        .unwrap();
+    let sample = graph.sample_growth().unwrap();
+    assert_eq!(sample.nodes, 2);
+    assert_eq!(sample.relationships, 1);
+}
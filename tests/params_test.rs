@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use maplit::{btreemap, hashmap};
+use redisgraph::{
+    params::{validate_params, CypherValue},
+    props::{NoneHandling, Props},
+    result_set::{Node, Scalar},
+    Graph, RedisGraphError, RedisString,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_cypher_value_literals() {
+    assert_eq!(CypherValue::Null.to_string(), "null");
+    assert_eq!(CypherValue::from(true).to_string(), "true");
+    assert_eq!(CypherValue::from(42).to_string(), "42");
+    assert_eq!(CypherValue::from(1.0).to_string(), "1.0");
+    assert_eq!(
+        CypherValue::from("say \"hi\" \\o/").to_string(),
+        r#""say \"hi\" \\o/""#
+    );
+    assert_eq!(
+        CypherValue::from(vec![Some(1), None]).to_string(),
+        "[1, null]"
+    );
+}
+
+fn filter() -> CypherValue {
+    let range = btreemap! {
+        "min".to_string() => CypherValue::from(1),
+        "max".to_string() => CypherValue::from(9),
+    };
+    CypherValue::from(btreemap! {
+        "tags".to_string() => CypherValue::from(vec!["a", "b"]),
+        "range".to_string() => CypherValue::from(range),
+        "not an identifier".to_string() => CypherValue::Null,
+    })
+}
+
+#[test]
+fn test_cypher_value_map_literals() {
+    assert_eq!(
+        filter().to_string(),
+        r#"{`not an identifier`: null, range: {max: 9, min: 1}, tags: ["a", "b"]}"#
+    );
+}
+
+#[test]
+fn test_cypher_value_scalar_conversions() {
+    let value = CypherValue::Array(vec![
+        CypherValue::Null,
+        CypherValue::from(true),
+        CypherValue::from(1),
+        CypherValue::from(2.5),
+        CypherValue::from("three"),
+    ]);
+    let scalar = Scalar::from(value.clone());
+    assert_eq!(
+        scalar,
+        Scalar::Array(vec![
+            Scalar::Nil,
+            Scalar::Boolean(true),
+            Scalar::Integer(1),
+            Scalar::Double(2.5),
+            Scalar::String("three".to_string().into()),
+        ])
+    );
+    assert_eq!(CypherValue::try_from(scalar).unwrap(), value);
+    assert_eq!(
+        CypherValue::try_from(Scalar::from(filter())).unwrap(),
+        filter()
+    );
+
+    let invalid_utf8 = Scalar::String(RedisString(vec![0xff]));
+    match CypherValue::try_from(invalid_utf8) {
+        Err(RedisGraphError::InvalidUtf8) => {}
+        other => panic!("expected invalid UTF-8, got {:?}", other),
+    }
+    let node = Scalar::Node(Node {
+        labels: Vec::new(),
+        properties: HashMap::new(),
+    });
+    match CypherValue::try_from(node) {
+        Err(RedisGraphError::ClientTypeError(_)) => {}
+        other => panic!("expected client type error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_props_none_handling() {
+    let skipped = Props::new()
+        .set("name", "Alice")
+        .set_opt::<_, i64>("age", None);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped.get("age"), None);
+
+    let nulled = Props::new()
+        .none_handling(NoneHandling::SetNull)
+        .set_opt::<_, i64>("age", None);
+    assert_eq!(nulled.get("age"), Some(&CypherValue::Null));
+
+    let map: HashMap<String, CypherValue> = skipped.into();
+    assert_eq!(
+        map,
+        hashmap! { "name".to_string() => CypherValue::from("Alice") }
+    );
+}
+
+#[test]
+fn test_validate_params() {
+    let params = hashmap! {
+        "name".to_string() => CypherValue::from("Alice"),
+        "age".to_string() => CypherValue::from(42),
+    };
+    assert!(validate_params(
+        "CREATE (:Person {name: $name, age: $age, alias: $name})",
+        &params
+    )
+    .is_ok());
+    match validate_params("RETURN $name, $agee", &params) {
+        Err(RedisGraphError::UnboundParameter(name)) => assert_eq!(name, "agee"),
+        other => panic!("expected unbound parameter, got {:?}", other),
+    }
+    match validate_params("RETURN $name, '$age'", &params) {
+        Err(RedisGraphError::UnusedParameter(name)) => assert_eq!(name, "age"),
+        other => panic!("expected unused parameter, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_query_with_params(graph: &mut Graph) {
+    let params = hashmap! {
+        "name".to_string() => CypherValue::from("O'Brien \"Bob\""),
+        "age".to_string() => CypherValue::from(42),
+    };
+    let (name, age): (String, i64) = graph
+        .query_with_params("RETURN $name, $age", &params)
+        .unwrap();
+    assert_eq!(name, "O'Brien \"Bob\"");
+    assert_eq!(age, 42);
+    let (name, age): (CypherValue, CypherValue) = graph
+        .query_with_params("RETURN $name, $age", &params)
+        .unwrap();
+    assert_eq!(name, params["name"]);
+    assert_eq!(age, params["age"]);
+
+    let invalid = hashmap! { "not valid".to_string() => CypherValue::Null };
+    match graph.query_with_params::<()>("RETURN 1", &invalid) {
+        Err(RedisGraphError::InvalidParameterName(name)) => assert_eq!(name, "not valid"),
+        other => panic!("expected invalid parameter name, got {:?}", other),
+    }
+
+    graph.set_param_validation(true);
+    match graph.query_with_params::<()>("RETURN $nmae", &params) {
+        Err(RedisGraphError::UnboundParameter(name)) => assert_eq!(name, "nmae"),
+        other => panic!("expected unbound parameter, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_query_with_nested_params(graph: &mut Graph) {
+    let params = hashmap! { "filter".to_string() => filter() };
+    let value: CypherValue = graph.query_with_params("RETURN $filter", &params).unwrap();
+    assert_eq!(value, params["filter"]);
+}
+
+#[graph_test]
+fn test_create_and_merge_node(graph: &mut Graph) {
+    let node = graph
+        .create_node(
+            &["Person", "Employee"],
+            &Props::new().set("name", "Alice").set("age", 30),
+        )
+        .unwrap();
+    assert_eq!(node.labels.len(), 2);
+    assert_eq!(
+        node.properties.get(&"age".to_string().into()),
+        Some(&Scalar::Integer(30))
+    );
+
+    let merged = graph
+        .merge_node(
+            &["Person"],
+            &Props::new().set("name", "Alice"),
+            &Props::new().set("age", 31),
+        )
+        .unwrap();
+    assert_eq!(
+        merged.properties.get(&"age".to_string().into()),
+        Some(&Scalar::Integer(31))
+    );
+    let count: i64 = graph.query("MATCH (n:Person) RETURN count(n)").unwrap();
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,65 @@
+use redisgraph::{
+    pattern::{node, Direction},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_pattern_directions() {
+    assert_eq!(
+        node("a")
+            .rel("R", Direction::Outgoing)
+            .node("b")
+            .to_string(),
+        "(`a`)-[:`R`]->(`b`)"
+    );
+    assert_eq!(
+        node("a")
+            .rel("R", Direction::Incoming)
+            .node("b")
+            .to_string(),
+        "(`a`)<-[:`R`]-(`b`)"
+    );
+    assert_eq!(
+        node("a").any_rel(Direction::Both).node("").to_string(),
+        "(`a`)-[]-()"
+    );
+}
+
+#[test]
+fn test_pattern_escaping() {
+    let pattern = node("p")
+        .label("Evil`) DETACH DELETE (x")
+        .rel_as("r", "KNOWS", Direction::Outgoing)
+        .node("q")
+        .label("Person");
+    assert_eq!(
+        pattern.to_string(),
+        "(`p`:`Evil``) DETACH DELETE (x`)-[`r`:`KNOWS`]->(`q`:`Person`)"
+    );
+}
+
+#[graph_test]
+fn test_neighbors(graph: &mut Graph) {
+    let id: u64 = graph
+        .query("CREATE (a:P { name: 'a' })-[:KNOWS]->(:P { name: 'b' }), (:P { name: 'c' })-[:KNOWS]->(a) RETURN id(a)")
+        .unwrap();
+    assert_eq!(
+        graph
+            .neighbors(id, "KNOWS", Direction::Outgoing)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        graph
+            .neighbors(id, "KNOWS", Direction::Incoming)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        graph.neighbors(id, "KNOWS", Direction::Both).unwrap().len(),
+        2
+    );
+}
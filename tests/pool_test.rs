@@ -0,0 +1,130 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use redisgraph::{
+    events::ConnectionEvent, pool::GraphPool, testing::unique_graph_name, RedisGraphError,
+};
+
+fn test_client() -> redis::Client {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    redis::Client::open(uri.as_str()).unwrap()
+}
+
+#[test]
+fn test_pool_connection_refused() {
+    let pool = GraphPool::new(redis::Client::open("redis://127.0.0.1:1").unwrap(), 2);
+    assert!(matches!(
+        pool.get("unreachable"),
+        Err(RedisGraphError::RedisError(_))
+    ));
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn test_pool_checkout_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let pool = GraphPool::new(redis::Client::open("redis://127.0.0.1:1").unwrap(), 2)
+        .on_connection_event(move |event| sink.lock().unwrap().push(event.clone()));
+    assert!(pool.get("unreachable").is_err());
+    pool.shutdown(Duration::from_millis(0));
+    assert!(pool.get("unreachable").is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        ConnectionEvent::CheckoutFailed {
+            graph_name, error, ..
+        } => {
+            assert_eq!(graph_name, "unreachable");
+            assert!(error.contains("refused"), "{}", error);
+        }
+        other => panic!("expected checkout failure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_shutdown_empty_pool() {
+    let pool = GraphPool::new(redis::Client::open("redis://127.0.0.1:1").unwrap(), 2);
+    assert!(pool.shutdown(Duration::from_millis(0)));
+    assert!(pool.is_shut_down());
+    assert!(matches!(
+        pool.get("unreachable"),
+        Err(RedisGraphError::PoolShutDown)
+    ));
+}
+
+#[test]
+fn test_pool() {
+    let pool = GraphPool::new(test_client(), 1).checkout_timeout(Duration::from_secs(5));
+    let name = unique_graph_name("pool");
+
+    let mut graph = pool
+        .get(&name)
+        .expect("failed to connect to the test database");
+    graph
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+        .unwrap();
+    assert!(matches!(
+        pool.get_with_timeout(&name, Duration::from_millis(50)),
+        Err(RedisGraphError::PoolTimeout)
+    ));
+    drop(graph);
+    assert_eq!((pool.size(), pool.idle()), (1, 1));
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = pool.clone();
+            let name = name.clone();
+            thread::spawn(move || {
+                let mut graph = pool.get(&name).unwrap();
+                graph
+                    .query::<String>("MATCH (r:Rider) RETURN r.name")
+                    .unwrap()
+            })
+        })
+        .collect();
+    for reader in readers {
+        assert_eq!(reader.join().unwrap(), "Valentino Rossi");
+    }
+    assert_eq!(pool.size(), 1);
+
+    let graph = pool.get(&name).unwrap().into_inner();
+    assert_eq!(pool.size(), 0);
+    graph.delete().unwrap();
+}
+
+#[test]
+fn test_pool_shutdown() {
+    let pool = GraphPool::new(test_client(), 2);
+    let name = unique_graph_name("pool");
+    let mut graph = pool
+        .get(&name)
+        .expect("failed to connect to the test database");
+    drop(pool.get(&name).unwrap());
+    assert_eq!((pool.size(), pool.idle()), (2, 1));
+
+    // Waits for the handle that is lent out.
+    let drain = thread::spawn({
+        let pool = pool.clone();
+        move || pool.shutdown(Duration::from_secs(5))
+    });
+    thread::sleep(Duration::from_millis(100));
+    assert!(matches!(
+        pool.get(&name),
+        Err(RedisGraphError::PoolShutDown)
+    ));
+    assert_eq!((pool.size(), pool.idle()), (1, 0));
+    graph.mutate("CREATE (:Rider)").unwrap();
+    drop(graph);
+    assert!(drain.join().unwrap());
+    assert_eq!(pool.size(), 0);
+
+    // Gives up at the deadline.
+    let pool = GraphPool::new(test_client(), 1);
+    let graph = pool.get(&name).unwrap();
+    assert!(!pool.shutdown(Duration::from_millis(50)));
+    graph.into_inner().delete().unwrap();
+    assert_eq!(pool.size(), 0);
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use redis::{FromRedisValue, Value};
+use redisgraph::{
+    profile::{ExecutionPlan, TimingStats},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+fn person_scan_plan() -> ExecutionPlan {
+    let value = Value::Bulk(vec![
+        Value::Data(b"Results | Records produced: 3, Execution time: 0.5 ms".to_vec()),
+        Value::Data(
+            b"    Project | Records produced: 3, Execution time: 0.25 ms".to_vec(),
+        ),
+        Value::Data(
+            b"        Node By Label Scan | (n:Person) | Records produced: 3, Execution time: 0.125 ms"
+                .to_vec(),
+        ),
+    ]);
+    ExecutionPlan::from_redis_value(&value).unwrap()
+}
+
+#[test]
+fn test_parse_execution_plan() {
+    let plan = person_scan_plan();
+
+    assert_eq!(plan.operations.len(), 3);
+    assert_eq!(plan.operations[2].name, "Node By Label Scan");
+    assert_eq!(plan.operations[2].details, vec!["(n:Person)".to_string()]);
+    assert_eq!(plan.operations[2].depth, 2);
+    assert_eq!(plan.operations[2].records_produced, 3);
+    assert_eq!(plan.execution_time(), Duration::from_micros(500));
+}
+
+#[test]
+fn test_plan_assertions() {
+    person_scan_plan()
+        .assertions()
+        .expects_operation("Node By Label Scan")
+        .max_records(3)
+        .max_execution_time(Duration::from_millis(1))
+        .forbids_operation("All Node Scan")
+        .assert();
+
+    let error = person_scan_plan()
+        .assertions()
+        .max_records(2)
+        .expects_operation("Node By Index Scan")
+        .forbids_operation("Project")
+        .check()
+        .unwrap_err();
+    assert_eq!(
+        error.failures,
+        vec![
+            "operation \"Results\" produced 3 records, expected at most 2",
+            "operation \"Project\" produced 3 records, expected at most 2",
+            "operation \"Node By Label Scan\" produced 3 records, expected at most 2",
+            "expected operation \"Node By Index Scan\"",
+            "unexpected operation \"Project\"",
+        ]
+    );
+    assert!(error
+        .to_string()
+        .contains("    Project | Records produced: 3"));
+}
+
+#[test]
+fn test_parse_malformed_execution_plan() {
+    let value = Value::Bulk(vec![Value::Data(b"Results".to_vec())]);
+    assert!(ExecutionPlan::from_redis_value(&value).is_err());
+}
+
+#[test]
+fn test_timing_stats() {
+    let times = [4, 1, 3, 2].iter().map(|ms| Duration::from_millis(*ms));
+    let stats = TimingStats::from_durations(&times.collect::<Vec<_>>()).unwrap();
+
+    assert_eq!(stats.iterations, 4);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.max, Duration::from_millis(4));
+    assert_eq!(stats.mean, Duration::from_micros(2500));
+    assert_eq!(stats.median, Duration::from_micros(2500));
+    assert!(TimingStats::from_durations(&[]).is_none());
+}
+
+#[graph_test]
+fn test_profile_compare(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person { age: 20 }), (:Person { age: 30 })")
+        .unwrap();
+    let comparison = graph
+        .profile_compare(
+            "MATCH (n) WHERE n.age > 25 RETURN n",
+            "MATCH (n:Person) WHERE n.age > 25 RETURN n",
+            3,
+        )
+        .unwrap();
+    assert_eq!(comparison.a.iterations, 3);
+    assert_eq!(comparison.b.iterations, 3);
+}
@@ -0,0 +1,42 @@
+use redisgraph::{
+    projection::ProjectionFilter,
+    testing::{test_connection, unique_graph_name},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+#[graph_test]
+fn test_project(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:Person {name: 'Alice', active: true}), \
+             (b:Person {name: 'Bob', active: true}), \
+             (c:Person {name: 'Carol', active: false}), \
+             (x:Company {name: 'Acme'}), \
+             (a)-[:KNOWS {since: 2010}]->(b), (b)-[:KNOWS]->(c), \
+             (a)-[:WORKS_AT]->(x), (b)-[:LIKES]->(a)",
+        )
+        .unwrap();
+
+    let mut dest = Graph::open(test_connection(), unique_graph_name("projection")).unwrap();
+    let filter = ProjectionFilter::new()
+        .labels("Person")
+        .node_predicate("n.active = true")
+        .rel_types(&["KNOWS"])
+        .batch_size(1);
+    let summary = graph.project(&filter, &mut dest).unwrap();
+    assert_eq!(summary.nodes_copied, 2);
+    assert_eq!(summary.relationships_copied, 1);
+
+    let (name, since, friend): (String, i64, String) = dest
+        .query("MATCH (a:Person)-[r:KNOWS]->(b:Person) RETURN a.name, r.since, b.name")
+        .unwrap();
+    assert_eq!(
+        (name.as_str(), since, friend.as_str()),
+        ("Alice", 2010, "Bob")
+    );
+    let count: i64 = graph.query("MATCH (n) RETURN count(n)").unwrap();
+    assert_eq!(count, 4);
+
+    dest.delete().unwrap();
+}
@@ -0,0 +1 @@
+CREATE (:Person { name: $name, age: $age })
@@ -0,0 +1,3 @@
+MATCH (p:Person {name: $name})
+WHERE p.`$nickname` IS NULL AND '$motto' <> "say \"$hi\""
+RETURN p.name
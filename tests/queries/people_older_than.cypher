@@ -0,0 +1,4 @@
+MATCH (p:Person)
+WHERE p.age > $min_age
+RETURN p.name
+ORDER BY p.name
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use redisgraph::{
+    fragment::QueryFragment,
+    params::{validate_params, CypherValue},
+    Graph,
+};
+use redisgraph_derive::{graph_test, queries};
+
+queries! {
+    dir = "tests/queries";
+
+    fn create_person(name: &str, age: i64);
+    fn people_older_than(min_age: i64) -> Vec<String>;
+    // Only compiles if the macro skips the `$` in the quoted identifier and the strings.
+    fn people_named(name: &str) -> Vec<String>;
+}
+
+#[test]
+fn test_queries_agree_with_runtime_checks() {
+    let text = include_str!("queries/people_named.cypher");
+    let fragment = QueryFragment::new(text).bind("name", "Alice");
+    assert!(fragment.unbound_params().is_empty());
+    assert!(fragment.into_parts().is_ok());
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), CypherValue::from("Alice"));
+    assert!(validate_params(text, &params).is_ok());
+}
+
+#[graph_test]
+fn test_generated_queries(graph: &mut Graph) {
+    create_person(graph, "Alice", 30).unwrap();
+    create_person(graph, "Bob", 17).unwrap();
+    create_person(graph, "Carol", 45).unwrap();
+
+    assert_eq!(
+        people_older_than(graph, 18).unwrap(),
+        vec!["Alice".to_string(), "Carol".to_string()]
+    );
+    assert_eq!(people_named(graph, "Bob").unwrap(), vec!["Bob".to_string()]);
+}
@@ -0,0 +1,156 @@
+use redisgraph::{
+    params::CypherValue,
+    pattern::{node, Direction},
+    query::{param, Expr, QueryBuilder, SortOrder},
+    Graph, RedisGraphError,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_expr() {
+    let expr = !Expr::prop("p", "age")
+        .lt(param("min"))
+        .or(Expr::prop("p", "email").is_null());
+    assert_eq!(
+        expr.to_string(),
+        "NOT ((`p`.`age` < $min) OR (`p`.`email` IS NULL))"
+    );
+    assert_eq!(
+        Expr::var("p").is_in(Expr::value(vec![1, 2])).to_string(),
+        "`p` IN [1, 2]"
+    );
+    assert_eq!(Expr::var("p").count().to_string(), "count(`p`)");
+}
+
+#[test]
+fn test_query_builder() {
+    let query = QueryBuilder::new()
+        .match_pattern(
+            node("p")
+                .label("Person")
+                .rel("KNOWS", Direction::Outgoing)
+                .node("q"),
+        )
+        .filter(Expr::prop("p", "age").gt(param("min")))
+        .filter(Expr::prop("q", "name").starts_with(param("q")))
+        .returns_as(Expr::prop("q", "name"), "name")
+        .param("min", 18)
+        .param("q", "A")
+        .build()
+        .unwrap();
+    let (text, params) = query.into_parts();
+    assert_eq!(
+        text,
+        "MATCH (`p`:`Person`)-[:`KNOWS`]->(`q`) \
+         WHERE (`p`.`age` > $min) AND (`q`.`name` STARTS WITH $q) \
+         RETURN `q`.`name` AS `name`"
+    );
+    assert_eq!(params.len(), 2);
+}
+
+#[test]
+fn test_query_builder_clause_order() {
+    let query = QueryBuilder::new()
+        .limit(5)
+        .order_by(Expr::var("age"), SortOrder::Descending)
+        .skip(10)
+        .returns_as(Expr::prop("p", "age"), "age")
+        .order_by(Expr::var("name"), SortOrder::Ascending)
+        .distinct()
+        .returns(Expr::prop("p", "name"))
+        .match_pattern(node("p"))
+        .build()
+        .unwrap();
+    assert_eq!(
+        query.query(),
+        "MATCH (`p`) RETURN DISTINCT `p`.`age` AS `age`, `p`.`name` \
+         ORDER BY `age` DESC, `name` ASC SKIP 10 LIMIT 5"
+    );
+    assert!(query.params().is_empty());
+}
+
+#[test]
+fn test_query_builder_invalid_params() {
+    let invalid = QueryBuilder::new()
+        .filter(Expr::var("p").eq(param("x) OR (true")))
+        .build();
+    match invalid {
+        Err(RedisGraphError::InvalidParameterName(name)) => assert_eq!(name, "x) OR (true"),
+        other => panic!("expected invalid parameter name, got {:?}", other),
+    }
+
+    let unbound = QueryBuilder::new().returns(param("missing")).build();
+    match unbound {
+        Err(RedisGraphError::UnboundParameter(name)) => assert_eq!(name, "missing"),
+        other => panic!("expected unbound parameter, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_query_builder_non_finite_values() {
+    let literal = QueryBuilder::new()
+        .match_pattern(node("p"))
+        .filter(Expr::prop("p", "score").lt(Expr::value(f64::INFINITY)))
+        .returns(Expr::var("p"))
+        .build();
+    match literal {
+        Err(RedisGraphError::NonFiniteLiteral(literal)) => assert_eq!(literal, "inf"),
+        other => panic!("expected non-finite literal, got {:?}", other),
+    }
+
+    let nested = QueryBuilder::new()
+        .returns(Expr::value(vec![1.0, f64::NAN]))
+        .build();
+    assert!(matches!(nested, Err(RedisGraphError::NonFiniteLiteral(_))));
+
+    let param_value = QueryBuilder::new()
+        .returns(param("score"))
+        .param("score", f64::NAN)
+        .build();
+    match param_value {
+        Err(RedisGraphError::NonFiniteParameter(name)) => assert_eq!(name, "score"),
+        other => panic!("expected non-finite parameter, got {:?}", other),
+    }
+}
+
+#[graph_test]
+fn test_query_builder_execution(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person { name: 'Alice', age: 30 }), (:Person { name: 'Bob', age: 15 })")
+        .unwrap();
+    let query = QueryBuilder::new()
+        .match_pattern(node("p").label("Person"))
+        .filter(Expr::prop("p", "age").ge(param("min")))
+        .returns(Expr::prop("p", "name"))
+        .param("min", 18)
+        .build()
+        .unwrap();
+    let names: Vec<String> = graph
+        .query_with_params(query.query(), query.params())
+        .unwrap();
+    assert_eq!(names, vec!["Alice".to_string()]);
+}
+
+#[test]
+fn test_query_builder_unwind() {
+    let rows = CypherValue::Array(vec![CypherValue::Array(vec![1.into(), "Alice".into()])]);
+    let query = QueryBuilder::new()
+        .unwind_param("rows", |row| {
+            QueryBuilder::new()
+                .merge_node("n", "Person", vec![("id", row.clone().field("id"))])
+                .set(Expr::prop("n", "name"), row.field("name"))
+                .returns(Expr::var("n"))
+        })
+        .unwind_param("others", |row| QueryBuilder::new().returns(row))
+        .param("rows", rows.clone())
+        .param("others", Vec::<i64>::new())
+        .build()
+        .unwrap();
+    assert_eq!(
+        query.query(),
+        "UNWIND $rows AS `row` UNWIND $others AS `row2` \
+         MERGE (`n`:`Person` {`id`: `row`.`id`}) SET `n`.`name` = `row`.`name` \
+         RETURN `n`, `row2`"
+    );
+    assert_eq!(query.params()["rows"], rows);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "tokio-comp")]
+
+use std::sync::{Arc, Mutex};
+
+use redisgraph::{
+    events::ConnectionEvent, reconnect::ReconnectingGraph, testing::unique_graph_name,
+};
+
+fn test_client() -> redis::Client {
+    let uri = std::env::var("TEST_REDIS_URI").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+    redis::Client::open(uri.as_str()).unwrap()
+}
+
+#[tokio::test]
+async fn test_open_refused() {
+    let client = redis::Client::open("redis://127.0.0.1:1").unwrap();
+    assert!(
+        ReconnectingGraph::open(client, unique_graph_name("reconnect"))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_reconnecting_graph() {
+    let client = test_client();
+    let mut graph = ReconnectingGraph::open(client.clone(), unique_graph_name("reconnect"))
+        .await
+        .expect("failed to connect to the test database");
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    graph.on_connection_event(move |event| sink.lock().unwrap().push(event.clone()));
+    graph
+        .mutate("CREATE (:Rider {name: 'Valentino Rossi'})")
+        .await
+        .unwrap();
+
+    // Kills the connection of the graph, but not this one.
+    let mut conn = client.get_connection().unwrap();
+    let mut kill = || {
+        redis::cmd("CLIENT")
+            .arg("KILL")
+            .arg("TYPE")
+            .arg("normal")
+            .arg("SKIPME")
+            .arg("yes")
+            .query::<()>(&mut conn)
+            .unwrap()
+    };
+    kill();
+    let names: Vec<String> = graph
+        .ro_query("MATCH (r:Rider) RETURN r.name")
+        .await
+        .unwrap();
+    assert_eq!(names, vec!["Valentino Rossi"]);
+    {
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(
+            matches!(&events[0], ConnectionEvent::Disconnected { error, .. } if !error.is_empty())
+        );
+        assert!(matches!(&events[1], ConnectionEvent::Reconnected { .. }));
+    }
+
+    // Queries that may write are not resent, but the next request reconnects.
+    kill();
+    assert!(graph
+        .query::<i64>("CREATE (r:Rider {name: 'Marc Marquez'}) RETURN count(r)")
+        .await
+        .is_err());
+    let count: i64 = graph
+        .query("MATCH (r:Rider) RETURN count(r)")
+        .await
+        .unwrap();
+    assert!(count == 1 || count == 2);
+    graph.delete().await.unwrap();
+}
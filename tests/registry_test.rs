@@ -0,0 +1,125 @@
+use maplit::hashmap;
+use redisgraph::{
+    params::CypherValue,
+    registry::{ParamType, QueryRegistry},
+    RedisGraphError,
+};
+
+fn registry() -> QueryRegistry {
+    let mut registry = QueryRegistry::new();
+    registry
+        .register(
+            "find_person",
+            "MATCH (p:Person) WHERE p.name = $name AND p.age > $min_age RETURN p",
+            &[("name", ParamType::String), ("min_age", ParamType::Integer)],
+        )
+        .unwrap();
+    registry
+}
+
+#[test]
+fn test_register_checks_declaration() {
+    let mut registry = registry();
+    match registry.register("find_person", "RETURN 1", &[]) {
+        Err(RedisGraphError::DuplicateQuery(name)) => assert_eq!(name, "find_person"),
+        other => panic!("expected duplicate query, got {:?}", other),
+    }
+    match registry.register("undeclared", "RETURN $x", &[]) {
+        Err(RedisGraphError::UnboundParameter(name)) => assert_eq!(name, "x"),
+        other => panic!("expected unbound parameter, got {:?}", other),
+    }
+    match registry.register("unused", "RETURN 1", &[("x", ParamType::Any)]) {
+        Err(RedisGraphError::UnusedParameter(name)) => assert_eq!(name, "x"),
+        other => panic!("expected unused parameter, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_from_embedded() {
+    let registry = QueryRegistry::from_embedded(&[
+        (
+            "create_person",
+            include_str!("queries/create_person.cypher"),
+            &[("name", ParamType::String), ("age", ParamType::Integer)],
+        ),
+        (
+            "people_older_than",
+            include_str!("queries/people_older_than.cypher"),
+            &[("min_age", ParamType::Integer)],
+        ),
+    ])
+    .unwrap();
+    assert_eq!(registry.iter().count(), 2);
+    assert!(registry
+        .get("people_older_than")
+        .unwrap()
+        .text
+        .contains("$min_age"));
+
+    match QueryRegistry::from_embedded(&[(
+        "create_person",
+        include_str!("queries/create_person.cypher"),
+        &[("name", ParamType::String)],
+    )]) {
+        Err(RedisGraphError::UnboundParameter(name)) => assert_eq!(name, "age"),
+        other => panic!("expected unbound parameter, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_prepare_checks_params() {
+    let registry = registry();
+    let valid = hashmap! {
+        "name".to_string() => CypherValue::from("Alice"),
+        "min_age".to_string() => CypherValue::from(18),
+    };
+    assert!(registry.prepare("find_person", &valid).is_ok());
+
+    let wrong_type = hashmap! {
+        "name".to_string() => CypherValue::from("Alice"),
+        "min_age".to_string() => CypherValue::from("18"),
+    };
+    match registry.prepare("find_person", &wrong_type) {
+        Err(RedisGraphError::ParameterTypeMismatch { param, expected }) => {
+            assert_eq!(param, "min_age");
+            assert_eq!(expected, ParamType::Integer);
+        }
+        other => panic!("expected type mismatch, got {:?}", other),
+    }
+
+    let mut extra = valid.clone();
+    extra.insert("limit".to_string(), CypherValue::from(10));
+    match registry.prepare("find_person", &extra) {
+        Err(RedisGraphError::UnusedParameter(name)) => assert_eq!(name, "limit"),
+        other => panic!("expected unused parameter, got {:?}", other),
+    }
+
+    match registry.prepare("missing", &valid) {
+        Err(RedisGraphError::UnknownQuery(name)) => assert_eq!(name, "missing"),
+        other => panic!("expected unknown query, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_param_type_accepts() {
+    assert!(ParamType::Double.accepts(&CypherValue::Integer(1)));
+    assert!(!ParamType::Integer.accepts(&CypherValue::Double(1.0)));
+    assert!(!ParamType::String.accepts(&CypherValue::Null));
+    assert!(ParamType::Any.accepts(&CypherValue::Null));
+}
+
+#[test]
+fn test_param_type_placeholder() {
+    let types = [
+        ParamType::Boolean,
+        ParamType::Integer,
+        ParamType::Double,
+        ParamType::String,
+        ParamType::Array,
+        ParamType::Map,
+        ParamType::Any,
+    ];
+    for param_type in &types {
+        assert!(param_type.accepts(&param_type.placeholder()));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use redis::Value;
+use redisgraph::{
+    result_ref::{EdgeRef, NodeView, ResultSetRef, ScalarRef},
+    result_set::{DecodeContext, Edge, FromRedisValueWithGraph, Node, Scalar},
+    Graph, RedisString, ResultSet,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_to_scalar() {
+    let node = NodeView {
+        labels: vec![b"Person"],
+        properties: vec![(b"name", ScalarRef::String(b"Alice"))],
+    };
+    assert_eq!(node.property("name"), Some(&ScalarRef::String(b"Alice")));
+    assert_eq!(node.property("age"), None);
+
+    let mut properties = HashMap::new();
+    properties.insert(
+        "name".to_string().into(),
+        Scalar::String("Alice".to_string().into()),
+    );
+    assert_eq!(
+        ScalarRef::Node(node).to_scalar(),
+        Scalar::Node(Node {
+            labels: vec!["Person".to_string().into()],
+            properties,
+        })
+    );
+
+    let edge = EdgeRef {
+        type_name: b"KNOWS",
+        properties: Vec::new(),
+    };
+    assert_eq!(
+        ScalarRef::Array(vec![ScalarRef::Edge(edge), ScalarRef::Nil]).to_scalar(),
+        Scalar::Array(vec![
+            Scalar::Edge(Edge {
+                type_name: "KNOWS".to_string().into(),
+                properties: HashMap::new(),
+            }),
+            Scalar::Nil,
+        ])
+    );
+}
+
+// Decodes replies that don't refer to any names.
+struct NoNames;
+
+impl DecodeContext for NoNames {
+    fn labels(&self) -> &[RedisString] {
+        &[]
+    }
+
+    fn relationship_types(&self) -> &[RedisString] {
+        &[]
+    }
+
+    fn property_keys(&self) -> &[RedisString] {
+        &[]
+    }
+
+    fn lenient_booleans(&self) -> bool {
+        false
+    }
+
+    fn reply_diagnostics(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_parse_vector() {
+    let data = |text: &str| Value::Data(text.as_bytes().to_vec());
+    let vector = Value::Bulk(vec![
+        Value::Int(12),
+        Value::Bulk(vec![data("1.5"), data("-2")]),
+    ]);
+    let response = Value::Bulk(vec![
+        Value::Bulk(vec![Value::Bulk(vec![Value::Int(1), data("v")])]),
+        Value::Bulk(vec![Value::Bulk(vec![vector])]),
+        Value::Bulk(vec![data(
+            "Query internal execution time: 0.1 milliseconds",
+        )]),
+    ]);
+
+    let borrowed = ResultSetRef::parse(&response, &NoNames).unwrap();
+    assert_eq!(
+        borrowed.get(0, 0),
+        Some(&ScalarRef::Array(vec![
+            ScalarRef::Double(1.5),
+            ScalarRef::Double(-2.0)
+        ]))
+    );
+    let owned = ResultSet::from_redis_value_with_graph(response.clone(), &NoNames).unwrap();
+    assert_eq!(
+        owned.get_scalar(0, 0).unwrap(),
+        &borrowed.get(0, 0).unwrap().to_scalar()
+    );
+}
+
+#[graph_test]
+fn test_query_with_ref(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person {name: 'Alice', age: 30})-[:KNOWS {since: 2010}]->(:Person {name: 'Bob'})")
+        .unwrap();
+
+    let query = "MATCH (a)-[r]->(b) RETURN a, r, b.name AS name, [1, 2.5, true] AS list";
+    let expected = graph.query::<(Node, Edge, String, Scalar)>(query).unwrap();
+    let (rows, names) = graph
+        .query_with_ref(query, |result_set| {
+            let names = result_set
+                .column_names()
+                .iter()
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .collect::<Vec<String>>();
+            let rows = result_set
+                .rows()
+                .map(|row| {
+                    row.iter()
+                        .map(ScalarRef::to_scalar)
+                        .collect::<Vec<Scalar>>()
+                })
+                .collect::<Vec<Vec<Scalar>>>();
+            Ok((rows, names))
+        })
+        .unwrap();
+
+    assert_eq!(names, vec!["a", "r", "name", "list"]);
+    assert_eq!(
+        rows,
+        vec![vec![
+            Scalar::Node(expected.0),
+            Scalar::Edge(expected.1),
+            Scalar::String(expected.2.into()),
+            expected.3,
+        ]]
+    );
+}
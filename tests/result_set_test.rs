@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use maplit::hashmap;
+use redisgraph::{
+    result_set::{Column, Edge, Node, PathBuilder, RawPath, Scalar, Statistics},
+    RedisGraphError, ResultSet,
+};
+
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+
+fn numbers() -> ResultSet {
+    ResultSet {
+        columns: vec![
+            Column::Scalars((0..100).map(Scalar::Integer).collect()),
+            Column::Scalars((0..100).map(|i| Scalar::Double(i as f64)).collect()),
+        ],
+        statistics: Statistics(Vec::new()),
+    }
+}
+
+#[test]
+fn test_rows() {
+    let result_set = numbers();
+    let rows: Vec<(i64, f64)> = result_set.rows().map(|row| row.parse().unwrap()).collect();
+    assert_eq!(rows.len(), 100);
+    assert_eq!(rows[42], (42, 42.0));
+
+    let last = result_set.rows().last().unwrap();
+    assert_eq!(last.idx(), 99);
+    assert_eq!(last.get::<i64>(0).unwrap(), 99);
+}
+
+#[test]
+fn test_transpose() {
+    let node = Node {
+        labels: Vec::new(),
+        properties: HashMap::new(),
+    };
+    let result_set = ResultSet {
+        columns: vec![
+            Column::Scalars(vec![Scalar::Integer(1), Scalar::Integer(2)]),
+            Column::Nodes(vec![node.clone(), node.clone()]),
+        ],
+        statistics: Statistics(Vec::new()),
+    };
+
+    let rows = result_set.transpose();
+    assert_eq!(
+        rows,
+        vec![
+            vec![Scalar::Integer(1), Scalar::Node(node.clone())],
+            vec![Scalar::Integer(2), Scalar::Node(node)],
+        ]
+    );
+    assert_eq!(result_set.into_rows(), rows);
+    assert!(numbers().transpose().iter().all(|row| row.len() == 2));
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_par_rows() {
+    let result_set = numbers();
+    let sum: i64 = result_set
+        .par_rows()
+        .map(|row| row.get::<i64>(0).unwrap())
+        .sum();
+    assert_eq!(sum, (0..100).sum::<i64>());
+}
+
+#[test]
+#[cfg(feature = "ndarray")]
+fn test_to_array2() {
+    let matrix = numbers().to_array2::<f64>().unwrap();
+    assert_eq!(matrix.shape(), &[100, 2]);
+    assert_eq!(matrix[[42, 0]], 42.0);
+    assert_eq!(matrix[[42, 1]], 42.0);
+
+    let result_set = ResultSet {
+        columns: vec![Column::Scalars(vec![Scalar::Double(0.5), Scalar::Nil])],
+        statistics: Statistics(Vec::new()),
+    };
+    let matrix = result_set.to_array2::<f32>().unwrap();
+    assert_eq!(matrix[[0, 0]], 0.5);
+    assert!(matrix[[1, 0]].is_nan());
+
+    let result_set = ResultSet {
+        columns: vec![Column::Scalars(vec![Scalar::Boolean(true)])],
+        statistics: Statistics(Vec::new()),
+    };
+    assert!(matches!(
+        result_set.to_array2::<f64>(),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+}
+
+#[test]
+fn test_group_by_column() {
+    let result_set = ResultSet {
+        columns: vec![
+            Column::Scalars(vec![
+                Scalar::Integer(1),
+                Scalar::Integer(2),
+                Scalar::Integer(1),
+            ]),
+            Column::Scalars(vec![
+                Scalar::Integer(10),
+                Scalar::Integer(20),
+                Scalar::Integer(11),
+            ]),
+            Column::Scalars(vec![
+                Scalar::Boolean(true),
+                Scalar::Boolean(false),
+                Scalar::Boolean(false),
+            ]),
+        ],
+        statistics: Statistics(Vec::new()),
+    };
+
+    let groups = result_set.group_by_column::<i64, (i64, bool)>(0).unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[&1], vec![(10, true), (11, false)]);
+    assert_eq!(groups[&2], vec![(20, false)]);
+
+    assert!(result_set.group_by_column::<i64, i64>(3).is_err());
+}
+
+#[test]
+fn test_parse_rows_lossy() {
+    let result_set = ResultSet {
+        columns: vec![Column::Scalars(vec![
+            Scalar::Integer(1),
+            Scalar::String("two".to_string().into()),
+            Scalar::Integer(3),
+        ])],
+        statistics: Statistics(Vec::new()),
+    };
+
+    let (values, errors) = result_set.parse_rows_lossy::<i64>();
+    assert_eq!(values, vec![1, 3]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].row_idx, 1);
+    match errors[0].error {
+        RedisGraphError::ClientTypeError(_) => {}
+        ref other => panic!("expected client type error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_debug_snapshot() {
+    let alice = Node {
+        labels: vec!["Person".to_string().into()],
+        properties: hashmap! {
+            "name".to_string().into() => Scalar::String("Alice".to_string().into()),
+            "age".to_string().into() => Scalar::Integer(30),
+            "score".to_string().into() => Scalar::Double(-0.0),
+        },
+    };
+    let knows = Edge {
+        type_name: "KNOWS".to_string().into(),
+        properties: HashMap::new(),
+    };
+    let result_set = ResultSet {
+        columns: vec![
+            Column::Nodes(vec![alice.clone()]),
+            Column::Relations(vec![knows]),
+            Column::Scalars(vec![Scalar::Array(vec![
+                Scalar::Nil,
+                Scalar::Boolean(true),
+                Scalar::Double(1.0 / 3.0),
+            ])]),
+        ],
+        statistics: Statistics(Vec::new()),
+    };
+
+    assert_eq!(
+        result_set.to_debug_snapshot(),
+        "row 0: [(:Person {age: 30, name: \"Alice\", score: 0.0}), [:KNOWS], [null, true, 0.333333333333]]\n\
+         statistics: []\n"
+    );
+}
+
+#[test]
+fn test_statistics_count() {
+    let statistics = Statistics(vec![
+        "Nodes created: 3".to_string(),
+        "Properties set: 6".to_string(),
+        "Query internal execution time: 0.2 milliseconds".to_string(),
+    ]);
+    assert_eq!(statistics.count("Nodes created"), 3);
+    assert_eq!(statistics.count("Properties set"), 6);
+    assert_eq!(statistics.count("Relationships created"), 0);
+    assert_eq!(statistics.count("Query internal execution time"), 0);
+}
+
+#[test]
+fn test_array_as() {
+    let ages = Scalar::Array(vec![Scalar::Integer(30), Scalar::Integer(42)]);
+    assert_eq!(ages.array_as::<i64>().unwrap(), vec![30, 42]);
+    assert_eq!(
+        ages.array_as::<Option<u8>>().unwrap(),
+        vec![Some(30), Some(42)]
+    );
+
+    let mixed = Scalar::Array(vec![
+        Scalar::Integer(30),
+        Scalar::Nil,
+        Scalar::String("unknown".to_string().into()),
+    ]);
+    match mixed.array_as::<Option<i64>>() {
+        Err(RedisGraphError::ArrayElementMismatch { index, found, .. }) => {
+            assert_eq!(index, 2);
+            assert_eq!(found, "String");
+        }
+        any => panic!("expected element mismatch, found {:?}", any),
+    }
+    assert!(matches!(
+        Scalar::Integer(30).array_as::<i64>(),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+}
+
+fn labeled(label: &str) -> Node {
+    Node {
+        labels: vec![label.to_string().into()],
+        properties: HashMap::new(),
+    }
+}
+
+fn typed(type_name: &str) -> Edge {
+    Edge {
+        type_name: type_name.to_string().into(),
+        properties: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_path_builder() {
+    let path = PathBuilder::new(labeled("L1"))
+        .step(typed("R1"), labeled("L2"))
+        .step(typed("R2"), labeled("L3"))
+        .build()
+        .unwrap();
+    assert_eq!(path.len(), 2);
+    assert_eq!(path.start(), &labeled("L1"));
+    assert_eq!(path.end(), &labeled("L3"));
+    assert_eq!(
+        path.nodes().cloned().collect::<Vec<_>>(),
+        vec![labeled("L1"), labeled("L2"), labeled("L3")]
+    );
+    assert_eq!(
+        path.edges().cloned().collect::<Vec<_>>(),
+        vec![typed("R1"), typed("R2")]
+    );
+    assert_eq!(
+        RawPath::from(path),
+        PathBuilder::new(labeled("L1"))
+            .step(typed("R1"), labeled("L2"))
+            .step(typed("R2"), labeled("L3"))
+            .build_raw()
+    );
+
+    let single = PathBuilder::new(labeled("L1"))
+        .step(typed("R1"), labeled("L2"))
+        .build()
+        .unwrap();
+    assert_eq!(single.nodes().count(), 2);
+    assert_eq!(single.end(), &labeled("L2"));
+
+    let empty = PathBuilder::new(labeled("L1"));
+    assert_eq!(empty.clone().build_raw().len(), 0);
+    assert!(empty.build().is_none());
+}
+
+#[test]
+fn test_node_get() {
+    let node = Node {
+        labels: Vec::new(),
+        properties: hashmap! {
+            "age".to_string().into() => Scalar::Integer(30),
+        },
+    };
+    assert_eq!(node.get::<i64>("age").unwrap(), 30);
+    assert_eq!(node.get::<Option<i64>>("age").unwrap(), Some(30));
+    assert_eq!(node.get::<Option<i64>>("height").unwrap(), None);
+    assert!(node.get::<i64>("height").is_err());
+    assert!(node.get::<String>("age").is_err());
+}
@@ -0,0 +1,60 @@
+use std::io;
+use std::time::Duration;
+
+use redis::RedisError;
+use redisgraph::{
+    retry::{is_ambiguous, is_transient, RetryPolicy},
+    Graph, RedisGraphError,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_is_ambiguous() {
+    let broken = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset");
+    assert!(is_ambiguous(&RedisGraphError::RedisError(
+        RedisError::from(broken)
+    )));
+    assert!(!is_ambiguous(&RedisGraphError::RedisError(
+        RedisError::from((redis::ErrorKind::ResponseError, "syntax error"))
+    )));
+    assert!(!is_ambiguous(&RedisGraphError::InvalidUtf8));
+}
+
+#[test]
+fn test_is_transient() {
+    assert!(is_transient(&RedisGraphError::RedisError(
+        RedisError::from((redis::ErrorKind::BusyLoadingError, "loading"))
+    )));
+    assert!(!is_transient(&RedisGraphError::RedisError(
+        RedisError::from((redis::ErrorKind::ResponseError, "syntax error"))
+    )));
+    let broken = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset");
+    assert!(!is_transient(&RedisGraphError::RedisError(
+        RedisError::from(broken)
+    )));
+}
+
+#[test]
+fn test_retry_policy() {
+    let policy = RetryPolicy::new(3, Duration::from_millis(10));
+    assert_eq!(policy.retries(), 3);
+    assert_eq!(policy.backoff(), Duration::from_millis(10));
+    assert_eq!(RetryPolicy::default().retries(), 2);
+    assert!(format!("{:?}", policy).contains("reconnect: false"));
+}
+
+#[graph_test]
+fn test_mutate_idempotent_marked(graph: &mut Graph) {
+    graph.set_retry_policy(Some(RetryPolicy::default()));
+    graph
+        .mutate_idempotent_marked("MERGE (:Person {name: 'Alice'})")
+        .unwrap();
+    graph
+        .mutate_idempotent_marked("MERGE (:Person {name: 'Alice'})")
+        .unwrap();
+    let count: i64 = graph.query("MATCH (p:Person) RETURN count(p)").unwrap();
+    assert_eq!(count, 1);
+
+    // Errors returned by the server are not ambiguous, so they are not retried.
+    assert!(graph.mutate_idempotent_marked("MERGE (").is_err());
+}
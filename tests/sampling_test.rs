@@ -0,0 +1,101 @@
+use redisgraph::{
+    result_set::{Column, Node, Scalar, Statistics},
+    sampling::{ColumnSummary, ValueType},
+    ResultSet,
+};
+
+fn result_set(num_rows: i64) -> ResultSet {
+    ResultSet {
+        columns: vec![
+            Column::Scalars((0..num_rows).map(Scalar::Integer).collect()),
+            Column::Nodes(
+                (0..num_rows)
+                    .map(|id| Node {
+                        labels: Vec::new(),
+                        properties: vec![("id".to_string().into(), Scalar::Integer(id))]
+                            .into_iter()
+                            .collect(),
+                    })
+                    .collect(),
+            ),
+        ],
+        statistics: Statistics(vec!["Nodes created: 1".to_string()]),
+    }
+}
+
+fn ids(result_set: &ResultSet) -> Vec<i64> {
+    result_set
+        .rows()
+        .map(|row| row.get::<i64>(0).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_head() {
+    let result_set = result_set(10);
+    let head = result_set.head(3);
+    assert_eq!(ids(&head), vec![0, 1, 2]);
+    assert_eq!(head.num_columns(), 2);
+    assert_eq!(head.statistics, result_set.statistics);
+    assert_eq!(result_set.head(20), result_set);
+}
+
+#[test]
+fn test_sample() {
+    let result_set = result_set(100);
+    let sample = result_set.sample(10, 42);
+    let sampled = ids(&sample);
+    assert_eq!(sampled.len(), 10);
+    assert!(sampled.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(ids(&result_set.sample(10, 42)), sampled);
+    assert_ne!(ids(&result_set.sample(10, 7)), sampled);
+    // Nodes are sampled along with the other columns of their rows.
+    for row in sample.rows() {
+        let node: Node = row.get(1).unwrap();
+        assert_eq!(node.get::<i64>("id").unwrap(), row.get::<i64>(0).unwrap());
+    }
+    assert_eq!(result_set.sample(200, 1), result_set);
+}
+
+#[test]
+fn test_summary() {
+    let result_set = ResultSet {
+        columns: vec![
+            Column::Scalars(vec![Scalar::Integer(3), Scalar::Nil, Scalar::Integer(-2)]),
+            Column::Scalars(vec![Scalar::Nil, Scalar::Nil, Scalar::Nil]),
+            Column::Scalars(vec![
+                Scalar::String("a".to_string().into()),
+                Scalar::Double(0.5),
+                Scalar::Boolean(true),
+            ]),
+        ],
+        statistics: Statistics(Vec::new()),
+    };
+    assert_eq!(
+        result_set.summary(),
+        vec![
+            ColumnSummary {
+                value_type: Some(ValueType::Integer),
+                nulls: 1,
+                min: Some(-2.0),
+                max: Some(3.0),
+            },
+            ColumnSummary {
+                value_type: None,
+                nulls: 3,
+                min: None,
+                max: None,
+            },
+            ColumnSummary {
+                value_type: Some(ValueType::Mixed),
+                nulls: 0,
+                min: Some(0.5),
+                max: Some(0.5),
+            },
+        ]
+    );
+    assert_eq!(
+        self::result_set(2).summary()[1].value_type,
+        Some(ValueType::Node)
+    );
+}
@@ -0,0 +1,62 @@
+use redisgraph::sanitize::{normalize_query, query_fingerprint, sanitize_query};
+
+#[test]
+fn test_sanitize_strings() {
+    assert_eq!(
+        sanitize_query(r#"CREATE (:Person { name: 'O\'Brien', email: "a@b.c" })"#),
+        "CREATE (:Person { name: ?, email: ? })"
+    );
+}
+
+#[test]
+fn test_sanitize_numbers() {
+    assert_eq!(
+        sanitize_query("MATCH (n) WHERE n.score > -1.5e-3 AND n.age = 42 RETURN n SKIP 5"),
+        "MATCH (n) WHERE n.score > -? AND n.age = ? RETURN n SKIP ?"
+    );
+    assert_eq!(
+        sanitize_query("MATCH (n1)-[*1..3]->(m) RETURN [1, 2.5]"),
+        "MATCH (n1)-[*?..?]->(m) RETURN [?, ?]"
+    );
+}
+
+#[test]
+fn test_sanitize_keeps_identifiers_and_params() {
+    assert_eq!(
+        sanitize_query("MATCH (`node 'one'`:L2 { id: $id2 }) RETURN `node 'one'`.p1"),
+        "MATCH (`node 'one'`:L2 { id: $id2 }) RETURN `node 'one'`.p1"
+    );
+}
+
+#[test]
+fn test_normalize_query() {
+    assert_eq!(
+        normalize_query("CYPHER ids=[1, 2] m={a: 'x y'} MATCH (n)  WHERE n.id IN $ids RETURN n"),
+        "MATCH (n) WHERE n.id IN $ids RETURN n"
+    );
+    assert_eq!(
+        normalize_query("RETURN [[1, 2], [3]], [], [n.a, 1]"),
+        "RETURN [[?], [?]], [], [n.a, ?]"
+    );
+    assert_eq!(
+        normalize_query("MATCH (`my  node`)\tRETURN `my  node`"),
+        "MATCH (`my  node`) RETURN `my  node`"
+    );
+}
+
+#[test]
+fn test_query_fingerprint() {
+    assert_eq!(
+        query_fingerprint("CYPHER id=1 MATCH (n) WHERE id(n) = $id RETURN n"),
+        query_fingerprint("CYPHER id=2  MATCH (n) WHERE id(n) = $id\nRETURN n")
+    );
+    assert_eq!(
+        query_fingerprint("MATCH (n) WHERE n.age IN [1, 2] RETURN n"),
+        query_fingerprint("MATCH (n) WHERE n.age IN [3, 4, 5] RETURN n")
+    );
+    assert_ne!(
+        query_fingerprint("MATCH (n) RETURN n"),
+        query_fingerprint("MATCH (n) RETURN n LIMIT 1")
+    );
+    assert_eq!(query_fingerprint(""), 0xcbf2_9ce4_8422_2325);
+}
@@ -0,0 +1,97 @@
+use redisgraph::{
+    schema::{ConstraintKind, GraphSchema},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_schema_diff() {
+    let expected = GraphSchema::new()
+        .with_label("Person")
+        .with_relationship_type("KNOWS")
+        .with_index("Person", "name")
+        .with_constraint(ConstraintKind::Unique, "Person", &["email"]);
+    let live = GraphSchema::new()
+        .with_label("Person")
+        .with_label("Legacy")
+        .with_relationship_type("KNOWS")
+        .with_index("Person", "email");
+
+    assert!(expected.diff(&expected).is_empty());
+    let drift = live.diff(&expected);
+    assert!(!drift.is_empty());
+    assert_eq!(
+        drift.missing,
+        GraphSchema::new()
+            .with_index("Person", "name")
+            .with_constraint(ConstraintKind::Unique, "Person", &["email"])
+    );
+    assert_eq!(
+        drift.unexpected,
+        GraphSchema::new()
+            .with_label("Legacy")
+            .with_index("Person", "email")
+    );
+    assert_eq!(
+        drift.to_string(),
+        "missing index :Person(name)\n\
+         missing Unique constraint :Person(email)\n\
+         unexpected label :Legacy\n\
+         unexpected index :Person(email)\n"
+    );
+}
+
+#[test]
+fn test_schema_macro() {
+    assert_eq!(redisgraph::schema! {}, GraphSchema::new());
+    assert_eq!(
+        redisgraph::schema! {
+            :Person { unique: [first_name, last_name] }
+            [:KNOWS,]
+        },
+        GraphSchema::new()
+            .with_label("Person")
+            .with_constraint(
+                ConstraintKind::Unique,
+                "Person",
+                &["first_name", "last_name"]
+            )
+            .with_relationship_type("KNOWS")
+    );
+}
+
+#[graph_test]
+fn test_graph_schema(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person {name: 'Alice'})-[:KNOWS]->(:Person {name: 'Bob'})")
+        .unwrap();
+    graph.mutate("CREATE INDEX ON :Person(name)").unwrap();
+
+    let expected = GraphSchema::new()
+        .with_label("Person")
+        .with_relationship_type("KNOWS")
+        .with_index("Person", "name");
+    assert!(graph.graph_schema().unwrap().diff(&expected).is_empty());
+}
+
+#[graph_test]
+fn test_apply_schema(graph: &mut Graph) {
+    graph
+        .mutate("CREATE (:Person {name: 'Alice', email: 'alice@example.com'})")
+        .unwrap();
+    let schema = redisgraph::schema! {
+        :Person { indexes: [name], unique: [email] }
+    };
+
+    let created = graph.apply_schema(&schema).unwrap();
+    assert_eq!(created.indexes.len(), 2);
+    assert_eq!(created.constraints.len(), 1);
+    assert!(graph.apply_schema(&schema).unwrap().indexes.is_empty());
+    assert!(graph
+        .graph_schema()
+        .unwrap()
+        .diff(&schema)
+        .missing
+        .indexes
+        .is_empty());
+}
@@ -0,0 +1,42 @@
+use redisgraph::{script::split_statements, Graph, RedisGraphError};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_split_statements() {
+    let script = r#"
+        CREATE (:Person { name: "semi;colon", quote: "\";" });
+        // a comment; with a semicolon
+        CREATE (:`Odd;Label`);
+
+        ;
+        MATCH (n) RETURN count(n)
+    "#;
+    assert_eq!(
+        split_statements(script),
+        vec![
+            r#"CREATE (:Person { name: "semi;colon", quote: "\";" })"#,
+            "CREATE (:`Odd;Label`)",
+            "MATCH (n) RETURN count(n)",
+        ]
+    );
+}
+
+#[graph_test]
+fn test_execute_script(graph: &mut Graph) {
+    let statistics = graph
+        .execute_script("CREATE (:A); CREATE (:B); CREATE INDEX ON :A(name)")
+        .unwrap();
+    assert_eq!(statistics.len(), 3);
+
+    match graph.execute_script("CREATE (:C); THIS IS NOT CYPHER; CREATE (:D)") {
+        Err(RedisGraphError::ScriptStatementFailed {
+            index, statement, ..
+        }) => {
+            assert_eq!(index, 1);
+            assert_eq!(statement, "THIS IS NOT CYPHER");
+        }
+        other => panic!("expected failed statement, got {:?}", other),
+    }
+    let count: i64 = graph.query("MATCH (n) RETURN count(n)").unwrap();
+    assert_eq!(count, 3);
+}
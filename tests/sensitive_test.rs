@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use redisgraph::{
+    params::CypherValue,
+    props::Props,
+    result_set::{Edge, Node, Scalar},
+    sensitive::{Sensitive, REDACTED},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_sensitive() {
+    let email = Sensitive::new("alice@example.com".to_string());
+    assert_eq!(format!("{:?}", email), REDACTED);
+    assert_eq!(email.to_string(), REDACTED);
+    assert_eq!(
+        CypherValue::from(email.clone()),
+        CypherValue::from("alice@example.com")
+    );
+    assert_eq!(email.into_inner(), "alice@example.com");
+}
+
+#[test]
+fn test_redacted() {
+    let mut properties = HashMap::new();
+    properties.insert(
+        "email".to_string().into(),
+        Scalar::String("alice@example.com".to_string().into()),
+    );
+    let node = Node {
+        labels: Vec::new(),
+        properties: properties.clone(),
+    };
+    let edge = Edge {
+        type_name: "CONTACT".to_string().into(),
+        properties,
+    };
+
+    assert!(format!("{:?}", node).contains("String(RedisString"));
+    assert_eq!(
+        format!("{:?}", node.redacted(&["email"])),
+        "Node { labels: [], properties: {RedisString([101, 109, 97, 105, 108]): <redacted>} }"
+    );
+    assert!(format!("{:?}", node.redacted(&["phone"])).contains("String(RedisString"));
+    assert!(!format!("{:?}", edge.redacted(&["email"])).contains("String(RedisString"));
+}
+
+#[graph_test]
+fn test_query_sensitive(graph: &mut Graph) {
+    let props = Props::new().set("email", Sensitive::new("alice@example.com"));
+    graph.create_node("Person", &props).unwrap();
+
+    let email: Sensitive<String> = graph.query("MATCH (p:Person) RETURN p.email").unwrap();
+    assert_eq!(email.expose(), "alice@example.com");
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "derive")]
+
+use redisgraph::{session::Session, Entity, Graph, ToProperties};
+use redisgraph_derive::graph_test;
+
+#[derive(Debug, Entity, ToProperties)]
+struct Account {
+    owner: String,
+    balance: i64,
+    note: Option<String>,
+}
+
+#[derive(Debug, Entity, ToProperties)]
+#[redisgraph(label = "Account")]
+struct Owner {
+    owner: String,
+}
+
+#[graph_test]
+fn test_session(graph: &mut Graph) {
+    let id: u64 = graph
+        .query("CREATE (a:Account {owner: 'Alice', balance: 100, note: 'vip'}) RETURN id(a)")
+        .unwrap();
+
+    let mut session = Session::new(graph);
+    session.load::<Account>(id).unwrap().unwrap().balance -= 10;
+    // Served from the identity map, so the change is still there.
+    assert_eq!(session.load::<Account>(id).unwrap().unwrap().balance, 90);
+    session.get::<Account>(id).unwrap().unwrap().note = None;
+    assert!(session.load::<Account>(id + 1000).unwrap().is_none());
+    assert!(session.get::<Owner>(id).is_err());
+
+    assert_eq!(session.flush().unwrap(), 1);
+    assert_eq!(session.flush().unwrap(), 0);
+
+    let (balance, note): (i64, Option<String>) = session
+        .graph()
+        .query("MATCH (a:Account) RETURN a.balance, a.note")
+        .unwrap();
+    assert_eq!(balance, 90);
+    assert_eq!(note, None);
+
+    let ids = session
+        .find_like(&Account {
+            owner: "Alice".to_string(),
+            balance: 90,
+            note: None,
+        })
+        .unwrap();
+    assert_eq!(ids, vec![id]);
+}
+
+#[graph_test]
+fn test_session_soft_delete(graph: &mut Graph) {
+    let id: u64 = graph
+        .query("CREATE (a:Account {owner: 'Alice', balance: 100, deleted_at: 1}) RETURN id(a)")
+        .unwrap();
+    graph.set_soft_delete(true);
+
+    let mut session = Session::new(graph);
+    assert!(session.load::<Account>(id).unwrap().is_none());
+    let ids = session
+        .find_like(&Account {
+            owner: "Alice".to_string(),
+            balance: 100,
+            note: None,
+        })
+        .unwrap();
+    assert!(ids.is_empty());
+}
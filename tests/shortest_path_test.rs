@@ -0,0 +1,32 @@
+use redisgraph::Graph;
+use redisgraph_derive::graph_test;
+
+fn city_id(graph: &mut Graph, name: &str) -> u64 {
+    graph
+        .query(&format!("MATCH (c:City {{name: '{}'}}) RETURN id(c)", name))
+        .unwrap()
+}
+
+#[graph_test]
+fn test_shortest_path(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:City {name: 'A'}), (b:City {name: 'B'}), (c:City {name: 'C'}), \
+             (d:City {name: 'D'}), \
+             (a)-[:ROAD {km: 10}]->(b), (b)-[:ROAD {km: 10}]->(c), (a)-[:ROAD {km: 30}]->(c), \
+             (c)-[:ROAD]->(d)",
+        )
+        .unwrap();
+    let (a, c, d) = (
+        city_id(graph, "A"),
+        city_id(graph, "C"),
+        city_id(graph, "D"),
+    );
+
+    let route = graph.shortest_path(a, c, "km").unwrap().unwrap();
+    assert_eq!(route.cost, 20.0);
+    assert_eq!(route.path.len(), 2);
+
+    assert!(graph.shortest_path(c, a, "km").unwrap().is_none());
+    assert!(graph.shortest_path(a, d, "km").unwrap().is_none());
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+use std::thread;
+
+use redisgraph::{
+    result_set::Scalar,
+    single_flight::SingleFlight,
+    testing::{test_connection, unique_graph_name},
+    Graph,
+};
+
+#[test]
+fn test_single_flight() {
+    let graph = Graph::open(test_connection(), unique_graph_name("single_flight")).unwrap();
+    let graph = Arc::new(SingleFlight::new(graph));
+    graph
+        .graph()
+        .mutate("CREATE (:Order {total: 10}), (:Order {total: 20})")
+        .unwrap();
+
+    let readers: Vec<_> = (0..8)
+        .map(|_| {
+            let graph = Arc::clone(&graph);
+            thread::spawn(move || graph.query("MATCH (o:Order) RETURN sum(o.total)").unwrap())
+        })
+        .collect();
+    for reader in readers {
+        let result_set = reader.join().unwrap();
+        assert_eq!(result_set.get_scalar(0, 0).unwrap(), &Scalar::Integer(30));
+    }
+    assert!(graph.query("MATCH (").is_err());
+
+    Arc::try_unwrap(graph)
+        .ok()
+        .unwrap()
+        .into_inner()
+        .delete()
+        .unwrap();
+}
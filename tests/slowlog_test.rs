@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+use redisgraph::{
+    slowlog::{SlowQueryAlert, SlowQueryMonitor, SlowlogEntry},
+    Graph,
+};
+use redisgraph_derive::graph_test;
+
+fn entry(secs: u64, query: &str, millis: u64) -> SlowlogEntry {
+    SlowlogEntry {
+        timestamp: UNIX_EPOCH + Duration::from_secs(secs),
+        command: "GRAPH.QUERY".to_string(),
+        query: query.to_string(),
+        duration: Duration::from_millis(millis),
+    }
+}
+
+#[test]
+fn test_slow_query_monitor() {
+    let alerts = Arc::new(Mutex::new(Vec::<SlowQueryAlert>::new()));
+    let mut monitor = SlowQueryMonitor::new(Duration::from_millis(100), 3, Duration::from_secs(60))
+        .on_alert({
+            let alerts = Arc::clone(&alerts);
+            move |alert| alerts.lock().unwrap().push(alert.clone())
+        });
+
+    let mut entries = vec![
+        entry(1000, "MATCH (p {id: 1}) RETURN p", 150),
+        entry(1001, "MATCH (p {id: 2}) RETURN p", 300),
+        entry(1002, "MATCH (p {id: 3}) RETURN p", 50),
+        entry(1003, "MATCH (c:Company) RETURN c", 500),
+    ];
+    monitor.record(&entries);
+    // Entries returned again by the next poll are not counted twice.
+    monitor.record(&entries);
+    assert!(alerts.lock().unwrap().is_empty());
+
+    entries.push(entry(1010, "MATCH (p {id: 4})\nRETURN p", 200));
+    monitor.record(&entries);
+    {
+        let alerts = alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].occurrences, 3);
+        assert_eq!(alerts[0].max_duration, Duration::from_millis(300));
+        assert_eq!(alerts[0].query, "MATCH (p {id: ?})\nRETURN p");
+    }
+
+    // The earlier occurrences are out of the window.
+    monitor.record(&[
+        entry(1100, "MATCH (p {id: 5}) RETURN p", 200),
+        entry(1101, "MATCH (p {id: 6}) RETURN p", 200),
+    ]);
+    assert_eq!(alerts.lock().unwrap().len(), 1);
+    monitor.record(&[entry(1102, "MATCH (p {id: 7}) RETURN p", 200)]);
+    assert_eq!(alerts.lock().unwrap().len(), 2);
+}
+
+#[graph_test]
+fn test_slowlog(graph: &mut Graph) {
+    graph
+        .mutate("UNWIND range(1, 1000) AS i CREATE ({i: i})")
+        .unwrap();
+    let entries = graph.slowlog().unwrap();
+    assert!(entries
+        .iter()
+        .any(|entry| entry.command == "GRAPH.QUERY" && entry.query.contains("UNWIND")));
+}
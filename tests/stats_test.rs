@@ -0,0 +1,68 @@
+use redisgraph::Graph;
+use redisgraph_derive::graph_test;
+
+#[graph_test]
+fn test_sample_statistics(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (:Person {name: 'Alice', email: 'alice@example.com'})-[:KNOWS {since: 2010}]->\
+             (:Person {name: 'Bob'}), (:City {name: 'Berlin'})",
+        )
+        .unwrap();
+
+    let stats = graph.sample_statistics_with_size(10).unwrap();
+
+    let people = stats
+        .labels
+        .iter()
+        .find(|label| label.name == "Person")
+        .unwrap();
+    assert_eq!(people.count, 2);
+    assert_eq!(people.sampled, 2);
+    assert_eq!(people.property_presence["name"], 1.0);
+    assert_eq!(people.property_presence["email"], 0.5);
+
+    let cities = stats
+        .labels
+        .iter()
+        .find(|label| label.name == "City")
+        .unwrap();
+    assert_eq!(cities.count, 1);
+
+    assert_eq!(stats.relationship_types.len(), 1);
+    let knows = &stats.relationship_types[0];
+    assert_eq!(knows.name, "KNOWS");
+    assert_eq!(knows.count, 1);
+    assert_eq!(knows.property_presence["since"], 1.0);
+}
+
+#[graph_test]
+fn test_hotspots(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (hub:Person {name: 'Hub'}), (a:Person), (b:Person), (c:Person), \
+             (hub)-[:KNOWS]->(a), (hub)-[:KNOWS]->(b), (c)-[:KNOWS]->(hub), (a)-[:KNOWS]->(b), \
+             (a)-[:LIKES]->(b)",
+        )
+        .unwrap();
+    let hub: u64 = graph
+        .query("MATCH (p:Person {name: 'Hub'}) RETURN id(p)")
+        .unwrap();
+
+    let hotspots = graph.hotspots(2).unwrap();
+    let knows = hotspots
+        .iter()
+        .find(|hotspots| hotspots.rel_type == "KNOWS")
+        .unwrap();
+    assert_eq!(knows.nodes.len(), 2);
+    assert_eq!(knows.nodes[0].node.id(), hub);
+    assert_eq!(knows.nodes[0].degree, 3);
+    assert_eq!(knows.nodes[1].degree, 2);
+
+    let likes = hotspots
+        .iter()
+        .find(|hotspots| hotspots.rel_type == "LIKES")
+        .unwrap();
+    assert_eq!(likes.nodes.len(), 2);
+    assert_eq!(likes.nodes[0].degree, 1);
+}
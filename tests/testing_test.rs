@@ -0,0 +1,10 @@
+#![cfg(feature = "testcontainers")]
+
+use redisgraph::testing::redisgraph_container;
+
+#[test]
+fn test_redisgraph_container() {
+    let mut graph = redisgraph_container();
+    let one: i64 = graph.query("RETURN 1").unwrap();
+    assert_eq!(one, 1);
+}
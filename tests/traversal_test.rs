@@ -0,0 +1,61 @@
+use redisgraph::{traversal::v, Graph};
+use redisgraph_derive::graph_test;
+
+#[test]
+fn test_traversal() {
+    let query = v()
+        .has_label("Person")
+        .has("name", "Alice")
+        .out("KNOWS")
+        .has_label("Person")
+        .has("age", 30)
+        .in_("LIKES")
+        .has_not("deleted_at")
+        .to_query()
+        .unwrap();
+    assert_eq!(
+        query.query(),
+        "MATCH (`v0`:`Person`)-[:`KNOWS`]->(`v1`:`Person`)<-[:`LIKES`]-(`v2`) \
+         WHERE (`v0`.`name` = $p0) AND (`v1`.`age` = $p1) AND (`v2`.`deleted_at` IS NULL) \
+         RETURN `v2`"
+    );
+    assert_eq!(query.params().len(), 2);
+}
+
+#[test]
+fn test_traversal_projections() {
+    assert_eq!(
+        v().both("KNOWS")
+            .values("name")
+            .dedup()
+            .skip(5)
+            .limit(10)
+            .to_query()
+            .unwrap()
+            .query(),
+        "MATCH (`v0`)-[:`KNOWS`]-(`v1`) RETURN DISTINCT `v1`.`name` SKIP 5 LIMIT 10"
+    );
+    assert_eq!(
+        v().has_label("Person").count().to_query().unwrap().query(),
+        "MATCH (`v0`:`Person`) RETURN count(`v0`)"
+    );
+}
+
+#[graph_test]
+fn test_traverse(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:Person { name: 'Alice' })-[:KNOWS]->(:Person { name: 'Bob' }), \
+             (a)-[:KNOWS]->(:Person { name: 'Carol' })",
+        )
+        .unwrap();
+
+    let mut friends: Vec<String> = graph
+        .traverse(&v().has("name", "Alice").out("KNOWS").values("name"))
+        .unwrap();
+    friends.sort();
+    assert_eq!(friends, vec!["Bob".to_string(), "Carol".to_string()]);
+
+    let people: i64 = graph.traverse(&v().has_label("Person").count()).unwrap();
+    assert_eq!(people, 3);
+}
@@ -0,0 +1,31 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use redisgraph::{
+    params::CypherValue,
+    props::Props,
+    ttl::{expires_at, EXPIRES_AT},
+};
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+#[test]
+fn test_expires_at() {
+    let before = now_millis();
+    let expires = expires_at(Duration::from_secs(60));
+    let after = now_millis();
+    assert!(expires >= before + 60_000 && expires <= after + 60_000);
+}
+
+#[test]
+fn test_props_expires_in() {
+    let props = Props::new().expires_in(Duration::from_secs(1));
+    match props.get(EXPIRES_AT) {
+        Some(CypherValue::Integer(millis)) => assert!(*millis > now_millis()),
+        other => panic!("expected integer expiration time, got {:?}", other),
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use redisgraph::{Graph, RedisGraphError};
+use redisgraph_derive::graph_test;
+
+#[graph_test]
+fn test_materialize(graph: &mut Graph) {
+    graph
+        .mutate(
+            "CREATE (a:Person {name: 'Alice'})-[:KNOWS]->(:Person {name: 'Bob'}), \
+             (a)-[:KNOWS]->(:Person {name: 'Carol'})",
+        )
+        .unwrap();
+    let mut view = graph
+        .materialize(
+            "friend_counts",
+            "MATCH (p:Person)-[:KNOWS]->(f) RETURN p.name AS name, count(f) AS friends",
+            Duration::from_secs(3600),
+        )
+        .unwrap();
+    assert_eq!(view.columns(), ["name".to_string(), "friends".to_string()]);
+
+    graph
+        .mutate(
+            "MATCH (b:Person {name: 'Bob'}), (c:Person {name: 'Carol'}) CREATE (b)-[:KNOWS]->(c)",
+        )
+        .unwrap();
+    let counts: Vec<(String, i64)> = graph.read_view(&mut view).unwrap();
+    assert_eq!(counts, vec![("Alice".to_string(), 2)]);
+
+    graph.refresh_view(&mut view).unwrap();
+    let mut counts: Vec<(String, i64)> = graph.read_view(&mut view).unwrap();
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![("Alice".to_string(), 2), ("Bob".to_string(), 1)]
+    );
+    let rows: i64 = graph.query("MATCH (r:_View) RETURN count(r)").unwrap();
+    assert_eq!(rows, 2);
+
+    graph.drop_view(view).unwrap();
+    let rows: i64 = graph.query("MATCH (r:_View) RETURN count(r)").unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[graph_test]
+fn test_materialize_nodes(graph: &mut Graph) {
+    graph.mutate("CREATE (:Person {name: 'Alice'})").unwrap();
+    assert!(matches!(
+        graph.materialize(
+            "people",
+            "MATCH (p:Person) RETURN p",
+            Duration::from_secs(60)
+        ),
+        Err(RedisGraphError::ClientTypeError(_))
+    ));
+}